@@ -0,0 +1,9 @@
+-- A host can run several Minecraft instances on different ports (hosting
+-- providers routinely serve 10+ from one IP). Identify a server by its
+-- (ip, port) address pair instead of ip alone, so each instance keeps its own
+-- row instead of collapsing into whichever port was probed last.
+--
+-- Existing rows hold exactly one port per ip, so they already satisfy the new
+-- constraint and are kept as-is.
+ALTER TABLE servers DROP CONSTRAINT servers_ip_key;
+ALTER TABLE servers ADD CONSTRAINT servers_ip_port_key UNIQUE (ip, port);
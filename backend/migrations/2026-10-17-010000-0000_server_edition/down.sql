@@ -0,0 +1,5 @@
+DROP INDEX idx_servers_edition;
+
+ALTER TABLE servers DROP COLUMN game_mode;
+ALTER TABLE servers DROP COLUMN edition;
+DROP TYPE edition;
@@ -0,0 +1,6 @@
+CREATE TYPE edition AS ENUM ('java', 'bedrock');
+
+ALTER TABLE servers ADD COLUMN edition edition NOT NULL DEFAULT 'java';
+ALTER TABLE servers ADD COLUMN game_mode TEXT;
+
+CREATE INDEX idx_servers_edition ON servers (edition);
@@ -0,0 +1,3 @@
+ALTER TABLE servers DROP COLUMN map_name;
+ALTER TABLE servers DROP COLUMN plugins;
+ALTER TABLE servers DROP COLUMN software;
@@ -0,0 +1,3 @@
+ALTER TABLE servers ADD COLUMN software TEXT;
+ALTER TABLE servers ADD COLUMN plugins TEXT[];
+ALTER TABLE servers ADD COLUMN map_name TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE servers DROP COLUMN max_client_protocol;
+ALTER TABLE servers DROP COLUMN min_client_protocol;
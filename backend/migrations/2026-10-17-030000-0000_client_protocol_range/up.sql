@@ -0,0 +1,2 @@
+ALTER TABLE servers ADD COLUMN min_client_protocol INT4;
+ALTER TABLE servers ADD COLUMN max_client_protocol INT4;
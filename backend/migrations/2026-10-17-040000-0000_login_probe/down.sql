@@ -0,0 +1,4 @@
+ALTER TABLE servers DROP COLUMN mod_handshake;
+ALTER TABLE servers DROP COLUMN whitelist_kick;
+ALTER TABLE servers DROP COLUMN auth_prompt;
+ALTER TABLE servers DROP COLUMN reached_play;
@@ -0,0 +1,4 @@
+ALTER TABLE servers ADD COLUMN reached_play BOOL;
+ALTER TABLE servers ADD COLUMN auth_prompt BOOL;
+ALTER TABLE servers ADD COLUMN whitelist_kick BOOL;
+ALTER TABLE servers ADD COLUMN mod_handshake BOOL;
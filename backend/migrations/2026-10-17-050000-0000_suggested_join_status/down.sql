@@ -0,0 +1,4 @@
+DROP INDEX idx_servers_suggested_join_status;
+
+ALTER TABLE servers DROP COLUMN suggestion_confidence;
+ALTER TABLE servers DROP COLUMN suggested_join_status;
@@ -0,0 +1,4 @@
+ALTER TABLE servers ADD COLUMN suggested_join_status join_status;
+ALTER TABLE servers ADD COLUMN suggestion_confidence REAL;
+
+CREATE INDEX idx_servers_suggested_join_status ON servers (suggested_join_status);
@@ -0,0 +1,3 @@
+DROP TABLE scan_shards;
+DROP TABLE scan_jobs;
+DROP TYPE shard_status;
@@ -0,0 +1,28 @@
+-- Operator-submitted range scans. A job's ranges are merged and split into
+-- fixed-size CIDR shards; the backend hands pending shards to online workers
+-- and records how far each got, so a restart (of either side) resumes a shard
+-- at `next_offset` instead of from the start.
+CREATE TYPE shard_status AS ENUM ('pending', 'assigned', 'done');
+
+CREATE TABLE scan_jobs (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    port INTEGER NOT NULL,
+    edition edition NOT NULL DEFAULT 'java',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE TABLE scan_shards (
+    id SERIAL PRIMARY KEY,
+    job_id INTEGER NOT NULL REFERENCES scan_jobs (id) ON DELETE CASCADE,
+    cidr TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    next_offset INTEGER NOT NULL DEFAULT 0,
+    status shard_status NOT NULL DEFAULT 'pending',
+    worker_id TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_scan_shards_job_id ON scan_shards (job_id);
+CREATE INDEX idx_scan_shards_status ON scan_shards (status);
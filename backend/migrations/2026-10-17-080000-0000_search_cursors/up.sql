@@ -0,0 +1,12 @@
+-- Where each worker's permutation search walk stands, as last reported in its
+-- heartbeat. Handed back when the worker reconnects so the walk resumes. The
+-- walk (seed + partition) is part of the row: a worker retuned to another walk
+-- starts that one from the beginning.
+CREATE TABLE search_cursors (
+    worker_id TEXT PRIMARY KEY,
+    seed BIGINT NOT NULL,
+    partition INTEGER NOT NULL,
+    partitions INTEGER NOT NULL,
+    position BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,9 @@
+-- Operator-managed exclusion list (abuse complaints, opt-outs). Pushed to every
+-- worker, which never probes an address inside one of these blocks. `cidr` is
+-- stored normalized (host bits cleared), so the same block is one row.
+CREATE TABLE exclusions (
+    id SERIAL PRIMARY KEY,
+    cidr TEXT NOT NULL UNIQUE,
+    reason TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
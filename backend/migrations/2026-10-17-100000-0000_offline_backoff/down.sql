@@ -0,0 +1,3 @@
+ALTER TABLE servers
+    DROP COLUMN offline_streak,
+    DROP COLUMN last_probed_at;
@@ -0,0 +1,7 @@
+-- Update-cycle backoff. `offline_streak` counts the consecutive re-probes a
+-- server failed (reset by any successful probe); `last_probed_at` is when the
+-- last re-probe result came in. Servers offline several cycles in a row are
+-- skipped by `FetchUpdateTargets` until their backoff has passed.
+ALTER TABLE servers
+    ADD COLUMN offline_streak INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN last_probed_at TIMESTAMPTZ;
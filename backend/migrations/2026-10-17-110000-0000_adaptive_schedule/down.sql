@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_servers_next_probe_at;
+
+ALTER TABLE servers
+    DROP COLUMN next_probe_at,
+    DROP COLUMN online_streak,
+    DROP COLUMN probe_priority;
+
+DROP TYPE probe_priority;
@@ -0,0 +1,19 @@
+-- Adaptive update scheduling. After every re-probe the backend sets
+-- `next_probe_at` from the server's recent player counts, its streaks and the
+-- operator's `probe_priority`; `FetchUpdateTargets` only serves servers that
+-- are due (NULL = due now). `online_streak` counts consecutive successful
+-- probes, the counterpart of `offline_streak`.
+CREATE TYPE probe_priority AS ENUM ('normal', 'high', 'low');
+
+ALTER TABLE servers
+    ADD COLUMN probe_priority probe_priority NOT NULL DEFAULT 'normal',
+    ADD COLUMN online_streak INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN next_probe_at TIMESTAMPTZ;
+
+-- Carry over the offline backoff the update cycle applied until now.
+UPDATE servers
+SET next_probe_at = last_probed_at + make_interval(secs => LEAST(
+        3600 * power(2, LEAST(offline_streak - 3, 20)), 604800))
+WHERE offline_streak >= 3 AND last_probed_at IS NOT NULL;
+
+CREATE INDEX idx_servers_next_probe_at ON servers (next_probe_at);
@@ -0,0 +1,4 @@
+DROP TABLE tasks;
+DROP TYPE worker_pick;
+DROP TYPE task_state;
+DROP TYPE task_kind;
@@ -0,0 +1,37 @@
+-- Pings and scans requested by operators (PingServer, AddTarget,
+-- AddTargetList). The backend leases queued tasks to online workers, at most a
+-- few per worker at once, and a worker's TaskDone finishes them. A lease that
+-- times out or loses its worker (or the backend) is queued again until the
+-- task has been tried `attempts` times, so work survives restarts of either
+-- side and a large import waits here instead of in a worker's channel.
+CREATE TYPE task_kind AS ENUM ('ping', 'scan');
+CREATE TYPE task_state AS ENUM ('queued', 'leased', 'done', 'failed', 'cancelled');
+CREATE TYPE worker_pick AS ENUM ('least_loaded', 'round_robin');
+
+CREATE TABLE tasks (
+    id BIGSERIAL PRIMARY KEY,
+    kind task_kind NOT NULL,
+    -- Empty for a scan by hostname, which the worker resolves.
+    ip TEXT NOT NULL,
+    port INTEGER NOT NULL,
+    edition edition NOT NULL DEFAULT 'java',
+    host TEXT,
+    with_connection BOOLEAN NOT NULL DEFAULT false,
+    -- The worker the operator chose; NULL lets the backend pick one by `pick`
+    -- among the online workers labelled `region` (any, when NULL).
+    worker_id TEXT,
+    region TEXT,
+    pick worker_pick NOT NULL DEFAULT 'least_loaded',
+    state task_state NOT NULL DEFAULT 'queued',
+    -- The worker holding (or, once finished, last holding) the task.
+    leased_to TEXT,
+    lease_expires_at TIMESTAMPTZ,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_tasks_queued ON tasks (id) WHERE state = 'queued';
+CREATE INDEX idx_tasks_leased_to ON tasks (leased_to) WHERE state = 'leased';
+CREATE INDEX idx_tasks_state ON tasks (state, updated_at);
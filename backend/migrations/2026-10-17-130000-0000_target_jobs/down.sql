@@ -0,0 +1,3 @@
+DROP TABLE target_job_servers;
+ALTER TABLE tasks DROP COLUMN job_id;
+DROP TABLE target_jobs;
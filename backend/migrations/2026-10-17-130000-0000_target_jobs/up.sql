@@ -0,0 +1,24 @@
+-- Named AddTargetList batches. Each target is queued as a task under its job;
+-- a job's progress is counted from its tasks and, as their results are
+-- persisted, from the servers that answered, split by whether the job added
+-- them. A job's tasks are kept as long as the job.
+CREATE TABLE target_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    new_servers BIGINT NOT NULL DEFAULT 0,
+    updated_servers BIGINT NOT NULL DEFAULT 0
+);
+
+ALTER TABLE tasks ADD COLUMN job_id BIGINT REFERENCES target_jobs (id) ON DELETE CASCADE;
+CREATE INDEX idx_tasks_job_id ON tasks (job_id, state) WHERE job_id IS NOT NULL;
+
+-- The servers that answered a job's probes, each counted once per job.
+CREATE TABLE target_job_servers (
+    job_id BIGINT NOT NULL REFERENCES target_jobs (id) ON DELETE CASCADE,
+    server_id INTEGER NOT NULL REFERENCES servers (id) ON DELETE CASCADE,
+    is_new BOOLEAN NOT NULL,
+    PRIMARY KEY (job_id, server_id)
+);
+
+CREATE INDEX idx_target_job_servers_server_id ON target_job_servers (server_id);
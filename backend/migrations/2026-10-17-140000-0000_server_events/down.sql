@@ -0,0 +1,2 @@
+DROP TABLE server_events;
+DROP TYPE server_field;
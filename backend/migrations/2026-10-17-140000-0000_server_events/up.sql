@@ -0,0 +1,24 @@
+-- History of server fields that re-probes overwrite in place. A row is written
+-- for every tracked field an update actually changed, with its value before
+-- and after as text (JSON text for the JSONB columns); NULL means unset.
+CREATE TYPE server_field AS ENUM (
+    'version_name',
+    'description',
+    'motd',
+    'favicon',
+    'is_online_mode',
+    'disconnect_reason'
+);
+
+CREATE TABLE server_events (
+    id BIGSERIAL PRIMARY KEY,
+    server_id INTEGER NOT NULL REFERENCES servers (id) ON DELETE CASCADE,
+    field server_field NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_server_events_server_id ON server_events (server_id, id DESC);
+-- "Changed since" server listing.
+CREATE INDEX idx_server_events_changed_at ON server_events (changed_at, server_id);
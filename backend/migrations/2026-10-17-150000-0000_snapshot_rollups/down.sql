@@ -0,0 +1,3 @@
+DROP INDEX idx_player_count_snapshots_recorded_at;
+DROP TABLE player_count_daily;
+DROP TABLE player_count_hourly;
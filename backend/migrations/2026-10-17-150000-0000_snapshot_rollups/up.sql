@@ -0,0 +1,31 @@
+-- Player counts rolled up per server and hour / day (UTC) by the backend's
+-- compaction task, so charts can cover weeks once the raw samples in
+-- `player_count_snapshots` have aged out. `online_*` summarise
+-- `players_online` over the bucket's `samples` raw samples; `players_max` is
+-- the highest advertised capacity.
+CREATE TABLE player_count_hourly (
+    server_id INTEGER NOT NULL REFERENCES servers (id) ON DELETE CASCADE,
+    bucket TIMESTAMPTZ NOT NULL,
+    online_min SMALLINT NOT NULL,
+    online_max SMALLINT NOT NULL,
+    online_avg REAL NOT NULL,
+    players_max SMALLINT NOT NULL,
+    samples INTEGER NOT NULL,
+    PRIMARY KEY (server_id, bucket)
+);
+
+CREATE TABLE player_count_daily (
+    server_id INTEGER NOT NULL REFERENCES servers (id) ON DELETE CASCADE,
+    bucket TIMESTAMPTZ NOT NULL,
+    online_min SMALLINT NOT NULL,
+    online_max SMALLINT NOT NULL,
+    online_avg REAL NOT NULL,
+    players_max SMALLINT NOT NULL,
+    samples INTEGER NOT NULL,
+    PRIMARY KEY (server_id, bucket)
+);
+
+-- Compaction walks all servers by time.
+CREATE INDEX idx_player_count_snapshots_recorded_at ON player_count_snapshots (recorded_at);
+CREATE INDEX idx_player_count_hourly_bucket ON player_count_hourly (bucket);
+CREATE INDEX idx_player_count_daily_bucket ON player_count_daily (bucket);
@@ -0,0 +1,2 @@
+DROP TABLE worker_stats_samples;
+DROP TABLE stats_samples;
@@ -0,0 +1,23 @@
+-- Global statistics recorded periodically by the backend, so the growth of the
+-- index can be charted (GetStatsHistory). Counts are as of `sampled_at`, except
+-- `new_servers`: the servers discovered since the previous sample.
+CREATE TABLE stats_samples (
+    sampled_at TIMESTAMPTZ PRIMARY KEY DEFAULT now(),
+    total_servers BIGINT NOT NULL,
+    online_servers BIGINT NOT NULL,
+    cracked_servers BIGINT NOT NULL,
+    total_players BIGINT NOT NULL,
+    players_online BIGINT NOT NULL,
+    new_servers BIGINT NOT NULL,
+    -- Most common versions: {"<version_name>": <servers>, ...}
+    versions JSONB NOT NULL
+);
+
+-- Probe rates each online worker reported at a sample.
+CREATE TABLE worker_stats_samples (
+    sampled_at TIMESTAMPTZ NOT NULL REFERENCES stats_samples (sampled_at) ON DELETE CASCADE,
+    worker_id TEXT NOT NULL,
+    scan_rate DOUBLE PRECISION NOT NULL,
+    update_rate DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (sampled_at, worker_id)
+);
@@ -0,0 +1,10 @@
+-- Collapse back to one row per (ip, port), keeping the most recently updated
+-- edition (its players/snapshots cascade with the dropped rows).
+DELETE FROM servers s
+USING servers t
+WHERE s.ip = t.ip
+  AND s.port = t.port
+  AND (s.updated_at, s.id) < (t.updated_at, t.id);
+
+ALTER TABLE servers DROP CONSTRAINT servers_ip_port_edition_key;
+ALTER TABLE servers ADD CONSTRAINT servers_ip_port_key UNIQUE (ip, port);
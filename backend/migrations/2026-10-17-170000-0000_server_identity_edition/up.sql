@@ -0,0 +1,5 @@
+-- A host can answer as a Java server over TCP and as a Bedrock server over UDP
+-- on the same port number. Make the edition part of a server's identity so the
+-- two keep separate rows instead of the later probe overwriting the other.
+ALTER TABLE servers DROP CONSTRAINT servers_ip_port_key;
+ALTER TABLE servers ADD CONSTRAINT servers_ip_port_edition_key UNIQUE (ip, port, edition);
@@ -10,7 +10,7 @@ use serde_json::Value;
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ChatObject {
-    Object(ChatComponentObject),
+    Object(Box<ChatComponentObject>),
     Array(Vec<ChatObject>),
     JsonPrimitive(Value),
 }
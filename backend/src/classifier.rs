@@ -0,0 +1,350 @@
+//! Suggests a [`JoinStatus`] from what a worker saw when it tried to log in: the
+//! disconnect reason (flattened via [`ChatObject::get_motd`]) and, when the
+//! worker ran the deep login probe, its outcome. The suggestion is stored next to
+//! the operator's manual `join_status`, never over it.
+//!
+//! Reasons are matched against a rule set of case-insensitive substrings. The
+//! built-in rules cover the common vanilla, Bukkit-plugin and Forge messages in
+//! several languages; operators add their own under `[classifier]` in
+//! config.toml:
+//!
+//! ```toml
+//! [[classifier.rules]]
+//! status = "whitelist"
+//! confidence = 0.9
+//! patterns = ["only for subscribers"]
+//! ```
+
+use proto::worker::LoginProbe;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{chat::ChatObject, models::servers::JoinStatus, server_filters::parse_join_status};
+
+/// `[classifier]` in config.toml.
+#[derive(Debug, Deserialize)]
+pub struct ClassifierConfig {
+    /// Extra rules, checked before the built-in ones.
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+    /// Whether the built-in rules apply at all. Defaults to true.
+    pub builtin_rules: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RuleConfig {
+    /// `join_status` enum text ("whitelist", "password", ...).
+    pub status: String,
+    /// 0..=1; how sure a match makes us.
+    pub confidence: f32,
+    pub patterns: Vec<String>,
+}
+
+/// A suggested status and how sure the classifier is of it (0..=1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion {
+    pub status: JoinStatus,
+    pub confidence: f32,
+}
+
+struct Rule {
+    status: JoinStatus,
+    confidence: f32,
+    /// Lowercased.
+    patterns: Vec<String>,
+}
+
+/// Built-in `(status, confidence, patterns)`. Translation keys are matched too,
+/// since vanilla kicks often carry no literal text.
+const BUILTIN_RULES: &[(JoinStatus, f32, &[&str])] = &[
+    (
+        JoinStatus::Whitelist,
+        0.95,
+        &["multiplayer.disconnect.not_whitelisted"],
+    ),
+    (
+        JoinStatus::Whitelist,
+        0.85,
+        &[
+            "whitelist",
+            "white-list",
+            "white list",
+            "вайтлист",
+            "белом списке",
+            "белый список",
+            "білому списку",
+            "liste blanche",
+            "lista blanca",
+            "lista branca",
+            "białej liście",
+            "beyaz liste",
+            "白名单",
+            "ホワイトリスト",
+        ],
+    ),
+    (
+        JoinStatus::Modded,
+        0.85,
+        &[
+            "mods that require fml",
+            "requires fml",
+            "missing mods",
+            "mod rejections",
+            "mismatched mod",
+            "incompatible mod",
+            "neoforge",
+            "forge",
+            "fabric",
+            "установите мод",
+            "требуются моды",
+            "нужны моды",
+        ],
+    ),
+    (
+        JoinStatus::Password,
+        0.8,
+        &[
+            "/login",
+            "/register",
+            "authme",
+            "login timeout",
+            "too long to log in",
+            "password",
+            "пароль",
+            "авториз",
+            "зарегистрир",
+            "mot de passe",
+            "passwort",
+            "contraseña",
+            "senha",
+            "hasło",
+        ],
+    ),
+    (
+        JoinStatus::Broken,
+        0.6,
+        &[
+            "internal exception",
+            "java.lang.",
+            "io.netty",
+            "could not connect to a default or fallback server",
+            "unable to connect you to",
+            "multiplayer.disconnect.unexpected_query_response",
+        ],
+    ),
+];
+
+pub struct JoinClassifier {
+    rules: Vec<Rule>,
+}
+
+impl Default for JoinClassifier {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl JoinClassifier {
+    /// Builds the rule set from config; a rule naming an unknown status is
+    /// skipped with a warning.
+    pub fn new(config: Option<&ClassifierConfig>) -> Self {
+        let mut rules = Vec::new();
+        for r in config.map(|c| c.rules.as_slice()).unwrap_or_default() {
+            let Some(status) = parse_join_status(&r.status) else {
+                tracing::warn!("classifier rule with unknown status {:?} ignored", r.status);
+                continue;
+            };
+            rules.push(Rule {
+                status,
+                confidence: r.confidence.clamp(0.0, 1.0),
+                patterns: r.patterns.iter().map(|p| p.to_lowercase()).collect(),
+            });
+        }
+        if config.and_then(|c| c.builtin_rules).unwrap_or(true) {
+            rules.extend(
+                BUILTIN_RULES
+                    .iter()
+                    .map(|&(status, confidence, patterns)| Rule {
+                        status,
+                        confidence,
+                        patterns: patterns.iter().map(|p| p.to_string()).collect(),
+                    }),
+            );
+        }
+        Self { rules }
+    }
+
+    /// Suggests a status from a disconnect reason and/or deep login outcome, or
+    /// `None` when neither says anything. The most confident signal wins; on a
+    /// tie, the earlier rule.
+    pub fn classify(
+        &self,
+        reason: Option<&Value>,
+        login: Option<&LoginProbe>,
+    ) -> Option<Suggestion> {
+        let mut best: Option<Suggestion> = None;
+        let mut offer = |status, confidence| {
+            if best.is_none_or(|b: Suggestion| confidence > b.confidence) {
+                best = Some(Suggestion { status, confidence });
+            }
+        };
+
+        if let Some(login) = login {
+            if login.whitelist_kick {
+                offer(JoinStatus::Whitelist, 0.95);
+            }
+            if login.auth_prompt {
+                offer(JoinStatus::Password, 0.9);
+            }
+            if login.mod_handshake && reason.is_some() {
+                offer(JoinStatus::Modded, 0.7);
+            }
+            if login.reached_play && !login.auth_prompt && reason.is_none() {
+                offer(JoinStatus::Spoofable, 0.6);
+            }
+        }
+
+        if let Some(text) = reason.map(reason_text).filter(|t| !t.is_empty()) {
+            for rule in &self.rules {
+                if rule.patterns.iter().any(|p| text.contains(p.as_str())) {
+                    offer(rule.status, rule.confidence);
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Lowercased plain text of a reason plus any translation keys in it, with
+/// legacy `§` formatting codes stripped.
+fn reason_text(reason: &Value) -> String {
+    let mut text = serde_json::from_value::<ChatObject>(reason.clone())
+        .map(|chat| chat.get_motd())
+        .unwrap_or_default();
+    collect_translate_keys(reason, &mut text);
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.extend(c.to_lowercase());
+        }
+    }
+    out
+}
+
+fn collect_translate_keys(v: &Value, out: &mut String) {
+    match v {
+        Value::Object(o) => {
+            if let Some(key) = o.get("translate").and_then(Value::as_str) {
+                out.push(' ');
+                out.push_str(key);
+            }
+            o.values().for_each(|v| collect_translate_keys(v, out));
+        }
+        Value::Array(a) => a.iter().for_each(|v| collect_translate_keys(v, out)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn status(reason: Value) -> Option<JoinStatus> {
+        JoinClassifier::default()
+            .classify(Some(&reason), None)
+            .map(|s| s.status)
+    }
+
+    #[test]
+    fn whitelist_kicks_in_any_language() {
+        let vanilla = json!({"translate": "multiplayer.disconnect.not_whitelisted"});
+        assert_eq!(status(vanilla), Some(JoinStatus::Whitelist));
+        assert_eq!(
+            status(json!({"text": "§cYou are not white-listed on this server!"})),
+            Some(JoinStatus::Whitelist)
+        );
+        assert_eq!(
+            status(json!({"text": "", "extra": ["Вы не в вайтлисте"]})),
+            Some(JoinStatus::Whitelist)
+        );
+    }
+
+    #[test]
+    fn mod_and_broken_reasons() {
+        assert_eq!(
+            status(json!(
+                "This server has mods that require FML/Forge to be installed on the client."
+            )),
+            Some(JoinStatus::Modded)
+        );
+        assert_eq!(
+            status(json!("Could not connect to a default or fallback server")),
+            Some(JoinStatus::Broken)
+        );
+        assert_eq!(status(json!("Server is restarting")), None);
+    }
+
+    #[test]
+    fn login_probe_outweighs_weaker_reason_rules() {
+        let c = JoinClassifier::default();
+        let prompt = LoginProbe {
+            reached_play: true,
+            auth_prompt: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            c.classify(None, Some(&prompt)).map(|s| s.status),
+            Some(JoinStatus::Password)
+        );
+        let joined = LoginProbe {
+            reached_play: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            c.classify(None, Some(&joined)),
+            Some(Suggestion {
+                status: JoinStatus::Spoofable,
+                confidence: 0.6
+            })
+        );
+        assert_eq!(c.classify(None, None), None);
+    }
+
+    #[test]
+    fn configured_rules_come_first_and_can_replace_builtins() {
+        let config: ClassifierConfig = toml::from_str("builtin_rules = false").unwrap();
+        assert!(
+            JoinClassifier::new(Some(&config))
+                .classify(Some(&json!("not whitelisted")), None)
+                .is_none()
+        );
+
+        let config: ClassifierConfig = toml::from_str(
+            r#"
+            [[rules]]
+            status = "Password"
+            confidence = 0.99
+            patterns = ["Введите /L"]
+
+            [[rules]]
+            status = "nonsense"
+            confidence = 1.0
+            patterns = ["введите"]
+            "#,
+        )
+        .unwrap();
+        let c = JoinClassifier::new(Some(&config));
+        assert_eq!(
+            c.classify(Some(&json!("Введите /l <пароль>")), None),
+            Some(Suggestion {
+                status: JoinStatus::Password,
+                confidence: 0.99
+            })
+        );
+    }
+}
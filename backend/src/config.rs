@@ -1,10 +1,14 @@
 use serde::Deserialize;
 use std::{env, fs, path::PathBuf};
 
+use crate::{classifier::ClassifierConfig, snapshots::SnapshotRetention};
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub database: Option<DatabaseConfig>,
     pub backend: Option<BackendConfig>,
+    /// Join status classifier rules; built-in rules only when absent.
+    pub classifier: Option<ClassifierConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +38,12 @@ pub struct BackendConfig {
     pub watchtower_url: Option<String>,
     /// Bearer token watchtower expects on its HTTP API (`WATCHTOWER_HTTP_API_TOKEN`).
     pub watchtower_token: Option<String>,
+    /// Days raw player count samples are kept (default 7, at least 1).
+    pub snapshot_raw_days: Option<u32>,
+    /// Days hourly player count rollups are kept (default 90, at least 2).
+    pub snapshot_hourly_days: Option<u32>,
+    /// Days daily player count rollups are kept; unset or 0 keeps them for good.
+    pub snapshot_daily_days: Option<u32>,
 }
 
 impl BackendConfig {
@@ -42,6 +52,14 @@ impl BackendConfig {
             .clone()
             .unwrap_or_else(|| "0.0.0.0:3000".to_string())
     }
+
+    pub fn snapshot_retention(&self) -> SnapshotRetention {
+        SnapshotRetention::from_days(
+            self.snapshot_raw_days,
+            self.snapshot_hourly_days,
+            self.snapshot_daily_days,
+        )
+    }
 }
 
 impl Config {
@@ -59,10 +77,10 @@ impl Config {
 
 fn config_path() -> PathBuf {
     let args: Vec<String> = env::args().collect();
-    if let Some(pos) = args.iter().position(|a| a == "--config") {
-        if let Some(val) = args.get(pos + 1) {
-            return PathBuf::from(val);
-        }
+    if let Some(pos) = args.iter().position(|a| a == "--config")
+        && let Some(val) = args.get(pos + 1)
+    {
+        return PathBuf::from(val);
     }
     if let Ok(val) = env::var("CONFIG_PATH") {
         return PathBuf::from(val);
@@ -0,0 +1,138 @@
+//! Operator exclusion list. Blocks added through the API (`AddExclusion`) are
+//! stored in `exclusions` and pushed whole to every worker over the `Session`
+//! stream: on register, and to all online workers whenever the list changes.
+//! The worker enforces it on every probe it sends (search, update cycle, scans,
+//! pings, scan job shards) and keeps a local copy so the list also holds across
+//! its restarts. `PurgeExcludedServers` removes the rows already collected.
+
+use std::net::IpAddr;
+
+use diesel::{dsl::insert_into, prelude::*, upsert::excluded};
+use diesel_async::RunQueryDsl;
+use ipnet::IpNet;
+use tonic::Status;
+
+use crate::{
+    database::DatabaseWrapper,
+    models::exclusions::{ExclusionInsert, ExclusionModel},
+    persistence::DbResult,
+    schema::exclusions,
+    state::AppState,
+};
+
+/// Widest IPv4 block accepted, against a typo excluding most of the internet.
+pub const MIN_PREFIX_V4: u8 = 8;
+/// Widest IPv6 block accepted.
+pub const MIN_PREFIX_V6: u8 = 16;
+
+/// Parses a block: a CIDR (IPv4 or IPv6) or a bare address meaning just that
+/// host. Host bits are cleared and IPv4-mapped IPv6 addresses become IPv4, so
+/// one block has one spelling.
+pub fn parse_cidr(s: &str) -> Result<IpNet, Status> {
+    let s = s.trim();
+    let invalid = || Status::invalid_argument(format!("Invalid block: {s}"));
+    let net = match s.parse::<IpNet>() {
+        Ok(net) => net.trunc(),
+        Err(_) => IpNet::from(s.parse::<IpAddr>().map_err(|_| invalid())?.to_canonical()),
+    };
+    let min = match net {
+        IpNet::V4(_) => MIN_PREFIX_V4,
+        IpNet::V6(_) => MIN_PREFIX_V6,
+    };
+    if net.prefix_len() < min {
+        return Err(Status::invalid_argument(format!(
+            "Block {s} is wider than /{min}"
+        )));
+    }
+    Ok(net)
+}
+
+pub async fn list(db: &DatabaseWrapper) -> DbResult<Vec<ExclusionModel>> {
+    let mut conn = db.conn().await?;
+    let rows = exclusions::table
+        .select(ExclusionModel::as_select())
+        .order(exclusions::id.desc())
+        .load(&mut conn)
+        .await?;
+    Ok(rows)
+}
+
+/// Adds a block. Adding one that is already listed replaces its reason.
+pub async fn add(db: &DatabaseWrapper, net: IpNet, reason: &str) -> DbResult<ExclusionModel> {
+    let mut conn = db.conn().await?;
+    let cidr = net.to_string();
+    let row = insert_into(exclusions::table)
+        .values(&ExclusionInsert {
+            cidr: &cidr,
+            reason,
+        })
+        .on_conflict(exclusions::cidr)
+        .do_update()
+        .set(exclusions::reason.eq(excluded(exclusions::reason)))
+        .returning(ExclusionModel::as_returning())
+        .get_result(&mut conn)
+        .await?;
+    Ok(row)
+}
+
+pub async fn delete(db: &DatabaseWrapper, id: i32) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let n = diesel::delete(exclusions::table.filter(exclusions::id.eq(id)))
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// Every listed block, as sent to the workers.
+pub async fn cidrs(db: &DatabaseWrapper) -> DbResult<Vec<String>> {
+    let mut conn = db.conn().await?;
+    let cidrs = exclusions::table
+        .select(exclusions::cidr)
+        .order(exclusions::id)
+        .load(&mut conn)
+        .await?;
+    Ok(cidrs)
+}
+
+/// Deletes every server inside a listed block (their players and snapshots
+/// cascade). Returns how many went.
+pub async fn purge_servers(db: &DatabaseWrapper) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let n = diesel::sql_query(
+        "DELETE FROM servers s USING exclusions e \
+         WHERE s.ip::inet <<= e.cidr::inet",
+    )
+    .execute(&mut conn)
+    .await?;
+    Ok(n)
+}
+
+/// Pushes the current list to every online worker, after a change.
+pub async fn broadcast(state: &AppState) {
+    match cidrs(&state.db).await {
+        Ok(cidrs) => state.registry.broadcast_exclusions(cidrs).await,
+        Err(e) => tracing::error!("failed to load exclusion list: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_are_normalised() {
+        let got = |s: &str| parse_cidr(s).unwrap().to_string();
+        assert_eq!(got(" 198.51.100.9/24 "), "198.51.100.0/24");
+        assert_eq!(got("8.8.8.8"), "8.8.8.8/32");
+        assert_eq!(got("::ffff:8.8.8.8"), "8.8.8.8/32");
+        assert_eq!(got("2a01:4f8:c17::1/48"), "2a01:4f8:c17::/48");
+        assert_eq!(got("2a01:4f8::1"), "2a01:4f8::1/128");
+    }
+
+    #[test]
+    fn rejects_bad_and_wide_blocks() {
+        for bad in ["", "example.com", "10.0.0.0/33", "10.0.0.0/7", "2000::/3"] {
+            assert!(parse_cidr(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -8,6 +8,7 @@ use tonic_web::GrpcWebLayer;
 
 use crate::{
     auth::{BACKEND_PASSWORD, BACKEND_SECRET, WORKER_TOKEN, generate_random_string},
+    classifier::JoinClassifier,
     database::DatabaseWrapper,
     registry::WorkerRegistry,
     services::{api::ApiService, worker::WorkerService},
@@ -18,18 +19,28 @@ pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
 mod auth;
 mod chat;
+mod classifier;
 mod config;
 mod database;
 mod events;
+mod exclusions;
 mod html;
 mod models;
 mod persistence;
 mod registry;
+mod scan_jobs;
+mod schedule;
 mod schema;
 #[macro_use]
 mod server_filters;
+mod server_history;
 mod services;
+mod snapshots;
 mod state;
+mod stats_history;
+mod target_jobs;
+mod tasks;
+mod update_leases;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -47,6 +58,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Missing [database] section in config.toml");
 
     let addr = backend_cfg.grpc_addr().parse()?;
+    let snapshot_retention = backend_cfg.snapshot_retention();
 
     // The backend owns the database: run migrations on startup.
     let mut migration_conn = PgConnection::establish(&database_cfg.url)
@@ -110,8 +122,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         registry: Arc::new(WorkerRegistry::default()),
         events: Arc::new(crate::events::ServerEvents::default()),
         watchtower,
+        classifier: Arc::new(JoinClassifier::new(config.classifier.as_ref())),
+        shard_wakeup: tokio::sync::Notify::new(),
+        task_wakeup: tokio::sync::Notify::new(),
     });
 
+    crate::scan_jobs::spawn_scheduler(state.clone());
+    crate::tasks::spawn_scheduler(state.clone());
+    crate::snapshots::spawn_compactor(state.db.clone(), snapshot_retention);
+    crate::stats_history::spawn_sampler(state.clone());
+
     // Periodically prune the worker-result idempotency ledger. First tick fires
     // immediately, then hourly.
     {
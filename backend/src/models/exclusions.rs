@@ -0,0 +1,20 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::exclusions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ExclusionModel {
+    pub id: i32,
+    pub cidr: String,
+    pub reason: String,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::exclusions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ExclusionInsert<'a> {
+    pub cidr: &'a str,
+    pub reason: &'a str,
+}
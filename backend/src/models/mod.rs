@@ -1,3 +1,9 @@
+pub mod exclusions;
 pub mod player_count_snapshots;
 pub mod players;
+pub mod scan_jobs;
+pub mod server_events;
 pub mod servers;
+pub mod stats_samples;
+pub mod target_jobs;
+pub mod tasks;
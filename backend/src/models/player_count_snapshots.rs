@@ -21,3 +21,23 @@ pub struct SnapshotInsert {
     pub players_online: i16,
     pub players_max: i16,
 }
+
+/// A row of `player_count_hourly` or `player_count_daily`.
+#[derive(QueryableByName)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SnapshotRollup {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub server_id: i32,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    pub bucket: chrono::DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::SmallInt)]
+    pub online_min: i16,
+    #[diesel(sql_type = diesel::sql_types::SmallInt)]
+    pub online_max: i16,
+    #[diesel(sql_type = diesel::sql_types::Float)]
+    pub online_avg: f32,
+    #[diesel(sql_type = diesel::sql_types::SmallInt)]
+    pub players_max: i16,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub samples: i32,
+}
@@ -0,0 +1,75 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::models::servers::Edition;
+
+/// Where a shard of a scan job stands. Postgres enum `shard_status`. A shard is
+/// `assigned` only while the worker holding it is connected; it goes back to
+/// `pending` (keeping its `next_offset`) when that worker or the backend drops.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::ShardStatus"]
+pub enum ShardStatus {
+    Pending,
+    Assigned,
+    Done,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::scan_jobs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ScanJobInsert<'a> {
+    pub name: &'a str,
+    pub port: i32,
+    pub edition: Edition,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::scan_shards)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ScanShardInsert {
+    pub job_id: i32,
+    pub cidr: String,
+    pub size: i32,
+}
+
+/// A job with its shard totals, as listed to the frontend.
+#[derive(QueryableByName)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ScanJobSummary {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub name: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub port: i32,
+    #[diesel(sql_type = crate::schema::sql_types::Edition)]
+    pub edition: Edition,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    pub created_at: chrono::DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>)]
+    pub finished_at: Option<chrono::DateTime<Utc>>,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub shards_total: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub shards_done: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub addresses_total: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub addresses_scanned: i64,
+}
+
+/// A shard just handed to a worker, with the job settings it is scanned with.
+#[derive(QueryableByName)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ClaimedShard {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub cidr: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub next_offset: i32,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub port: i32,
+    #[diesel(sql_type = crate::schema::sql_types::Edition)]
+    pub edition: Edition,
+}
@@ -0,0 +1,77 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// A server field whose changes are kept in `server_events`. Postgres enum
+/// `server_field`; mirrors the `api.ServerField` proto enum.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::ServerField"]
+pub enum ServerField {
+    VersionName,
+    Description,
+    Motd,
+    Favicon,
+    IsOnlineMode,
+    DisconnectReason,
+}
+
+impl ServerField {
+    pub fn from_proto(i: i32) -> Self {
+        use proto::api::ServerField as Pb;
+        match Pb::try_from(i) {
+            Ok(Pb::Description) => ServerField::Description,
+            Ok(Pb::Motd) => ServerField::Motd,
+            Ok(Pb::Favicon) => ServerField::Favicon,
+            Ok(Pb::IsOnlineMode) => ServerField::IsOnlineMode,
+            Ok(Pb::DisconnectReason) => ServerField::DisconnectReason,
+            _ => ServerField::VersionName,
+        }
+    }
+
+    pub fn to_proto(self) -> i32 {
+        use proto::api::ServerField as Pb;
+        let field = match self {
+            ServerField::VersionName => Pb::VersionName,
+            ServerField::Description => Pb::Description,
+            ServerField::Motd => Pb::Motd,
+            ServerField::Favicon => Pb::Favicon,
+            ServerField::IsOnlineMode => Pb::IsOnlineMode,
+            ServerField::DisconnectReason => Pb::DisconnectReason,
+        };
+        field as i32
+    }
+}
+
+/// The tracked fields of a `servers` row.
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::servers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TrackedFields {
+    pub version_name: String,
+    pub description: serde_json::Value,
+    pub motd: String,
+    pub favicon: Option<String>,
+    pub is_online_mode: bool,
+    pub disconnect_reason: Option<serde_json::Value>,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::server_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerEventModel {
+    pub id: i64,
+    pub server_id: i32,
+    pub field: ServerField,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::server_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerEventInsert {
+    pub server_id: i32,
+    pub field: ServerField,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
@@ -6,7 +6,7 @@ use serde_json::Value;
 /// probing it. Independent of `is_checked`/`is_crashed` and of the auto-detected
 /// `requires_mods`. Postgres enum `join_status`; variants lowercase to the DB
 /// labels via `diesel_derive_enum` (see [`crate::models::players::PlayerStatus`]).
-#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[ExistingTypePath = "crate::schema::sql_types::JoinStatus"]
 pub enum JoinStatus {
     Undetermined,
@@ -17,6 +17,41 @@ pub enum JoinStatus {
     Broken,
 }
 
+/// Which Minecraft edition a server speaks. Postgres enum `edition`; mirrors the
+/// `worker.Edition` proto enum, whose zero value (Java) is also the column default.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[ExistingTypePath = "crate::schema::sql_types::Edition"]
+pub enum Edition {
+    Java,
+    Bedrock,
+}
+
+impl Edition {
+    pub fn from_proto(i: i32) -> Self {
+        match proto::worker::Edition::try_from(i) {
+            Ok(proto::worker::Edition::Bedrock) => Edition::Bedrock,
+            _ => Edition::Java,
+        }
+    }
+
+    pub fn to_proto(self) -> i32 {
+        match self {
+            Edition::Java => proto::worker::Edition::Java as i32,
+            Edition::Bedrock => proto::worker::Edition::Bedrock as i32,
+        }
+    }
+}
+
+/// Operator weight on how often the update cycle re-probes a server (see
+/// [`crate::schedule`]). Postgres enum `probe_priority`.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::ProbePriority"]
+pub enum ProbePriority {
+    Normal,
+    High,
+    Low,
+}
+
 #[derive(Queryable, Selectable, Identifiable)]
 #[diesel(table_name = crate::schema::servers)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -38,6 +73,22 @@ pub struct ServerModel {
     pub updated_at: chrono::DateTime<Utc>,
     pub favicon: Option<String>,
     pub ping: Option<i64>,
+    pub edition: Edition,
+    pub game_mode: Option<String>,
+    pub software: Option<String>,
+    pub plugins: Option<Vec<String>>,
+    pub map_name: Option<String>,
+    pub min_client_protocol: Option<i32>,
+    pub max_client_protocol: Option<i32>,
+    pub reached_play: Option<bool>,
+    pub auth_prompt: Option<bool>,
+    pub whitelist_kick: Option<bool>,
+    pub mod_handshake: Option<bool>,
+    pub suggested_join_status: Option<JoinStatus>,
+    pub suggestion_confidence: Option<f32>,
+    pub hostname: Option<String>,
+    pub probe_priority: ProbePriority,
+    pub next_probe_at: Option<chrono::DateTime<Utc>>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Clone)]
@@ -48,6 +99,8 @@ pub struct ServerModelMini {
     pub ip: String,
     pub port: i32,
     pub is_online_mode: bool,
+    pub edition: Edition,
+    pub hostname: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -67,6 +120,8 @@ pub struct ServerInsert<'a> {
     pub requires_mods: bool,
     pub favicon: Option<&'a str>,
     pub ping: Option<i64>,
+    pub edition: Edition,
+    pub game_mode: Option<&'a str>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -83,6 +138,10 @@ pub struct ServerUpdate<'a> {
     pub requires_mods: bool,
     pub favicon: Option<&'a str>,
     pub ping: Option<i64>,
+    pub game_mode: Option<&'a str>,
+    /// Reset by every successful re-probe (see [`crate::schedule`]).
+    pub offline_streak: i32,
+    pub last_probed_at: Option<chrono::DateTime<Utc>>,
 }
 
 #[derive(Insertable, AsChangeset)]
@@ -92,3 +151,50 @@ pub struct ServerExtraUpdate {
     pub is_online_mode: bool,
     pub disconnect_reason: Option<Value>,
 }
+
+/// GameSpy4 query results. Written only when a report carries a query, so a
+/// server that stops answering keeps its last known plugins/software; an
+/// answered query with no software/map clears the old value.
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::servers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(treat_none_as_null = true)]
+pub struct ServerQueryUpdate<'a> {
+    pub software: Option<&'a str>,
+    pub plugins: &'a [String],
+    pub map_name: Option<&'a str>,
+}
+
+/// Accepted client protocol range from a negotiating probe. Written only when the
+/// report carries accepted protocols.
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::servers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerProtocolRangeUpdate {
+    pub min_client_protocol: i32,
+    pub max_client_protocol: i32,
+}
+
+/// Outcome of a deep login probe. Written only when the report's extra carries
+/// one, so a shallow re-probe keeps the last deep result.
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::servers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerLoginProbeUpdate {
+    pub reached_play: bool,
+    pub auth_prompt: bool,
+    pub whitelist_kick: bool,
+    pub mod_handshake: bool,
+}
+
+/// The classifier's [`JoinStatus`] suggestion (see [`crate::classifier`]).
+/// Rewritten, possibly to NULL, whenever a report carries login results, so it
+/// tracks the latest attempt; `join_status` itself is only ever set by hand.
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::servers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(treat_none_as_null = true)]
+pub struct ServerSuggestionUpdate {
+    pub suggested_join_status: Option<JoinStatus>,
+    pub suggestion_confidence: Option<f32>,
+}
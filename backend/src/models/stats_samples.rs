@@ -0,0 +1,27 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = crate::schema::stats_samples)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StatsSampleModel {
+    pub sampled_at: chrono::DateTime<Utc>,
+    pub total_servers: i64,
+    pub online_servers: i64,
+    pub cracked_servers: i64,
+    pub total_players: i64,
+    pub players_online: i64,
+    pub new_servers: i64,
+    /// Most common versions, `{"<version_name>": <servers>}`.
+    pub versions: serde_json::Value,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = crate::schema::worker_stats_samples)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkerStatsSampleModel {
+    pub sampled_at: chrono::DateTime<Utc>,
+    pub worker_id: String,
+    pub scan_rate: f64,
+    pub update_rate: f64,
+}
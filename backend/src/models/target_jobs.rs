@@ -0,0 +1,35 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::target_jobs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TargetJobInsert<'a> {
+    pub name: &'a str,
+}
+
+/// A job with its task totals, as listed to the frontend.
+#[derive(QueryableByName)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TargetJobSummary {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub id: i64,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub name: String,
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    pub created_at: chrono::DateTime<Utc>,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub new_servers: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub updated_servers: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub total: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub queued: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub probed: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub failed: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub cancelled: i64,
+}
@@ -0,0 +1,116 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::models::servers::Edition;
+
+/// Postgres enum `task_kind`.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::TaskKind"]
+pub enum TaskKind {
+    Ping,
+    Scan,
+}
+
+/// Where a queued ping or scan stands. Postgres enum `task_state`. A task is
+/// `leased` only while a worker may be running it; it goes back to `queued`
+/// when the lease expires or its worker (or the backend) drops, and ends up
+/// `failed` once it has used up its attempts.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::TaskState"]
+pub enum TaskState {
+    Queued,
+    Leased,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl TaskState {
+    pub fn from_proto(i: i32) -> Self {
+        use proto::api::TaskState as Pb;
+        match Pb::try_from(i) {
+            Ok(Pb::Leased) => TaskState::Leased,
+            Ok(Pb::Done) => TaskState::Done,
+            Ok(Pb::Failed) => TaskState::Failed,
+            Ok(Pb::Cancelled) => TaskState::Cancelled,
+            _ => TaskState::Queued,
+        }
+    }
+
+    pub fn to_proto(self) -> i32 {
+        use proto::api::TaskState as Pb;
+        let state = match self {
+            TaskState::Queued => Pb::Queued,
+            TaskState::Leased => Pb::Leased,
+            TaskState::Done => Pb::Done,
+            TaskState::Failed => Pb::Failed,
+            TaskState::Cancelled => Pb::Cancelled,
+        };
+        state as i32
+    }
+}
+
+/// How a task without a chosen worker is placed. Postgres enum `worker_pick`;
+/// mirrors the `api.WorkerPick` proto enum.
+#[derive(diesel_derive_enum::DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[ExistingTypePath = "crate::schema::sql_types::WorkerPick"]
+pub enum WorkerPick {
+    LeastLoaded,
+    RoundRobin,
+}
+
+impl WorkerPick {
+    pub fn from_proto(i: i32) -> Self {
+        match proto::api::WorkerPick::try_from(i) {
+            Ok(proto::api::WorkerPick::RoundRobin) => WorkerPick::RoundRobin,
+            _ => WorkerPick::LeastLoaded,
+        }
+    }
+
+    pub fn to_proto(self) -> i32 {
+        match self {
+            WorkerPick::LeastLoaded => proto::api::WorkerPick::LeastLoaded as i32,
+            WorkerPick::RoundRobin => proto::api::WorkerPick::RoundRobin as i32,
+        }
+    }
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::tasks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TaskModel {
+    pub id: i64,
+    pub kind: TaskKind,
+    pub ip: String,
+    pub port: i32,
+    pub edition: Edition,
+    pub host: Option<String>,
+    pub with_connection: bool,
+    pub worker_id: Option<String>,
+    pub region: Option<String>,
+    pub pick: WorkerPick,
+    pub state: TaskState,
+    pub leased_to: Option<String>,
+    pub lease_expires_at: Option<chrono::DateTime<Utc>>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+    pub job_id: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::tasks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TaskInsert<'a> {
+    pub kind: TaskKind,
+    pub ip: String,
+    pub port: i32,
+    pub edition: Edition,
+    pub host: Option<String>,
+    pub with_connection: bool,
+    pub worker_id: Option<&'a str>,
+    pub region: Option<&'a str>,
+    pub pick: WorkerPick,
+    pub job_id: Option<i64>,
+}
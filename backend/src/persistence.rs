@@ -14,34 +14,34 @@ use std::time::Duration;
 
 use crate::{
     chat::ChatObject,
+    classifier::JoinClassifier,
     models::{
         player_count_snapshots::SnapshotInsert,
         players::PlayerInsert,
+        server_events::TrackedFields,
         servers::{
-            ServerExtraUpdate, ServerInsert, ServerModel, ServerModelMini, ServerUpdate,
+            Edition, ProbePriority, ServerExtraUpdate, ServerInsert, ServerLoginProbeUpdate,
+            ServerModel, ServerModelMini, ServerProtocolRangeUpdate, ServerQueryUpdate,
+            ServerSuggestionUpdate, ServerUpdate,
         },
     },
-    schema,
+    schedule, schema, server_history, target_jobs,
+    update_leases::Slice,
 };
 use chrono::Utc;
-use diesel::{dsl::insert_into, pg::Pg, prelude::*, sql_types::Bool};
+use diesel::{
+    dsl::insert_into,
+    pg::Pg,
+    prelude::*,
+    sql_types::{BigInt, Bool, Integer, Text},
+};
 use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
-use proto::worker::ServerReport;
+use proto::worker::{PermutationScan, SearchCursor, ServerReport};
 
 use crate::database::DatabaseWrapper;
 
 pub type DbResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
-/// Keeps only the 100 newest snapshots for a server.
-const PRUNE_SQL: &str = "DELETE FROM player_count_snapshots \
-     WHERE server_id = $1 \
-     AND recorded_at < ( \
-         SELECT recorded_at FROM player_count_snapshots \
-         WHERE server_id = $1 \
-         ORDER BY recorded_at DESC \
-         LIMIT 1 OFFSET 99 \
-     )";
-
 /// How long to keep `processed_results` idempotency rows. Must comfortably
 /// exceed the worker's outbox replay horizon so a replayed result is always
 /// recognised as a duplicate. Kept in sync with the worker's outbox max age.
@@ -106,20 +106,26 @@ async fn claim_result(conn: &mut AsyncPgConnection, result_id: &str) -> QueryRes
     Ok(inserted == 1)
 }
 
-/// Upsert-by-ip; conflict only bumps updated_at/is_online/favicon (rediscovery
-/// does not overwrite version/description).
+/// Upsert by address and edition (`(ip, port, edition)`); conflict only bumps
+/// updated_at/is_online/favicon (rediscovery does not overwrite
+/// version/description). A result for task `task_id` (0 for none) counts
+/// towards its target job, if any (see [`crate::target_jobs`]).
 pub async fn persist_discovered(
     db: &DatabaseWrapper,
+    classifier: &JoinClassifier,
     report: ServerReport,
     result_id: &str,
+    task_id: u64,
 ) -> DbResult<Option<i32>> {
-    with_retry(|| discovered_txn(db, &report, result_id)).await
+    with_retry(|| discovered_txn(db, classifier, &report, result_id, task_id)).await
 }
 
 async fn discovered_txn(
     db: &DatabaseWrapper,
+    classifier: &JoinClassifier,
     report: &ServerReport,
     result_id: &str,
+    task_id: u64,
 ) -> DbResult<Option<i32>> {
     let mut conn = db.conn().await?;
     let conn: &mut AsyncPgConnection = &mut conn;
@@ -139,6 +145,16 @@ async fn discovered_txn(
                 None => (false, None),
             };
             let favicon = report.favicon.as_deref();
+            let edition = Edition::from_proto(report.edition);
+            // A rediscovered server's row before the upsert, for its history.
+            let before = schema::servers::table
+                .filter(schema::servers::ip.eq(&report.ip))
+                .filter(schema::servers::port.eq(report.port))
+                .filter(schema::servers::edition.eq(edition))
+                .select(TrackedFields::as_select())
+                .first::<TrackedFields>(conn)
+                .await
+                .optional()?;
 
             let server_insert = ServerInsert {
                 ip: &report.ip,
@@ -152,41 +168,69 @@ async fn discovered_txn(
                 requires_mods: report.requires_mods,
                 favicon,
                 ping: report.ping,
+                edition,
+                game_mode: report.game_mode.as_deref(),
             };
 
             let server: ServerModel = insert_into(schema::servers::table)
                 .values(&server_insert)
-                .on_conflict(schema::servers::ip)
+                .on_conflict((
+                    schema::servers::ip,
+                    schema::servers::port,
+                    schema::servers::edition,
+                ))
                 .do_update()
                 .set((
                     schema::servers::updated_at.eq(Utc::now()),
                     schema::servers::is_online.eq(true),
                     schema::servers::favicon.eq(favicon),
+                    schema::servers::offline_streak.eq(0),
+                    schema::servers::online_streak.eq(schema::servers::online_streak + 1),
                 ))
                 .returning(ServerModel::as_returning())
                 .get_result(conn)
                 .await?;
 
+            if let Some(before) = &before {
+                let after = schema::servers::table
+                    .find(server.id)
+                    .select(TrackedFields::as_select())
+                    .first::<TrackedFields>(conn)
+                    .await?;
+                server_history::record(conn, server.id, before, &after).await?;
+            }
+
+            write_probe_details(conn, classifier, server.id, report).await?;
             write_snapshot_and_players(conn, server.id, report, false).await?;
+            schedule_next_probe(conn, server.id).await?;
+            if task_id != 0 {
+                let is_new = before.is_none();
+                target_jobs::record_server(conn, task_id as i64, server.id, is_new).await?;
+            }
             Ok(Some(server.id))
         })
         .await?;
     Ok(server_id)
 }
 
-/// Full field update by ip (reachable path).
+/// Full field update by address and edition (reachable path). Counts towards the target
+/// job of task `task_id` like [`persist_discovered`].
 pub async fn persist_updated(
     db: &DatabaseWrapper,
+    classifier: &JoinClassifier,
     report: ServerReport,
     result_id: &str,
+    task_id: u64,
 ) -> DbResult<Option<i32>> {
-    with_retry(|| updated_txn(db, &report, result_id)).await
+    with_retry(|| updated_txn(db, classifier, &report, result_id, task_id)).await
 }
 
 async fn updated_txn(
     db: &DatabaseWrapper,
+    classifier: &JoinClassifier,
     report: &ServerReport,
     result_id: &str,
+    task_id: u64,
 ) -> DbResult<Option<i32>> {
     let mut conn = db.conn().await?;
     let conn: &mut AsyncPgConnection = &mut conn;
@@ -196,10 +240,12 @@ async fn updated_txn(
                 return Ok(None); // replay — already persisted
             }
 
-            let Some(server_id) = schema::servers::table
+            let Some((server_id, before)) = schema::servers::table
                 .filter(schema::servers::ip.eq(&report.ip))
-                .select(schema::servers::id)
-                .first::<i32>(conn)
+                .filter(schema::servers::port.eq(report.port))
+                .filter(schema::servers::edition.eq(Edition::from_proto(report.edition)))
+                .select((schema::servers::id, TrackedFields::as_select()))
+                .first::<(i32, TrackedFields)>(conn)
                 .await
                 .optional()?
             else {
@@ -210,6 +256,24 @@ async fn updated_txn(
             let description = parse_json(&report.description_json);
             let motd = motd_from_description(&description);
             let favicon = report.favicon.as_deref();
+            // Login details are only overwritten by a probe that connected.
+            let (is_online_mode, disconnect_reason) = match &report.extra {
+                Some(e) => (
+                    e.is_online_mode,
+                    e.disconnect_reason_json.as_deref().map(parse_json),
+                ),
+                None => (before.is_online_mode, before.disconnect_reason.clone()),
+            };
+            let probed = TrackedFields {
+                version_name: report.version_name.clone(),
+                description: description.clone(),
+                motd: motd.clone(),
+                favicon: report.favicon.clone(),
+                is_online_mode,
+                disconnect_reason,
+            };
+            let after = server_history::stored_after(&before, probed);
+            server_history::record(conn, server_id, &before, &after).await?;
 
             let server_change = ServerUpdate {
                 version_name: &report.version_name,
@@ -221,18 +285,24 @@ async fn updated_txn(
                 requires_mods: report.requires_mods,
                 favicon,
                 ping: report.ping,
+                game_mode: report.game_mode.as_deref(),
+                offline_streak: 0,
+                last_probed_at: Some(Utc::now()),
             };
 
             diesel::update(schema::servers::table)
                 .filter(schema::servers::id.eq(server_id))
-                .set(server_change)
+                .set((
+                    server_change,
+                    schema::servers::online_streak.eq(schema::servers::online_streak + 1),
+                ))
                 .execute(conn)
                 .await?;
 
-            if let Some(extra) = &report.extra {
+            if report.extra.is_some() {
                 let server_extra_change = ServerExtraUpdate {
-                    is_online_mode: extra.is_online_mode,
-                    disconnect_reason: extra.disconnect_reason_json.as_deref().map(parse_json),
+                    is_online_mode: after.is_online_mode,
+                    disconnect_reason: after.disconnect_reason,
                 };
                 diesel::update(schema::servers::table)
                     .filter(schema::servers::id.eq(server_id))
@@ -241,23 +311,41 @@ async fn updated_txn(
                     .await?;
             }
 
+            write_probe_details(conn, classifier, server_id, report).await?;
             write_snapshot_and_players(conn, server_id, report, true).await?;
+            schedule_next_probe(conn, server_id).await?;
+            if task_id != 0 {
+                target_jobs::record_server(conn, task_id as i64, server_id, false).await?;
+            }
             Ok(Some(server_id))
         })
         .await?;
     Ok(server_id)
 }
 
-/// Marks a server offline after a failed re-probe.
+/// Marks a server offline after a failed re-probe, extending its offline
+/// streak, and reschedules it (see [`crate::schedule`]).
+///
+/// A `port` of 0 comes from a result queued by a worker that predates per-port
+/// identity (replayed from its outbox); every server on `ip` is then marked
+/// offline, matching what that worker meant at the time.
 pub async fn persist_offline(
     db: &DatabaseWrapper,
     ip: &str,
+    port: i32,
+    edition: Edition,
     result_id: &str,
 ) -> DbResult<Option<i32>> {
-    with_retry(|| offline_txn(db, ip, result_id)).await
+    with_retry(|| offline_txn(db, ip, port, edition, result_id)).await
 }
 
-async fn offline_txn(db: &DatabaseWrapper, ip: &str, result_id: &str) -> DbResult<Option<i32>> {
+async fn offline_txn(
+    db: &DatabaseWrapper,
+    ip: &str,
+    port: i32,
+    edition: Edition,
+    result_id: &str,
+) -> DbResult<Option<i32>> {
     let mut conn = db.conn().await?;
     let conn: &mut AsyncPgConnection = &mut conn;
     let id = conn
@@ -265,22 +353,179 @@ async fn offline_txn(db: &DatabaseWrapper, ip: &str, result_id: &str) -> DbResul
             if !claim_result(conn, result_id).await? {
                 return Ok(None); // replay — already persisted
             }
-            diesel::update(schema::servers::table)
+            let port_filter: Box<dyn BoxableExpression<_, Pg, SqlType = Bool>> = match port {
+                0 => Box::new(diesel::dsl::sql::<Bool>("TRUE")),
+                p => Box::new(schema::servers::port.eq(p)),
+            };
+            let ids = diesel::update(schema::servers::table)
                 .filter(schema::servers::ip.eq(ip))
-                .set(schema::servers::is_online.eq(false))
+                .filter(port_filter)
+                .filter(schema::servers::edition.eq(edition))
+                .set((
+                    schema::servers::is_online.eq(false),
+                    schema::servers::offline_streak.eq(schema::servers::offline_streak + 1),
+                    schema::servers::online_streak.eq(0),
+                    schema::servers::last_probed_at.eq(Utc::now()),
+                ))
                 .returning(schema::servers::id)
-                .get_result::<i32>(conn)
-                .await
-                .optional()
+                .get_results::<i32>(conn)
+                .await?;
+            for &id in &ids {
+                schedule_next_probe(conn, id).await?;
+            }
+            Ok(ids.into_iter().next())
         })
         .await?;
     Ok(id)
 }
 
-/// Inserts a player-count snapshot, prunes old ones, and records players. When
-/// `update_last_seen` is set, existing players have their `last_seen_at` bumped
-/// (update cycle); otherwise duplicates are ignored (discovery). Runs on the
-/// caller's transaction connection.
+/// Pushes back the next probe of a server a worker skipped, by
+/// [`schedule::SKIPPED_INTERVAL`]. Its state is left alone: it was not probed.
+pub async fn persist_skipped(
+    db: &DatabaseWrapper,
+    ip: &str,
+    port: i32,
+    edition: Edition,
+    result_id: &str,
+) -> DbResult<()> {
+    with_retry(|| skipped_txn(db, ip, port, edition, result_id)).await
+}
+
+async fn skipped_txn(
+    db: &DatabaseWrapper,
+    ip: &str,
+    port: i32,
+    edition: Edition,
+    result_id: &str,
+) -> DbResult<()> {
+    let mut conn = db.conn().await?;
+    let conn: &mut AsyncPgConnection = &mut conn;
+    conn.transaction::<(), diesel::result::Error, _>(async |conn| {
+        if !claim_result(conn, result_id).await? {
+            return Ok(()); // replay — already persisted
+        }
+        diesel::update(schema::servers::table)
+            .filter(schema::servers::ip.eq(ip))
+            .filter(schema::servers::port.eq(port))
+            .filter(schema::servers::edition.eq(edition))
+            .set(schema::servers::next_probe_at.eq(Utc::now() + schedule::SKIPPED_INTERVAL))
+            .execute(conn)
+            .await?;
+        Ok(())
+    })
+    .await?;
+    Ok(())
+}
+
+/// Sets `next_probe_at` from the server's state after a probe and its newest
+/// snapshots (see [`crate::schedule`]). Runs last in a result's transaction, so
+/// the snapshot just written counts.
+async fn schedule_next_probe(conn: &mut AsyncPgConnection, server_id: i32) -> QueryResult<()> {
+    let (is_online, online_streak, offline_streak, priority) = schema::servers::table
+        .filter(schema::servers::id.eq(server_id))
+        .select((
+            schema::servers::is_online,
+            schema::servers::online_streak,
+            schema::servers::offline_streak,
+            schema::servers::probe_priority,
+        ))
+        .first::<(bool, i32, i32, ProbePriority)>(conn)
+        .await?;
+    let player_counts: Vec<i16> = schema::player_count_snapshots::table
+        .filter(schema::player_count_snapshots::server_id.eq(server_id))
+        .order(schema::player_count_snapshots::recorded_at.desc())
+        .limit(schedule::ACTIVITY_WINDOW)
+        .select(schema::player_count_snapshots::players_online)
+        .load(conn)
+        .await?;
+    let interval = schedule::next_interval(&schedule::Activity {
+        is_online,
+        online_streak,
+        offline_streak,
+        priority,
+        player_counts: &player_counts,
+    });
+    diesel::update(schema::servers::table)
+        .filter(schema::servers::id.eq(server_id))
+        .set(schema::servers::next_probe_at.eq(Utc::now() + interval))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Stores the optional probe results a report may carry: the hostname, the
+/// GameSpy4 query fields, the accepted client protocol range, the deep login
+/// outcome and the join status suggested from the login attempt. Absent results
+/// leave the previous values in place.
+async fn write_probe_details(
+    conn: &mut AsyncPgConnection,
+    classifier: &JoinClassifier,
+    server_id: i32,
+    report: &ServerReport,
+) -> QueryResult<()> {
+    // Only set by probes of targets added by name; an address-only probe keeps
+    // the name the server was added by.
+    if let Some(hostname) = &report.hostname {
+        diesel::update(schema::servers::table)
+            .filter(schema::servers::id.eq(server_id))
+            .set(schema::servers::hostname.eq(hostname))
+            .execute(conn)
+            .await?;
+    }
+    if let Some(query) = &report.query {
+        let query_change = ServerQueryUpdate {
+            software: query.software.as_deref(),
+            plugins: &query.plugins,
+            map_name: query.map.as_deref(),
+        };
+        diesel::update(schema::servers::table)
+            .filter(schema::servers::id.eq(server_id))
+            .set(query_change)
+            .execute(conn)
+            .await?;
+    }
+    let protocols = &report.accepted_protocols;
+    if let (Some(&min), Some(&max)) = (protocols.iter().min(), protocols.iter().max()) {
+        diesel::update(schema::servers::table)
+            .filter(schema::servers::id.eq(server_id))
+            .set(ServerProtocolRangeUpdate {
+                min_client_protocol: min,
+                max_client_protocol: max,
+            })
+            .execute(conn)
+            .await?;
+    }
+    if let Some(login) = report.extra.as_ref().and_then(|e| e.login.as_ref()) {
+        diesel::update(schema::servers::table)
+            .filter(schema::servers::id.eq(server_id))
+            .set(ServerLoginProbeUpdate {
+                reached_play: login.reached_play,
+                auth_prompt: login.auth_prompt,
+                whitelist_kick: login.whitelist_kick,
+                mod_handshake: login.mod_handshake,
+            })
+            .execute(conn)
+            .await?;
+    }
+    if let Some(extra) = &report.extra {
+        let reason = extra.disconnect_reason_json.as_deref().map(parse_json);
+        let suggestion = classifier.classify(reason.as_ref(), extra.login.as_ref());
+        diesel::update(schema::servers::table)
+            .filter(schema::servers::id.eq(server_id))
+            .set(ServerSuggestionUpdate {
+                suggested_join_status: suggestion.map(|s| s.status),
+                suggestion_confidence: suggestion.map(|s| s.confidence),
+            })
+            .execute(conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Inserts a player-count snapshot (aged out by `crate::snapshots`) and records
+/// players. When `update_last_seen` is set, existing players have their
+/// `last_seen_at` bumped (update cycle); otherwise duplicates are ignored
+/// (discovery). Runs on the caller's transaction connection.
 async fn write_snapshot_and_players(
     conn: &mut AsyncPgConnection,
     server_id: i32,
@@ -297,16 +542,15 @@ async fn write_snapshot_and_players(
         .execute(conn)
         .await?;
 
-    diesel::sql_query(PRUNE_SQL)
-        .bind::<diesel::sql_types::Integer, _>(server_id)
-        .execute(conn)
-        .await?;
-
-    // De-duplicate names within this report.
+    // De-duplicate names within this report. The query's full player list
+    // overlaps the status sample, which may also hold spoofed names the query
+    // doesn't; both are recorded.
     let mut seen = std::collections::HashSet::new();
+    let query_players = report.query.iter().flat_map(|q| &q.players);
     let inserts: Vec<PlayerInsert> = report
         .player_names
         .iter()
+        .chain(query_players)
         .filter(|n| seen.insert(n.as_str()))
         .map(|n| PlayerInsert { server_id, name: n })
         .collect();
@@ -343,49 +587,62 @@ pub async fn prune_processed_results(db: &DatabaseWrapper) -> DbResult<usize> {
     Ok(n)
 }
 
-/// Counts the servers a worker should re-probe this cycle, honouring the same
-/// filters as `fetch_update_targets_batch`. Run once at the start of a cycle so
-/// the worker can report a fixed total instead of a count that climbs as rows
-/// stream in.
+/// Whether a server is due for a re-probe: its `next_probe_at` has passed, or
+/// it was never scheduled (see [`crate::schedule`]).
+fn due_for_update() -> diesel::expression::SqlLiteral<Bool> {
+    diesel::dsl::sql::<Bool>("(servers.next_probe_at IS NULL OR servers.next_probe_at <= now())")
+}
+
+/// Highest server id, or 0 with no servers: where an update round ends (see
+/// `crate::update_leases`).
+pub async fn max_server_id(db: &DatabaseWrapper) -> DbResult<i32> {
+    let mut conn = db.conn().await?;
+    let max: Option<i32> = schema::servers::table
+        .select(diesel::dsl::max(schema::servers::id))
+        .get_result(&mut conn)
+        .await?;
+    Ok(max.unwrap_or(0))
+}
+
+/// Counts the servers above id `after_id` a worker should re-probe, honouring
+/// the same filters as `fetch_update_targets_slice`. Run once at the start of a
+/// cycle so the worker can report a fixed total instead of a count that climbs
+/// as rows stream in.
 pub async fn count_update_targets(
     db: &DatabaseWrapper,
     filters: &crate::server_filters::ServerFilters,
+    after_id: i32,
 ) -> DbResult<i64> {
     let mut conn = db.conn().await?;
     let n: i64 = crate::apply_server_filters!(schema::servers::table, filters)
+        .filter(schema::servers::id.gt(after_id))
+        .filter(due_for_update())
         .count()
         .get_result(&mut conn)
         .await?;
     Ok(n)
 }
 
-/// Fetches one keyset-paginated batch of servers a worker should re-probe this
-/// cycle, honouring the spoofable/cracked filters. Rows are ordered by ascending
-/// id and start strictly after `after_id`; the caller pages by passing the last
-/// returned id back as `after_id` until a short batch signals the end. This
-/// replaces a single load-the-whole-table query so the streaming RPC never has
-/// to buffer every server at once. The pooled connection is held only for the
-/// duration of this one batch query.
-pub async fn fetch_update_targets_batch(
+/// Fetches the servers of one leased id slice (see `crate::update_leases`) a
+/// worker should re-probe, honouring the spoofable/cracked filters and skipping
+/// servers not yet due ([`due_for_update`]), in ascending id order. A slice
+/// spans at most `SLICE_IDS` ids, so the streaming RPC never has to buffer
+/// every server at once. The pooled connection is held only for the duration
+/// of this one query.
+pub async fn fetch_update_targets_slice(
     db: &DatabaseWrapper,
     filters: &crate::server_filters::ServerFilters,
-    after_id: Option<i32>,
-    limit: i64,
+    slice: Slice,
 ) -> DbResult<Vec<ServerModelMini>> {
     let mut conn = db.conn().await?;
 
-    let cursor_filter: Box<dyn BoxableExpression<_, Pg, SqlType = Bool>> = match after_id {
-        Some(id) => Box::new(schema::servers::id.gt(id)),
-        None => Box::new(diesel::dsl::sql::<Bool>("TRUE")),
-    };
-
     // Same server-property predicates as the dashboard list query, via the shared
     // macro, so update targeting and the UI filter set stay in lockstep.
     let servers: Vec<ServerModelMini> =
         crate::apply_server_filters!(schema::servers::table, filters)
-            .filter(cursor_filter)
+            .filter(schema::servers::id.between(slice.first, slice.last))
+            .filter(due_for_update())
             .order(schema::servers::id.asc())
-            .limit(limit)
             .select(ServerModelMini::as_select())
             .load(&mut conn)
             .await?;
@@ -394,14 +651,88 @@ pub async fn fetch_update_targets_batch(
 }
 
 /// Looks up a server's address by id (used to translate a frontend PingServer
-/// request, which is keyed by id, into a worker ping task keyed by ip/port).
-pub async fn server_addr_by_id(db: &DatabaseWrapper, id: i32) -> DbResult<Option<(String, i32)>> {
+/// request, which is keyed by id, into a worker ping task keyed by ip/port and
+/// carrying the edition that picks the worker's prober and the hostname to
+/// announce).
+pub async fn server_addr_by_id(
+    db: &DatabaseWrapper,
+    id: i32,
+) -> DbResult<Option<(String, i32, Edition, Option<String>)>> {
     let mut conn = db.conn().await?;
     let row = schema::servers::table
         .filter(schema::servers::id.eq(id))
-        .select((schema::servers::ip, schema::servers::port))
-        .first::<(String, i32)>(&mut conn)
+        .select((
+            schema::servers::ip,
+            schema::servers::port,
+            schema::servers::edition,
+            schema::servers::hostname,
+        ))
+        .first::<(String, i32, Edition, Option<String>)>(&mut conn)
         .await
         .optional()?;
     Ok(row)
 }
+
+/// Records the permutation search cursor a worker reported in its heartbeat.
+/// Within one walk the position only moves forward: until the resume sent at
+/// registration lands, a reconnected worker reports from the start of its walk,
+/// which must not overwrite the stored position. A different walk (seed or
+/// partition) replaces the row.
+pub async fn save_search_cursor(
+    db: &DatabaseWrapper,
+    worker_id: &str,
+    cursor: &SearchCursor,
+) -> DbResult<()> {
+    let Some(scan) = cursor.scan else {
+        return Ok(());
+    };
+    let mut conn = db.conn().await?;
+    diesel::sql_query(
+        "INSERT INTO search_cursors (worker_id, seed, partition, partitions, position) \
+         VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (worker_id) DO UPDATE SET \
+             position = CASE \
+                 WHEN (search_cursors.seed, search_cursors.partition, search_cursors.partitions) \
+                     = (EXCLUDED.seed, EXCLUDED.partition, EXCLUDED.partitions) \
+                 THEN GREATEST(search_cursors.position, EXCLUDED.position) \
+                 ELSE EXCLUDED.position END, \
+             seed = EXCLUDED.seed, \
+             partition = EXCLUDED.partition, \
+             partitions = EXCLUDED.partitions, \
+             updated_at = now()",
+    )
+    .bind::<Text, _>(worker_id)
+    .bind::<BigInt, _>(scan.seed as i64)
+    .bind::<Integer, _>(scan.partition.min(i32::MAX as u32) as i32)
+    .bind::<Integer, _>(scan.partitions.min(i32::MAX as u32) as i32)
+    .bind::<BigInt, _>(cursor.position.min(i64::MAX as u64) as i64)
+    .execute(&mut conn)
+    .await?;
+    Ok(())
+}
+
+/// The last permutation search cursor a worker reported, to hand back when it
+/// registers.
+pub async fn load_search_cursor(
+    db: &DatabaseWrapper,
+    worker_id: &str,
+) -> DbResult<Option<SearchCursor>> {
+    use schema::search_cursors as c;
+    let mut conn = db.conn().await?;
+    let row = c::table
+        .filter(c::worker_id.eq(worker_id))
+        .select((c::seed, c::partition, c::partitions, c::position))
+        .first::<(i64, i32, i32, i64)>(&mut conn)
+        .await
+        .optional()?;
+    Ok(
+        row.map(|(seed, partition, partitions, position)| SearchCursor {
+            scan: Some(PermutationScan {
+                seed: seed as u32,
+                partition: partition as u32,
+                partitions: partitions as u32,
+            }),
+            position: position.max(0) as u64,
+        }),
+    )
+}
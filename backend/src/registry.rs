@@ -1,23 +1,33 @@
 //! In-memory registry of connected workers. Each worker that opens a `Session`
 //! stream gets a [`WorkerHandle`] holding its latest config/metrics and an
 //! outbound command channel. The frontend's worker-management RPCs read and
-//! mutate this registry; `dispatch_*` routes scan/ping work to a live worker.
+//! mutate this registry; `dispatch_*` routes work to a live worker, e.g. the
+//! queued pings and scans leased to it (see `crate::tasks`). The registry also
+//! keeps the leases of the shared update rounds (see `crate::update_leases`).
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Instant};
 
 use chrono::Utc;
 use proto::{
     api::{WorkerInfo, WorkerList},
     worker::{
-        PingTask, ScanTask, ServerCommand, SetName, WorkerConfig, WorkerMetrics, server_command,
+        Control, Exclusions, ScanShard, ServerCommand, SetName, WorkerConfig, WorkerMetrics,
+        server_command,
     },
 };
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{Mutex, RwLock, mpsc};
 use tonic::Status;
 
+use crate::{
+    server_filters::ServerFilters,
+    update_leases::{Joined, Slice, UpdateLeases},
+};
+
 pub struct WorkerHandle {
     pub name: Option<String>,
     pub version: String,
+    /// Operator label from the worker's config, matched by `api.WorkerSelector`.
+    pub region: Option<String>,
     pub online: bool,
     pub last_seen: i64,
     pub config: WorkerConfig,
@@ -35,6 +45,17 @@ pub struct WorkerRegistry {
     /// a rename survives a worker dropping and re-registering with its old name
     /// before it has persisted the change to its own config file.
     desired_name: RwLock<HashMap<String, Option<String>>>,
+    /// Update rounds and the id slices leased out of them.
+    update_leases: Mutex<UpdateLeases>,
+}
+
+/// An online worker, as the task scheduler places work on it.
+pub struct OnlineWorker {
+    pub id: String,
+    pub region: Option<String>,
+    /// Tasks it reported pending in its last heartbeat.
+    pub pending: u32,
+    pub threads: u32,
 }
 
 fn now() -> i64 {
@@ -50,6 +71,7 @@ impl WorkerRegistry {
         id: String,
         name: Option<String>,
         version: String,
+        region: Option<String>,
         reported: WorkerConfig,
         cmd_tx: mpsc::Sender<Result<ServerCommand, Status>>,
     ) -> WorkerConfig {
@@ -74,6 +96,7 @@ impl WorkerRegistry {
             WorkerHandle {
                 name,
                 version,
+                region: region.filter(|r| !r.trim().is_empty()),
                 online: true,
                 last_seen: now(),
                 config: effective.clone(),
@@ -156,6 +179,36 @@ impl WorkerRegistry {
         }
     }
 
+    /// Ids of the workers with a live session.
+    pub async fn online_ids(&self) -> Vec<String> {
+        self.workers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, h)| h.online)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// The workers with a live session, sorted by id.
+    pub async fn online_workers(&self) -> Vec<OnlineWorker> {
+        let mut online: Vec<OnlineWorker> = self
+            .workers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, h)| h.online)
+            .map(|(id, h)| OnlineWorker {
+                id: id.clone(),
+                region: h.region.clone(),
+                pending: h.metrics.as_ref().map_or(0, |m| m.pending_tasks),
+                threads: h.metrics.as_ref().map_or(0, |m| m.active_threads),
+            })
+            .collect();
+        online.sort_by(|a, b| a.id.cmp(&b.id));
+        online
+    }
+
     pub async fn get(&self, id: &str) -> Result<WorkerInfo, Status> {
         self.workers
             .read()
@@ -184,34 +237,101 @@ impl WorkerRegistry {
             .map_err(|_| Status::unavailable("worker disconnected"))
     }
 
-    pub async fn dispatch_ping(
+    /// Sends a queued ping or scan leased to a worker (see `crate::tasks`).
+    /// Errors when the worker is unknown or offline, so the caller can queue
+    /// the task again.
+    pub async fn dispatch_task(
         &self,
         worker_id: &str,
-        ip: String,
-        port: i32,
-        with_connection: bool,
+        cmd: server_command::Cmd,
     ) -> Result<(), Status> {
-        self.dispatch_to(
-            worker_id,
-            server_command::Cmd::Ping(PingTask {
-                ip,
-                port,
-                with_connection,
-            }),
-        )
-        .await
+        self.dispatch_to(worker_id, cmd).await
     }
 
-    /// Sends a scan task to a specific operator-chosen worker. Errors (fail-fast)
-    /// when the worker is unknown or currently offline.
-    pub async fn dispatch_scan_to(
+    /// Hands a scan job shard to a worker (see `crate::scan_jobs`). Errors when
+    /// the worker is unknown or offline, so the caller can release the shard.
+    pub async fn dispatch_shard(&self, worker_id: &str, shard: ScanShard) -> Result<(), Status> {
+        self.dispatch_to(worker_id, server_command::Cmd::Shard(shard))
+            .await
+    }
+
+    /// Pushes the exclusion list (see `crate::exclusions`) to every online
+    /// worker. Workers that drop before it arrives get it when they register.
+    pub async fn broadcast_exclusions(&self, cidrs: Vec<String>) {
+        let txs: Vec<_> = self
+            .workers
+            .read()
+            .await
+            .values()
+            .filter(|h| h.online)
+            .map(|h| h.cmd_tx.clone())
+            .collect();
+        for tx in txs {
+            let cmd = server_command::Cmd::SetExclusions(Exclusions {
+                cidrs: cidrs.clone(),
+            });
+            let _ = tx.send(Ok(ServerCommand { cmd: Some(cmd) })).await;
+        }
+    }
+
+    /// A worker's `FetchUpdateTargets` stream joins the update round of its
+    /// filter; `max_id` is the highest server id now.
+    pub async fn join_update_round(&self, filters: &ServerFilters, max_id: i32) -> Joined {
+        self.update_leases.lock().await.join(filters, max_id)
+    }
+
+    pub async fn leave_update_round(&self, filters: &ServerFilters, round: u64) {
+        self.update_leases.lock().await.leave(filters, round);
+    }
+
+    /// Leases the next id slice of `round` to `worker_id`; `None` once the
+    /// round is walked.
+    pub async fn lease_update_slice(
         &self,
+        filters: &ServerFilters,
+        round: u64,
         worker_id: &str,
-        ip: String,
-        port: i32,
-    ) -> Result<(), Status> {
-        self.dispatch_to(worker_id, server_command::Cmd::Scan(ScanTask { ip, port }))
+    ) -> Option<(u64, Slice)> {
+        self.update_leases
+            .lock()
             .await
+            .lease(filters, round, worker_id, Instant::now())
+    }
+
+    pub async fn release_update_slice(&self, filters: &ServerFilters, lease: u64) {
+        self.update_leases.lock().await.release(filters, lease);
+    }
+
+    /// Hands a slice whose targets may not have reached the worker back to the
+    /// round.
+    pub async fn give_back_update_slice(&self, filters: &ServerFilters, lease: u64) {
+        self.update_leases.lock().await.give_back(filters, lease);
+    }
+
+    /// Gives back the update slices of a worker whose session ended. A round
+    /// left with slices but no stream to take them triggers an update cycle on
+    /// another online worker running the same filter, which picks them up.
+    pub async fn reassign_update_leases(&self, worker_id: &str) {
+        let stalled = self.update_leases.lock().await.drop_worker(worker_id);
+        for filters in stalled {
+            let target = self
+                .workers
+                .read()
+                .await
+                .iter()
+                .find(|(id, h)| {
+                    h.online
+                        && *id != worker_id
+                        && h.config.update_module
+                        && ServerFilters::from(&h.config.update_filter.clone().unwrap_or_default())
+                            == filters
+                })
+                .map(|(id, _)| id.clone());
+            if let Some(id) = target {
+                tracing::info!(worker = %id, from = %worker_id, "reassigning update slices");
+                let _ = self.send_control(&id, Control::TriggerUpdate as i32).await;
+            }
+        }
     }
 
     /// Sends a parameterless control command (pause/resume search, abort/trigger
@@ -229,6 +349,7 @@ impl WorkerHandle {
             name: self.name.clone(),
             version: self.version.clone(),
             online: self.online,
+            region: self.region.clone(),
             last_seen_unix: self.last_seen,
             config: Some(self.config.clone()),
             metrics: self.metrics.clone(),
@@ -0,0 +1,401 @@
+//! Range scan jobs. An operator submits CIDR ranges (see `CreateScanJob`); the
+//! ranges are merged and cut into shards of at most [`SHARD_PREFIX`], stored in
+//! `scan_shards`, and handed out over the `Session` stream by the scheduler
+//! task ([`spawn_scheduler`]). Workers report how far they got with
+//! `ShardProgress`, which is written back per shard, so a shard interrupted by
+//! a worker or backend restart goes back to `pending` and is resumed from its
+//! recorded offset by whichever worker claims it next.
+//!
+//! Only IPv4 blocks are accepted. ASN lists are not resolved here: expand an AS
+//! to its announced prefixes and submit those as a ranges file. Reserved
+//! addresses inside a range are skipped by the worker's usual guard.
+
+use std::{net::Ipv4Addr, sync::Arc, time::Duration};
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    sql_types::{BigInt, Bool, Integer, Text},
+};
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use ipnet::Ipv4Net;
+use proto::worker::{ScanShard, ShardProgress};
+use tonic::Status;
+
+use crate::{
+    database::DatabaseWrapper,
+    models::{
+        scan_jobs::{ClaimedShard, ScanJobInsert, ScanJobSummary, ScanShardInsert, ShardStatus},
+        servers::Edition,
+    },
+    persistence::DbResult,
+    schema::{scan_jobs, scan_shards},
+    state::AppState,
+};
+
+/// Largest shard: a /22 (1024 addresses) takes a worker a few minutes at
+/// default settings, which bounds the work redone after an unclean restart.
+pub const SHARD_PREFIX: u8 = 22;
+/// Widest range accepted in one entry.
+pub const MIN_PREFIX: u8 = 8;
+/// Cap on the addresses one job may cover after merging (a /8's worth).
+pub const MAX_JOB_ADDRESSES: u64 = 1 << 24;
+/// Shards a worker holds at once: one being scanned, one queued behind it so
+/// the worker is not idle while the next assignment round trips. The worker
+/// scans them one at a time.
+const MAX_SHARDS_PER_WORKER: i64 = 2;
+/// How often the scheduler looks for idle workers when nothing woke it.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(5);
+/// Rows per insert when storing a job's shards.
+const SHARD_INSERT_BATCH: usize = 1000;
+
+/// Parses one range: `a.b.c.d/n`, or a bare address meaning its /32. Host bits
+/// are cleared, so `10.1.2.3/16` is `10.1.0.0/16`.
+pub fn parse_range(s: &str) -> Result<Ipv4Net, Status> {
+    let s = s.trim();
+    let invalid = || Status::invalid_argument(format!("Invalid range: {s}"));
+    let net = if s.contains('/') {
+        s.parse::<Ipv4Net>().map_err(|_| invalid())?
+    } else {
+        Ipv4Net::from(s.parse::<Ipv4Addr>().map_err(|_| invalid())?)
+    };
+    if net.prefix_len() < MIN_PREFIX {
+        return Err(Status::invalid_argument(format!(
+            "Range {s} is wider than /{MIN_PREFIX}"
+        )));
+    }
+    Ok(net.trunc())
+}
+
+/// Parses the request's ranges plus the lines of a ranges file (blank lines and
+/// `#` comments ignored) into a merged, non-overlapping list.
+pub fn parse_ranges(ranges: &[String], file: &str) -> Result<Vec<Ipv4Net>, Status> {
+    let lines = file
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty());
+    let nets = ranges
+        .iter()
+        .map(String::as_str)
+        .chain(lines)
+        .map(parse_range)
+        .collect::<Result<Vec<_>, _>>()?;
+    if nets.is_empty() {
+        return Err(Status::invalid_argument("No ranges given"));
+    }
+
+    let merged = Ipv4Net::aggregate(&nets);
+    let total: u64 = merged.iter().map(addresses).sum();
+    if total > MAX_JOB_ADDRESSES {
+        return Err(Status::invalid_argument(format!(
+            "Ranges cover {total} addresses, more than the {MAX_JOB_ADDRESSES} a job may scan"
+        )));
+    }
+    Ok(merged)
+}
+
+/// Cuts merged ranges into shards no wider than [`SHARD_PREFIX`].
+pub fn split_shards(nets: &[Ipv4Net]) -> Vec<Ipv4Net> {
+    nets.iter()
+        .flat_map(|net| {
+            net.subnets(SHARD_PREFIX.max(net.prefix_len()))
+                .expect("prefix is at most 32")
+        })
+        .collect()
+}
+
+fn addresses(net: &Ipv4Net) -> u64 {
+    1u64 << (32 - net.prefix_len())
+}
+
+/// Stores a job and its shards, all pending. Returns the job id.
+pub async fn create_job(
+    db: &DatabaseWrapper,
+    name: &str,
+    port: i32,
+    edition: Edition,
+    shards: &[Ipv4Net],
+) -> DbResult<i32> {
+    let mut conn = db.conn().await?;
+    let conn: &mut AsyncPgConnection = &mut conn;
+    let id = conn
+        .transaction::<i32, diesel::result::Error, _>(async |conn| {
+            let id: i32 = insert_into(scan_jobs::table)
+                .values(&ScanJobInsert {
+                    name,
+                    port,
+                    edition,
+                })
+                .returning(scan_jobs::id)
+                .get_result(conn)
+                .await?;
+            for chunk in shards.chunks(SHARD_INSERT_BATCH) {
+                let rows: Vec<ScanShardInsert> = chunk
+                    .iter()
+                    .map(|net| ScanShardInsert {
+                        job_id: id,
+                        cidr: net.to_string(),
+                        size: addresses(net) as i32,
+                    })
+                    .collect();
+                insert_into(scan_shards::table)
+                    .values(&rows)
+                    .execute(conn)
+                    .await?;
+            }
+            Ok(id)
+        })
+        .await?;
+    Ok(id)
+}
+
+/// Every job with its shard and address totals, newest first. A done shard
+/// counts all its addresses as scanned.
+pub async fn list_jobs(db: &DatabaseWrapper) -> DbResult<Vec<ScanJobSummary>> {
+    let mut conn = db.conn().await?;
+    let jobs = diesel::sql_query(
+        "SELECT j.id, j.name, j.port, j.edition, j.created_at, j.finished_at, \
+                count(s.id) AS shards_total, \
+                count(s.id) FILTER (WHERE s.status = 'done') AS shards_done, \
+                COALESCE(SUM(s.size), 0)::int8 AS addresses_total, \
+                COALESCE(SUM(s.next_offset), 0)::int8 AS addresses_scanned \
+         FROM scan_jobs j \
+         LEFT JOIN scan_shards s ON s.job_id = j.id \
+         GROUP BY j.id \
+         ORDER BY j.id DESC",
+    )
+    .load(&mut conn)
+    .await?;
+    Ok(jobs)
+}
+
+/// Deletes a job and its shards. Workers still scanning one of its shards
+/// finish the shard; their progress reports then match nothing.
+pub async fn delete_job(db: &DatabaseWrapper, id: i32) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let n = diesel::delete(scan_jobs::table.filter(scan_jobs::id.eq(id)))
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// Returns shards held by `worker_id` (or by anyone, when `None`) to the
+/// pending pool, keeping their offsets.
+pub async fn release_shards(db: &DatabaseWrapper, worker_id: Option<&str>) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let mut query = diesel::update(scan_shards::table)
+        .filter(scan_shards::status.eq(ShardStatus::Assigned))
+        .into_boxed();
+    if let Some(worker_id) = worker_id {
+        query = query.filter(scan_shards::worker_id.eq(worker_id));
+    }
+    let n = query
+        .set((
+            scan_shards::status.eq(ShardStatus::Pending),
+            scan_shards::worker_id.eq(None::<String>),
+            scan_shards::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// Assigns pending shards to `worker_id` until it holds
+/// [`MAX_SHARDS_PER_WORKER`], oldest job first.
+async fn claim_shards(db: &DatabaseWrapper, worker_id: &str) -> DbResult<Vec<ClaimedShard>> {
+    let mut conn = db.conn().await?;
+    let held: i64 = scan_shards::table
+        .filter(scan_shards::status.eq(ShardStatus::Assigned))
+        .filter(scan_shards::worker_id.eq(worker_id))
+        .count()
+        .get_result(&mut conn)
+        .await?;
+    let want = MAX_SHARDS_PER_WORKER - held;
+    if want <= 0 {
+        return Ok(Vec::new());
+    }
+    let shards = diesel::sql_query(
+        "WITH claimed AS ( \
+             UPDATE scan_shards \
+             SET status = 'assigned', worker_id = $1, updated_at = now() \
+             WHERE id IN ( \
+                 SELECT id FROM scan_shards \
+                 WHERE status = 'pending' \
+                 ORDER BY job_id, id \
+                 LIMIT $2 \
+                 FOR UPDATE SKIP LOCKED \
+             ) \
+             RETURNING id, job_id, cidr, next_offset \
+         ) \
+         SELECT claimed.id, claimed.cidr, claimed.next_offset, j.port, j.edition \
+         FROM claimed JOIN scan_jobs j ON j.id = claimed.job_id \
+         ORDER BY claimed.id",
+    )
+    .bind::<Text, _>(worker_id)
+    .bind::<BigInt, _>(want)
+    .load(&mut conn)
+    .await?;
+    Ok(shards)
+}
+
+/// Records a worker's progress on a shard it holds; reports for shards it no
+/// longer holds (released, or deleted with their job) are ignored. Returns
+/// whether the shard finished, which frees a slot for the next one, and marks
+/// the job finished when it was the last.
+pub async fn record_progress(
+    db: &DatabaseWrapper,
+    worker_id: &str,
+    progress: &ShardProgress,
+) -> DbResult<bool> {
+    #[derive(QueryableByName)]
+    struct Updated {
+        #[diesel(sql_type = Integer)]
+        job_id: i32,
+    }
+
+    let mut conn = db.conn().await?;
+    let updated: Option<Updated> = diesel::sql_query(
+        "UPDATE scan_shards SET \
+             next_offset = CASE WHEN $4 THEN size \
+                 ELSE LEAST(GREATEST(next_offset, $3), size) END, \
+             status = CASE WHEN $4 THEN 'done'::shard_status ELSE status END, \
+             updated_at = now() \
+         WHERE id = $1 AND worker_id = $2 AND status = 'assigned' \
+         RETURNING job_id",
+    )
+    .bind::<Integer, _>(progress.shard_id)
+    .bind::<Text, _>(worker_id)
+    .bind::<Integer, _>(progress.next.min(i32::MAX as u32) as i32)
+    .bind::<Bool, _>(progress.done)
+    .get_result(&mut conn)
+    .await
+    .optional()?;
+
+    let Some(Updated { job_id }) = updated else {
+        return Ok(false);
+    };
+    if progress.done {
+        diesel::sql_query(
+            "UPDATE scan_jobs SET finished_at = now() \
+             WHERE id = $1 AND finished_at IS NULL \
+             AND NOT EXISTS ( \
+                 SELECT 1 FROM scan_shards WHERE job_id = $1 AND status <> 'done' \
+             )",
+        )
+        .bind::<Integer, _>(job_id)
+        .execute(&mut conn)
+        .await?;
+    }
+    Ok(progress.done)
+}
+
+/// Starts the task that keeps online workers supplied with shards. Shards left
+/// `assigned` by a previous backend run are released first: their sessions are
+/// gone, and the workers abandoned them with it. Runs every
+/// [`SCHEDULE_INTERVAL`], and early whenever `AppState::shard_wakeup` fires (a
+/// worker registered or finished a shard, or a job was created).
+pub fn spawn_scheduler(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        match release_shards(&state.db, None).await {
+            Ok(n) if n > 0 => tracing::info!("released {n} scan shards from the previous run"),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("failed to release stale scan shards: {e}"),
+        }
+
+        let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = state.shard_wakeup.notified() => {}
+            }
+            for worker_id in state.registry.online_ids().await {
+                if let Err(e) = assign(&state, &worker_id).await {
+                    tracing::warn!(worker = %worker_id, "failed to assign scan shards: {e}");
+                }
+            }
+        }
+    });
+}
+
+/// Claims shards for one worker and sends them. A shard that cannot be sent
+/// (the worker dropped meanwhile) is released again.
+async fn assign(state: &AppState, worker_id: &str) -> DbResult<()> {
+    for shard in claim_shards(&state.db, worker_id).await? {
+        let task = ScanShard {
+            shard_id: shard.id,
+            cidr: shard.cidr,
+            start: shard.next_offset.max(0) as u32,
+            port: shard.port,
+            edition: shard.edition.to_proto(),
+        };
+        if let Err(e) = state.registry.dispatch_shard(worker_id, task).await {
+            tracing::debug!(worker = %worker_id, "shard {} not sent: {e}", shard.id);
+            release_shard(&state.db, shard.id, worker_id).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn release_shard(db: &DatabaseWrapper, id: i32, worker_id: &str) -> DbResult<()> {
+    let mut conn = db.conn().await?;
+    diesel::update(scan_shards::table)
+        .filter(scan_shards::id.eq(id))
+        .filter(scan_shards::worker_id.eq(worker_id))
+        .set((
+            scan_shards::status.eq(ShardStatus::Pending),
+            scan_shards::worker_id.eq(None::<String>),
+            scan_shards::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut conn)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nets(v: &[&str]) -> Vec<Ipv4Net> {
+        v.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn ranges_are_normalised_and_merged() {
+        let file = "# office\n10.0.0.0/24\n\n 10.0.1.0/24 # lab\n";
+        let got = parse_ranges(
+            &[
+                "192.0.2.7".into(),
+                "10.0.0.128/25".into(),
+                "198.51.100.9/23".into(),
+            ],
+            file,
+        )
+        .unwrap();
+        assert_eq!(
+            got,
+            nets(&["10.0.0.0/23", "192.0.2.7/32", "198.51.100.0/23"])
+        );
+    }
+
+    #[test]
+    fn rejects_bad_wide_and_oversized_ranges() {
+        for bad in ["", "10.0.0.0/33", "::1/128", "example.com", "10.0.0.0/7"] {
+            assert!(parse_ranges(&[bad.into()], "").is_err(), "{bad}");
+        }
+        assert!(parse_ranges(&[], "# nothing here\n").is_err());
+        let two_eights = ["10.0.0.0/8".to_string(), "11.0.0.0/8".to_string()];
+        assert!(parse_ranges(&two_eights[..1], "").is_ok());
+        assert!(parse_ranges(&two_eights, "").is_err());
+    }
+
+    #[test]
+    fn shards_are_capped_at_the_shard_prefix() {
+        let shards = split_shards(&nets(&["10.0.0.0/20", "192.0.2.0/24"]));
+        assert_eq!(shards.len(), 5);
+        assert!(shards.iter().all(|s| s.prefix_len() >= SHARD_PREFIX));
+        assert_eq!(shards[1], "10.0.4.0/22".parse().unwrap());
+        assert_eq!(shards[4], "192.0.2.0/24".parse().unwrap());
+        let total: u64 = shards.iter().map(addresses).sum();
+        assert_eq!(total, 4096 + 256);
+    }
+}
@@ -0,0 +1,168 @@
+//! Adaptive update scheduling. After every probe result the backend decides
+//! when the server is next worth re-probing and stores it as
+//! `servers.next_probe_at`; `FetchUpdateTargets` serves only the servers that
+//! are due. Servers whose player count moves between probes come up soon again,
+//! empty or dead ones ever later, so the same worker capacity covers far more
+//! servers. Operators weight single servers with `probe_priority`.
+//!
+//! Workers fetch targets once per update cycle (`update_interval_secs`), so the
+//! cycle interval is the resolution: an interval shorter than it means "every
+//! cycle". A target a worker skips (see [`SKIPPED_INTERVAL`]) is not probed at
+//! all and comes up again after a fixed wait.
+
+use chrono::TimeDelta;
+
+use crate::models::servers::ProbePriority;
+
+/// Snapshots, newest first, the player-count variance is taken over.
+pub const ACTIVITY_WINDOW: i64 = 12;
+/// Snapshots needed before the variance is trusted; servers with less history
+/// are probed at [`MIN_INTERVAL_SECS`].
+const MIN_HISTORY: usize = 3;
+
+/// Interval for the busiest servers.
+const MIN_INTERVAL_SECS: i64 = 5 * 60;
+/// Interval for servers with players but a flat count, and the longest one for
+/// a count that moves at all.
+const STEADY_INTERVAL_SECS: i64 = 30 * 60;
+/// Longest interval for an online server, reached by ones that stay empty.
+const IDLE_MAX_SECS: i64 = 12 * 3600;
+
+/// Consecutive failed probes after which a server is backed off.
+const BACKOFF_AFTER: i32 = 3;
+/// Wait before the first backed-off re-probe, doubled for every further failure.
+const BACKOFF_BASE_SECS: i64 = 3600;
+const BACKOFF_MAX_SECS: i64 = 7 * 86400;
+
+/// Wait before serving again a server a worker skipped because its address is
+/// excluded or not probeable there. Exclusion lists change rarely; a day is
+/// soon enough to notice one was lifted.
+pub const SKIPPED_INTERVAL: TimeDelta = TimeDelta::days(1);
+
+/// `high` priority divides the interval by this, `low` multiplies it.
+const PRIORITY_FACTOR: i64 = 4;
+
+/// What a server's next probe is decided from.
+pub struct Activity<'a> {
+    pub is_online: bool,
+    pub online_streak: i32,
+    pub offline_streak: i32,
+    pub priority: ProbePriority,
+    /// Recent `players_online` values, newest first.
+    pub player_counts: &'a [i16],
+}
+
+/// How long after this probe the server is due again.
+pub fn next_interval(a: &Activity) -> TimeDelta {
+    let secs = if a.is_online {
+        online_secs(a.online_streak, a.player_counts)
+    } else {
+        offline_secs(a.offline_streak)
+    };
+    let secs = match a.priority {
+        ProbePriority::Normal => secs,
+        ProbePriority::High => secs / PRIORITY_FACTOR,
+        ProbePriority::Low => (secs * PRIORITY_FACTOR).min(BACKOFF_MAX_SECS),
+    };
+    TimeDelta::seconds(secs)
+}
+
+/// A count that moves is re-probed sooner the wider it swings: the steady
+/// interval divided by the count's standard deviation, down to
+/// [`MIN_INTERVAL_SECS`]. A server that stays empty waits twice as long for
+/// every window's worth of probes it has been online, up to [`IDLE_MAX_SECS`].
+fn online_secs(online_streak: i32, counts: &[i16]) -> i64 {
+    if counts.len() < MIN_HISTORY {
+        return MIN_INTERVAL_SECS;
+    }
+    let n = counts.len() as f64;
+    let mean = counts.iter().map(|&c| c as f64).sum::<f64>() / n;
+    let variance = counts
+        .iter()
+        .map(|&c| (c as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    if variance > 0.0 {
+        let secs = STEADY_INTERVAL_SECS as f64 / variance.sqrt();
+        return (secs as i64).clamp(MIN_INTERVAL_SECS, STEADY_INTERVAL_SECS);
+    }
+    if mean > 0.0 {
+        return STEADY_INTERVAL_SECS;
+    }
+    let doublings = (online_streak.max(0) as i64 / ACTIVITY_WINDOW).min(8) as u32;
+    (STEADY_INTERVAL_SECS << doublings).min(IDLE_MAX_SECS)
+}
+
+/// A server that just went offline may be restarting and is re-probed at
+/// [`MIN_INTERVAL_SECS`]; after [`BACKOFF_AFTER`] failures in a row it backs
+/// off exponentially.
+fn offline_secs(offline_streak: i32) -> i64 {
+    if offline_streak < BACKOFF_AFTER {
+        return MIN_INTERVAL_SECS;
+    }
+    let doublings = (offline_streak - BACKOFF_AFTER).min(20) as u32;
+    (BACKOFF_BASE_SECS << doublings).min(BACKOFF_MAX_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(is_online: bool, streak: i32, priority: ProbePriority, counts: &[i16]) -> i64 {
+        next_interval(&Activity {
+            is_online,
+            online_streak: if is_online { streak } else { 0 },
+            offline_streak: if is_online { 0 } else { streak },
+            priority,
+            player_counts: counts,
+        })
+        .num_seconds()
+    }
+
+    #[test]
+    fn busier_servers_come_up_sooner() {
+        let online = |streak, counts: &[i16]| secs(true, streak, ProbePriority::Normal, counts);
+        assert_eq!(
+            online(0, &[40, 12]),
+            MIN_INTERVAL_SECS,
+            "too little history"
+        );
+        assert_eq!(online(50, &[40, 12, 55, 3, 31]), MIN_INTERVAL_SECS);
+        let small_swing = online(50, &[3, 6, 3, 6]);
+        assert!(MIN_INTERVAL_SECS < small_swing && small_swing < STEADY_INTERVAL_SECS);
+        assert_eq!(online(50, &[7, 7, 7, 7]), STEADY_INTERVAL_SECS);
+        // Empty servers wait longer the longer they have been up.
+        let empty = [0; ACTIVITY_WINDOW as usize];
+        assert_eq!(online(5, &empty), STEADY_INTERVAL_SECS);
+        assert_eq!(online(30, &empty), 4 * STEADY_INTERVAL_SECS);
+        assert_eq!(online(1000, &empty), IDLE_MAX_SECS);
+    }
+
+    #[test]
+    fn offline_servers_back_off() {
+        let offline = |streak| secs(false, streak, ProbePriority::Normal, &[]);
+        assert_eq!(offline(1), MIN_INTERVAL_SECS);
+        assert_eq!(offline(2), MIN_INTERVAL_SECS);
+        assert_eq!(offline(3), BACKOFF_BASE_SECS);
+        assert_eq!(offline(5), 4 * BACKOFF_BASE_SECS);
+        assert_eq!(offline(100), BACKOFF_MAX_SECS);
+    }
+
+    #[test]
+    fn priority_scales_the_interval() {
+        let flat = [7; 4];
+        assert_eq!(
+            secs(true, 10, ProbePriority::High, &flat),
+            STEADY_INTERVAL_SECS / PRIORITY_FACTOR
+        );
+        assert_eq!(
+            secs(true, 10, ProbePriority::Low, &flat),
+            STEADY_INTERVAL_SECS * PRIORITY_FACTOR
+        );
+        assert_eq!(
+            secs(true, 10, ProbePriority::High, &[0, 90, 3]),
+            MIN_INTERVAL_SECS / PRIORITY_FACTOR
+        );
+        assert_eq!(secs(false, 100, ProbePriority::Low, &[]), BACKOFF_MAX_SECS);
+    }
+}
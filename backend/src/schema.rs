@@ -1,6 +1,10 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "edition"))]
+    pub struct Edition;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "join_status"))]
     pub struct JoinStatus;
@@ -8,6 +12,63 @@ pub mod sql_types {
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "player_status"))]
     pub struct PlayerStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "probe_priority"))]
+    pub struct ProbePriority;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "server_field"))]
+    pub struct ServerField;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "shard_status"))]
+    pub struct ShardStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "task_kind"))]
+    pub struct TaskKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "task_state"))]
+    pub struct TaskState;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "worker_pick"))]
+    pub struct WorkerPick;
+}
+
+diesel::table! {
+    exclusions (id) {
+        id -> Int4,
+        cidr -> Text,
+        reason -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    player_count_daily (server_id, bucket) {
+        server_id -> Int4,
+        bucket -> Timestamptz,
+        online_min -> Int2,
+        online_max -> Int2,
+        online_avg -> Float4,
+        players_max -> Int2,
+        samples -> Int4,
+    }
+}
+
+diesel::table! {
+    player_count_hourly (server_id, bucket) {
+        server_id -> Int4,
+        bucket -> Timestamptz,
+        online_min -> Int2,
+        online_max -> Int2,
+        online_avg -> Float4,
+        players_max -> Int2,
+        samples -> Int4,
+    }
 }
 
 diesel::table! {
@@ -39,9 +100,66 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::Edition;
+
+    scan_jobs (id) {
+        id -> Int4,
+        name -> Text,
+        port -> Int4,
+        edition -> Edition,
+        created_at -> Timestamptz,
+        finished_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ShardStatus;
+
+    scan_shards (id) {
+        id -> Int4,
+        job_id -> Int4,
+        cidr -> Text,
+        size -> Int4,
+        next_offset -> Int4,
+        status -> ShardStatus,
+        worker_id -> Nullable<Text>,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    search_cursors (worker_id) {
+        worker_id -> Text,
+        seed -> Int8,
+        partition -> Int4,
+        partitions -> Int4,
+        position -> Int8,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ServerField;
+
+    server_events (id) {
+        id -> Int8,
+        server_id -> Int4,
+        field -> ServerField,
+        old_value -> Nullable<Text>,
+        new_value -> Nullable<Text>,
+        changed_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::JoinStatus;
+    use super::sql_types::Edition;
+    use super::sql_types::ProbePriority;
 
     servers (id) {
         id -> Int4,
@@ -62,15 +180,123 @@ diesel::table! {
         ping -> Nullable<Int8>,
         motd -> Text,
         join_status -> JoinStatus,
+        edition -> Edition,
+        game_mode -> Nullable<Text>,
+        software -> Nullable<Text>,
+        plugins -> Nullable<Array<Text>>,
+        map_name -> Nullable<Text>,
+        min_client_protocol -> Nullable<Int4>,
+        max_client_protocol -> Nullable<Int4>,
+        reached_play -> Nullable<Bool>,
+        auth_prompt -> Nullable<Bool>,
+        whitelist_kick -> Nullable<Bool>,
+        mod_handshake -> Nullable<Bool>,
+        suggested_join_status -> Nullable<JoinStatus>,
+        suggestion_confidence -> Nullable<Float4>,
+        hostname -> Nullable<Varchar>,
+        offline_streak -> Int4,
+        last_probed_at -> Nullable<Timestamptz>,
+        probe_priority -> ProbePriority,
+        online_streak -> Int4,
+        next_probe_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    stats_samples (sampled_at) {
+        sampled_at -> Timestamptz,
+        total_servers -> Int8,
+        online_servers -> Int8,
+        cracked_servers -> Int8,
+        total_players -> Int8,
+        players_online -> Int8,
+        new_servers -> Int8,
+        versions -> Jsonb,
+    }
+}
+
+diesel::table! {
+    target_job_servers (job_id, server_id) {
+        job_id -> Int8,
+        server_id -> Int4,
+        is_new -> Bool,
+    }
+}
+
+diesel::table! {
+    target_jobs (id) {
+        id -> Int8,
+        name -> Text,
+        created_at -> Timestamptz,
+        new_servers -> Int8,
+        updated_servers -> Int8,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::TaskKind;
+    use super::sql_types::Edition;
+    use super::sql_types::WorkerPick;
+    use super::sql_types::TaskState;
+
+    tasks (id) {
+        id -> Int8,
+        kind -> TaskKind,
+        ip -> Text,
+        port -> Int4,
+        edition -> Edition,
+        host -> Nullable<Text>,
+        with_connection -> Bool,
+        worker_id -> Nullable<Text>,
+        region -> Nullable<Text>,
+        pick -> WorkerPick,
+        state -> TaskState,
+        leased_to -> Nullable<Text>,
+        lease_expires_at -> Nullable<Timestamptz>,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        job_id -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    worker_stats_samples (sampled_at, worker_id) {
+        sampled_at -> Timestamptz,
+        worker_id -> Text,
+        scan_rate -> Float8,
+        update_rate -> Float8,
     }
 }
 
+diesel::joinable!(player_count_daily -> servers (server_id));
+diesel::joinable!(player_count_hourly -> servers (server_id));
 diesel::joinable!(player_count_snapshots -> servers (server_id));
 diesel::joinable!(players -> servers (server_id));
+diesel::joinable!(scan_shards -> scan_jobs (job_id));
+diesel::joinable!(server_events -> servers (server_id));
+diesel::joinable!(target_job_servers -> servers (server_id));
+diesel::joinable!(target_job_servers -> target_jobs (job_id));
+diesel::joinable!(tasks -> target_jobs (job_id));
+diesel::joinable!(worker_stats_samples -> stats_samples (sampled_at));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    exclusions,
+    player_count_daily,
+    player_count_hourly,
     player_count_snapshots,
     players,
     processed_results,
+    scan_jobs,
+    scan_shards,
+    search_cursors,
+    server_events,
     servers,
+    stats_samples,
+    target_job_servers,
+    target_jobs,
+    tasks,
+    worker_stats_samples,
 );
@@ -1,14 +1,15 @@
 //! Optional server-property filters shared by the dashboard server list query
 //! (`ApiService::list_servers`) and the worker update-target query
-//! (`persistence::fetch_update_targets_batch`). Keeping the predicate
+//! (`persistence::fetch_update_targets_slice`). Keeping the predicate
 //! construction in one place means the two paths can never drift on filter
 //! semantics. Mirrors the `worker.ServerFilter` proto message and the dashboard
 //! `ServerListRequest` filter fields.
 
-use crate::models::servers::JoinStatus;
+use crate::models::servers::{Edition, JoinStatus};
 
 /// Optional filters over the `servers` table. `None` means "no constraint".
-#[derive(Debug, Default, Clone)]
+/// Hashable so update rounds can be kept per filter (see `crate::update_leases`).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct ServerFilters {
     pub online: Option<bool>,
     pub licensed: Option<bool>, // is_online_mode
@@ -19,6 +20,9 @@ pub struct ServerFilters {
     pub has_none_players: Option<bool>,
     pub join_status: Option<JoinStatus>,
     pub query: Option<String>,
+    pub edition: Option<Edition>,
+    /// Matches the classifier's suggestion rather than the manual status.
+    pub suggested_join_status: Option<JoinStatus>,
 }
 
 impl From<&proto::worker::ServerFilter> for ServerFilters {
@@ -33,6 +37,11 @@ impl From<&proto::worker::ServerFilter> for ServerFilters {
             has_none_players: f.has_none_players,
             join_status: f.join_status.as_deref().and_then(parse_join_status),
             query: f.query.clone(),
+            edition: f.edition.as_deref().and_then(parse_edition),
+            suggested_join_status: f
+                .suggested_join_status
+                .as_deref()
+                .and_then(parse_join_status),
         }
     }
 }
@@ -61,6 +70,15 @@ pub fn parse_join_status(s: &str) -> Option<JoinStatus> {
     }
 }
 
+/// Parses the DB enum text of an `edition` like [`parse_join_status`].
+pub fn parse_edition(s: &str) -> Option<Edition> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "java" => Some(Edition::Java),
+        "bedrock" => Some(Edition::Bedrock),
+        _ => None,
+    }
+}
+
 /// Applies every set filter in `$filters` (a `&ServerFilters`) to a Diesel query
 /// whose FROM clause includes `servers`. Works for both a bare `servers::table`
 /// and a join because each predicate is boxed and its query source is inferred
@@ -128,6 +146,15 @@ macro_rules! apply_server_filters {
                 Some(v) => Box::new(servers::join_status.eq(v)),
                 None => Box::new(sql::<Bool>("TRUE")),
             };
+        let edition: Box<dyn ::diesel::BoxableExpression<_, Pg, SqlType = Bool>> = match f.edition {
+            Some(v) => Box::new(servers::edition.eq(v)),
+            None => Box::new(sql::<Bool>("TRUE")),
+        };
+        let suggested_join_status: Box<dyn ::diesel::BoxableExpression<_, Pg, SqlType = Bool>> =
+            match f.suggested_join_status {
+                Some(v) => Box::new(servers::suggested_join_status.assume_not_null().eq(v)),
+                None => Box::new(sql::<Bool>("TRUE")),
+            };
         let search: Box<dyn ::diesel::BoxableExpression<_, Pg, SqlType = Bool>> = match f
             .query
             .as_deref()
@@ -155,6 +182,8 @@ macro_rules! apply_server_filters {
             .filter(has_players)
             .filter(has_none_players)
             .filter(join_status)
+            .filter(edition)
+            .filter(suggested_join_status)
             .filter(search)
     }};
 }
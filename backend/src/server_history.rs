@@ -0,0 +1,219 @@
+//! Server change history. A re-probe overwrites a server's version, MOTD,
+//! favicon and login details in place (see `persistence::persist_updated`);
+//! before it does, the fields it is about to change are compared with the
+//! stored row and every one that differs is recorded in `server_events` with
+//! its old and new value, in the same transaction. `GetServerHistory` reads the
+//! timeline back, and `ListServers` can keep to servers changed since a time.
+
+use chrono::{DateTime, Utc};
+use diesel::{dsl::insert_into, prelude::*};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+use crate::{
+    database::DatabaseWrapper,
+    models::server_events::{ServerEventInsert, ServerEventModel, ServerField, TrackedFields},
+    persistence::DbResult,
+    schema::server_events,
+};
+
+/// Most changes returned by one `GetServerHistory` call.
+pub const MAX_LIST: i64 = 500;
+
+/// The fields that differ between `old` and `new`, as rows for `server_id`.
+/// JSON fields are stored as JSON text, flags as `true`/`false`.
+pub fn changes(server_id: i32, old: &TrackedFields, new: &TrackedFields) -> Vec<ServerEventInsert> {
+    let json = |v: &serde_json::Value| v.to_string();
+    let fields = [
+        (
+            ServerField::VersionName,
+            Some(old.version_name.clone()),
+            Some(new.version_name.clone()),
+        ),
+        (
+            ServerField::Description,
+            Some(json(&old.description)),
+            Some(json(&new.description)),
+        ),
+        (
+            ServerField::Motd,
+            Some(old.motd.clone()),
+            Some(new.motd.clone()),
+        ),
+        (
+            ServerField::Favicon,
+            old.favicon.clone(),
+            new.favicon.clone(),
+        ),
+        (
+            ServerField::IsOnlineMode,
+            Some(old.is_online_mode.to_string()),
+            Some(new.is_online_mode.to_string()),
+        ),
+        (
+            ServerField::DisconnectReason,
+            old.disconnect_reason.as_ref().map(json),
+            new.disconnect_reason.as_ref().map(json),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(field, old_value, new_value)| ServerEventInsert {
+            server_id,
+            field,
+            old_value,
+            new_value,
+        })
+        .collect()
+}
+
+/// The tracked fields as a re-probe that found `probed` leaves the row: the
+/// update keeps the stored favicon and disconnect reason when the probe has
+/// none (`ServerUpdate` and `ServerExtraUpdate` skip `None` fields).
+pub fn stored_after(before: &TrackedFields, probed: TrackedFields) -> TrackedFields {
+    TrackedFields {
+        favicon: probed.favicon.or_else(|| before.favicon.clone()),
+        disconnect_reason: probed
+            .disconnect_reason
+            .or_else(|| before.disconnect_reason.clone()),
+        ..probed
+    }
+}
+
+/// Records what changed between `old` and `new`. Returns how many fields did.
+pub async fn record(
+    conn: &mut AsyncPgConnection,
+    server_id: i32,
+    old: &TrackedFields,
+    new: &TrackedFields,
+) -> QueryResult<usize> {
+    let rows = changes(server_id, old, new);
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    insert_into(server_events::table)
+        .values(&rows)
+        .execute(conn)
+        .await
+}
+
+/// Changes to `server_id`, newest first, optionally only to `field` and older
+/// than change `before_id`.
+pub async fn list(
+    db: &DatabaseWrapper,
+    server_id: i32,
+    field: Option<ServerField>,
+    before_id: Option<i64>,
+    limit: i64,
+) -> DbResult<Vec<ServerEventModel>> {
+    let mut conn = db.conn().await?;
+    let mut query = server_events::table
+        .filter(server_events::server_id.eq(server_id))
+        .select(ServerEventModel::as_select())
+        .into_boxed();
+    if let Some(field) = field {
+        query = query.filter(server_events::field.eq(field));
+    }
+    if let Some(before_id) = before_id {
+        query = query.filter(server_events::id.lt(before_id));
+    }
+    let rows = query
+        .order(server_events::id.desc())
+        .limit(limit.clamp(1, MAX_LIST))
+        .load(&mut conn)
+        .await?;
+    Ok(rows)
+}
+
+/// Ids of the servers with a change recorded at or after `since`, as a
+/// subselect for server listings.
+pub fn changed_since(
+    since: DateTime<Utc>,
+) -> server_events::BoxedQuery<'static, diesel::pg::Pg, diesel::sql_types::Integer> {
+    server_events::table
+        .filter(server_events::changed_at.ge(since))
+        .select(server_events::server_id)
+        .into_boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn server() -> TrackedFields {
+        TrackedFields {
+            version_name: "Paper 1.20.4".into(),
+            description: json!({"text": "A server"}),
+            motd: "A server".into(),
+            favicon: None,
+            is_online_mode: false,
+            disconnect_reason: None,
+        }
+    }
+
+    #[test]
+    fn only_changed_fields_are_recorded() {
+        let old = server();
+        assert!(changes(1, &old, &old.clone()).is_empty());
+
+        let new = TrackedFields {
+            version_name: "Paper 1.21.1".into(),
+            is_online_mode: true,
+            disconnect_reason: Some(json!({"translate": "multiplayer.disconnect.not_whitelisted"})),
+            ..old.clone()
+        };
+        let rows = changes(7, &old, &new);
+        assert_eq!(
+            rows,
+            vec![
+                ServerEventInsert {
+                    server_id: 7,
+                    field: ServerField::VersionName,
+                    old_value: Some("Paper 1.20.4".into()),
+                    new_value: Some("Paper 1.21.1".into()),
+                },
+                ServerEventInsert {
+                    server_id: 7,
+                    field: ServerField::IsOnlineMode,
+                    old_value: Some("false".into()),
+                    new_value: Some("true".into()),
+                },
+                ServerEventInsert {
+                    server_id: 7,
+                    field: ServerField::DisconnectReason,
+                    old_value: None,
+                    new_value: Some(
+                        r#"{"translate":"multiplayer.disconnect.not_whitelisted"}"#.into()
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn probe_without_favicon_or_reason_records_nothing() {
+        let old = TrackedFields {
+            favicon: Some("data:image/png;base64,iVBORw0KGgo=".into()),
+            disconnect_reason: Some(json!({"text": "You are not whitelisted"})),
+            ..server()
+        };
+        let probed = TrackedFields {
+            favicon: None,
+            disconnect_reason: None,
+            ..old.clone()
+        };
+        let after = stored_after(&old, probed);
+        assert_eq!(after, old);
+        assert!(changes(1, &old, &after).is_empty());
+
+        let probed = TrackedFields {
+            favicon: Some("data:image/png;base64,R0lGODlh".into()),
+            ..old.clone()
+        };
+        let rows = changes(1, &old, &stored_after(&old, probed));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].field, ServerField::Favicon);
+    }
+}
@@ -2,18 +2,27 @@
 //! REST handler, plus the new worker-management RPCs. Auth is enforced
 //! per-method via `auth::require_session` (everything except `login`).
 
-use std::{pin::Pin, sync::Arc, time::Duration};
+use std::{net::IpAddr, pin::Pin, sync::Arc, time::Duration};
 
 use crate::{
+    exclusions,
     models::{
+        exclusions::ExclusionModel,
         player_count_snapshots::SnapshotModel,
         players::{PlayerModel, PlayerStatus as DbStatus, PlayerUpdate},
-        servers::{JoinStatus, ServerModel, ServerModelMini},
+        scan_jobs::ScanJobSummary,
+        server_events::ServerField,
+        servers::{Edition, JoinStatus, ProbePriority, ServerModel, ServerModelMini},
+        target_jobs::TargetJobSummary,
+        tasks::{TaskKind, TaskModel, TaskState},
     },
+    scan_jobs,
     schema::{self, players, servers},
     server_filters::ServerFilters,
+    server_history, snapshots, stats_history, target_jobs,
+    tasks::{self, Assignee, NewTask, TaskFilter},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::{
     PgTextExpressionMethods,
     dsl::sql,
@@ -24,13 +33,18 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use futures::Stream;
 use proto::api::{
-    AddAddrRequest, AddTargetListRequest, ControlWorkerRequest, DeletePlayerRequest, Empty,
-    GetWorkerRequest, LoginRequest, LoginResponse, OverwriteServerRequest, PingServerRequest,
+    AddAddrRequest, AddExclusionRequest, AddTargetListRequest, ControlWorkerRequest,
+    CreateScanJobRequest, DeleteExclusionRequest, DeletePlayerRequest, DeleteScanJobRequest,
+    DeleteTargetJobRequest, Empty, Exclusion, ExclusionList, GetWorkerRequest, JobProgressRequest,
+    ListTasksRequest, LoginRequest, LoginResponse, OverwriteServerRequest, PingServerRequest,
     Player, PlayerListRequest, PlayerListResponse, PlayerSearchRequest, PlayerSearchResponse,
-    PlayerSearchResult, ServerDeleteRequest, ServerInfo, ServerInfoRequest, ServerListRequest,
-    ServerListResponse, ServerSnapshot, ServerSnapshotsRequest, ServerSnapshotsResponse,
-    SetWorkerNameRequest, StatsResponse, UpdatePlayerRequest, UpdateServerRequest,
-    UpdateWorkerConfigRequest, VersionStat, WorkerInfo, WorkerList, api_server::Api,
+    PlayerSearchResult, PurgeExcludedServersResponse, ScanJob, ScanJobList, ServerChange,
+    ServerDeleteRequest, ServerHistory, ServerHistoryRequest, ServerInfo, ServerInfoRequest,
+    ServerListRequest, ServerListResponse, ServerSnapshot, ServerSnapshotsRequest,
+    ServerSnapshotsResponse, SetWorkerNameRequest, SnapshotResolution, StatsHistory,
+    StatsHistoryRequest, StatsResponse, TargetJob, TargetJobList, Task, TaskCounts, TaskIdsRequest,
+    TaskList, TasksAffected, UpdatePlayerRequest, UpdateServerRequest, UpdateWorkerConfigRequest,
+    VersionStat, WorkerInfo, WorkerList, WorkerSelector, api_server::Api,
 };
 use tokio_stream::{
     StreamExt,
@@ -47,6 +61,7 @@ use crate::{
 
 const SESSION_DURATION_HOURS: i64 = 24;
 const DEFAULT_PORT: i32 = 25565;
+const DEFAULT_BEDROCK_PORT: i32 = 19132;
 
 pub struct ApiService {
     pub state: Arc<AppState>,
@@ -106,10 +121,27 @@ fn db_join_status(i: i32) -> JoinStatus {
     }
 }
 
+fn proto_probe_priority(p: ProbePriority) -> i32 {
+    match p {
+        ProbePriority::Normal => 0,
+        ProbePriority::High => 1,
+        ProbePriority::Low => 2,
+    }
+}
+
+fn db_probe_priority(i: i32) -> ProbePriority {
+    match i {
+        1 => ProbePriority::High,
+        2 => ProbePriority::Low,
+        _ => ProbePriority::Normal,
+    }
+}
+
 fn server_info(server: ServerModel, snap: SnapshotModel) -> ServerInfo {
     ServerInfo {
         id: server.id,
         ip: server.ip,
+        port: server.port,
         online: snap.players_online as i32,
         max: snap.players_max as i32,
         version_name: server.version_name,
@@ -125,53 +157,277 @@ fn server_info(server: ServerModel, snap: SnapshotModel) -> ServerInfo {
         requires_mods: server.requires_mods,
         favicon: server.favicon,
         ping: server.ping,
+        edition: server.edition.to_proto(),
+        game_mode: server.game_mode,
+        software: server.software,
+        plugins: server.plugins.unwrap_or_default(),
+        map: server.map_name,
+        min_client_protocol: server.min_client_protocol,
+        max_client_protocol: server.max_client_protocol,
+        // The four columns are written together; `reached_play` stands for all.
+        login_probe: server
+            .reached_play
+            .map(|reached_play| proto::worker::LoginProbe {
+                reached_play,
+                auth_prompt: server.auth_prompt.unwrap_or_default(),
+                whitelist_kick: server.whitelist_kick.unwrap_or_default(),
+                mod_handshake: server.mod_handshake.unwrap_or_default(),
+            }),
+        suggested_join_status: server.suggested_join_status.map(proto_join_status),
+        suggestion_confidence: server.suggestion_confidence,
+        hostname: server.hostname,
+        probe_priority: proto_probe_priority(server.probe_priority),
+        next_probe_at: server.next_probe_at.map(|t| t.to_rfc3339()),
     }
 }
 
-/// Loads a server's current `ServerInfo` by ip (joined with its latest snapshot).
-/// Shared by the unary `GetServerInfo` and the streaming `StreamServerInfo`.
-async fn load_server_info(db: &DatabaseWrapper, ip: &str) -> Result<ServerInfo, Status> {
+/// Loads a server's current `ServerInfo` by address (joined with its latest
+/// snapshot). Shared by the unary `GetServerInfo` and the streaming
+/// `StreamServerInfo`.
+async fn load_server_info(
+    db: &DatabaseWrapper,
+    ip: &str,
+    port: i32,
+    edition: Edition,
+) -> Result<ServerInfo, Status> {
+    let ip = canonical_ip(ip);
     let mut conn = db.conn().await.map_err(|e| db_err("get conn", e))?;
     let (server, snap) = servers::table
         .inner_join(
             schema::player_count_snapshots::table
                 .on(schema::player_count_snapshots::server_id.eq(servers::id)),
         )
-        .filter(servers::ip.eq(ip))
+        .filter(servers::ip.eq(&ip))
+        .filter(servers::port.eq(port))
+        .filter(servers::edition.eq(edition))
         .order_by(schema::player_count_snapshots::recorded_at.desc())
         .select((ServerModel::as_select(), SnapshotModel::as_select()))
         .first::<(ServerModel, SnapshotModel)>(&mut conn)
         .await
-        .map_err(|_| Status::not_found(format!("server '{ip}' not found")))?;
+        .map_err(|_| Status::not_found(format!("server '{ip}:{port}' not found")))?;
     Ok(server_info(server, snap))
 }
 
-/// Splits an address into `(ip, port)`.
-fn parse_addr(addr: &str) -> Result<(String, i32), Status> {
+/// Who runs a ping or scan request: the worker it names, or with none named
+/// one the task scheduler picks.
+fn assignee(worker_id: &str, any_worker: Option<WorkerSelector>) -> Assignee {
+    match worker_id.trim() {
+        "" => Assignee::Any(any_worker.unwrap_or_default()),
+        id => Assignee::Worker(id.to_string()),
+    }
+}
+
+/// Queues pings or scans. A chosen worker must be known, though it may be
+/// offline: its tasks wait for it.
+async fn queue(state: &AppState, to: Assignee, tasks: Vec<NewTask>) -> Result<(), Status> {
+    if let Assignee::Worker(id) = &to {
+        state.registry.get(id).await?;
+    }
+    tasks::enqueue(&state.db, &to, tasks)
+        .await
+        .map_err(|e| db_err("queue tasks", e))?;
+    state.task_wakeup.notify_one();
+    Ok(())
+}
+
+/// Resolves the port and edition of a request's `(ip, port, edition)` server
+/// key. Proto3 can't tell an unset `int32` from 0, so 0 means the edition's
+/// default port.
+fn request_key(port: i32, edition: proto::worker::Edition) -> (i32, Edition) {
+    let port = if port == 0 {
+        default_port(edition)
+    } else {
+        port
+    };
+    (port, Edition::from_proto(edition as i32))
+}
+
+fn scan_job_to_proto(job: ScanJobSummary) -> ScanJob {
+    ScanJob {
+        id: job.id,
+        name: job.name,
+        port: job.port,
+        edition: job.edition.to_proto(),
+        created_at: job.created_at.to_rfc3339(),
+        finished_at: job.finished_at.map(|t| t.to_rfc3339()),
+        shards_total: job.shards_total as i32,
+        shards_done: job.shards_done as i32,
+        addresses_total: job.addresses_total,
+        addresses_scanned: job.addresses_scanned,
+    }
+}
+
+fn target_job_to_proto(job: TargetJobSummary) -> TargetJob {
+    TargetJob {
+        id: job.id,
+        name: job.name,
+        created_at: job.created_at.to_rfc3339(),
+        total: job.total,
+        queued: job.queued,
+        probed: job.probed,
+        failed: job.failed,
+        cancelled: job.cancelled,
+        responded: job.new_servers + job.updated_servers,
+        new_servers: job.new_servers,
+        updated_servers: job.updated_servers,
+    }
+}
+
+async fn load_target_job(db: &DatabaseWrapper, id: i64) -> Result<TargetJob, Status> {
+    target_jobs::list(db, Some(id))
+        .await
+        .map_err(|e| db_err("load target job", e))?
+        .pop()
+        .map(target_job_to_proto)
+        .ok_or_else(|| Status::not_found("target job not found"))
+}
+
+fn task_to_proto(t: TaskModel) -> Task {
+    Task {
+        id: t.id,
+        kind: match t.kind {
+            TaskKind::Ping => proto::api::TaskKind::Ping as i32,
+            TaskKind::Scan => proto::api::TaskKind::Scan as i32,
+        },
+        ip: t.ip,
+        port: t.port,
+        host: t.host,
+        edition: t.edition.to_proto(),
+        with_connection: t.with_connection,
+        worker_id: t.worker_id,
+        any_worker: Some(WorkerSelector {
+            pick: t.pick.to_proto(),
+            region: t.region,
+        }),
+        state: t.state.to_proto(),
+        leased_to: t.leased_to,
+        attempts: t.attempts,
+        last_error: t.last_error,
+        created_at: t.created_at.to_rfc3339(),
+        updated_at: t.updated_at.to_rfc3339(),
+        lease_expires_at: t.lease_expires_at.map(|t| t.to_rfc3339()),
+        job_id: t.job_id,
+    }
+}
+
+/// The tasks a cancel or retry request names: its ids, or with `all` every
+/// task the RPC applies to.
+fn task_ids(body: &TaskIdsRequest) -> Result<Option<&[i64]>, Status> {
+    match (body.all, body.ids.is_empty()) {
+        (true, _) => Ok(None),
+        (false, false) => Ok(Some(&body.ids)),
+        (false, true) => Err(Status::invalid_argument("No tasks given")),
+    }
+}
+
+fn exclusion_to_proto(e: ExclusionModel) -> Exclusion {
+    Exclusion {
+        id: e.id,
+        cidr: e.cidr,
+        reason: e.reason,
+        created_at: e.created_at.to_rfc3339(),
+    }
+}
+
+/// Parses an RFC3339 time from a request.
+fn parse_time(s: &str) -> Result<DateTime<Utc>, Status> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.to_utc())
+        .map_err(|_| Status::invalid_argument(format!("Invalid time: {s}")))
+}
+
+/// Port assumed for an address given without one: the edition's vanilla default.
+fn default_port(edition: proto::worker::Edition) -> i32 {
+    match edition {
+        proto::worker::Edition::Java => DEFAULT_PORT,
+        proto::worker::Edition::Bedrock => DEFAULT_BEDROCK_PORT,
+    }
+}
+
+/// Canonical text of an IP literal (compressed lowercase IPv6, IPv4-mapped
+/// IPv6 as plain IPv4), so one host keeps one `servers` row however it is
+/// spelled. Anything else is returned trimmed, as given.
+fn canonical_ip(ip: &str) -> String {
+    let ip = ip.trim();
+    match ip.parse::<IpAddr>() {
+        Ok(addr) => addr.to_canonical().to_string(),
+        Err(_) => ip.to_string(),
+    }
+}
+
+/// An AddTarget address, as [`parse_addr`] reads it.
+#[derive(Debug, PartialEq)]
+enum Target {
+    /// A canonical IP literal and its port.
+    Ip(String, i32),
+    /// A lowercased hostname, resolved by the worker, and its port: 0 when none
+    /// was given, which leaves the worker to follow the SRV record or the
+    /// edition's default port.
+    Host(String, i32),
+}
+
+impl Target {
+    /// The scan task for this address.
+    fn into_scan(self, edition: Edition) -> NewTask {
+        let (ip, port, host) = match self {
+            Target::Ip(ip, port) => (ip, port, None),
+            Target::Host(host, port) => (String::new(), port, Some(host)),
+        };
+        NewTask {
+            kind: TaskKind::Scan,
+            ip,
+            port,
+            edition,
+            host,
+            with_connection: false,
+        }
+    }
+}
+
+/// Splits an address into a [`Target`]. An IP literal without a port gets
+/// `fallback_port`. IPv6 takes the usual forms: bare (`2001:db8::1`) or
+/// bracketed when a port follows (`[2001:db8::1]:25565`). Anything else must be
+/// a hostname (`play.example.net`, `play.example.net:25570`). A given port must
+/// be in 1..=65535; only a hostname without one gets port 0.
+fn parse_addr(addr: &str, fallback_port: i32) -> Result<Target, Status> {
     let addr = addr.trim();
+    let invalid = || Status::invalid_argument(format!("Invalid address: {addr}"));
     let parse_port = |s: &str| -> Result<i32, Status> {
-        s.parse::<i32>()
-            .map_err(|_| Status::invalid_argument(format!("Invalid port: {s}")))
+        match s.parse::<u16>() {
+            Ok(port) if port != 0 => Ok(port.into()),
+            _ => Err(Status::invalid_argument(format!("Invalid port: {s}"))),
+        }
     };
 
-    let (ip, port) = if let Some(rest) = addr.strip_prefix('[') {
-        let (ip, after) = rest
-            .split_once(']')
-            .ok_or_else(|| Status::invalid_argument(format!("Invalid address: {addr}")))?;
+    let (host, port) = if let Some(rest) = addr.strip_prefix('[') {
+        let (ip, after) = rest.split_once(']').ok_or_else(invalid)?;
+        if ip.parse::<std::net::Ipv6Addr>().is_err() {
+            return Err(invalid());
+        }
         let port = match after.strip_prefix(':') {
-            Some(p) => parse_port(p)?,
-            None if after.is_empty() => DEFAULT_PORT,
-            None => return Err(Status::invalid_argument(format!("Invalid address: {addr}"))),
+            Some(p) => Some(parse_port(p)?),
+            None if after.is_empty() => None,
+            None => return Err(invalid()),
         };
-        (ip.to_string(), port)
+        (ip, port)
     } else if addr.matches(':').count() == 1 {
-        let (ip, port_str) = addr.rsplit_once(':').unwrap();
-        (ip.to_string(), parse_port(port_str)?)
+        let (host, port_str) = addr.rsplit_once(':').unwrap();
+        (host, Some(parse_port(port_str)?))
     } else {
-        (addr.to_string(), DEFAULT_PORT)
+        (addr, None)
     };
 
-    Ok((ip, port))
+    if host.parse::<IpAddr>().is_ok() {
+        Ok(Target::Ip(
+            canonical_ip(host),
+            port.unwrap_or(fallback_port),
+        ))
+    } else if proto::is_hostname(host) {
+        let host = host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase();
+        Ok(Target::Host(host, port.unwrap_or(0)))
+    } else {
+        Err(invalid())
+    }
 }
 
 #[tonic::async_trait]
@@ -340,6 +596,24 @@ impl Api for ApiService {
         }))
     }
 
+    async fn get_stats_history(
+        &self,
+        request: Request<StatsHistoryRequest>,
+    ) -> Result<Response<StatsHistory>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let (since, until, bucket_secs) = stats_history::resolve_range(
+            body.since.as_deref().map(parse_time).transpose()?,
+            body.until.as_deref().map(parse_time).transpose()?,
+            body.bucket_secs,
+            Utc::now(),
+        )?;
+        let points = stats_history::history(&self.state.db, since, until, bucket_secs)
+            .await
+            .map_err(|e| db_err("load stats history", e))?;
+        Ok(Response::new(StatsHistory { points }))
+    }
+
     async fn list_servers(
         &self,
         request: Request<ServerListRequest>,
@@ -370,12 +644,34 @@ impl Api for ApiService {
             has_none_players: body.has_none_players,
             join_status: body.join_status.map(db_join_status),
             query: body.query.clone(),
+            edition: body.edition.map(Edition::from_proto),
+            suggested_join_status: body.suggested_join_status.map(db_join_status),
         };
 
         let pagination: Box<dyn BoxableExpression<_, Pg, SqlType = Bool>> = match body.offset_id {
             Some(id) => Box::new(servers::id.lt(id)),
             None => Box::new(sql::<Bool>("TRUE")),
         };
+        // Servers that answered a target job, optionally only the ones it added.
+        let job: Box<dyn BoxableExpression<_, Pg, SqlType = Bool>> = match body.job_id {
+            Some(job_id) => {
+                let mut answered = schema::target_job_servers::table
+                    .filter(schema::target_job_servers::job_id.eq(job_id))
+                    .select(schema::target_job_servers::server_id)
+                    .into_boxed();
+                if let Some(is_new) = body.job_new {
+                    answered = answered.filter(schema::target_job_servers::is_new.eq(is_new));
+                }
+                Box::new(servers::id.eq_any(answered))
+            }
+            None => Box::new(sql::<Bool>("TRUE")),
+        };
+        let changed: Box<dyn BoxableExpression<_, Pg, SqlType = Bool>> = match &body.changed_since {
+            Some(since) => {
+                Box::new(servers::id.eq_any(server_history::changed_since(parse_time(since)?)))
+            }
+            None => Box::new(sql::<Bool>("TRUE")),
+        };
 
         let base = servers::table.inner_join(
             schema::player_count_snapshots::table
@@ -383,6 +679,8 @@ impl Api for ApiService {
         );
         let rows = crate::apply_server_filters!(base, &filters)
             .filter(pagination)
+            .filter(job)
+            .filter(changed)
             .order((
                 servers::id.desc(),
                 schema::player_count_snapshots::recorded_at.desc(),
@@ -407,8 +705,11 @@ impl Api for ApiService {
         request: Request<ServerInfoRequest>,
     ) -> Result<Response<ServerInfo>, Status> {
         auth::require_session(&request)?;
-        let ip = request.into_inner().ip;
-        Ok(Response::new(load_server_info(&self.state.db, &ip).await?))
+        let body = request.into_inner();
+        let (port, edition) = request_key(body.port, body.edition());
+        Ok(Response::new(
+            load_server_info(&self.state.db, &body.ip, port, edition).await?,
+        ))
     }
 
     type StreamServerInfoStream =
@@ -419,12 +720,14 @@ impl Api for ApiService {
         request: Request<ServerInfoRequest>,
     ) -> Result<Response<Self::StreamServerInfoStream>, Status> {
         auth::require_session(&request)?;
-        let ip = request.into_inner().ip;
+        let body = request.into_inner();
+        let (port, edition) = request_key(body.port, body.edition());
+        let ip = body.ip;
         let db = self.state.db.clone();
 
         // Emit the current state immediately; this also 404s if the server is
         // unknown, before we commit to a long-lived stream.
-        let initial = load_server_info(&db, &ip).await?;
+        let initial = load_server_info(&db, &ip, port, edition).await?;
         let server_id = initial.id;
         let rx = self.state.events.subscribe();
 
@@ -438,7 +741,7 @@ impl Api for ApiService {
             .then(move |_| {
                 let db = db.clone();
                 let ip = ip.clone();
-                async move { load_server_info(&db, &ip).await }
+                async move { load_server_info(&db, &ip, port, edition).await }
             });
 
         let stream = tokio_stream::once(Ok(initial)).chain(updates);
@@ -451,15 +754,58 @@ impl Api for ApiService {
     ) -> Result<Response<ServerSnapshotsResponse>, Status> {
         auth::require_session(&request)?;
         let body = request.into_inner();
+        let since = body.since.as_deref().map(parse_time).transpose()?;
+        let until = body.until.as_deref().map(parse_time).transpose()?;
+        let resolution = match body.resolution() {
+            SnapshotResolution::Raw => None,
+            SnapshotResolution::Hourly => Some(snapshots::Resolution::Hourly),
+            SnapshotResolution::Daily => Some(snapshots::Resolution::Daily),
+        };
+
+        if let Some(resolution) = resolution {
+            let rollups = snapshots::rollups(
+                &self.state.db,
+                body.server_id,
+                resolution,
+                since,
+                until,
+                body.limit,
+            )
+            .await
+            .map_err(|e| db_err("load snapshot rollups", e))?;
+            return Ok(Response::new(ServerSnapshotsResponse {
+                snapshots: rollups
+                    .into_iter()
+                    .map(|r| ServerSnapshot {
+                        server_id: r.server_id,
+                        players_online: r.online_avg.round() as i32,
+                        players_max: r.players_max as i32,
+                        recorded_at: r.bucket.to_rfc3339(),
+                        online_min: Some(r.online_min as i32),
+                        online_max: Some(r.online_max as i32),
+                        online_avg: Some(r.online_avg as f64),
+                        samples: r.samples,
+                    })
+                    .collect(),
+            }));
+        }
+
         let mut conn = self
             .state
             .db
             .conn()
             .await
             .map_err(|e| db_err("get conn", e))?;
-
-        let results: Vec<SnapshotModel> = schema::player_count_snapshots::table
+        let mut query = schema::player_count_snapshots::table
             .filter(schema::player_count_snapshots::server_id.eq(body.server_id))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(schema::player_count_snapshots::recorded_at.ge(since));
+        }
+        if let Some(until) = until {
+            query = query.filter(schema::player_count_snapshots::recorded_at.lt(until));
+        }
+        let results: Vec<SnapshotModel> = query
             .order(schema::player_count_snapshots::recorded_at.desc())
             .limit(body.limit)
             .load(&mut conn)
@@ -474,11 +820,44 @@ impl Api for ApiService {
                     players_online: s.players_online as i32,
                     players_max: s.players_max as i32,
                     recorded_at: s.recorded_at.to_rfc3339(),
+                    online_min: None,
+                    online_max: None,
+                    online_avg: None,
+                    samples: 1,
                 })
                 .collect(),
         }))
     }
 
+    async fn get_server_history(
+        &self,
+        request: Request<ServerHistoryRequest>,
+    ) -> Result<Response<ServerHistory>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let rows = server_history::list(
+            &self.state.db,
+            body.server_id,
+            body.field.map(ServerField::from_proto),
+            body.before_id,
+            body.limit,
+        )
+        .await
+        .map_err(|e| db_err("load server history", e))?;
+        let changes = rows
+            .into_iter()
+            .map(|e| ServerChange {
+                id: e.id,
+                server_id: e.server_id,
+                field: e.field.to_proto(),
+                old_value: e.old_value,
+                new_value: e.new_value,
+                changed_at: e.changed_at.to_rfc3339(),
+            })
+            .collect();
+        Ok(Response::new(ServerHistory { changes }))
+    }
+
     async fn update_server(
         &self,
         request: Request<UpdateServerRequest>,
@@ -498,14 +877,24 @@ impl Api for ApiService {
             is_checked: Option<bool>,
             join_status: Option<JoinStatus>,
             is_crashed: Option<bool>,
+            probe_priority: Option<ProbePriority>,
+            // Outer `Some(None)` clears it: a new priority takes effect at the
+            // next cycle rather than after the interval set under the old one.
+            next_probe_at: Option<Option<chrono::DateTime<Utc>>>,
         }
 
+        let probe_priority = body.probe_priority.map(db_probe_priority);
+        let (port, edition) = request_key(body.server_port, body.server_edition());
         let affected = diesel::update(servers::table)
-            .filter(servers::ip.eq(&body.server_ip))
+            .filter(servers::ip.eq(canonical_ip(&body.server_ip)))
+            .filter(servers::port.eq(port))
+            .filter(servers::edition.eq(edition))
             .set(Options {
                 is_checked: body.is_checked,
                 join_status: body.join_status.map(db_join_status),
                 is_crashed: body.is_crashed,
+                probe_priority,
+                next_probe_at: probe_priority.map(|_| None),
             })
             .execute(&mut conn)
             .await
@@ -593,14 +982,21 @@ impl Api for ApiService {
     ) -> Result<Response<Empty>, Status> {
         auth::require_session(&request)?;
         let body = request.into_inner();
-        let addr = crate::persistence::server_addr_by_id(&self.state.db, body.server_id)
-            .await
-            .map_err(|e| db_err("resolve server", e))?
-            .ok_or_else(|| Status::not_found("server not found"))?;
-        self.state
-            .registry
-            .dispatch_ping(&body.worker_id, addr.0, addr.1, body.with_connection)
-            .await?;
+        let (ip, port, edition, hostname) =
+            crate::persistence::server_addr_by_id(&self.state.db, body.server_id)
+                .await
+                .map_err(|e| db_err("resolve server", e))?
+                .ok_or_else(|| Status::not_found("server not found"))?;
+        let task = NewTask {
+            kind: TaskKind::Ping,
+            ip,
+            port,
+            edition,
+            host: hostname,
+            with_connection: body.with_connection,
+        };
+        let to = assignee(&body.worker_id, body.any_worker);
+        queue(&self.state, to, vec![task]).await?;
         Ok(Response::new(Empty {}))
     }
 
@@ -610,38 +1006,300 @@ impl Api for ApiService {
     ) -> Result<Response<Empty>, Status> {
         auth::require_session(&request)?;
         let body = request.into_inner();
-        let (ip, port) = parse_addr(&body.addr)?;
-        self.state
-            .registry
-            .dispatch_scan_to(&body.worker_id, ip, port)
-            .await?;
+        let edition = body.edition();
+        let task = parse_addr(&body.addr, default_port(edition))?
+            .into_scan(Edition::from_proto(edition as i32));
+        let to = assignee(&body.worker_id, body.any_worker);
+        queue(&self.state, to, vec![task]).await?;
         Ok(Response::new(Empty {}))
     }
 
     async fn add_target_list(
         &self,
         request: Request<AddTargetListRequest>,
-    ) -> Result<Response<Empty>, Status> {
+    ) -> Result<Response<TargetJob>, Status> {
         auth::require_session(&request)?;
         let body = request.into_inner();
         // Validate every address up front so a malformed entry rejects the whole
-        // batch before any target is dispatched.
-        let parsed = body
+        // batch before any target is queued.
+        let tasks = body
             .targets
             .iter()
-            .map(|t| parse_addr(&t.addr))
-            .collect::<Result<Vec<_>, _>>()?;
-        // Fail-fast: the operator picks the worker; if it is unknown or offline the
-        // whole import errors rather than silently dropping work.
-        for (ip, port) in parsed {
-            self.state
-                .registry
-                .dispatch_scan_to(&body.worker_id, ip, port)
-                .await?;
+            .map(|t| {
+                let edition = t.edition();
+                Ok(parse_addr(&t.addr, default_port(edition))?
+                    .into_scan(Edition::from_proto(edition as i32)))
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+        if tasks.is_empty() {
+            return Err(Status::invalid_argument("No targets given"));
+        }
+        let name = match body.name.trim() {
+            "" => match body.targets.len() {
+                1 => body.targets[0].addr.clone(),
+                n => format!("{} and {} more", body.targets[0].addr, n - 1),
+            },
+            name => name.to_string(),
+        };
+        // The batch is queued whole; in "any worker" mode each target is placed
+        // on its own when leased.
+        let to = assignee(&body.worker_id, body.any_worker);
+        if let Assignee::Worker(id) = &to {
+            self.state.registry.get(id).await?;
         }
+        let id = target_jobs::create(&self.state.db, &name, &to, tasks)
+            .await
+            .map_err(|e| db_err("create target job", e))?;
+        self.state.task_wakeup.notify_one();
+        Ok(Response::new(load_target_job(&self.state.db, id).await?))
+    }
+
+    async fn list_target_jobs(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<TargetJobList>, Status> {
+        auth::require_session(&request)?;
+        let jobs = target_jobs::list(&self.state.db, None)
+            .await
+            .map_err(|e| db_err("list target jobs", e))?;
+        Ok(Response::new(TargetJobList {
+            jobs: jobs.into_iter().map(target_job_to_proto).collect(),
+        }))
+    }
+
+    type StreamJobProgressStream =
+        Pin<Box<dyn Stream<Item = Result<TargetJob, Status>> + Send + 'static>>;
+
+    async fn stream_job_progress(
+        &self,
+        request: Request<JobProgressRequest>,
+    ) -> Result<Response<Self::StreamJobProgressStream>, Status> {
+        auth::require_session(&request)?;
+        let id = request.into_inner().job_id;
+        let db = self.state.db.clone();
+
+        // The first load 404s an unknown job before the stream starts. The job
+        // is then reloaded every few seconds, and the stream ends after the
+        // first load with nothing queued (or once the job is deleted).
+        let initial = load_target_job(&db, id).await?;
+        let stream = futures::stream::unfold(Some(Ok(initial)), move |next| {
+            let db = db.clone();
+            async move {
+                let item = next?;
+                let following = match &item {
+                    Ok(job) if job.queued > 0 => {
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        Some(load_target_job(&db, id).await)
+                    }
+                    _ => None,
+                };
+                Some((item, following))
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn delete_target_job(
+        &self,
+        request: Request<DeleteTargetJobRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        auth::require_session(&request)?;
+        let id = request.into_inner().id;
+        let affected = target_jobs::delete(&self.state.db, id)
+            .await
+            .map_err(|e| db_err("delete target job", e))?;
+        require_affected(affected, "target job")?;
         Ok(Response::new(Empty {}))
     }
 
+    async fn list_tasks(
+        &self,
+        request: Request<ListTasksRequest>,
+    ) -> Result<Response<TaskList>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let filter = TaskFilter {
+            state: body.state.map(TaskState::from_proto),
+            worker_id: body.worker_id.as_deref(),
+            before_id: body.before_id,
+            job_id: body.job_id,
+            limit: body.limit,
+        };
+        let rows = tasks::list(&self.state.db, &filter)
+            .await
+            .map_err(|e| db_err("list tasks", e))?;
+        let mut counts = TaskCounts::default();
+        for (state, n) in tasks::counts(&self.state.db)
+            .await
+            .map_err(|e| db_err("count tasks", e))?
+        {
+            match state {
+                TaskState::Queued => counts.queued = n,
+                TaskState::Leased => counts.leased = n,
+                TaskState::Done => counts.done = n,
+                TaskState::Failed => counts.failed = n,
+                TaskState::Cancelled => counts.cancelled = n,
+            }
+        }
+        Ok(Response::new(TaskList {
+            tasks: rows.into_iter().map(task_to_proto).collect(),
+            counts: Some(counts),
+        }))
+    }
+
+    async fn cancel_tasks(
+        &self,
+        request: Request<TaskIdsRequest>,
+    ) -> Result<Response<TasksAffected>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let affected = tasks::cancel(&self.state.db, task_ids(&body)?)
+            .await
+            .map_err(|e| db_err("cancel tasks", e))?;
+        Ok(Response::new(TasksAffected {
+            affected: affected as i64,
+        }))
+    }
+
+    async fn retry_tasks(
+        &self,
+        request: Request<TaskIdsRequest>,
+    ) -> Result<Response<TasksAffected>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let affected = tasks::retry(&self.state.db, task_ids(&body)?)
+            .await
+            .map_err(|e| db_err("retry tasks", e))?;
+        self.state.task_wakeup.notify_one();
+        Ok(Response::new(TasksAffected {
+            affected: affected as i64,
+        }))
+    }
+
+    async fn create_scan_job(
+        &self,
+        request: Request<CreateScanJobRequest>,
+    ) -> Result<Response<ScanJob>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let edition = body.edition();
+        let port = match body.port {
+            0 => default_port(edition),
+            1..=65535 => body.port,
+            _ => return Err(Status::invalid_argument("Invalid port")),
+        };
+        let ranges = scan_jobs::parse_ranges(&body.ranges, &body.ranges_file)?;
+        let shards = scan_jobs::split_shards(&ranges);
+        let name = match body.name.trim() {
+            "" => ranges
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            name => name.to_string(),
+        };
+
+        let id = scan_jobs::create_job(
+            &self.state.db,
+            &name,
+            port,
+            Edition::from_proto(edition as i32),
+            &shards,
+        )
+        .await
+        .map_err(|e| db_err("create scan job", e))?;
+        self.state.shard_wakeup.notify_one();
+
+        let job = scan_jobs::list_jobs(&self.state.db)
+            .await
+            .map_err(|e| db_err("list scan jobs", e))?
+            .into_iter()
+            .find(|j| j.id == id)
+            .ok_or_else(|| Status::internal("scan job vanished"))?;
+        Ok(Response::new(scan_job_to_proto(job)))
+    }
+
+    async fn list_scan_jobs(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<ScanJobList>, Status> {
+        auth::require_session(&request)?;
+        let jobs = scan_jobs::list_jobs(&self.state.db)
+            .await
+            .map_err(|e| db_err("list scan jobs", e))?;
+        Ok(Response::new(ScanJobList {
+            jobs: jobs.into_iter().map(scan_job_to_proto).collect(),
+        }))
+    }
+
+    async fn delete_scan_job(
+        &self,
+        request: Request<DeleteScanJobRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        auth::require_session(&request)?;
+        let id = request.into_inner().id;
+        let affected = scan_jobs::delete_job(&self.state.db, id)
+            .await
+            .map_err(|e| db_err("delete scan job", e))?;
+        require_affected(affected, "scan job")?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn list_exclusions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<ExclusionList>, Status> {
+        auth::require_session(&request)?;
+        let rows = exclusions::list(&self.state.db)
+            .await
+            .map_err(|e| db_err("list exclusions", e))?;
+        Ok(Response::new(ExclusionList {
+            exclusions: rows.into_iter().map(exclusion_to_proto).collect(),
+        }))
+    }
+
+    async fn add_exclusion(
+        &self,
+        request: Request<AddExclusionRequest>,
+    ) -> Result<Response<Exclusion>, Status> {
+        auth::require_session(&request)?;
+        let body = request.into_inner();
+        let net = exclusions::parse_cidr(&body.cidr)?;
+        let row = exclusions::add(&self.state.db, net, body.reason.trim())
+            .await
+            .map_err(|e| db_err("add exclusion", e))?;
+        exclusions::broadcast(&self.state).await;
+        Ok(Response::new(exclusion_to_proto(row)))
+    }
+
+    async fn delete_exclusion(
+        &self,
+        request: Request<DeleteExclusionRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        auth::require_session(&request)?;
+        let id = request.into_inner().id;
+        let affected = exclusions::delete(&self.state.db, id)
+            .await
+            .map_err(|e| db_err("delete exclusion", e))?;
+        require_affected(affected, "exclusion")?;
+        exclusions::broadcast(&self.state).await;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn purge_excluded_servers(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<PurgeExcludedServersResponse>, Status> {
+        auth::require_session(&request)?;
+        let deleted = exclusions::purge_servers(&self.state.db)
+            .await
+            .map_err(|e| db_err("purge excluded servers", e))?;
+        tracing::info!("purged {deleted} servers inside excluded blocks");
+        Ok(Response::new(PurgeExcludedServersResponse {
+            deleted: deleted as i64,
+        }))
+    }
+
     async fn list_players(
         &self,
         request: Request<PlayerListRequest>,
@@ -729,6 +1387,8 @@ impl Api for ApiService {
                     id: player.id,
                     server_id: player.server_id,
                     server_ip: server.ip,
+                    server_port: server.port,
+                    server_edition: server.edition.to_proto(),
                     name: player.name,
                     status: proto_status(player.status),
                     last_seen_at: player.last_seen_at.to_rfc3339(),
@@ -858,3 +1518,65 @@ impl Api for ApiService {
         Ok(Response::new(Empty {}))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_addr_accepts_ipv6_forms() {
+        let ok = |addr| parse_addr(addr, DEFAULT_PORT).unwrap();
+        let ip = |ip: &str, port| Target::Ip(ip.into(), port);
+        assert_eq!(ok("1.2.3.4"), ip("1.2.3.4", 25565));
+        assert_eq!(ok("1.2.3.4:25570"), ip("1.2.3.4", 25570));
+        assert_eq!(ok("1.2.3.4:65535"), ip("1.2.3.4", 65535));
+        assert_eq!(ok("2001:DB8:0::1"), ip("2001:db8::1", 25565));
+        assert_eq!(ok("[2001:db8::1]"), ip("2001:db8::1", 25565));
+        assert_eq!(ok(" [2001:db8::1]:25570 "), ip("2001:db8::1", 25570));
+        assert_eq!(ok("::ffff:1.2.3.4"), ip("1.2.3.4", 25565));
+
+        for bad in [
+            "[1.2.3.4]:25565",
+            "[2001:db8::1",
+            "[2001:db8::1]x",
+            "1.2.3.4:port",
+            "1.2.3.4:0",
+            "1.2.3.4:65536",
+            "1.2.3.4:-1",
+            "[2001:db8::1]:70000",
+        ] {
+            assert!(parse_addr(bad, DEFAULT_PORT).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn parse_addr_reads_hostnames() {
+        let ok = |addr| parse_addr(addr, DEFAULT_PORT).unwrap();
+        let host = |h: &str, port| Target::Host(h.into(), port);
+        // No port: left to the worker's SRV lookup.
+        assert_eq!(ok("Play.Example.NET"), host("play.example.net", 0));
+        assert_eq!(
+            ok("play.example.net.:25570"),
+            host("play.example.net", 25570)
+        );
+        assert_eq!(ok("localhost"), host("localhost", 0));
+        let scan = ok("mc.example.net").into_scan(Edition::Java);
+        assert_eq!(
+            (scan.ip, scan.port, scan.host),
+            (String::new(), 0, Some("mc.example.net".into()))
+        );
+
+        for bad in [
+            "",
+            "1.2.3",
+            "play example.net",
+            "-bad.example.net",
+            "a..b",
+            "x/y:25565",
+            "play.example.net:0",
+            "play.example.net:99999",
+        ] {
+            assert!(parse_addr(bad, DEFAULT_PORT).is_err(), "{bad:?}");
+        }
+    }
+}
@@ -1,34 +1,42 @@
 //! WorkerControl gRPC service: the worker-facing control plane. Workers dial in
 //! and open the `Session` stream; the backend persists their results and pushes
-//! commands. `FetchUpdateTargets` serves the per-cycle re-probe list.
+//! commands. `FetchUpdateTargets` serves each worker its share of the re-probe
+//! list (see `crate::update_leases`).
 
-use std::{pin::Pin, sync::Arc};
+use std::{collections::VecDeque, pin::Pin, sync::Arc};
 
 use futures::Stream;
 use proto::worker::{
-    Ack, FetchUpdateTargetsRequest, FetchUpdateTargetsResponse, ScanResult, ServerCommand,
-    UpdateTarget, WorkerMessage, fetch_update_targets_response, scan_result, server_command,
-    worker_control_server::WorkerControl, worker_message,
+    Ack, Exclusions, FetchUpdateTargetsRequest, FetchUpdateTargetsResponse, ScanResult,
+    ServerCommand, TaskDone, UpdateTarget, WorkerMessage, fetch_update_targets_response,
+    scan_result, server_command, worker_control_server::WorkerControl, worker_message,
 };
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status, Streaming};
 
-use crate::{auth, persistence, server_filters::ServerFilters, state::AppState};
+use crate::{
+    auth, exclusions, models::servers::Edition, persistence, scan_jobs,
+    server_filters::ServerFilters, state::AppState, tasks, update_leases::Joined,
+};
 
 /// Capacity of the per-session result queue feeding the writer task. Sized to
 /// absorb short DB hiccups; on overflow results are dropped and replayed from
 /// the worker's outbox.
 const WRITER_QUEUE: usize = 1024;
 
+/// What the writer task persists, in the order the worker sent it.
+enum Write {
+    Result(Box<ScanResult>),
+    /// Queued behind the task's results, so its job's progress has counted
+    /// them by the time the task shows as done.
+    TaskDone(String, TaskDone),
+}
+
 pub struct WorkerService {
     pub state: Arc<AppState>,
 }
 
-/// Rows per keyset page when streaming update targets. Big enough to amortise
-/// round-trips, small enough that each page is a bounded gRPC message.
-const UPDATE_TARGET_BATCH: i64 = 1000;
-
 #[tonic::async_trait]
 impl WorkerControl for WorkerService {
     type SessionStream = Pin<Box<dyn Stream<Item = Result<ServerCommand, Status>> + Send>>;
@@ -53,7 +61,7 @@ impl WorkerControl for WorkerService {
             // processes heartbeats — a stall there would make the worker look
             // offline). Results that don't fit are dropped; the worker's outbox
             // replays anything left un-acked.
-            let (result_tx, result_rx) = mpsc::channel::<ScanResult>(WRITER_QUEUE);
+            let (result_tx, result_rx) = mpsc::channel::<Write>(WRITER_QUEUE);
             let writer = tokio::spawn(writer_task(state.clone(), cmd_tx.clone(), result_rx));
 
             loop {
@@ -64,7 +72,14 @@ impl WorkerControl for WorkerService {
                             let config = reg.config.unwrap_or_default();
                             let effective = state
                                 .registry
-                                .register(id.clone(), reg.name, reg.version, config, cmd_tx.clone())
+                                .register(
+                                    id.clone(),
+                                    reg.name,
+                                    reg.version,
+                                    reg.region,
+                                    config,
+                                    cmd_tx.clone(),
+                                )
                                 .await;
                             worker_id = Some(id.clone());
                             tracing::info!(worker = %id, "worker registered");
@@ -75,21 +90,110 @@ impl WorkerControl for WorkerService {
                                     cmd: Some(server_command::Cmd::SetConfig(effective)),
                                 }))
                                 .await;
+                            // The exclusion list goes out before any work can: a
+                            // shard must not race it. On failure the worker keeps
+                            // the copy it saved last time.
+                            match exclusions::cidrs(&state.db).await {
+                                Ok(cidrs) => {
+                                    let _ = cmd_tx
+                                        .send(Ok(ServerCommand {
+                                            cmd: Some(server_command::Cmd::SetExclusions(
+                                                Exclusions { cidrs },
+                                            )),
+                                        }))
+                                        .await;
+                                }
+                                Err(e) => tracing::warn!(
+                                    worker = %id,
+                                    "failed to load exclusion list: {e}"
+                                ),
+                            }
+                            state.shard_wakeup.notify_one();
+                            state.task_wakeup.notify_one();
+                            // Hand back where its permutation search stopped; the
+                            // worker ignores a cursor for a walk it no longer runs.
+                            let (state, cmd_tx) = (state.clone(), cmd_tx.clone());
+                            tokio::spawn(async move {
+                                match persistence::load_search_cursor(&state.db, &id).await {
+                                    Ok(Some(cursor)) => {
+                                        let _ = cmd_tx
+                                            .send(Ok(ServerCommand {
+                                                cmd: Some(server_command::Cmd::ResumeCursor(
+                                                    cursor,
+                                                )),
+                                            }))
+                                            .await;
+                                    }
+                                    Ok(None) => {}
+                                    Err(e) => tracing::warn!(
+                                        worker = %id,
+                                        "failed to load search cursor: {e}"
+                                    ),
+                                }
+                            });
                         }
                         Some(worker_message::Kind::Heartbeat(hb)) => {
                             if let (Some(id), Some(metrics)) = (worker_id.as_ref(), hb.metrics) {
+                                if let Some(cursor) = metrics.search_cursor {
+                                    let (state, id) = (state.clone(), id.clone());
+                                    tokio::spawn(async move {
+                                        if let Err(e) =
+                                            persistence::save_search_cursor(&state.db, &id, &cursor)
+                                                .await
+                                        {
+                                            tracing::warn!(
+                                                worker = %id,
+                                                "failed to save search cursor: {e}"
+                                            );
+                                        }
+                                    });
+                                }
                                 state.registry.heartbeat(id, metrics).await;
                             }
                         }
                         Some(worker_message::Kind::Result(result)) => {
                             if let Err(mpsc::error::TrySendError::Full(_)) =
-                                result_tx.try_send(result)
+                                result_tx.try_send(Write::Result(Box::new(result)))
                             {
                                 tracing::warn!(
                                     "db writer queue full; dropping scan result (worker will replay)"
                                 );
                             }
                         }
+                        Some(worker_message::Kind::ShardProgress(progress)) => {
+                            // Off the read loop, like result persistence. Reports
+                            // may land out of order; the update only ever moves a
+                            // shard forward.
+                            if let Some(id) = worker_id.clone() {
+                                let state = state.clone();
+                                tokio::spawn(async move {
+                                    match scan_jobs::record_progress(&state.db, &id, &progress)
+                                        .await
+                                    {
+                                        Ok(true) => state.shard_wakeup.notify_one(),
+                                        Ok(false) => {}
+                                        Err(e) => tracing::warn!(
+                                            worker = %id,
+                                            "failed to record shard progress: {e}"
+                                        ),
+                                    }
+                                });
+                            }
+                        }
+                        Some(worker_message::Kind::TaskDone(done)) => {
+                            if let Some(id) = worker_id.clone() {
+                                // The worker does not replay these: with the
+                                // queue full, finish it off the read loop. Its
+                                // dropped results are replayed and counted then.
+                                if let Err(mpsc::error::TrySendError::Full(Write::TaskDone(
+                                    id,
+                                    done,
+                                ))) = result_tx.try_send(Write::TaskDone(id, done))
+                                {
+                                    tokio::spawn(finish_task(state.clone(), id, done));
+                                }
+                            }
+                        }
                         None => {}
                     },
                     Ok(None) => break,
@@ -107,6 +211,19 @@ impl WorkerControl for WorkerService {
             if let Some(id) = worker_id {
                 tracing::info!(worker = %id, "worker disconnected");
                 state.registry.mark_offline(&id).await;
+                // The worker dropped its shards with the session; hand them to
+                // whoever is free, resuming from the last reported offset.
+                if let Err(e) = scan_jobs::release_shards(&state.db, Some(&id)).await {
+                    tracing::warn!(worker = %id, "failed to release scan shards: {e}");
+                }
+                // Likewise the pings and scans it had not finished, and the
+                // update slices it was re-probing.
+                if let Err(e) = tasks::release_tasks(&state.db, Some(&id)).await {
+                    tracing::warn!(worker = %id, "failed to release tasks: {e}");
+                }
+                state.registry.reassign_update_leases(&id).await;
+                state.shard_wakeup.notify_one();
+                state.task_wakeup.notify_one();
             }
         });
 
@@ -130,19 +247,19 @@ impl WorkerControl for WorkerService {
             .map(|c| c.update_with_connection)
             .unwrap_or(false);
 
-        // Which existing servers this cycle re-probes. Absent filter = re-probe all.
+        // Which existing servers this cycle re-probes. Absent filter = all of
+        // them, though only those due are served (see `crate::schedule`).
         let filters: ServerFilters = req.filter.as_ref().map(ServerFilters::from).unwrap_or_default();
 
-        // Page through the servers table and stream one target per row. Each
-        // batch re-acquires (and releases) a pooled connection, so a slow worker
-        // draining the stream never pins a connection for the whole cycle. The
-        // leading frame carries the total count so the worker's progress has a
-        // fixed denominator from the start.
+        // Stream the slices of the update round this worker shares with the
+        // others on the same filter, one target per row. Each slice re-acquires
+        // (and releases) a pooled connection, so a slow worker draining the
+        // stream never pins a connection for the whole cycle.
         let (tx, rx) = mpsc::channel::<Result<FetchUpdateTargetsResponse, Status>>(256);
         let state = self.state.clone();
         tokio::spawn(async move {
-            let total = match persistence::count_update_targets(&state.db, &filters).await {
-                Ok(n) => n.max(0) as u64,
+            let max_id = match persistence::max_server_id(&state.db).await {
+                Ok(id) => id,
                 Err(e) => {
                     let _ = tx
                         .send(Err(Status::internal(format!("db error: {e}"))))
@@ -150,61 +267,110 @@ impl WorkerControl for WorkerService {
                     return;
                 }
             };
+            let joined = state.registry.join_update_round(&filters, max_id).await;
+            stream_update_round(
+                &state,
+                &tx,
+                &filters,
+                &req.worker_id,
+                joined,
+                with_connection,
+            )
+            .await;
+            state
+                .registry
+                .leave_update_round(&filters, joined.round)
+                .await;
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+/// Sends one worker its share of an update round (see `crate::update_leases`):
+/// a leading frame with the total, then the due targets of every slice it
+/// leases until the round is walked. The total is an estimate, the due targets
+/// not yet handed out split evenly between the streams sharing the round, so
+/// the worker's progress has a fixed denominator from the start. A stream that
+/// breaks gives its slices back for another worker to take.
+async fn stream_update_round(
+    state: &AppState,
+    tx: &mpsc::Sender<Result<FetchUpdateTargetsResponse, Status>>,
+    filters: &ServerFilters,
+    worker_id: &str,
+    joined: Joined,
+    with_connection: bool,
+) {
+    let total = match persistence::count_update_targets(&state.db, filters, joined.cursor).await {
+        Ok(n) => (n.max(0) as u64).div_ceil(joined.streams.max(1) as u64),
+        Err(e) => {
+            let _ = tx
+                .send(Err(Status::internal(format!("db error: {e}"))))
+                .await;
+            return;
+        }
+    };
+    if tx
+        .send(Ok(FetchUpdateTargetsResponse {
+            kind: Some(fetch_update_targets_response::Kind::Total(total)),
+        }))
+        .await
+        .is_err()
+    {
+        return; // worker dropped the stream
+    }
+
+    // The slice being sent and the one before it, which the worker may still
+    // be probing.
+    let mut held = VecDeque::new();
+    let give_back = async |held: VecDeque<u64>| {
+        for lease in held {
+            state.registry.give_back_update_slice(filters, lease).await;
+        }
+    };
+    while let Some((lease, slice)) = state
+        .registry
+        .lease_update_slice(filters, joined.round, worker_id)
+        .await
+    {
+        held.push_back(lease);
+        let rows = match persistence::fetch_update_targets_slice(&state.db, filters, slice).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                give_back(held).await;
+                let _ = tx
+                    .send(Err(Status::internal(format!("db error: {e}"))))
+                    .await;
+                return;
+            }
+        };
+        for row in rows {
+            let target = UpdateTarget {
+                ip: row.ip,
+                port: row.port,
+                with_connection,
+                edition: row.edition.to_proto(),
+                host: row.hostname,
+            };
             if tx
                 .send(Ok(FetchUpdateTargetsResponse {
-                    kind: Some(fetch_update_targets_response::Kind::Total(total)),
+                    kind: Some(fetch_update_targets_response::Kind::Target(target)),
                 }))
                 .await
                 .is_err()
             {
+                give_back(held).await;
                 return; // worker dropped the stream
             }
-
-            let mut after_id: Option<i32> = None;
-            loop {
-                let rows = match persistence::fetch_update_targets_batch(
-                    &state.db,
-                    &filters,
-                    after_id,
-                    UPDATE_TARGET_BATCH,
-                )
-                .await
-                {
-                    Ok(rows) => rows,
-                    Err(e) => {
-                        let _ = tx
-                            .send(Err(Status::internal(format!("db error: {e}"))))
-                            .await;
-                        return;
-                    }
-                };
-
-                let got = rows.len();
-                for row in rows {
-                    after_id = Some(row.id);
-                    let target = UpdateTarget {
-                        ip: row.ip,
-                        port: row.port,
-                        with_connection,
-                    };
-                    if tx
-                        .send(Ok(FetchUpdateTargetsResponse {
-                            kind: Some(fetch_update_targets_response::Kind::Target(target)),
-                        }))
-                        .await
-                        .is_err()
-                    {
-                        return; // worker dropped the stream
-                    }
-                }
-
-                if (got as i64) < UPDATE_TARGET_BATCH {
-                    break;
-                }
+        }
+        while held.len() > 2 {
+            if let Some(done) = held.pop_front() {
+                state.registry.release_update_slice(filters, done).await;
             }
-        });
-
-        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+        }
+    }
+    for lease in held {
+        state.registry.release_update_slice(filters, lease).await;
     }
 }
 
@@ -215,19 +381,47 @@ impl WorkerControl for WorkerService {
 async fn writer_task(
     state: Arc<AppState>,
     cmd_tx: mpsc::Sender<Result<ServerCommand, Status>>,
-    mut result_rx: mpsc::Receiver<ScanResult>,
+    mut result_rx: mpsc::Receiver<Write>,
 ) {
-    while let Some(result) = result_rx.recv().await {
+    while let Some(write) = result_rx.recv().await {
+        let result = match write {
+            Write::Result(result) => *result,
+            Write::TaskDone(id, done) => {
+                finish_task(state.clone(), id, done).await;
+                continue;
+            }
+        };
         let result_id = result.result_id.clone();
         let outcome = match result.outcome {
             Some(scan_result::Outcome::Discovered(s)) => {
-                persistence::persist_discovered(&state.db, s, &result_id).await
+                persistence::persist_discovered(
+                    &state.db,
+                    &state.classifier,
+                    s,
+                    &result_id,
+                    result.task_id,
+                )
+                .await
             }
             Some(scan_result::Outcome::Updated(s)) => {
-                persistence::persist_updated(&state.db, s, &result_id).await
+                persistence::persist_updated(
+                    &state.db,
+                    &state.classifier,
+                    s,
+                    &result_id,
+                    result.task_id,
+                )
+                .await
             }
             Some(scan_result::Outcome::Offline(o)) => {
-                persistence::persist_offline(&state.db, &o.ip, &result_id).await
+                let edition = Edition::from_proto(o.edition);
+                persistence::persist_offline(&state.db, &o.ip, o.port, edition, &result_id).await
+            }
+            Some(scan_result::Outcome::Skipped(s)) => {
+                let edition = Edition::from_proto(s.edition);
+                persistence::persist_skipped(&state.db, &s.ip, s.port, edition, &result_id)
+                    .await
+                    .map(|()| None)
             }
             None => Ok(None),
         };
@@ -250,3 +444,13 @@ async fn writer_task(
         }
     }
 }
+
+/// Marks a task the worker reported done (or rejected) as finished.
+async fn finish_task(state: Arc<AppState>, id: String, done: TaskDone) {
+    let error = done.error.as_deref();
+    match tasks::finish(&state.db, &id, done.task_id, error).await {
+        Ok(true) => state.task_wakeup.notify_one(),
+        Ok(false) => {}
+        Err(e) => tracing::warn!(worker = %id, "failed to finish task {}: {e}", done.task_id),
+    }
+}
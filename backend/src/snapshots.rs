@@ -0,0 +1,389 @@
+//! Player count history. Every probe that reaches a server appends a raw
+//! sample to `player_count_snapshots`. A background task ([`spawn_compactor`])
+//! rolls the samples up into `player_count_hourly` and those into
+//! `player_count_daily` (min/max/avg players per server and UTC hour / day),
+//! then drops each tier past the age set in `[backend]` (see
+//! [`SnapshotRetention`]), so recent charts stay detailed while weekly and
+//! longer trends survive in the rollups.
+//!
+//! A rollup pass rebuilds every complete bucket from the newest one already
+//! rolled up onward, so a pass cut short, or a backend that was down for a
+//! while, is caught up by the next one. Buckets are first rolled up
+//! [`ROLLUP_DELAY`] after they end, once samples stamped inside them have
+//! committed. A server's newest raw sample is never dropped: server listings
+//! are joined with it.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::{
+    QueryResult,
+    sql_types::{BigInt, Integer, Nullable, Timestamptz},
+};
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+
+use crate::{
+    database::DatabaseWrapper, models::player_count_snapshots::SnapshotRollup,
+    persistence::DbResult,
+};
+
+/// How often rollups are brought up to date and old rows dropped.
+const COMPACT_INTERVAL: Duration = Duration::from_secs(3600);
+/// How long after a bucket ends it is rolled up. A sample's `recorded_at` is
+/// the start of the probe transaction that stores it, which may commit a few
+/// seconds later.
+const ROLLUP_DELAY: TimeDelta = TimeDelta::minutes(5);
+
+const DEFAULT_RAW_DAYS: u32 = 7;
+const DEFAULT_HOURLY_DAYS: u32 = 90;
+/// Shortest hourly retention: a day is rolled up from its hours once it is
+/// over, so they must outlive it.
+const MIN_HOURLY_DAYS: u32 = 2;
+
+/// How long each tier is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotRetention {
+    pub raw: TimeDelta,
+    pub hourly: TimeDelta,
+    /// `None`: daily rollups are kept for good.
+    pub daily: Option<TimeDelta>,
+}
+
+impl SnapshotRetention {
+    /// The policy for the `[backend]` settings, in days. Raw samples are kept
+    /// at least a day and hourly rollups at least [`MIN_HOURLY_DAYS`], which
+    /// the rollups are built from; a daily retention of 0 keeps them for good.
+    pub fn from_days(raw: Option<u32>, hourly: Option<u32>, daily: Option<u32>) -> Self {
+        let days = |d: u32| TimeDelta::days(d as i64);
+        Self {
+            raw: days(raw.unwrap_or(DEFAULT_RAW_DAYS).max(1)),
+            hourly: days(hourly.unwrap_or(DEFAULT_HOURLY_DAYS).max(MIN_HOURLY_DAYS)),
+            daily: daily.filter(|&d| d > 0).map(days),
+        }
+    }
+}
+
+/// Time resolution of a player count series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Hourly,
+    Daily,
+}
+
+impl Resolution {
+    fn table(self) -> &'static str {
+        match self {
+            Resolution::Hourly => "player_count_hourly",
+            Resolution::Daily => "player_count_daily",
+        }
+    }
+}
+
+/// Rebuilds the hourly buckets from the newest one rolled up through the last
+/// hour complete at `$1`, from the raw samples.
+const ROLLUP_HOURLY_SQL: &str = "\
+    INSERT INTO player_count_hourly \
+        (server_id, bucket, online_min, online_max, online_avg, players_max, samples) \
+    SELECT server_id, date_trunc('hour', recorded_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC', \
+           min(players_online), max(players_online), avg(players_online)::real, \
+           max(players_max), count(*)::int4 \
+    FROM player_count_snapshots \
+    WHERE recorded_at >= COALESCE((SELECT max(bucket) FROM player_count_hourly), '-infinity') \
+      AND recorded_at < date_trunc('hour', $1 AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' \
+    GROUP BY 1, 2 \
+    ON CONFLICT (server_id, bucket) DO UPDATE SET \
+        online_min = excluded.online_min, online_max = excluded.online_max, \
+        online_avg = excluded.online_avg, players_max = excluded.players_max, \
+        samples = excluded.samples";
+
+/// Same for the daily buckets, from the hourly ones; the average is weighted
+/// by each hour's samples.
+const ROLLUP_DAILY_SQL: &str = "\
+    INSERT INTO player_count_daily \
+        (server_id, bucket, online_min, online_max, online_avg, players_max, samples) \
+    SELECT server_id, date_trunc('day', bucket AT TIME ZONE 'UTC') AT TIME ZONE 'UTC', \
+           min(online_min), max(online_max), \
+           (sum(online_avg * samples) / sum(samples))::real, \
+           max(players_max), sum(samples)::int4 \
+    FROM player_count_hourly \
+    WHERE bucket >= COALESCE((SELECT max(bucket) FROM player_count_daily), '-infinity') \
+      AND bucket < date_trunc('day', $1 AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' \
+    GROUP BY 1, 2 \
+    ON CONFLICT (server_id, bucket) DO UPDATE SET \
+        online_min = excluded.online_min, online_max = excluded.online_max, \
+        online_avg = excluded.online_avg, players_max = excluded.players_max, \
+        samples = excluded.samples";
+
+/// Drops raw samples recorded before `$1`, except each server's newest.
+const PRUNE_RAW_SQL: &str = "\
+    DELETE FROM player_count_snapshots s \
+    WHERE s.recorded_at < $1 \
+      AND s.recorded_at < ( \
+          SELECT max(recorded_at) FROM player_count_snapshots l \
+          WHERE l.server_id = s.server_id \
+      )";
+
+/// Rows written or dropped by one compaction pass.
+#[derive(Debug, Default)]
+pub struct Compacted {
+    pub hourly: usize,
+    pub daily: usize,
+    pub pruned: usize,
+}
+
+/// Brings the rollups up to date, then drops rows past `retention`.
+pub async fn compact(db: &DatabaseWrapper, retention: &SnapshotRetention) -> DbResult<Compacted> {
+    let mut conn = db.conn().await?;
+    let conn: &mut AsyncPgConnection = &mut conn;
+    let now = Utc::now();
+    let compacted = conn
+        .transaction::<Compacted, diesel::result::Error, _>(async |conn| {
+            let (hourly, daily) = roll_up(conn, now - ROLLUP_DELAY).await?;
+            let mut pruned = prune_raw(conn, now - retention.raw).await?;
+            pruned += diesel::sql_query("DELETE FROM player_count_hourly WHERE bucket < $1")
+                .bind::<Timestamptz, _>(now - retention.hourly)
+                .execute(conn)
+                .await?;
+            if let Some(daily) = retention.daily {
+                pruned += diesel::sql_query("DELETE FROM player_count_daily WHERE bucket < $1")
+                    .bind::<Timestamptz, _>(now - daily)
+                    .execute(conn)
+                    .await?;
+            }
+            Ok(Compacted {
+                hourly,
+                daily,
+                pruned,
+            })
+        })
+        .await?;
+    Ok(compacted)
+}
+
+/// Rolls up the hourly, then the daily buckets complete at `until`. Returns
+/// the rows written to each.
+async fn roll_up(
+    conn: &mut AsyncPgConnection,
+    until: DateTime<Utc>,
+) -> QueryResult<(usize, usize)> {
+    let hourly = diesel::sql_query(ROLLUP_HOURLY_SQL)
+        .bind::<Timestamptz, _>(until)
+        .execute(conn)
+        .await?;
+    let daily = diesel::sql_query(ROLLUP_DAILY_SQL)
+        .bind::<Timestamptz, _>(until)
+        .execute(conn)
+        .await?;
+    Ok((hourly, daily))
+}
+
+async fn prune_raw(conn: &mut AsyncPgConnection, before: DateTime<Utc>) -> QueryResult<usize> {
+    diesel::sql_query(PRUNE_RAW_SQL)
+        .bind::<Timestamptz, _>(before)
+        .execute(conn)
+        .await
+}
+
+/// Compacts right away, then every [`COMPACT_INTERVAL`].
+pub fn spawn_compactor(db: Arc<DatabaseWrapper>, retention: SnapshotRetention) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(COMPACT_INTERVAL);
+        loop {
+            interval.tick().await;
+            match compact(&db, &retention).await {
+                Ok(c) => tracing::debug!(
+                    "snapshot compaction: {} hourly, {} daily rows rolled up, {} pruned",
+                    c.hourly,
+                    c.daily,
+                    c.pruned
+                ),
+                Err(e) => tracing::warn!("failed to compact player count snapshots: {e}"),
+            }
+        }
+    });
+}
+
+/// A server's rollups at `resolution`, newest first, with buckets starting in
+/// `[since, until)` where given.
+pub async fn rollups(
+    db: &DatabaseWrapper,
+    server_id: i32,
+    resolution: Resolution,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    limit: i64,
+) -> DbResult<Vec<SnapshotRollup>> {
+    let mut conn = db.conn().await?;
+    let rows = diesel::sql_query(format!(
+        "SELECT server_id, bucket, online_min, online_max, online_avg, players_max, samples \
+         FROM {} \
+         WHERE server_id = $1 \
+           AND ($2 IS NULL OR bucket >= $2) \
+           AND ($3 IS NULL OR bucket < $3) \
+         ORDER BY bucket DESC \
+         LIMIT $4",
+        resolution.table()
+    ))
+    .bind::<Integer, _>(server_id)
+    .bind::<Nullable<Timestamptz>, _>(since)
+    .bind::<Nullable<Timestamptz>, _>(until)
+    .bind::<BigInt, _>(limit)
+    .load(&mut conn)
+    .await?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::player_count_snapshots::SnapshotRollup, schema::player_count_snapshots};
+    use diesel::{ExpressionMethods, QueryDsl, QueryableByName};
+
+    /// A connection to the migrated database at `TEST_DATABASE_URL`, in a
+    /// transaction that is never committed, with the rollup tables emptied.
+    async fn test_conn() -> AsyncPgConnection {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let mut conn = AsyncPgConnection::establish(&url).await.unwrap();
+        conn.begin_test_transaction().await.unwrap();
+        diesel::sql_query("TRUNCATE player_count_hourly, player_count_daily")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        conn
+    }
+
+    async fn insert_server(conn: &mut AsyncPgConnection, ip: &str) -> i32 {
+        #[derive(QueryableByName)]
+        struct Id {
+            #[diesel(sql_type = Integer)]
+            id: i32,
+        }
+        let row: Id = diesel::sql_query(
+            "INSERT INTO servers (ip, port, version_name, protocol, description, is_online_mode) \
+             VALUES ($1, 25565, '1.21', 767, '{}', true) RETURNING id",
+        )
+        .bind::<diesel::sql_types::Text, _>(ip)
+        .get_result(conn)
+        .await
+        .unwrap();
+        row.id
+    }
+
+    async fn insert_sample(conn: &mut AsyncPgConnection, server_id: i32, at: &str, online: i16) {
+        diesel::sql_query(
+            "INSERT INTO player_count_snapshots (server_id, players_online, players_max, recorded_at) \
+             VALUES ($1, $2, 100, $3)",
+        )
+        .bind::<Integer, _>(server_id)
+        .bind::<diesel::sql_types::SmallInt, _>(online)
+        .bind::<Timestamptz, _>(time(at))
+        .execute(conn)
+        .await
+        .unwrap();
+    }
+
+    async fn hourly(conn: &mut AsyncPgConnection, server_id: i32) -> Vec<SnapshotRollup> {
+        diesel::sql_query(
+            "SELECT server_id, bucket, online_min, online_max, online_avg, players_max, samples \
+             FROM player_count_hourly WHERE server_id = $1 ORDER BY bucket",
+        )
+        .bind::<Integer, _>(server_id)
+        .load(conn)
+        .await
+        .unwrap()
+    }
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database at TEST_DATABASE_URL"]
+    async fn rollup_rebuilds_the_newest_bucket_for_late_samples() {
+        let mut conn = test_conn().await;
+        let id = insert_server(&mut conn, "192.0.2.10").await;
+        insert_sample(&mut conn, id, "2020-01-01T10:10:00Z", 5).await;
+        insert_sample(&mut conn, id, "2020-01-01T10:50:00Z", 15).await;
+        insert_sample(&mut conn, id, "2020-01-01T11:20:00Z", 8).await;
+
+        // 11:00 is not over yet.
+        roll_up(&mut conn, time("2020-01-01T11:30:00Z"))
+            .await
+            .unwrap();
+        let rows = hourly(&mut conn, id).await;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].bucket, time("2020-01-01T10:00:00Z"));
+        assert_eq!((rows[0].online_min, rows[0].online_max), (5, 15));
+        assert_eq!(rows[0].samples, 2);
+
+        // A sample stamped in 10:00 that committed after the pass is picked up
+        // by the next one, which starts at the newest bucket rolled up.
+        insert_sample(&mut conn, id, "2020-01-01T10:59:59Z", 25).await;
+        roll_up(&mut conn, time("2020-01-01T12:10:00Z"))
+            .await
+            .unwrap();
+        let rows = hourly(&mut conn, id).await;
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].online_max, rows[0].samples), (25, 3));
+        assert_eq!(rows[1].bucket, time("2020-01-01T11:00:00Z"));
+        assert_eq!(rows[1].samples, 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated database at TEST_DATABASE_URL"]
+    async fn prune_keeps_each_servers_newest_sample() {
+        let mut conn = test_conn().await;
+        let stale = insert_server(&mut conn, "192.0.2.11").await;
+        let active = insert_server(&mut conn, "192.0.2.12").await;
+        insert_sample(&mut conn, stale, "2020-01-01T10:00:00Z", 1).await;
+        insert_sample(&mut conn, stale, "2020-01-02T10:00:00Z", 2).await;
+        insert_sample(&mut conn, active, "2020-01-01T10:00:00Z", 3).await;
+        insert_sample(&mut conn, active, "2020-02-01T10:00:00Z", 4).await;
+
+        let pruned = prune_raw(&mut conn, time("2020-01-15T00:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(pruned, 2);
+        let left: Vec<(i32, DateTime<Utc>)> = player_count_snapshots::table
+            .select((
+                player_count_snapshots::server_id,
+                player_count_snapshots::recorded_at,
+            ))
+            .filter(player_count_snapshots::server_id.eq_any([stale, active]))
+            .order(player_count_snapshots::server_id)
+            .load(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(
+            left,
+            vec![
+                (stale, time("2020-01-02T10:00:00Z")),
+                (active, time("2020-02-01T10:00:00Z")),
+            ]
+        );
+    }
+
+    #[test]
+    fn retention_defaults_and_floors() {
+        assert_eq!(
+            SnapshotRetention::from_days(None, None, None),
+            SnapshotRetention {
+                raw: TimeDelta::days(7),
+                hourly: TimeDelta::days(90),
+                daily: None,
+            }
+        );
+        assert_eq!(
+            SnapshotRetention::from_days(Some(0), Some(1), Some(0)),
+            SnapshotRetention {
+                raw: TimeDelta::days(1),
+                hourly: TimeDelta::days(2),
+                daily: None,
+            }
+        );
+        assert_eq!(
+            SnapshotRetention::from_days(Some(3), Some(30), Some(730)).daily,
+            Some(TimeDelta::days(730))
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use std::sync::Arc;
 
-use crate::{database::DatabaseWrapper, events::ServerEvents, registry::WorkerRegistry};
+use tokio::sync::Notify;
+
+use crate::{
+    classifier::JoinClassifier, database::DatabaseWrapper, events::ServerEvents,
+    registry::WorkerRegistry,
+};
 
 /// Resolved watchtower HTTP API settings, present only when both URL and token
 /// are configured. Drives the manual "update stack" action.
@@ -15,4 +20,9 @@ pub struct AppState {
     pub registry: Arc<WorkerRegistry>,
     pub events: Arc<ServerEvents>,
     pub watchtower: Option<WatchtowerConfig>,
+    pub classifier: Arc<JoinClassifier>,
+    /// Wakes the scan job scheduler early (see `crate::scan_jobs`).
+    pub shard_wakeup: Notify,
+    /// Wakes the task scheduler early (see `crate::tasks`).
+    pub task_wakeup: Notify,
 }
@@ -0,0 +1,367 @@
+//! Global statistics over time. `GetStats` computes its aggregates live; a
+//! background task ([`spawn_sampler`]) also records the headline numbers every
+//! [`SAMPLE_INTERVAL`] in `stats_samples`, with the probe rates of the online
+//! workers in `worker_stats_samples`. `GetStatsHistory` folds the samples in a
+//! time range into fixed-width buckets ([`fold`]) for the dashboard's trend
+//! charts.
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::{
+    dsl::{count_star, insert_into, max},
+    prelude::*,
+    sql_types::{BigInt, Timestamptz},
+};
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use proto::api::{StatsPoint, VersionStat, WorkerThroughput};
+use tonic::Status;
+
+use crate::{
+    database::DatabaseWrapper,
+    models::stats_samples::{StatsSampleModel, WorkerStatsSampleModel},
+    persistence::DbResult,
+    schema::{servers, stats_samples, worker_stats_samples},
+    state::AppState,
+};
+
+/// How often statistics are recorded; also the narrowest bucket.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Versions kept per sample, most common first.
+const TOP_VERSIONS: i64 = 10;
+/// Range queried when a request gives no start.
+const DEFAULT_RANGE: TimeDelta = TimeDelta::days(7);
+const DEFAULT_BUCKET_SECS: i64 = 3600;
+/// Most buckets one request may span.
+pub const MAX_POINTS: i64 = 5000;
+
+/// Resolves a request's range and bucket width (seconds), defaulting to the
+/// last [`DEFAULT_RANGE`] in hourly buckets.
+pub fn resolve_range(
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    bucket_secs: i64,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>, i64), Status> {
+    let until = until.unwrap_or(now);
+    let since = since.unwrap_or(until - DEFAULT_RANGE);
+    let bucket_secs = if bucket_secs == 0 {
+        DEFAULT_BUCKET_SECS
+    } else {
+        bucket_secs
+    };
+    if bucket_secs < SAMPLE_INTERVAL.as_secs() as i64 {
+        return Err(Status::invalid_argument(format!(
+            "Buckets must be at least {}s wide",
+            SAMPLE_INTERVAL.as_secs()
+        )));
+    }
+    if since >= until {
+        return Err(Status::invalid_argument("Empty time range"));
+    }
+    if (until - since).num_seconds() / bucket_secs >= MAX_POINTS {
+        return Err(Status::invalid_argument(format!(
+            "Range spans more than {MAX_POINTS} buckets"
+        )));
+    }
+    Ok((since, until, bucket_secs))
+}
+
+#[derive(QueryableByName)]
+struct Totals {
+    #[diesel(sql_type = BigInt)]
+    total_servers: i64,
+    #[diesel(sql_type = BigInt)]
+    online_servers: i64,
+    #[diesel(sql_type = BigInt)]
+    cracked_servers: i64,
+    #[diesel(sql_type = BigInt)]
+    new_servers: i64,
+    #[diesel(sql_type = BigInt)]
+    total_players: i64,
+    #[diesel(sql_type = BigInt)]
+    players_online: i64,
+}
+
+/// Records the current statistics and the online workers' probe rates.
+pub async fn sample(state: &AppState) -> DbResult<()> {
+    let mut conn = state.db.conn().await?;
+    let conn: &mut AsyncPgConnection = &mut conn;
+    let now = Utc::now();
+    let workers = state.registry.list().await.workers;
+    conn.transaction::<(), diesel::result::Error, _>(async |conn| {
+        let previous: Option<DateTime<Utc>> = stats_samples::table
+            .select(max(stats_samples::sampled_at))
+            .get_result(conn)
+            .await?;
+        let totals: Totals = diesel::sql_query(
+            "SELECT count(*) AS total_servers, \
+                    count(*) FILTER (WHERE is_online) AS online_servers, \
+                    count(*) FILTER (WHERE NOT is_online_mode) AS cracked_servers, \
+                    count(*) FILTER (WHERE created_at > $1 AND created_at <= $2) AS new_servers, \
+                    (SELECT count(*) FROM players) AS total_players, \
+                    (SELECT COALESCE(SUM(p.players_online), 0)::int8 \
+                     FROM servers v \
+                     CROSS JOIN LATERAL ( \
+                         SELECT players_online FROM player_count_snapshots \
+                         WHERE server_id = v.id \
+                         ORDER BY recorded_at DESC \
+                         LIMIT 1 \
+                     ) p \
+                     WHERE v.is_online) AS players_online \
+             FROM servers",
+        )
+        .bind::<Timestamptz, _>(previous.unwrap_or(now - SAMPLE_INTERVAL))
+        .bind::<Timestamptz, _>(now)
+        .get_result(conn)
+        .await?;
+        let versions: serde_json::Map<String, serde_json::Value> = servers::table
+            .group_by(servers::version_name)
+            .select((servers::version_name, count_star()))
+            .order(count_star().desc())
+            .limit(TOP_VERSIONS)
+            .load::<(String, i64)>(conn)
+            .await?
+            .into_iter()
+            .map(|(version, count)| (version, count.into()))
+            .collect();
+
+        insert_into(stats_samples::table)
+            .values(&StatsSampleModel {
+                sampled_at: now,
+                total_servers: totals.total_servers,
+                online_servers: totals.online_servers,
+                cracked_servers: totals.cracked_servers,
+                total_players: totals.total_players,
+                players_online: totals.players_online,
+                new_servers: totals.new_servers,
+                versions: versions.into(),
+            })
+            .execute(conn)
+            .await?;
+        let rates: Vec<WorkerStatsSampleModel> = workers
+            .into_iter()
+            .filter(|w| w.online)
+            .filter_map(|w| {
+                let m = w.metrics?;
+                Some(WorkerStatsSampleModel {
+                    sampled_at: now,
+                    worker_id: w.worker_id,
+                    scan_rate: m.scan_rate,
+                    update_rate: m.update_rate,
+                })
+            })
+            .collect();
+        if !rates.is_empty() {
+            insert_into(worker_stats_samples::table)
+                .values(&rates)
+                .execute(conn)
+                .await?;
+        }
+        Ok(())
+    })
+    .await?;
+    Ok(())
+}
+
+/// Samples right away, then every [`SAMPLE_INTERVAL`].
+pub fn spawn_sampler(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sample(&state).await {
+                tracing::warn!("failed to record stats sample: {e}");
+            }
+        }
+    });
+}
+
+/// The samples recorded in `[since, until)`, folded into `bucket_secs` buckets.
+pub async fn history(
+    db: &DatabaseWrapper,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    bucket_secs: i64,
+) -> DbResult<Vec<StatsPoint>> {
+    let mut conn = db.conn().await?;
+    let samples: Vec<StatsSampleModel> = stats_samples::table
+        .filter(stats_samples::sampled_at.ge(since))
+        .filter(stats_samples::sampled_at.lt(until))
+        .order(stats_samples::sampled_at.asc())
+        .select(StatsSampleModel::as_select())
+        .load(&mut conn)
+        .await?;
+    let workers: Vec<WorkerStatsSampleModel> = worker_stats_samples::table
+        .filter(worker_stats_samples::sampled_at.ge(since))
+        .filter(worker_stats_samples::sampled_at.lt(until))
+        .select(WorkerStatsSampleModel::as_select())
+        .load(&mut conn)
+        .await?;
+    Ok(fold(&samples, &workers, bucket_secs))
+}
+
+fn bucket_start(t: DateTime<Utc>, bucket_secs: i64) -> i64 {
+    t.timestamp().div_euclid(bucket_secs) * bucket_secs
+}
+
+/// Folds samples (oldest first) into one point per bucket that has any: the
+/// totals of its last sample, the discoveries summed over all of them and each
+/// worker's mean rates.
+pub fn fold(
+    samples: &[StatsSampleModel],
+    workers: &[WorkerStatsSampleModel],
+    bucket_secs: i64,
+) -> Vec<StatsPoint> {
+    // Per bucket, then worker: summed rates and sample count.
+    let mut rates: BTreeMap<i64, BTreeMap<&str, (f64, f64, u32)>> = BTreeMap::new();
+    for w in workers {
+        let entry = rates
+            .entry(bucket_start(w.sampled_at, bucket_secs))
+            .or_default()
+            .entry(&w.worker_id)
+            .or_default();
+        entry.0 += w.scan_rate;
+        entry.1 += w.update_rate;
+        entry.2 += 1;
+    }
+
+    let mut points: Vec<(i64, StatsPoint)> = Vec::new();
+    for s in samples {
+        let start = bucket_start(s.sampled_at, bucket_secs);
+        let new_servers = match points.last() {
+            Some((last, point)) if *last == start => point.new_servers + s.new_servers,
+            _ => s.new_servers,
+        };
+        let mut version_distribution: Vec<VersionStat> = s
+            .versions
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(version, count)| VersionStat {
+                version: version.clone(),
+                count: count.as_i64().unwrap_or_default(),
+            })
+            .collect();
+        version_distribution.sort_by(|a, b| b.count.cmp(&a.count).then(a.version.cmp(&b.version)));
+        let point = StatsPoint {
+            bucket: DateTime::from_timestamp(start, 0)
+                .unwrap_or_default()
+                .to_rfc3339(),
+            total_servers: s.total_servers,
+            online_servers: s.online_servers,
+            cracked_servers: s.cracked_servers,
+            total_players: s.total_players,
+            players_online: s.players_online,
+            version_distribution,
+            new_servers,
+            workers: Vec::new(),
+        };
+        match points.last_mut() {
+            Some((last, existing)) if *last == start => *existing = point,
+            _ => points.push((start, point)),
+        }
+    }
+
+    points
+        .into_iter()
+        .map(|(start, mut point)| {
+            point.workers = rates
+                .get(&start)
+                .into_iter()
+                .flatten()
+                .map(|(&worker_id, &(scan, update, n))| WorkerThroughput {
+                    worker_id: worker_id.to_string(),
+                    scan_rate: scan / n as f64,
+                    update_rate: update / n as f64,
+                })
+                .collect();
+            point
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    fn sample(secs: i64, total: i64, new: i64) -> StatsSampleModel {
+        StatsSampleModel {
+            sampled_at: at(secs),
+            total_servers: total,
+            online_servers: total / 2,
+            cracked_servers: 0,
+            total_players: 0,
+            players_online: 0,
+            new_servers: new,
+            versions: json!({"1.20.4": total - 1, "1.21.1": 1}),
+        }
+    }
+
+    fn rates(secs: i64, worker: &str, scan_rate: f64) -> WorkerStatsSampleModel {
+        WorkerStatsSampleModel {
+            sampled_at: at(secs),
+            worker_id: worker.into(),
+            scan_rate,
+            update_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn samples_fold_into_buckets() {
+        let samples = [
+            sample(0, 10, 4),
+            sample(900, 12, 2),
+            sample(2700, 13, 1),
+            sample(7200, 20, 7),
+        ];
+        let workers = [
+            rates(0, "a", 100.0),
+            rates(900, "a", 200.0),
+            rates(900, "b", 50.0),
+            rates(7200, "b", 10.0),
+        ];
+        let points = fold(&samples, &workers, 3600);
+        assert_eq!(points.len(), 2, "the empty hour is left out");
+
+        let first = &points[0];
+        assert_eq!(first.bucket, at(0).to_rfc3339());
+        assert_eq!(first.total_servers, 13, "as of the last sample");
+        assert_eq!(first.new_servers, 7, "summed");
+        assert_eq!(first.version_distribution[0].version, "1.20.4");
+        assert_eq!(first.version_distribution[0].count, 12);
+        let rates: Vec<_> = first
+            .workers
+            .iter()
+            .map(|w| (w.worker_id.as_str(), w.scan_rate))
+            .collect();
+        assert_eq!(rates, [("a", 150.0), ("b", 50.0)]);
+
+        assert_eq!(points[1].bucket, at(7200).to_rfc3339());
+        assert_eq!(points[1].new_servers, 7);
+        assert_eq!(points[1].workers.len(), 1);
+    }
+
+    #[test]
+    fn ranges_default_and_are_bounded() {
+        let now = at(10_000_000);
+        let (since, until, bucket) = resolve_range(None, None, 0, now).unwrap();
+        assert_eq!((until, until - since, bucket), (now, DEFAULT_RANGE, 3600));
+        assert!(
+            resolve_range(None, None, 60, now).is_err(),
+            "below the sample interval"
+        );
+        assert!(resolve_range(Some(now), Some(now), 3600, now).is_err());
+        assert!(
+            resolve_range(Some(at(0)), None, 900, now).is_err(),
+            "too many buckets"
+        );
+        assert!(resolve_range(Some(at(0)), None, 3600, now).is_ok());
+    }
+}
@@ -0,0 +1,245 @@
+//! Target jobs: every `AddTargetList` batch is stored as a named job and its
+//! targets are queued as tasks under the job's id (see `crate::tasks`). How
+//! far a job got is counted from its tasks; which servers answered is recorded
+//! as their results are persisted ([`record_server`]), each server once per
+//! job, split by whether the job added it. A job's tasks are kept, and purged,
+//! with the job.
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    sql_types::{BigInt, Bool, Integer, Nullable},
+};
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+
+use crate::{
+    database::DatabaseWrapper,
+    models::target_jobs::{TargetJobInsert, TargetJobSummary},
+    persistence::DbResult,
+    schema::target_jobs,
+    tasks::{self, Assignee, NewTask},
+};
+
+/// Stores a job and queues its targets for `to`, in one transaction. Returns
+/// the job id.
+pub async fn create(
+    db: &DatabaseWrapper,
+    name: &str,
+    to: &Assignee,
+    targets: Vec<NewTask>,
+) -> DbResult<i64> {
+    let mut conn = db.conn().await?;
+    let conn: &mut AsyncPgConnection = &mut conn;
+    let id = conn
+        .transaction::<i64, diesel::result::Error, _>(async |conn| {
+            let id: i64 = insert_into(target_jobs::table)
+                .values(&TargetJobInsert { name })
+                .returning(target_jobs::id)
+                .get_result(conn)
+                .await?;
+            tasks::insert(conn, to, targets, Some(id)).await?;
+            Ok(id)
+        })
+        .await?;
+    Ok(id)
+}
+
+/// Jobs with their task totals, newest first; only job `id` when given.
+pub async fn list(db: &DatabaseWrapper, id: Option<i64>) -> DbResult<Vec<TargetJobSummary>> {
+    let mut conn = db.conn().await?;
+    Ok(summaries(&mut conn, id).await?)
+}
+
+/// [`list`] on a connection. A job's `queued` counts its leased tasks too, so
+/// it reaches 0 (where the progress stream ends) only once every task is done,
+/// failed or cancelled.
+async fn summaries(
+    conn: &mut AsyncPgConnection,
+    id: Option<i64>,
+) -> QueryResult<Vec<TargetJobSummary>> {
+    diesel::sql_query(
+        "SELECT j.id, j.name, j.created_at, j.new_servers, j.updated_servers, \
+                count(t.id) AS total, \
+                count(t.id) FILTER (WHERE t.state IN ('queued', 'leased')) AS queued, \
+                count(t.id) FILTER (WHERE t.state = 'done') AS probed, \
+                count(t.id) FILTER (WHERE t.state = 'failed') AS failed, \
+                count(t.id) FILTER (WHERE t.state = 'cancelled') AS cancelled \
+         FROM target_jobs j \
+         LEFT JOIN tasks t ON t.job_id = j.id \
+         WHERE $1 IS NULL OR j.id = $1 \
+         GROUP BY j.id \
+         ORDER BY j.id DESC",
+    )
+    .bind::<Nullable<BigInt>, _>(id)
+    .load(conn)
+    .await
+}
+
+/// Deletes a job with its tasks. A worker already running one of them still
+/// reports it; the result is persisted but counted for no job.
+pub async fn delete(db: &DatabaseWrapper, id: i64) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let n = diesel::delete(target_jobs::table.filter(target_jobs::id.eq(id)))
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// Counts `server_id` as having answered the job of task `task_id`, as a new
+/// server if `is_new`. No-op for a task outside any job, and for a server
+/// the job already counted.
+pub async fn record_server(
+    conn: &mut AsyncPgConnection,
+    task_id: i64,
+    server_id: i32,
+    is_new: bool,
+) -> QueryResult<()> {
+    diesel::sql_query(
+        "WITH added AS ( \
+             INSERT INTO target_job_servers (job_id, server_id, is_new) \
+             SELECT job_id, $2, $3 FROM tasks WHERE id = $1 AND job_id IS NOT NULL \
+             ON CONFLICT DO NOTHING \
+             RETURNING job_id, is_new \
+         ) \
+         UPDATE target_jobs j \
+         SET new_servers = j.new_servers + (CASE WHEN a.is_new THEN 1 ELSE 0 END), \
+             updated_servers = j.updated_servers + (CASE WHEN a.is_new THEN 0 ELSE 1 END) \
+         FROM added a \
+         WHERE j.id = a.job_id",
+    )
+    .bind::<BigInt, _>(task_id)
+    .bind::<Integer, _>(server_id)
+    .bind::<Bool, _>(is_new)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{servers::Edition, tasks::TaskKind},
+        schema::tasks as task_rows,
+    };
+
+    /// A connection to the migrated database at `TEST_DATABASE_URL`, in a
+    /// transaction that is never committed.
+    async fn test_conn() -> AsyncPgConnection {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let mut conn = AsyncPgConnection::establish(&url).await.unwrap();
+        conn.begin_test_transaction().await.unwrap();
+        conn
+    }
+
+    async fn insert_server(conn: &mut AsyncPgConnection, ip: &str) -> i32 {
+        #[derive(QueryableByName)]
+        struct Id {
+            #[diesel(sql_type = Integer)]
+            id: i32,
+        }
+        let row: Id = diesel::sql_query(
+            "INSERT INTO servers (ip, port, version_name, protocol, description, is_online_mode) \
+             VALUES ($1, 25565, '1.21', 767, '{}', true) RETURNING id",
+        )
+        .bind::<diesel::sql_types::Text, _>(ip)
+        .get_result(conn)
+        .await
+        .unwrap();
+        row.id
+    }
+
+    async fn set_state(conn: &mut AsyncPgConnection, task_id: i64, state: &str) {
+        diesel::sql_query("UPDATE tasks SET state = $2::task_state WHERE id = $1")
+            .bind::<BigInt, _>(task_id)
+            .bind::<diesel::sql_types::Text, _>(state)
+            .execute(conn)
+            .await
+            .unwrap();
+    }
+
+    fn ping(ip: &str) -> NewTask {
+        NewTask {
+            kind: TaskKind::Ping,
+            ip: ip.into(),
+            port: 25565,
+            edition: Edition::Java,
+            host: None,
+            with_connection: false,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a migrated Postgres at TEST_DATABASE_URL"]
+    async fn progress_counts_tasks_and_servers_until_none_is_left() {
+        let mut conn = test_conn().await;
+        let job: i64 = insert_into(target_jobs::table)
+            .values(&TargetJobInsert { name: "batch" })
+            .returning(target_jobs::id)
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        let to = Assignee::Worker("w1".into());
+        let targets = vec![
+            ping("203.0.113.1"),
+            ping("203.0.113.2"),
+            ping("203.0.113.3"),
+        ];
+        tasks::insert(&mut conn, &to, targets, Some(job))
+            .await
+            .unwrap();
+        let ids: Vec<i64> = task_rows::table
+            .filter(task_rows::job_id.eq(job))
+            .order(task_rows::id)
+            .select(task_rows::id)
+            .load(&mut conn)
+            .await
+            .unwrap();
+
+        let summary = summaries(&mut conn, Some(job))
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!((summary.total, summary.queued, summary.probed), (3, 3, 0));
+
+        // A leased task still counts as queued; its server is counted once.
+        set_state(&mut conn, ids[0], "leased").await;
+        let server = insert_server(&mut conn, "203.0.113.1").await;
+        record_server(&mut conn, ids[0], server, true)
+            .await
+            .unwrap();
+        record_server(&mut conn, ids[0], server, true)
+            .await
+            .unwrap();
+        let summary = summaries(&mut conn, Some(job))
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(summary.queued, 3);
+        assert_eq!((summary.new_servers, summary.updated_servers), (1, 0));
+
+        set_state(&mut conn, ids[0], "done").await;
+        set_state(&mut conn, ids[1], "failed").await;
+        let summary = summaries(&mut conn, Some(job))
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!((summary.queued, summary.probed, summary.failed), (1, 1, 1));
+
+        // Finished once nothing is queued or leased.
+        set_state(&mut conn, ids[2], "cancelled").await;
+        let summary = summaries(&mut conn, Some(job))
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(summary.queued, 0);
+        assert_eq!(
+            (summary.probed, summary.failed, summary.cancelled),
+            (1, 1, 1)
+        );
+    }
+}
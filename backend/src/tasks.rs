@@ -0,0 +1,680 @@
+//! Task queue for operator pings and scans. `PingServer`, `AddTarget` and
+//! `AddTargetList` store every request as a `tasks` row ([`enqueue`], or under
+//! a target job, see `crate::target_jobs`); the scheduler task
+//! ([`spawn_scheduler`]) leases queued rows to online workers, at most
+//! [`MAX_TASKS_PER_WORKER`] each, and sends them over the `Session` stream. The
+//! worker's `TaskDone` finishes a task ([`finish`]). A lease that outlives
+//! [`LEASE_TTL`], or whose worker or backend goes away, is queued again until
+//! the task has been leased [`MAX_ATTEMPTS`] times, after which it fails. A
+//! large import thus waits in the table rather than in a worker's command
+//! channel, and survives restarts of either side.
+//!
+//! A task for a chosen worker waits for that worker. One for any worker goes
+//! to an online worker with its region label, picked by [`pick`] when leased.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{TimeDelta, Utc};
+use diesel::{
+    dsl::{count_star, insert_into},
+    prelude::*,
+    sql_types::{Integer, Nullable, Text},
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use proto::{
+    api::WorkerSelector,
+    worker::{PingTask, ScanTask, server_command},
+};
+
+use crate::{
+    database::DatabaseWrapper,
+    models::{
+        servers::Edition,
+        tasks::{TaskInsert, TaskKind, TaskModel, TaskState, WorkerPick},
+    },
+    persistence::DbResult,
+    registry::OnlineWorker,
+    schema::tasks,
+    state::AppState,
+};
+
+/// Tasks a worker holds at once. The rest stay queued, so a worker's command
+/// channel never fills up with them.
+pub const MAX_TASKS_PER_WORKER: i64 = 32;
+/// How long a worker may hold a task without reporting it done. Any ping or
+/// scan finishes well within it.
+pub const LEASE_TTL: TimeDelta = TimeDelta::minutes(5);
+/// Leases a task gets before it fails.
+pub const MAX_ATTEMPTS: i32 = 3;
+/// Largest page `ListTasks` returns.
+pub const MAX_LIST: i64 = 500;
+/// How long finished, failed and cancelled tasks are kept.
+const RETENTION: TimeDelta = TimeDelta::days(7);
+/// How often the scheduler looks for queued tasks when nothing woke it.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(5);
+/// How often tasks past [`RETENTION`] are deleted.
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+/// Rows per insert when queueing a batch.
+const INSERT_BATCH: usize = 1000;
+
+/// Who runs a ping or scan.
+pub enum Assignee {
+    Worker(String),
+    Any(WorkerSelector),
+}
+
+/// A ping or scan to queue.
+pub struct NewTask {
+    pub kind: TaskKind,
+    /// Empty for a scan by hostname.
+    pub ip: String,
+    pub port: i32,
+    pub edition: Edition,
+    pub host: Option<String>,
+    /// Pings only.
+    pub with_connection: bool,
+}
+
+/// Queues `tasks` for `to`. Returns how many were queued.
+pub async fn enqueue(db: &DatabaseWrapper, to: &Assignee, tasks: Vec<NewTask>) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    Ok(insert(&mut conn, to, tasks, None).await?)
+}
+
+/// Queues `tasks` for `to` on `conn`, under the target job `job_id` if any
+/// (see `crate::target_jobs`).
+pub async fn insert(
+    conn: &mut AsyncPgConnection,
+    to: &Assignee,
+    tasks: Vec<NewTask>,
+    job_id: Option<i64>,
+) -> QueryResult<usize> {
+    let (worker_id, region, pick) = match to {
+        Assignee::Worker(id) => (Some(id.as_str()), None, WorkerPick::LeastLoaded),
+        Assignee::Any(selector) => (
+            None,
+            normalize_region(selector.region.as_deref()),
+            WorkerPick::from_proto(selector.pick),
+        ),
+    };
+    let mut queued = 0;
+    let mut tasks = tasks.into_iter().peekable();
+    while tasks.peek().is_some() {
+        let rows: Vec<TaskInsert> = tasks
+            .by_ref()
+            .take(INSERT_BATCH)
+            .map(|t| TaskInsert {
+                kind: t.kind,
+                ip: t.ip,
+                port: t.port,
+                edition: t.edition,
+                host: t.host,
+                with_connection: t.with_connection,
+                worker_id,
+                region: region.as_deref(),
+                pick,
+                job_id,
+            })
+            .collect();
+        queued += insert_into(tasks::table)
+            .values(&rows)
+            .execute(conn)
+            .await?;
+    }
+    Ok(queued)
+}
+
+/// Region labels match case-insensitively; a blank one means any region.
+pub fn normalize_region(region: Option<&str>) -> Option<String> {
+    region
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Which tasks `ListTasks` returns.
+pub struct TaskFilter<'a> {
+    pub state: Option<TaskState>,
+    /// Chosen by or leased to this worker.
+    pub worker_id: Option<&'a str>,
+    pub before_id: Option<i64>,
+    pub job_id: Option<i64>,
+    pub limit: i64,
+}
+
+/// Tasks matching `filter`, newest first.
+pub async fn list(db: &DatabaseWrapper, filter: &TaskFilter<'_>) -> DbResult<Vec<TaskModel>> {
+    let mut conn = db.conn().await?;
+    let mut query = tasks::table.select(TaskModel::as_select()).into_boxed();
+    if let Some(state) = filter.state {
+        query = query.filter(tasks::state.eq(state));
+    }
+    if let Some(worker_id) = filter.worker_id {
+        query = query.filter(
+            tasks::worker_id
+                .eq(worker_id)
+                .or(tasks::leased_to.eq(worker_id)),
+        );
+    }
+    if let Some(before_id) = filter.before_id {
+        query = query.filter(tasks::id.lt(before_id));
+    }
+    if let Some(job_id) = filter.job_id {
+        query = query.filter(tasks::job_id.eq(job_id));
+    }
+    let rows = query
+        .order(tasks::id.desc())
+        .limit(filter.limit.clamp(1, MAX_LIST))
+        .load(&mut conn)
+        .await?;
+    Ok(rows)
+}
+
+/// Number of tasks in each state.
+pub async fn counts(db: &DatabaseWrapper) -> DbResult<Vec<(TaskState, i64)>> {
+    let mut conn = db.conn().await?;
+    let counts = tasks::table
+        .group_by(tasks::state)
+        .select((tasks::state, count_star()))
+        .load(&mut conn)
+        .await?;
+    Ok(counts)
+}
+
+/// Cancels queued and leased tasks: those in `ids`, or all of them when `None`.
+/// A worker already running a cancelled task finishes it; its `TaskDone` then
+/// matches nothing.
+pub async fn cancel(db: &DatabaseWrapper, ids: Option<&[i64]>) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let mut query = diesel::update(tasks::table)
+        .filter(tasks::state.eq_any([TaskState::Queued, TaskState::Leased]))
+        .into_boxed();
+    if let Some(ids) = ids {
+        query = query.filter(tasks::id.eq_any(ids));
+    }
+    let n = query
+        .set((
+            tasks::state.eq(TaskState::Cancelled),
+            tasks::lease_expires_at.eq(None::<chrono::DateTime<Utc>>),
+            tasks::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// Queues failed and cancelled tasks again with fresh attempts: those in
+/// `ids`, or all of them when `None`.
+pub async fn retry(db: &DatabaseWrapper, ids: Option<&[i64]>) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let mut query = diesel::update(tasks::table)
+        .filter(tasks::state.eq_any([TaskState::Failed, TaskState::Cancelled]))
+        .into_boxed();
+    if let Some(ids) = ids {
+        query = query.filter(tasks::id.eq_any(ids));
+    }
+    let n = query
+        .set((
+            tasks::state.eq(TaskState::Queued),
+            tasks::attempts.eq(0),
+            tasks::last_error.eq(None::<String>),
+            tasks::leased_to.eq(None::<String>),
+            tasks::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// Records a worker's `TaskDone` for a task it holds: done, or failed with the
+/// worker's `error`. Reports for tasks it no longer holds (expired, cancelled)
+/// are ignored. Returns whether the task was finished, which frees a slot.
+pub async fn finish(
+    db: &DatabaseWrapper,
+    worker_id: &str,
+    task_id: u64,
+    error: Option<&str>,
+) -> DbResult<bool> {
+    let mut conn = db.conn().await?;
+    let state = match error {
+        Some(_) => TaskState::Failed,
+        None => TaskState::Done,
+    };
+    let n = diesel::update(tasks::table)
+        .filter(tasks::id.eq(task_id as i64))
+        .filter(tasks::leased_to.eq(worker_id))
+        .filter(tasks::state.eq(TaskState::Leased))
+        .set((
+            tasks::state.eq(state),
+            tasks::last_error.eq(error),
+            tasks::lease_expires_at.eq(None::<chrono::DateTime<Utc>>),
+            tasks::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut conn)
+        .await?;
+    Ok(n > 0)
+}
+
+/// Takes back the leases of `worker_id` (or everyone's, when `None`), after
+/// its session or the previous backend run ended.
+pub async fn release_tasks(db: &DatabaseWrapper, worker_id: Option<&str>) -> DbResult<usize> {
+    let reason = match worker_id {
+        Some(_) => "worker disconnected",
+        None => "backend restarted",
+    };
+    requeue(db, worker_id, false, reason).await
+}
+
+/// Queues leased tasks again, those of `worker_id` (any worker's, when
+/// `None`), and with `expired_only` only those past their lease. A task that
+/// has used up its attempts fails with `reason` instead.
+async fn requeue(
+    db: &DatabaseWrapper,
+    worker_id: Option<&str>,
+    expired_only: bool,
+    reason: &str,
+) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let n = diesel::sql_query(
+        "UPDATE tasks SET \
+             state = CASE WHEN attempts >= $1 THEN 'failed'::task_state \
+                 ELSE 'queued'::task_state END, \
+             last_error = $2, \
+             lease_expires_at = NULL, \
+             updated_at = now() \
+         WHERE state = 'leased' \
+         AND ($3::text IS NULL OR leased_to = $3) \
+         AND (NOT $4 OR lease_expires_at <= now())",
+    )
+    .bind::<Integer, _>(MAX_ATTEMPTS)
+    .bind::<Text, _>(reason)
+    .bind::<Nullable<Text>, _>(worker_id)
+    .bind::<diesel::sql_types::Bool, _>(expired_only)
+    .execute(&mut conn)
+    .await?;
+    Ok(n)
+}
+
+/// Starts the task that leases queued tasks to online workers. Leases left by
+/// a previous backend run are taken back first: their sessions are gone.
+/// Runs every [`SCHEDULE_INTERVAL`], and early whenever `AppState::task_wakeup`
+/// fires (tasks were queued or finished, or a worker came or went).
+pub fn spawn_scheduler(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        match release_tasks(&state.db, None).await {
+            Ok(n) if n > 0 => tracing::info!("released {n} tasks from the previous run"),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("failed to release stale tasks: {e}"),
+        }
+
+        let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+        let mut last_purge: Option<Instant> = None;
+        let mut turn = 0;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = state.task_wakeup.notified() => {}
+            }
+            if last_purge.is_none_or(|t| t.elapsed() >= PURGE_INTERVAL) {
+                last_purge = Some(Instant::now());
+                if let Err(e) = purge(&state.db).await {
+                    tracing::warn!("failed to purge old tasks: {e}");
+                }
+            }
+            if let Err(e) = assign(&state, &mut turn).await {
+                tracing::warn!("failed to assign tasks: {e}");
+            }
+        }
+    });
+}
+
+/// Deletes tasks finished longer than [`RETENTION`] ago, except those of a
+/// target job, which go with the job.
+async fn purge(db: &DatabaseWrapper) -> DbResult<usize> {
+    let mut conn = db.conn().await?;
+    let n = diesel::delete(tasks::table)
+        .filter(tasks::state.eq_any([TaskState::Done, TaskState::Failed, TaskState::Cancelled]))
+        .filter(tasks::updated_at.lt(Utc::now() - RETENTION))
+        .filter(tasks::job_id.is_null())
+        .execute(&mut conn)
+        .await?;
+    Ok(n)
+}
+
+/// An online worker tasks may be leased to.
+struct Candidate {
+    id: String,
+    /// Lowercased, as tasks store it.
+    region: Option<String>,
+    /// Tasks it may take before [`MAX_TASKS_PER_WORKER`].
+    free: i64,
+    /// Pending tasks: the larger of those leased here and those it reported.
+    pending: u32,
+    threads: u32,
+}
+
+impl Candidate {
+    fn new(worker: OnlineWorker, held: i64) -> Self {
+        Self {
+            region: normalize_region(worker.region.as_deref()),
+            id: worker.id,
+            free: MAX_TASKS_PER_WORKER - held,
+            pending: (held as u32).max(worker.pending),
+            threads: worker.threads,
+        }
+    }
+
+    fn takes(&self, task: &TaskModel) -> bool {
+        self.free > 0
+            && match &task.worker_id {
+                Some(id) => *id == self.id,
+                None => task.region.is_none() || task.region == self.region,
+            }
+    }
+}
+
+/// Picks among the `eligible` candidates (indexes into the candidates, sorted
+/// by id). `turn` counts round-robin picks.
+fn pick(
+    candidates: &[Candidate],
+    eligible: &[usize],
+    how: WorkerPick,
+    turn: usize,
+) -> Option<usize> {
+    match how {
+        WorkerPick::LeastLoaded => eligible
+            .iter()
+            .copied()
+            .min_by_key(|&i| (candidates[i].pending, candidates[i].threads)),
+        WorkerPick::RoundRobin => eligible.get(turn % eligible.len().max(1)).copied(),
+    }
+}
+
+/// One scheduling pass: expired leases are queued again, then queued tasks
+/// are leased to the online workers with free slots and sent. A task that
+/// cannot be sent (the worker dropped meanwhile) is queued again.
+async fn assign(state: &AppState, turn: &mut usize) -> DbResult<()> {
+    requeue(&state.db, None, true, "lease expired").await?;
+    let held = leased_counts(&state.db).await?;
+    let mut candidates: Vec<Candidate> = state
+        .registry
+        .online_workers()
+        .await
+        .into_iter()
+        .map(|w| {
+            let held = held.get(&w.id).copied().unwrap_or(0);
+            Candidate::new(w, held)
+        })
+        .collect();
+
+    let queued = queued_for(&mut *state.db.conn().await?, &candidates).await?;
+    let mut batches: HashMap<usize, Vec<i64>> = HashMap::new();
+    for task in &queued {
+        let eligible: Vec<usize> = (0..candidates.len())
+            .filter(|&i| candidates[i].takes(task))
+            .collect();
+        let Some(i) = pick(&candidates, &eligible, task.pick, *turn) else {
+            continue;
+        };
+        if task.worker_id.is_none() && task.pick == WorkerPick::RoundRobin {
+            *turn += 1;
+        }
+        candidates[i].free -= 1;
+        candidates[i].pending += 1;
+        batches.entry(i).or_default().push(task.id);
+    }
+
+    for (i, ids) in batches {
+        let worker_id = &candidates[i].id;
+        for task in lease(&state.db, worker_id, &ids).await? {
+            if let Err(e) = state
+                .registry
+                .dispatch_task(worker_id, command(&task))
+                .await
+            {
+                tracing::debug!(worker = %worker_id, "task {} not sent: {e}", task.id);
+                unlease(&state.db, task.id, worker_id).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tasks leased per worker.
+async fn leased_counts(db: &DatabaseWrapper) -> DbResult<HashMap<String, i64>> {
+    let mut conn = db.conn().await?;
+    let rows: Vec<(Option<String>, i64)> = tasks::table
+        .filter(tasks::state.eq(TaskState::Leased))
+        .group_by(tasks::leased_to)
+        .select((tasks::leased_to, count_star()))
+        .load(&mut conn)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(worker, n)| Some((worker?, n)))
+        .collect())
+}
+
+/// The oldest queued tasks some candidate with a free slot may take: those
+/// pinned to each candidate up to its free slots, and unpinned ones up to the
+/// free slots in all, oldest first. Tasks for an offline or full worker, or a
+/// region without one, are left out so they do not hold up the rest, and a
+/// large batch pinned to one worker does not crowd out the others'.
+async fn queued_for(
+    conn: &mut AsyncPgConnection,
+    candidates: &[Candidate],
+) -> QueryResult<Vec<TaskModel>> {
+    let free: Vec<&Candidate> = candidates.iter().filter(|c| c.free > 0).collect();
+    let slots: i64 = free.iter().map(|c| c.free).sum();
+    if slots == 0 {
+        return Ok(Vec::new());
+    }
+    let regions: Vec<&str> = free.iter().filter_map(|c| c.region.as_deref()).collect();
+    let mut queued = tasks::table
+        .filter(tasks::state.eq(TaskState::Queued))
+        .filter(tasks::worker_id.is_null())
+        .filter(tasks::region.is_null().or(tasks::region.eq_any(regions)))
+        .order(tasks::id)
+        .limit(slots)
+        .select(TaskModel::as_select())
+        .load(conn)
+        .await?;
+    for c in free {
+        let pinned: Vec<TaskModel> = tasks::table
+            .filter(tasks::state.eq(TaskState::Queued))
+            .filter(tasks::worker_id.eq(&c.id))
+            .order(tasks::id)
+            .limit(c.free)
+            .select(TaskModel::as_select())
+            .load(conn)
+            .await?;
+        queued.extend(pinned);
+    }
+    queued.sort_by_key(|t| t.id);
+    Ok(queued)
+}
+
+/// Leases the tasks `ids` that are still queued to `worker_id`.
+async fn lease(db: &DatabaseWrapper, worker_id: &str, ids: &[i64]) -> DbResult<Vec<TaskModel>> {
+    let mut conn = db.conn().await?;
+    let tasks = diesel::update(tasks::table)
+        .filter(tasks::id.eq_any(ids))
+        .filter(tasks::state.eq(TaskState::Queued))
+        .set((
+            tasks::state.eq(TaskState::Leased),
+            tasks::leased_to.eq(worker_id),
+            tasks::lease_expires_at.eq(Utc::now() + LEASE_TTL),
+            tasks::attempts.eq(tasks::attempts + 1),
+            tasks::updated_at.eq(diesel::dsl::now),
+        ))
+        .returning(TaskModel::as_returning())
+        .get_results(&mut conn)
+        .await?;
+    Ok(tasks)
+}
+
+/// Undoes a lease whose task never reached the worker, attempt included.
+async fn unlease(db: &DatabaseWrapper, id: i64, worker_id: &str) -> DbResult<()> {
+    let mut conn = db.conn().await?;
+    diesel::update(tasks::table)
+        .filter(tasks::id.eq(id))
+        .filter(tasks::leased_to.eq(worker_id))
+        .filter(tasks::state.eq(TaskState::Leased))
+        .set((
+            tasks::state.eq(TaskState::Queued),
+            tasks::leased_to.eq(None::<String>),
+            tasks::lease_expires_at.eq(None::<chrono::DateTime<Utc>>),
+            tasks::attempts.eq(tasks::attempts - 1),
+            tasks::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut conn)
+        .await?;
+    Ok(())
+}
+
+fn command(task: &TaskModel) -> server_command::Cmd {
+    let task_id = task.id as u64;
+    match task.kind {
+        TaskKind::Ping => server_command::Cmd::Ping(PingTask {
+            ip: task.ip.clone(),
+            port: task.port,
+            with_connection: task.with_connection,
+            edition: task.edition.to_proto(),
+            host: task.host.clone(),
+            task_id,
+        }),
+        TaskKind::Scan => server_command::Cmd::Scan(ScanTask {
+            ip: task.ip.clone(),
+            port: task.port,
+            edition: task.edition.to_proto(),
+            host: task.host.clone(),
+            task_id,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel_async::AsyncConnection;
+
+    fn candidate(id: &str, pending: u32, threads: u32) -> Candidate {
+        Candidate {
+            id: id.into(),
+            region: None,
+            free: MAX_TASKS_PER_WORKER,
+            pending,
+            threads,
+        }
+    }
+
+    #[test]
+    fn picks_by_load_or_in_turn() {
+        let candidates = [
+            candidate("a", 3, 0),
+            candidate("b", 1, 500),
+            candidate("c", 1, 100),
+        ];
+        let all = [0, 1, 2];
+        assert_eq!(pick(&candidates, &all, WorkerPick::LeastLoaded, 0), Some(2));
+        let turns: Vec<_> = (4..7)
+            .map(|turn| pick(&candidates, &all, WorkerPick::RoundRobin, turn))
+            .collect();
+        assert_eq!(turns, [Some(1), Some(2), Some(0)]);
+        assert_eq!(
+            pick(&candidates, &[0, 1], WorkerPick::LeastLoaded, 0),
+            Some(1)
+        );
+        assert_eq!(pick(&candidates, &[], WorkerPick::RoundRobin, 1), None);
+        assert_eq!(pick(&candidates, &[], WorkerPick::LeastLoaded, 1), None);
+    }
+
+    fn task(worker_id: Option<&str>, region: Option<&str>) -> TaskModel {
+        TaskModel {
+            id: 1,
+            kind: TaskKind::Scan,
+            ip: "203.0.113.7".into(),
+            port: 25565,
+            edition: Edition::Java,
+            host: None,
+            with_connection: false,
+            worker_id: worker_id.map(Into::into),
+            region: normalize_region(region),
+            pick: WorkerPick::LeastLoaded,
+            state: TaskState::Queued,
+            leased_to: None,
+            lease_expires_at: None,
+            attempts: 0,
+            last_error: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            job_id: None,
+        }
+    }
+
+    #[test]
+    fn tasks_go_to_their_worker_or_region() {
+        let mut eu = candidate("a", 0, 0);
+        eu.region = normalize_region(Some(" EU "));
+        let anywhere = candidate("b", 0, 0);
+        assert!(eu.takes(&task(None, None)));
+        assert!(eu.takes(&task(None, Some("eu"))));
+        assert!(!anywhere.takes(&task(None, Some("eu"))));
+        assert!(anywhere.takes(&task(Some("b"), None)));
+        assert!(!eu.takes(&task(Some("b"), None)));
+        // A full worker takes nothing, not even its own tasks.
+        eu.free = 0;
+        assert!(!eu.takes(&task(Some("a"), None)));
+    }
+    #[tokio::test]
+    #[ignore = "needs a migrated Postgres at TEST_DATABASE_URL"]
+    async fn a_large_pinned_batch_leaves_room_for_other_workers() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let mut conn = AsyncPgConnection::establish(&url).await.unwrap();
+        conn.begin_test_transaction().await.unwrap();
+        diesel::delete(tasks::table)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let ping = |ip: &str| NewTask {
+            kind: TaskKind::Ping,
+            ip: ip.into(),
+            port: 25565,
+            edition: Edition::Java,
+            host: None,
+            with_connection: false,
+        };
+        let batch = (1..=5).map(|i| ping(&format!("203.0.113.{i}"))).collect();
+        insert(&mut conn, &Assignee::Worker("a".into()), batch, None)
+            .await
+            .unwrap();
+        let b = vec![ping("198.51.100.1")];
+        insert(&mut conn, &Assignee::Worker("b".into()), b, None)
+            .await
+            .unwrap();
+        let any = Assignee::Any(WorkerSelector::default());
+        insert(&mut conn, &any, vec![ping("198.51.100.2")], None)
+            .await
+            .unwrap();
+
+        let mut a = candidate("a", 0, 0);
+        a.free = 2;
+        let mut b = candidate("b", 0, 0);
+        b.free = 2;
+        let queued = queued_for(&mut conn, &[a, b]).await.unwrap();
+        let pinned = |id: &str| {
+            queued
+                .iter()
+                .filter(|t| t.worker_id.as_deref() == Some(id))
+                .count()
+        };
+        assert_eq!((pinned("a"), pinned("b")), (2, 1));
+        assert_eq!(queued.iter().filter(|t| t.worker_id.is_none()).count(), 1);
+        assert!(queued.is_sorted_by_key(|t| t.id));
+    }
+}
@@ -0,0 +1,301 @@
+//! Update cycles shared between workers. Every worker with `update_module` on
+//! streams its targets from `FetchUpdateTargets`; without coordination each
+//! would walk the whole filtered set. Instead, workers with the same
+//! `update_filter` share a *round*: one walk over the `servers` id space, cut
+//! into [`SLICE_IDS`]-wide slices that are leased to the fetching workers one
+//! at a time, so concurrent cycles split the set between them.
+//!
+//! A stream holds the slice it is sending and the one before it, which the
+//! worker may still be probing. A lease that outlives [`LEASE_TTL`] (a stuck
+//! stream) and every lease of a worker whose session ends go back to the round
+//! and are leased again ahead of fresh slices. Re-walking a slice only serves
+//! the servers in it that are still due (see `crate::schedule`), so the ones
+//! already probed are not probed twice. A stream that joins a walked round
+//! starts the next one, even while the last slices of the old one are still
+//! out: their holders finish them, and the new walk only serves what is still
+//! due by the time it gets there. The state lives in the
+//! [`crate::registry::WorkerRegistry`] and is lost on a backend restart, which
+//! merely starts every round afresh.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::server_filters::ServerFilters;
+
+/// Ids per slice. Ids are unique, so one slice is at most this many targets.
+pub const SLICE_IDS: i32 = 1000;
+/// How long a stream may hold a slice before it is handed to another worker.
+pub const LEASE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// An inclusive range of server ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slice {
+    pub first: i32,
+    pub last: i32,
+}
+
+struct Lease {
+    worker: String,
+    slice: Slice,
+    expires: Instant,
+}
+
+/// One walk over the servers matching a filter.
+struct Round {
+    /// Tells the round apart from the ones before it, whose streams may still
+    /// be finishing.
+    id: u64,
+    /// Last id handed out in a fresh slice.
+    cursor: i32,
+    /// Highest server id when the round started (or when a stream joined).
+    end: i32,
+    /// Slices given back by dropped or stuck workers, leased before fresh ones.
+    returned: VecDeque<Slice>,
+    leases: HashMap<u64, Lease>,
+    /// Streams currently drawing from the round.
+    streams: usize,
+}
+
+impl Round {
+    fn new(id: u64, end: i32) -> Self {
+        Self {
+            id,
+            cursor: 0,
+            end,
+            returned: VecDeque::new(),
+            leases: HashMap::new(),
+            streams: 0,
+        }
+    }
+
+    fn walked(&self) -> bool {
+        self.cursor >= self.end && self.returned.is_empty()
+    }
+
+    fn reclaim_expired(&mut self, now: Instant) {
+        let mut expired: Vec<u64> = self
+            .leases
+            .iter()
+            .filter(|(_, l)| l.expires <= now)
+            .map(|(&id, _)| id)
+            .collect();
+        // Oldest first, like the walk.
+        expired.sort_unstable();
+        for id in expired {
+            if let Some(lease) = self.leases.remove(&id) {
+                self.returned.push_back(lease.slice);
+            }
+        }
+    }
+}
+
+/// Where a joining stream stands in its round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Joined {
+    /// The round joined, to lease from and leave.
+    pub round: u64,
+    /// Ids above this have not been handed out yet.
+    pub cursor: i32,
+    /// Streams sharing the round, this one included.
+    pub streams: usize,
+}
+
+#[derive(Default)]
+pub struct UpdateLeases {
+    rounds: HashMap<ServerFilters, Round>,
+    next_lease: u64,
+    next_round: u64,
+}
+
+impl UpdateLeases {
+    /// A stream joins the round of its filter, starting a new round over the
+    /// ids up to `max_id` when none is under way or the current one has no
+    /// slice left to hand out. The old round's leases are dropped with it;
+    /// releasing or giving them back is then a no-op.
+    pub fn join(&mut self, filters: &ServerFilters, max_id: i32) -> Joined {
+        let next_round = &mut self.next_round;
+        let mut start = || {
+            *next_round += 1;
+            Round::new(*next_round, max_id)
+        };
+        let round = self
+            .rounds
+            .entry(filters.clone())
+            .or_insert_with(&mut start);
+        if round.walked() {
+            *round = start();
+        } else {
+            // Servers added since the round started are walked by it too.
+            round.end = round.end.max(max_id);
+        }
+        round.streams += 1;
+        Joined {
+            round: round.id,
+            cursor: round.cursor,
+            streams: round.streams,
+        }
+    }
+
+    /// A stream stopped drawing from `round`. Its leases stay out until
+    /// released, given back or expired.
+    pub fn leave(&mut self, filters: &ServerFilters, round: u64) {
+        if let Some(current) = self.rounds.get_mut(filters)
+            && current.id == round
+        {
+            current.streams = current.streams.saturating_sub(1);
+        }
+    }
+
+    /// Leases the next slice of `round` to `worker`: a returned one if any,
+    /// else a fresh one. `None` once the round is walked or a new one has
+    /// replaced it.
+    pub fn lease(
+        &mut self,
+        filters: &ServerFilters,
+        round: u64,
+        worker: &str,
+        now: Instant,
+    ) -> Option<(u64, Slice)> {
+        let round = self
+            .rounds
+            .get_mut(filters)
+            .filter(|current| current.id == round)?;
+        round.reclaim_expired(now);
+        let slice = match round.returned.pop_front() {
+            Some(slice) => slice,
+            None if round.cursor < round.end => {
+                let first = round.cursor + 1;
+                let last = first.saturating_add(SLICE_IDS - 1).min(round.end);
+                round.cursor = last;
+                Slice { first, last }
+            }
+            None => return None,
+        };
+        self.next_lease += 1;
+        round.leases.insert(
+            self.next_lease,
+            Lease {
+                worker: worker.to_string(),
+                slice,
+                expires: now + LEASE_TTL,
+            },
+        );
+        Some((self.next_lease, slice))
+    }
+
+    /// The holder is done with a slice.
+    pub fn release(&mut self, filters: &ServerFilters, lease: u64) {
+        if let Some(round) = self.rounds.get_mut(filters) {
+            round.leases.remove(&lease);
+        }
+    }
+
+    /// Hands a slice back for another stream to lease.
+    pub fn give_back(&mut self, filters: &ServerFilters, lease: u64) {
+        if let Some(round) = self.rounds.get_mut(filters)
+            && let Some(lease) = round.leases.remove(&lease)
+        {
+            round.returned.push_back(lease.slice);
+        }
+    }
+
+    /// Gives back every slice `worker` holds, after its session ended. Returns
+    /// the filters of the rounds now left with slices but no stream to take
+    /// them, whose workers should be woken.
+    pub fn drop_worker(&mut self, worker: &str) -> Vec<ServerFilters> {
+        let mut stalled = Vec::new();
+        for (filters, round) in &mut self.rounds {
+            let mut held: Vec<u64> = round
+                .leases
+                .iter()
+                .filter(|(_, l)| l.worker == worker)
+                .map(|(&id, _)| id)
+                .collect();
+            held.sort_unstable();
+            for id in &held {
+                if let Some(lease) = round.leases.remove(id) {
+                    round.returned.push_back(lease.slice);
+                }
+            }
+            if !held.is_empty() && round.streams == 0 {
+                stalled.push(filters.clone());
+            }
+        }
+        stalled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn online() -> ServerFilters {
+        ServerFilters {
+            online: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn workers_split_a_round() {
+        let (all, now) = (ServerFilters::default(), Instant::now());
+        let mut leases = UpdateLeases::default();
+        let r = leases.join(&all, 2500).round;
+        assert_eq!(
+            leases.join(&all, 2500),
+            Joined {
+                round: r,
+                cursor: 0,
+                streams: 2
+            }
+        );
+        let (a, s1) = leases.lease(&all, r, "a", now).unwrap();
+        let (b, s2) = leases.lease(&all, r, "b", now).unwrap();
+        assert_eq!((s1.first, s1.last), (1, 1000));
+        assert_eq!((s2.first, s2.last), (1001, 2000));
+        // Another filter is its own round.
+        let other = leases.join(&online(), 2500).round;
+        assert_eq!(leases.lease(&online(), other, "c", now).unwrap().1.first, 1);
+
+        let (c, s3) = leases.lease(&all, r, "a", now).unwrap();
+        assert_eq!((s3.first, s3.last), (2001, 2500));
+        assert_eq!(leases.lease(&all, r, "b", now), None);
+
+        // Walked while its last slice is still out: a joining stream starts the
+        // next round instead of getting nothing.
+        leases.release(&all, a);
+        leases.release(&all, b);
+        leases.leave(&all, r);
+        let next = leases.join(&all, 3000);
+        assert_ne!(next.round, r);
+        assert_eq!((next.cursor, next.streams), (0, 1));
+        // The old round's last stream finishes without touching the new one.
+        assert_eq!(leases.lease(&all, r, "a", now), None);
+        leases.release(&all, c);
+        leases.leave(&all, r);
+        assert_eq!(leases.join(&all, 3000).streams, 2);
+        assert_eq!(leases.lease(&all, next.round, "b", now).unwrap().1.first, 1);
+    }
+
+    #[test]
+    fn dropped_and_stuck_slices_are_reassigned() {
+        let (all, now) = (ServerFilters::default(), Instant::now());
+        let mut leases = UpdateLeases::default();
+        let r = leases.join(&all, 10_000).round;
+        let (_, s1) = leases.lease(&all, r, "a", now).unwrap();
+        let (_, s2) = leases.lease(&all, r, "b", now).unwrap();
+        // "a" stays connected; "b" drops while still streaming.
+        assert!(leases.drop_worker("b").is_empty());
+        assert_eq!(leases.lease(&all, r, "a", now).unwrap().1, s2);
+        // "a" stops pulling; its first slice goes to the next taker once the
+        // lease expires.
+        let later = now + LEASE_TTL;
+        assert_eq!(leases.lease(&all, r, "c", later).unwrap().1, s1);
+
+        // A worker dropping after every stream left stalls the round.
+        leases.leave(&all, r);
+        assert_eq!(leases.drop_worker("c"), vec![all]);
+    }
+}
@@ -36,7 +36,7 @@ function App() {
                       <AppShell>
                         <Routes>
                           <Route path="/" element={<Dashboard />} />
-                          <Route path="/server/:ip" element={<ServerDetail />} />
+                          <Route path="/server/:ip/:port?/:edition?" element={<ServerDetail />} />
                           <Route path="/stats" element={<Stats />} />
                           <Route path="/players" element={<Players />} />
                           <Route path="/workers" element={<Workers />} />
@@ -3,7 +3,7 @@ import { createGrpcWebTransport } from "@connectrpc/connect-web";
 import { Api } from "@/gen/api_pb";
 import { PlayerStatus as PbPlayerStatus, JoinStatus as PbJoinStatus } from "@/gen/api_pb";
 import type { WorkerInfo } from "@/gen/api_pb";
-import { Control } from "@/gen/worker_pb";
+import { Control, Edition as PbEdition } from "@/gen/worker_pb";
 import type { ServerFilter } from "@/gen/worker_pb";
 import type { ServerFilterValue } from "@/constants/dashboardFilters";
 import type {
@@ -11,6 +11,8 @@ import type {
   StatsResponse,
   ServerSnapshotsRequest,
   ServerListRequest,
+  Edition,
+  ServerInfoRequest,
   ServerInfoResponse,
   ServerSnapshotsResponse,
   AddAddrRequest,
@@ -93,10 +95,21 @@ const JOIN_STATUS_TO_NUM: Record<JoinStatus, PbJoinStatus> = {
   Broken: PbJoinStatus.BROKEN,
 };
 
+const EDITION_TO_STR: Record<number, Edition> = {
+  [PbEdition.JAVA]: "java",
+  [PbEdition.BEDROCK]: "bedrock",
+};
+const EDITION_TO_NUM: Record<Edition, PbEdition> = {
+  java: PbEdition.JAVA,
+  bedrock: PbEdition.BEDROCK,
+};
+
 type PbServerInfo = Awaited<ReturnType<typeof client.getServerInfo>>;
 const toServerInfo = (s: PbServerInfo): ServerInfoResponse => ({
   id: s.id,
   ip: s.ip,
+  port: s.port,
+  edition: EDITION_TO_STR[s.edition] ?? "java",
   online: s.online,
   max: s.max,
   version_name: s.versionName,
@@ -178,17 +191,24 @@ export const serverApi = {
     return res.servers.map(toServerInfo);
   },
 
-  fetchServerInfo: async (ip: string): Promise<ServerInfoResponse> => {
-    return toServerInfo(await client.getServerInfo({ ip }));
+  fetchServerInfo: async ({
+    ip,
+    port,
+    edition,
+  }: ServerInfoRequest): Promise<ServerInfoResponse> => {
+    return toServerInfo(
+      await client.getServerInfo({ ip, port, edition: EDITION_TO_NUM[edition] }),
+    );
   },
 
   // Live subscription: yields the current ServerInfo immediately, then again
   // each time the server's row changes (manual ping or background re-probe).
   streamServerInfo: async function* (
-    ip: string,
+    { ip, port, edition }: ServerInfoRequest,
     signal: AbortSignal,
   ): AsyncGenerator<ServerInfoResponse> {
-    for await (const s of client.streamServerInfo({ ip }, { signal })) {
+    const request = { ip, port, edition: EDITION_TO_NUM[edition] };
+    for await (const s of client.streamServerInfo(request, { signal })) {
       yield toServerInfo(s);
     }
   },
@@ -211,6 +231,8 @@ export const serverApi = {
   updateServer: (body: UpdateServerRequest) =>
     client.updateServer({
       serverIp: body.server_ip,
+      serverPort: body.server_port,
+      serverEdition: EDITION_TO_NUM[body.server_edition],
       isChecked: u(body.is_checked) ?? undefined,
       joinStatus: body.join_status ? JOIN_STATUS_TO_NUM[body.join_status] : undefined,
       isCrashed: u(body.is_crashed) ?? undefined,
@@ -278,6 +300,8 @@ export const serverApi = {
       id: p.id,
       server_id: p.serverId,
       server_ip: p.serverIp,
+      server_port: p.serverPort,
+      server_edition: EDITION_TO_STR[p.serverEdition] ?? "java",
       name: p.name,
       status: STATUS_TO_STR[p.status] ?? "None",
       last_seen_at: p.lastSeenAt,
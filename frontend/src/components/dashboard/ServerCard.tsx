@@ -4,6 +4,7 @@ import { formatDistanceToNow } from "date-fns";
 import DOMPurify from "dompurify";
 import { cn } from "@/cn";
 import type { ServerInfoResponse } from "@/types";
+import { serverPath } from "@/utils/serverDetailHelpers";
 import { useTranslation } from "@/i18n";
 
 function getPingBadgeClass(ping: bigint | null): string {
@@ -54,7 +55,7 @@ export const ServerCard = memo(({ server, cardRef }: ServerCardProps) => {
   return (
     <Link
       ref={cardRef}
-      to={`/server/${server.ip}`}
+      to={serverPath(server.ip, server.port, server.edition)}
       className={cn(
         "block p-4 bg-panel border border-border rounded-xl transition-all duration-150",
         "hover:border-indigo-500/60 hover:shadow-lg hover:shadow-indigo-950/30",
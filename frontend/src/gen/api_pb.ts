@@ -4,7 +4,7 @@
 
 import type { GenEnum, GenFile, GenMessage, GenService } from "@bufbuild/protobuf/codegenv2";
 import { enumDesc, fileDesc, messageDesc, serviceDesc } from "@bufbuild/protobuf/codegenv2";
-import type { Control, WorkerConfig, WorkerMetrics } from "./worker_pb";
+import type { Control, Edition, LoginProbe, WorkerConfig, WorkerMetrics } from "./worker_pb";
 import { file_worker } from "./worker_pb";
 import type { Message } from "@bufbuild/protobuf";
 
@@ -12,7 +12,7 @@ import type { Message } from "@bufbuild/protobuf";
  * Describes the file api.proto.
  */
 export const file_api: GenFile = /*@__PURE__*/
-  fileDesc("CglhcGkucHJvdG8SA2FwaSIHCgVFbXB0eSIgCgxMb2dpblJlcXVlc3QSEAoIcGFzc3dvcmQYASABKAkiHgoNTG9naW5SZXNwb25zZRINCgV0b2tlbhgBIAEoCSItCgtWZXJzaW9uU3RhdBIPCgd2ZXJzaW9uGAEgASgJEg0KBWNvdW50GAIgASgDItgCCg1TdGF0c1Jlc3BvbnNlEhUKDXRvdGFsX3NlcnZlcnMYASABKAMSFwoPY3JhY2tlZF9zZXJ2ZXJzGAIgASgDEhYKDm9ubGluZV9zZXJ2ZXJzGAMgASgDEhcKD2NyYXNoZWRfc2VydmVycxgEIAEoAxIcChRtb2RfcmVxdWlyZWRfc2VydmVycxgFIAEoAxIZChFzcG9vZmFibGVfc2VydmVycxgGIAEoAxIVCg10b3RhbF9wbGF5ZXJzGAcgASgDEhUKDWFkbWluX3BsYXllcnMYCCABKAMSFQoIYXZnX3BpbmcYCSABKAFIAIgBARIuChR2ZXJzaW9uX2Rpc3RyaWJ1dGlvbhgKIAMoCzIQLmFwaS5WZXJzaW9uU3RhdBISCgpkYl9zaXplX21iGAsgASgBEhcKD2Zhdmljb25fc2l6ZV9tYhgMIAEoAUILCglfYXZnX3BpbmcitQMKEVNlcnZlckxpc3RSZXF1ZXN0Eg0KBWxpbWl0GAEgASgDEhYKCW9mZnNldF9pZBgCIAEoBUgAiAEBEhUKCGxpY2Vuc2VkGAMgASgISAGIAQESFAoHY2hlY2tlZBgEIAEoCEgCiAEBEikKC2pvaW5fc3RhdHVzGAUgASgOMg8uYXBpLkpvaW5TdGF0dXNIA4gBARIUCgdjcmFzaGVkGAYgASgISASIAQESGAoLaGFzX3BsYXllcnMYByABKAhIBYgBARITCgZvbmxpbmUYCCABKAhIBogBARIaCg1yZXF1aXJlc19tb2RzGAkgASgISAeIAQESHQoQaGFzX25vbmVfcGxheWVycxgKIAEoCEgIiAEBEhIKBXF1ZXJ5GAsgASgJSAmIAQFCDAoKX29mZnNldF9pZEILCglfbGljZW5zZWRCCgoIX2NoZWNrZWRCDgoMX2pvaW5fc3RhdHVzQgoKCF9jcmFzaGVkQg4KDF9oYXNfcGxheWVyc0IJCgdfb25saW5lQhAKDl9yZXF1aXJlc19tb2RzQhMKEV9oYXNfbm9uZV9wbGF5ZXJzQggKBl9xdWVyeSKcAwoKU2VydmVySW5mbxIKCgJpZBgBIAEoBRIKCgJpcBgCIAEoCRIOCgZvbmxpbmUYAyABKAUSCwoDbWF4GAQgASgFEhQKDHZlcnNpb25fbmFtZRgFIAEoCRIQCghwcm90b2NvbBgGIAEoBRIPCgdsaWNlbnNlGAcgASgIEiMKFmRpc2Nvbm5lY3RfcmVhc29uX2h0bWwYCCABKAlIAIgBARIPCgd1cGRhdGVkGAkgASgJEhgKEGRlc2NyaXB0aW9uX2h0bWwYCiABKAkSEgoKd2FzX29ubGluZRgLIAEoCBISCgppc19jaGVja2VkGAwgASgIEiQKC2pvaW5fc3RhdHVzGA0gASgOMg8uYXBpLkpvaW5TdGF0dXMSEgoKaXNfY3Jhc2hlZBgOIAEoCBIVCg1yZXF1aXJlc19tb2RzGA8gASgIEhQKB2Zhdmljb24YECABKAlIAYgBARIRCgRwaW5nGBEgASgDSAKIAQFCGQoXX2Rpc2Nvbm5lY3RfcmVhc29uX2h0bWxCCgoIX2Zhdmljb25CBwoFX3BpbmciNgoSU2VydmVyTGlzdFJlc3BvbnNlEiAKB3NlcnZlcnMYASADKAsyDy5hcGkuU2VydmVySW5mbyIfChFTZXJ2ZXJJbmZvUmVxdWVzdBIKCgJpcBgBIAEoCSI6ChZTZXJ2ZXJTbmFwc2hvdHNSZXF1ZXN0EhEKCXNlcnZlcl9pZBgBIAEoBRINCgVsaW1pdBgCIAEoAyJlCg5TZXJ2ZXJTbmFwc2hvdBIRCglzZXJ2ZXJfaWQYASABKAUSFgoOcGxheWVyc19vbmxpbmUYAiABKAUSEwoLcGxheWVyc19tYXgYAyABKAUSEwoLcmVjb3JkZWRfYXQYBCABKAkiQQoXU2VydmVyU25hcHNob3RzUmVzcG9uc2USJgoJc25hcHNob3RzGAEgAygLMhMuYXBpLlNlcnZlclNuYXBzaG90IrMBChNVcGRhdGVTZXJ2ZXJSZXF1ZXN0EhEKCXNlcnZlcl9pcBgBIAEoCRIXCgppc19jaGVja2VkGAIgASgISACIAQESKQoLam9pbl9zdGF0dXMYAyABKA4yDy5hcGkuSm9pblN0YXR1c0gBiAEBEhcKCmlzX2NyYXNoZWQYBCABKAhIAogBAUINCgtfaXNfY2hlY2tlZEIOCgxfam9pbl9zdGF0dXNCDQoLX2lzX2NyYXNoZWQi5AMKFk92ZXJ3cml0ZVNlcnZlclJlcXVlc3QSEQoJc2VydmVyX2lkGAEgASgFEhEKBHBvcnQYAiABKAVIAIgBARIZCgx2ZXJzaW9uX25hbWUYAyABKAlIAYgBARIVCghwcm90b2NvbBgEIAEoBUgCiAEBEhsKDmlzX29ubGluZV9tb2RlGAUgASgISAOIAQESGgoNcmVxdWlyZXNfbW9kcxgGIAEoCEgEiAEBEhYKCWlzX29ubGluZRgHIAEoCEgFiAEBEhEKBHBpbmcYCCABKANIBogBARIUCgdmYXZpY29uGAkgASgJSAeIAQESFwoKaXNfY2hlY2tlZBgKIAEoCEgIiAEBEikKC2pvaW5fc3RhdHVzGAsgASgOMg8uYXBpLkpvaW5TdGF0dXNICYgBARIXCgppc19jcmFzaGVkGAwgASgISAqIAQFCBwoFX3BvcnRCDwoNX3ZlcnNpb25fbmFtZUILCglfcHJvdG9jb2xCEQoPX2lzX29ubGluZV9tb2RlQhAKDl9yZXF1aXJlc19tb2RzQgwKCl9pc19vbmxpbmVCBwoFX3BpbmdCCgoIX2Zhdmljb25CDQoLX2lzX2NoZWNrZWRCDgoMX2pvaW5fc3RhdHVzQg0KC19pc19jcmFzaGVkIiEKE1NlcnZlckRlbGV0ZVJlcXVlc3QSCgoCaWQYASABKAUiUgoRUGluZ1NlcnZlclJlcXVlc3QSEQoJc2VydmVyX2lkGAEgASgFEhcKD3dpdGhfY29ubmVjdGlvbhgCIAEoCBIRCgl3b3JrZXJfaWQYAyABKAkiQAoOQWRkQWRkclJlcXVlc3QSDAoEYWRkchgBIAEoCRINCgVxdWljaxgCIAEoCBIRCgl3b3JrZXJfaWQYAyABKAkiTwoUQWRkVGFyZ2V0TGlzdFJlcXVlc3QSJAoHdGFyZ2V0cxgBIAMoCzITLmFwaS5BZGRBZGRyUmVxdWVzdBIRCgl3b3JrZXJfaWQYAiABKAkiJgoRUGxheWVyTGlzdFJlcXVlc3QSEQoJc2VydmVyX2lkGAEgASgFIm4KBlBsYXllchIKCgJpZBgBIAEoBRIRCglzZXJ2ZXJfaWQYAiABKAUSDAoEbmFtZRgDIAEoCRIhCgZzdGF0dXMYBCABKA4yES5hcGkuUGxheWVyU3RhdHVzEhQKDGxhc3Rfc2Vlbl9hdBgFIAEoCSIyChJQbGF5ZXJMaXN0UmVzcG9uc2USHAoHcGxheWVycxgBIAMoCzILLmFwaS5QbGF5ZXIizwEKE1BsYXllclNlYXJjaFJlcXVlc3QSDQoFbGltaXQYASABKAMSFgoJb2Zmc2V0X2lkGAIgASgFSACIAQESGgoNbmFtZV9jb250YWlucxgDIAEoCUgBiAEBEiYKBnN0YXR1cxgEIAEoDjIRLmFwaS5QbGF5ZXJTdGF0dXNIAogBARIVCghsaWNlbnNlZBgFIAEoCEgDiAEBQgwKCl9vZmZzZXRfaWRCEAoOX25hbWVfY29udGFpbnNCCQoHX3N0YXR1c0ILCglfbGljZW5zZWQinwEKElBsYXllclNlYXJjaFJlc3VsdBIKCgJpZBgBIAEoBRIRCglzZXJ2ZXJfaWQYAiABKAUSEQoJc2VydmVyX2lwGAMgASgJEgwKBG5hbWUYBCABKAkSIQoGc3RhdHVzGAUgASgOMhEuYXBpLlBsYXllclN0YXR1cxIUCgxsYXN0X3NlZW5fYXQYBiABKAkSEAoIbGljZW5zZWQYByABKAgiQAoUUGxheWVyU2VhcmNoUmVzcG9uc2USKAoHcGxheWVycxgBIAMoCzIXLmFwaS5QbGF5ZXJTZWFyY2hSZXN1bHQiRAoTVXBkYXRlUGxheWVyUmVxdWVzdBIKCgJpZBgBIAEoBRIhCgZzdGF0dXMYAiABKA4yES5hcGkuUGxheWVyU3RhdHVzIiEKE0RlbGV0ZVBsYXllclJlcXVlc3QSCgoCaWQYASABKAUiwgEKCldvcmtlckluZm8SEQoJd29ya2VyX2lkGAEgASgJEhEKBG5hbWUYAiABKAlIAIgBARIPCgd2ZXJzaW9uGAMgASgJEg4KBm9ubGluZRgEIAEoCBIWCg5sYXN0X3NlZW5fdW5peBgFIAEoAxIkCgZjb25maWcYBiABKAsyFC53b3JrZXIuV29ya2VyQ29uZmlnEiYKB21ldHJpY3MYByABKAsyFS53b3JrZXIuV29ya2VyTWV0cmljc0IHCgVfbmFtZSIuCgpXb3JrZXJMaXN0EiAKB3dvcmtlcnMYASADKAsyDy5hcGkuV29ya2VySW5mbyIlChBHZXRXb3JrZXJSZXF1ZXN0EhEKCXdvcmtlcl9pZBgBIAEoCSJUChlVcGRhdGVXb3JrZXJDb25maWdSZXF1ZXN0EhEKCXdvcmtlcl9pZBgBIAEoCRIkCgZjb25maWcYAiABKAsyFC53b3JrZXIuV29ya2VyQ29uZmlnIkUKFFNldFdvcmtlck5hbWVSZXF1ZXN0EhEKCXdvcmtlcl9pZBgBIAEoCRIRCgRuYW1lGAIgASgJSACIAQFCBwoFX25hbWUiSwoUQ29udHJvbFdvcmtlclJlcXVlc3QSEQoJd29ya2VyX2lkGAEgASgJEiAKB2NvbnRyb2wYAiABKA4yDy53b3JrZXIuQ29udHJvbCpaCgxQbGF5ZXJTdGF0dXMSFgoSUExBWUVSX1NUQVRVU19OT05FEAASGQoVUExBWUVSX1NUQVRVU19SRUdVTEFSEAESFwoTUExBWUVSX1NUQVRVU19BRE1JThACKqoBCgpKb2luU3RhdHVzEhwKGEpPSU5fU1RBVFVTX1VOREVURVJNSU5FRBAAEhkKFUpPSU5fU1RBVFVTX1NQT09GQUJMRRABEhkKFUpPSU5fU1RBVFVTX1dISVRFTElTVBACEhgKFEpPSU5fU1RBVFVTX1BBU1NXT1JEEAMSFgoSSk9JTl9TVEFUVVNfTU9EREVEEAQSFgoSSk9JTl9TVEFUVVNfQlJPS0VOEAUypwoKA0FwaRIuCgVMb2dpbhIRLmFwaS5Mb2dpblJlcXVlc3QaEi5hcGkuTG9naW5SZXNwb25zZRIcCgJNZRIKLmFwaS5FbXB0eRoKLmFwaS5FbXB0eRIqCghHZXRTdGF0cxIKLmFwaS5FbXB0eRoSLmFwaS5TdGF0c1Jlc3BvbnNlEicKDVRyaWdnZXJVcGRhdGUSCi5hcGkuRW1wdHkaCi5hcGkuRW1wdHkSPgoLTGlzdFNlcnZlcnMSFi5hcGkuU2VydmVyTGlzdFJlcXVlc3QaFy5hcGkuU2VydmVyTGlzdFJlc3BvbnNlEjgKDUdldFNlcnZlckluZm8SFi5hcGkuU2VydmVySW5mb1JlcXVlc3QaDy5hcGkuU2VydmVySW5mbxI9ChBTdHJlYW1TZXJ2ZXJJbmZvEhYuYXBpLlNlcnZlckluZm9SZXF1ZXN0Gg8uYXBpLlNlcnZlckluZm8wARJPChJHZXRTZXJ2ZXJTbmFwc2hvdHMSGy5hcGkuU2VydmVyU25hcHNob3RzUmVxdWVzdBocLmFwaS5TZXJ2ZXJTbmFwc2hvdHNSZXNwb25zZRI0CgxVcGRhdGVTZXJ2ZXISGC5hcGkuVXBkYXRlU2VydmVyUmVxdWVzdBoKLmFwaS5FbXB0eRI6Cg9PdmVyd3JpdGVTZXJ2ZXISGy5hcGkuT3ZlcndyaXRlU2VydmVyUmVxdWVzdBoKLmFwaS5FbXB0eRI0CgxEZWxldGVTZXJ2ZXISGC5hcGkuU2VydmVyRGVsZXRlUmVxdWVzdBoKLmFwaS5FbXB0eRIwCgpQaW5nU2VydmVyEhYuYXBpLlBpbmdTZXJ2ZXJSZXF1ZXN0GgouYXBpLkVtcHR5EiwKCUFkZFRhcmdldBITLmFwaS5BZGRBZGRyUmVxdWVzdBoKLmFwaS5FbXB0eRI2Cg1BZGRUYXJnZXRMaXN0EhkuYXBpLkFkZFRhcmdldExpc3RSZXF1ZXN0GgouYXBpLkVtcHR5Ej4KC0xpc3RQbGF5ZXJzEhYuYXBpLlBsYXllckxpc3RSZXF1ZXN0GhcuYXBpLlBsYXllckxpc3RSZXNwb25zZRJECg1TZWFyY2hQbGF5ZXJzEhguYXBpLlBsYXllclNlYXJjaFJlcXVlc3QaGS5hcGkuUGxheWVyU2VhcmNoUmVzcG9uc2USNAoMVXBkYXRlUGxheWVyEhguYXBpLlVwZGF0ZVBsYXllclJlcXVlc3QaCi5hcGkuRW1wdHkSNAoMRGVsZXRlUGxheWVyEhguYXBpLkRlbGV0ZVBsYXllclJlcXVlc3QaCi5hcGkuRW1wdHkSKgoLTGlzdFdvcmtlcnMSCi5hcGkuRW1wdHkaDy5hcGkuV29ya2VyTGlzdBIuCg1TdHJlYW1Xb3JrZXJzEgouYXBpLkVtcHR5Gg8uYXBpLldvcmtlckxpc3QwARIzCglHZXRXb3JrZXISFS5hcGkuR2V0V29ya2VyUmVxdWVzdBoPLmFwaS5Xb3JrZXJJbmZvEkAKElVwZGF0ZVdvcmtlckNvbmZpZxIeLmFwaS5VcGRhdGVXb3JrZXJDb25maWdSZXF1ZXN0GgouYXBpLkVtcHR5EjYKDVNldFdvcmtlck5hbWUSGS5hcGkuU2V0V29ya2VyTmFtZVJlcXVlc3QaCi5hcGkuRW1wdHkSNgoNQ29udHJvbFdvcmtlchIZLmFwaS5Db250cm9sV29ya2VyUmVxdWVzdBoKLmFwaS5FbXB0eWIGcHJvdG8z", [file_worker]);
+  fileDesc("CglhcGkucHJvdG8SA2FwaSIHCgVFbXB0eSIgCgxMb2dpblJlcXVlc3QSEAoIcGFzc3dvcmQYASABKAkiHgoNTG9naW5SZXNwb25zZRINCgV0b2tlbhgBIAEoCSItCgtWZXJzaW9uU3RhdBIPCgd2ZXJzaW9uGAEgASgJEg0KBWNvdW50GAIgASgDItgCCg1TdGF0c1Jlc3BvbnNlEhUKDXRvdGFsX3NlcnZlcnMYASABKAMSFwoPY3JhY2tlZF9zZXJ2ZXJzGAIgASgDEhYKDm9ubGluZV9zZXJ2ZXJzGAMgASgDEhcKD2NyYXNoZWRfc2VydmVycxgEIAEoAxIcChRtb2RfcmVxdWlyZWRfc2VydmVycxgFIAEoAxIZChFzcG9vZmFibGVfc2VydmVycxgGIAEoAxIVCg10b3RhbF9wbGF5ZXJzGAcgASgDEhUKDWFkbWluX3BsYXllcnMYCCABKAMSFQoIYXZnX3BpbmcYCSABKAFIAIgBARIuChR2ZXJzaW9uX2Rpc3RyaWJ1dGlvbhgKIAMoCzIQLmFwaS5WZXJzaW9uU3RhdBISCgpkYl9zaXplX21iGAsgASgBEhcKD2Zhdmljb25fc2l6ZV9tYhgMIAEoAUILCglfYXZnX3BpbmciZgoTU3RhdHNIaXN0b3J5UmVxdWVzdBISCgVzaW5jZRgBIAEoCUgAiAEBEhIKBXVudGlsGAIgASgJSAGIAQESEwoLYnVja2V0X3NlY3MYAyABKANCCAoGX3NpbmNlQggKBl91bnRpbCJNChBXb3JrZXJUaHJvdWdocHV0EhEKCXdvcmtlcl9pZBgBIAEoCRIRCglzY2FuX3JhdGUYAiABKAESEwoLdXBkYXRlX3JhdGUYAyABKAEigAIKClN0YXRzUG9pbnQSDgoGYnVja2V0GAEgASgJEhUKDXRvdGFsX3NlcnZlcnMYAiABKAMSFgoOb25saW5lX3NlcnZlcnMYAyABKAMSFwoPY3JhY2tlZF9zZXJ2ZXJzGAQgASgDEhUKDXRvdGFsX3BsYXllcnMYBSABKAMSFgoOcGxheWVyc19vbmxpbmUYBiABKAMSLgoUdmVyc2lvbl9kaXN0cmlidXRpb24YByADKAsyEC5hcGkuVmVyc2lvblN0YXQSEwoLbmV3X3NlcnZlcnMYCCABKAMSJgoHd29ya2VycxgJIAMoCzIVLmFwaS5Xb3JrZXJUaHJvdWdocHV0Ii8KDFN0YXRzSGlzdG9yeRIfCgZwb2ludHMYASADKAsyDy5hcGkuU3RhdHNQb2ludCKnBQoRU2VydmVyTGlzdFJlcXVlc3QSDQoFbGltaXQYASABKAMSFgoJb2Zmc2V0X2lkGAIgASgFSACIAQESFQoIbGljZW5zZWQYAyABKAhIAYgBARIUCgdjaGVja2VkGAQgASgISAKIAQESKQoLam9pbl9zdGF0dXMYBSABKA4yDy5hcGkuSm9pblN0YXR1c0gDiAEBEhQKB2NyYXNoZWQYBiABKAhIBIgBARIYCgtoYXNfcGxheWVycxgHIAEoCEgFiAEBEhMKBm9ubGluZRgIIAEoCEgGiAEBEhoKDXJlcXVpcmVzX21vZHMYCSABKAhIB4gBARIdChBoYXNfbm9uZV9wbGF5ZXJzGAogASgISAiIAQESEgoFcXVlcnkYCyABKAlICYgBARIlCgdlZGl0aW9uGAwgASgOMg8ud29ya2VyLkVkaXRpb25ICogBARIzChVzdWdnZXN0ZWRfam9pbl9zdGF0dXMYDSABKA4yDy5hcGkuSm9pblN0YXR1c0gLiAEBEhMKBmpvYl9pZBgOIAEoA0gMiAEBEhQKB2pvYl9uZXcYDyABKAhIDYgBARIaCg1jaGFuZ2VkX3NpbmNlGBAgASgJSA6IAQFCDAoKX29mZnNldF9pZEILCglfbGljZW5zZWRCCgoIX2NoZWNrZWRCDgoMX2pvaW5fc3RhdHVzQgoKCF9jcmFzaGVkQg4KDF9oYXNfcGxheWVyc0IJCgdfb25saW5lQhAKDl9yZXF1aXJlc19tb2RzQhMKEV9oYXNfbm9uZV9wbGF5ZXJzQggKBl9xdWVyeUIKCghfZWRpdGlvbkIYChZfc3VnZ2VzdGVkX2pvaW5fc3RhdHVzQgkKB19qb2JfaWRCCgoIX2pvYl9uZXdCEAoOX2NoYW5nZWRfc2luY2Ui/gcKClNlcnZlckluZm8SCgoCaWQYASABKAUSCgoCaXAYAiABKAkSDgoGb25saW5lGAMgASgFEgsKA21heBgEIAEoBRIUCgx2ZXJzaW9uX25hbWUYBSABKAkSEAoIcHJvdG9jb2wYBiABKAUSDwoHbGljZW5zZRgHIAEoCBIjChZkaXNjb25uZWN0X3JlYXNvbl9odG1sGAggASgJSACIAQESDwoHdXBkYXRlZBgJIAEoCRIYChBkZXNjcmlwdGlvbl9odG1sGAogASgJEhIKCndhc19vbmxpbmUYCyABKAgSEgoKaXNfY2hlY2tlZBgMIAEoCBIkCgtqb2luX3N0YXR1cxgNIAEoDjIPLmFwaS5Kb2luU3RhdHVzEhIKCmlzX2NyYXNoZWQYDiABKAgSFQoNcmVxdWlyZXNfbW9kcxgPIAEoCBIUCgdmYXZpY29uGBAgASgJSAGIAQESEQoEcGluZxgRIAEoA0gCiAEBEgwKBHBvcnQYEiABKAUSIAoHZWRpdGlvbhgTIAEoDjIPLndvcmtlci5FZGl0aW9uEhYKCWdhbWVfbW9kZRgUIAEoCUgDiAEBEhUKCHNvZnR3YXJlGBUgASgJSASIAQESDwoHcGx1Z2lucxgWIAMoCRIQCgNtYXAYFyABKAlIBYgBARIgChNtaW5fY2xpZW50X3Byb3RvY29sGBggASgFSAaIAQESIAoTbWF4X2NsaWVudF9wcm90b2NvbBgZIAEoBUgHiAEBEiwKC2xvZ2luX3Byb2JlGBogASgLMhIud29ya2VyLkxvZ2luUHJvYmVICIgBARIzChVzdWdnZXN0ZWRfam9pbl9zdGF0dXMYGyABKA4yDy5hcGkuSm9pblN0YXR1c0gJiAEBEiIKFXN1Z2dlc3Rpb25fY29uZmlkZW5jZRgcIAEoAkgKiAEBEhUKCGhvc3RuYW1lGB0gASgJSAuIAQESKgoOcHJvYmVfcHJpb3JpdHkYHiABKA4yEi5hcGkuUHJvYmVQcmlvcml0eRIaCg1uZXh0X3Byb2JlX2F0GB8gASgJSAyIAQFCGQoXX2Rpc2Nvbm5lY3RfcmVhc29uX2h0bWxCCgoIX2Zhdmljb25CBwoFX3BpbmdCDAoKX2dhbWVfbW9kZUILCglfc29mdHdhcmVCBgoEX21hcEIWChRfbWluX2NsaWVudF9wcm90b2NvbEIWChRfbWF4X2NsaWVudF9wcm90b2NvbEIOCgxfbG9naW5fcHJvYmVCGAoWX3N1Z2dlc3RlZF9qb2luX3N0YXR1c0IYChZfc3VnZ2VzdGlvbl9jb25maWRlbmNlQgsKCV9ob3N0bmFtZUIQCg5fbmV4dF9wcm9iZV9hdCI2ChJTZXJ2ZXJMaXN0UmVzcG9uc2USIAoHc2VydmVycxgBIAMoCzIPLmFwaS5TZXJ2ZXJJbmZvIk8KEVNlcnZlckluZm9SZXF1ZXN0EgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUSIAoHZWRpdGlvbhgDIAEoDjIPLndvcmtlci5FZGl0aW9uIqMBChZTZXJ2ZXJTbmFwc2hvdHNSZXF1ZXN0EhEKCXNlcnZlcl9pZBgBIAEoBRINCgVsaW1pdBgCIAEoAxIrCgpyZXNvbHV0aW9uGAMgASgOMhcuYXBpLlNuYXBzaG90UmVzb2x1dGlvbhISCgVzaW5jZRgEIAEoCUgAiAEBEhIKBXVudGlsGAUgASgJSAGIAQFCCAoGX3NpbmNlQggKBl91bnRpbCLuAQoOU2VydmVyU25hcHNob3QSEQoJc2VydmVyX2lkGAEgASgFEhYKDnBsYXllcnNfb25saW5lGAIgASgFEhMKC3BsYXllcnNfbWF4GAMgASgFEhMKC3JlY29yZGVkX2F0GAQgASgJEhcKCm9ubGluZV9taW4YBSABKAVIAIgBARIXCgpvbmxpbmVfbWF4GAYgASgFSAGIAQESFwoKb25saW5lX2F2ZxgHIAEoAUgCiAEBEg8KB3NhbXBsZXMYCCABKAVCDQoLX29ubGluZV9taW5CDQoLX29ubGluZV9tYXhCDQoLX29ubGluZV9hdmciQQoXU2VydmVyU25hcHNob3RzUmVzcG9uc2USJgoJc25hcHNob3RzGAEgAygLMhMuYXBpLlNlcnZlclNuYXBzaG90Io4BChRTZXJ2ZXJIaXN0b3J5UmVxdWVzdBIRCglzZXJ2ZXJfaWQYASABKAUSFgoJYmVmb3JlX2lkGAIgASgDSACIAQESDQoFbGltaXQYAyABKAMSJAoFZmllbGQYBCABKA4yEC5hcGkuU2VydmVyRmllbGRIAYgBAUIMCgpfYmVmb3JlX2lkQggKBl9maWVsZCKuAQoMU2VydmVyQ2hhbmdlEgoKAmlkGAEgASgDEhEKCXNlcnZlcl9pZBgCIAEoBRIfCgVmaWVsZBgDIAEoDjIQLmFwaS5TZXJ2ZXJGaWVsZBIWCglvbGRfdmFsdWUYBCABKAlIAIgBARIWCgluZXdfdmFsdWUYBSABKAlIAYgBARISCgpjaGFuZ2VkX2F0GAYgASgJQgwKCl9vbGRfdmFsdWVCDAoKX25ld192YWx1ZSIzCg1TZXJ2ZXJIaXN0b3J5EiIKB2NoYW5nZXMYASADKAsyES5hcGkuU2VydmVyQ2hhbmdlIrUCChNVcGRhdGVTZXJ2ZXJSZXF1ZXN0EhEKCXNlcnZlcl9pcBgBIAEoCRIXCgppc19jaGVja2VkGAIgASgISACIAQESKQoLam9pbl9zdGF0dXMYAyABKA4yDy5hcGkuSm9pblN0YXR1c0gBiAEBEhcKCmlzX2NyYXNoZWQYBCABKAhIAogBARITCgtzZXJ2ZXJfcG9ydBgFIAEoBRIvCg5wcm9iZV9wcmlvcml0eRgGIAEoDjISLmFwaS5Qcm9iZVByaW9yaXR5SAOIAQESJwoOc2VydmVyX2VkaXRpb24YByABKA4yDy53b3JrZXIuRWRpdGlvbkINCgtfaXNfY2hlY2tlZEIOCgxfam9pbl9zdGF0dXNCDQoLX2lzX2NyYXNoZWRCEQoPX3Byb2JlX3ByaW9yaXR5IuQDChZPdmVyd3JpdGVTZXJ2ZXJSZXF1ZXN0EhEKCXNlcnZlcl9pZBgBIAEoBRIRCgRwb3J0GAIgASgFSACIAQESGQoMdmVyc2lvbl9uYW1lGAMgASgJSAGIAQESFQoIcHJvdG9jb2wYBCABKAVIAogBARIbCg5pc19vbmxpbmVfbW9kZRgFIAEoCEgDiAEBEhoKDXJlcXVpcmVzX21vZHMYBiABKAhIBIgBARIWCglpc19vbmxpbmUYByABKAhIBYgBARIRCgRwaW5nGAggASgDSAaIAQESFAoHZmF2aWNvbhgJIAEoCUgHiAEBEhcKCmlzX2NoZWNrZWQYCiABKAhICIgBARIpCgtqb2luX3N0YXR1cxgLIAEoDjIPLmFwaS5Kb2luU3RhdHVzSAmIAQESFwoKaXNfY3Jhc2hlZBgMIAEoCEgKiAEBQgcKBV9wb3J0Qg8KDV92ZXJzaW9uX25hbWVCCwoJX3Byb3RvY29sQhEKD19pc19vbmxpbmVfbW9kZUIQCg5fcmVxdWlyZXNfbW9kc0IMCgpfaXNfb25saW5lQgcKBV9waW5nQgoKCF9mYXZpY29uQg0KC19pc19jaGVja2VkQg4KDF9qb2luX3N0YXR1c0INCgtfaXNfY3Jhc2hlZCIhChNTZXJ2ZXJEZWxldGVSZXF1ZXN0EgoKAmlkGAEgASgFIk8KDldvcmtlclNlbGVjdG9yEh0KBHBpY2sYASABKA4yDy5hcGkuV29ya2VyUGljaxITCgZyZWdpb24YAiABKAlIAIgBAUIJCgdfcmVnaW9uInsKEVBpbmdTZXJ2ZXJSZXF1ZXN0EhEKCXNlcnZlcl9pZBgBIAEoBRIXCg93aXRoX2Nvbm5lY3Rpb24YAiABKAgSEQoJd29ya2VyX2lkGAMgASgJEicKCmFueV93b3JrZXIYBCABKAsyEy5hcGkuV29ya2VyU2VsZWN0b3IiiwEKDkFkZEFkZHJSZXF1ZXN0EgwKBGFkZHIYASABKAkSDQoFcXVpY2sYAiABKAgSEQoJd29ya2VyX2lkGAMgASgJEiAKB2VkaXRpb24YBCABKA4yDy53b3JrZXIuRWRpdGlvbhInCgphbnlfd29ya2VyGAUgASgLMhMuYXBpLldvcmtlclNlbGVjdG9yIoYBChRBZGRUYXJnZXRMaXN0UmVxdWVzdBIkCgd0YXJnZXRzGAEgAygLMhMuYXBpLkFkZEFkZHJSZXF1ZXN0EhEKCXdvcmtlcl9pZBgCIAEoCRInCgphbnlfd29ya2VyGAMgASgLMhMuYXBpLldvcmtlclNlbGVjdG9yEgwKBG5hbWUYBCABKAkizAEKCVRhcmdldEpvYhIKCgJpZBgBIAEoAxIMCgRuYW1lGAIgASgJEhIKCmNyZWF0ZWRfYXQYAyABKAkSDQoFdG90YWwYBCABKAMSDgoGcXVldWVkGAUgASgDEg4KBnByb2JlZBgGIAEoAxIOCgZmYWlsZWQYByABKAMSEQoJY2FuY2VsbGVkGAggASgDEhEKCXJlc3BvbmRlZBgJIAEoAxITCgtuZXdfc2VydmVycxgKIAEoAxIXCg91cGRhdGVkX3NlcnZlcnMYCyABKAMiLQoNVGFyZ2V0Sm9iTGlzdBIcCgRqb2JzGAEgAygLMg4uYXBpLlRhcmdldEpvYiIkChJKb2JQcm9ncmVzc1JlcXVlc3QSDgoGam9iX2lkGAEgASgDIiQKFkRlbGV0ZVRhcmdldEpvYlJlcXVlc3QSCgoCaWQYASABKAMi6gMKBFRhc2sSCgoCaWQYASABKAMSGwoEa2luZBgCIAEoDjINLmFwaS5UYXNrS2luZBIKCgJpcBgDIAEoCRIMCgRwb3J0GAQgASgFEhEKBGhvc3QYBSABKAlIAIgBARIgCgdlZGl0aW9uGAYgASgOMg8ud29ya2VyLkVkaXRpb24SFwoPd2l0aF9jb25uZWN0aW9uGAcgASgIEhYKCXdvcmtlcl9pZBgIIAEoCUgBiAEBEicKCmFueV93b3JrZXIYCSABKAsyEy5hcGkuV29ya2VyU2VsZWN0b3ISHQoFc3RhdGUYCiABKA4yDi5hcGkuVGFza1N0YXRlEhYKCWxlYXNlZF90bxgLIAEoCUgCiAEBEhAKCGF0dGVtcHRzGAwgASgFEhcKCmxhc3RfZXJyb3IYDSABKAlIA4gBARISCgpjcmVhdGVkX2F0GA4gASgJEhIKCnVwZGF0ZWRfYXQYDyABKAkSHQoQbGVhc2VfZXhwaXJlc19hdBgQIAEoCUgEiAEBEhMKBmpvYl9pZBgRIAEoA0gFiAEBQgcKBV9ob3N0QgwKCl93b3JrZXJfaWRCDAoKX2xlYXNlZF90b0INCgtfbGFzdF9lcnJvckITChFfbGVhc2VfZXhwaXJlc19hdEIJCgdfam9iX2lkIrsBChBMaXN0VGFza3NSZXF1ZXN0EiIKBXN0YXRlGAEgASgOMg4uYXBpLlRhc2tTdGF0ZUgAiAEBEhYKCXdvcmtlcl9pZBgCIAEoCUgBiAEBEg0KBWxpbWl0GAMgASgDEhYKCWJlZm9yZV9pZBgEIAEoA0gCiAEBEhMKBmpvYl9pZBgFIAEoA0gDiAEBQggKBl9zdGF0ZUIMCgpfd29ya2VyX2lkQgwKCl9iZWZvcmVfaWRCCQoHX2pvYl9pZCJdCgpUYXNrQ291bnRzEg4KBnF1ZXVlZBgBIAEoAxIOCgZsZWFzZWQYAiABKAMSDAoEZG9uZRgDIAEoAxIOCgZmYWlsZWQYBCABKAMSEQoJY2FuY2VsbGVkGAUgASgDIkUKCFRhc2tMaXN0EhgKBXRhc2tzGAEgAygLMgkuYXBpLlRhc2sSHwoGY291bnRzGAIgASgLMg8uYXBpLlRhc2tDb3VudHMiKgoOVGFza0lkc1JlcXVlc3QSCwoDaWRzGAEgAygDEgsKA2FsbBgCIAEoCCIhCg1UYXNrc0FmZmVjdGVkEhAKCGFmZmVjdGVkGAEgASgDInkKFENyZWF0ZVNjYW5Kb2JSZXF1ZXN0EgwKBG5hbWUYASABKAkSDgoGcmFuZ2VzGAIgAygJEhMKC3Jhbmdlc19maWxlGAMgASgJEgwKBHBvcnQYBCABKAUSIAoHZWRpdGlvbhgFIAEoDjIPLndvcmtlci5FZGl0aW9uIvABCgdTY2FuSm9iEgoKAmlkGAEgASgFEgwKBG5hbWUYAiABKAkSDAoEcG9ydBgDIAEoBRIgCgdlZGl0aW9uGAQgASgOMg8ud29ya2VyLkVkaXRpb24SEgoKY3JlYXRlZF9hdBgFIAEoCRIYCgtmaW5pc2hlZF9hdBgGIAEoCUgAiAEBEhQKDHNoYXJkc190b3RhbBgHIAEoBRITCgtzaGFyZHNfZG9uZRgIIAEoBRIXCg9hZGRyZXNzZXNfdG90YWwYCSABKAMSGQoRYWRkcmVzc2VzX3NjYW5uZWQYCiABKANCDgoMX2ZpbmlzaGVkX2F0IikKC1NjYW5Kb2JMaXN0EhoKBGpvYnMYASADKAsyDC5hcGkuU2NhbkpvYiIiChREZWxldGVTY2FuSm9iUmVxdWVzdBIKCgJpZBgBIAEoBSJJCglFeGNsdXNpb24SCgoCaWQYASABKAUSDAoEY2lkchgCIAEoCRIOCgZyZWFzb24YAyABKAkSEgoKY3JlYXRlZF9hdBgEIAEoCSIzCg1FeGNsdXNpb25MaXN0EiIKCmV4Y2x1c2lvbnMYASADKAsyDi5hcGkuRXhjbHVzaW9uIjMKE0FkZEV4Y2x1c2lvblJlcXVlc3QSDAoEY2lkchgBIAEoCRIOCgZyZWFzb24YAiABKAkiJAoWRGVsZXRlRXhjbHVzaW9uUmVxdWVzdBIKCgJpZBgBIAEoBSIvChxQdXJnZUV4Y2x1ZGVkU2VydmVyc1Jlc3BvbnNlEg8KB2RlbGV0ZWQYASABKAMiJgoRUGxheWVyTGlzdFJlcXVlc3QSEQoJc2VydmVyX2lkGAEgASgFIm4KBlBsYXllchIKCgJpZBgBIAEoBRIRCglzZXJ2ZXJfaWQYAiABKAUSDAoEbmFtZRgDIAEoCRIhCgZzdGF0dXMYBCABKA4yES5hcGkuUGxheWVyU3RhdHVzEhQKDGxhc3Rfc2Vlbl9hdBgFIAEoCSIyChJQbGF5ZXJMaXN0UmVzcG9uc2USHAoHcGxheWVycxgBIAMoCzILLmFwaS5QbGF5ZXIizwEKE1BsYXllclNlYXJjaFJlcXVlc3QSDQoFbGltaXQYASABKAMSFgoJb2Zmc2V0X2lkGAIgASgFSACIAQESGgoNbmFtZV9jb250YWlucxgDIAEoCUgBiAEBEiYKBnN0YXR1cxgEIAEoDjIRLmFwaS5QbGF5ZXJTdGF0dXNIAogBARIVCghsaWNlbnNlZBgFIAEoCEgDiAEBQgwKCl9vZmZzZXRfaWRCEAoOX25hbWVfY29udGFpbnNCCQoHX3N0YXR1c0ILCglfbGljZW5zZWQi3QEKElBsYXllclNlYXJjaFJlc3VsdBIKCgJpZBgBIAEoBRIRCglzZXJ2ZXJfaWQYAiABKAUSEQoJc2VydmVyX2lwGAMgASgJEgwKBG5hbWUYBCABKAkSIQoGc3RhdHVzGAUgASgOMhEuYXBpLlBsYXllclN0YXR1cxIUCgxsYXN0X3NlZW5fYXQYBiABKAkSEAoIbGljZW5zZWQYByABKAgSEwoLc2VydmVyX3BvcnQYCCABKAUSJwoOc2VydmVyX2VkaXRpb24YCSABKA4yDy53b3JrZXIuRWRpdGlvbiJAChRQbGF5ZXJTZWFyY2hSZXNwb25zZRIoCgdwbGF5ZXJzGAEgAygLMhcuYXBpLlBsYXllclNlYXJjaFJlc3VsdCJEChNVcGRhdGVQbGF5ZXJSZXF1ZXN0EgoKAmlkGAEgASgFEiEKBnN0YXR1cxgCIAEoDjIRLmFwaS5QbGF5ZXJTdGF0dXMiIQoTRGVsZXRlUGxheWVyUmVxdWVzdBIKCgJpZBgBIAEoBSLiAQoKV29ya2VySW5mbxIRCgl3b3JrZXJfaWQYASABKAkSEQoEbmFtZRgCIAEoCUgAiAEBEg8KB3ZlcnNpb24YAyABKAkSDgoGb25saW5lGAQgASgIEhYKDmxhc3Rfc2Vlbl91bml4GAUgASgDEiQKBmNvbmZpZxgGIAEoCzIULndvcmtlci5Xb3JrZXJDb25maWcSJgoHbWV0cmljcxgHIAEoCzIVLndvcmtlci5Xb3JrZXJNZXRyaWNzEhMKBnJlZ2lvbhgIIAEoCUgBiAEBQgcKBV9uYW1lQgkKB19yZWdpb24iLgoKV29ya2VyTGlzdBIgCgd3b3JrZXJzGAEgAygLMg8uYXBpLldvcmtlckluZm8iJQoQR2V0V29ya2VyUmVxdWVzdBIRCgl3b3JrZXJfaWQYASABKAkiVAoZVXBkYXRlV29ya2VyQ29uZmlnUmVxdWVzdBIRCgl3b3JrZXJfaWQYASABKAkSJAoGY29uZmlnGAIgASgLMhQud29ya2VyLldvcmtlckNvbmZpZyJFChRTZXRXb3JrZXJOYW1lUmVxdWVzdBIRCgl3b3JrZXJfaWQYASABKAkSEQoEbmFtZRgCIAEoCUgAiAEBQgcKBV9uYW1lIksKFENvbnRyb2xXb3JrZXJSZXF1ZXN0EhEKCXdvcmtlcl9pZBgBIAEoCRIgCgdjb250cm9sGAIgASgOMg8ud29ya2VyLkNvbnRyb2wqWgoMUGxheWVyU3RhdHVzEhYKElBMQVlFUl9TVEFUVVNfTk9ORRAAEhkKFVBMQVlFUl9TVEFUVVNfUkVHVUxBUhABEhcKE1BMQVlFUl9TVEFUVVNfQURNSU4QAiqqAQoKSm9pblN0YXR1cxIcChhKT0lOX1NUQVRVU19VTkRFVEVSTUlORUQQABIZChVKT0lOX1NUQVRVU19TUE9PRkFCTEUQARIZChVKT0lOX1NUQVRVU19XSElURUxJU1QQAhIYChRKT0lOX1NUQVRVU19QQVNTV09SRBADEhYKEkpPSU5fU1RBVFVTX01PRERFRBAEEhYKEkpPSU5fU1RBVFVTX0JST0tFThAFKlsKDVByb2JlUHJpb3JpdHkSGQoVUFJPQkVfUFJJT1JJVFlfTk9STUFMEAASFwoTUFJPQkVfUFJJT1JJVFlfSElHSBABEhYKElBST0JFX1BSSU9SSVRZX0xPVxACKnAKElNuYXBzaG90UmVzb2x1dGlvbhIbChdTTkFQU0hPVF9SRVNPTFVUSU9OX1JBVxAAEh4KGlNOQVBTSE9UX1JFU09MVVRJT05fSE9VUkxZEAESHQoZU05BUFNIT1RfUkVTT0xVVElPTl9EQUlMWRACKsABCgtTZXJ2ZXJGaWVsZBIdChlTRVJWRVJfRklFTERfVkVSU0lPTl9OQU1FEAASHAoYU0VSVkVSX0ZJRUxEX0RFU0NSSVBUSU9OEAESFQoRU0VSVkVSX0ZJRUxEX01PVEQQAhIYChRTRVJWRVJfRklFTERfRkFWSUNPThADEh8KG1NFUlZFUl9GSUVMRF9JU19PTkxJTkVfTU9ERRAEEiIKHlNFUlZFUl9GSUVMRF9ESVNDT05ORUNUX1JFQVNPThAFKkcKCldvcmtlclBpY2sSHAoYV09SS0VSX1BJQ0tfTEVBU1RfTE9BREVEEAASGwoXV09SS0VSX1BJQ0tfUk9VTkRfUk9CSU4QASp/CglUYXNrU3RhdGUSFQoRVEFTS19TVEFURV9RVUVVRUQQABIVChFUQVNLX1NUQVRFX0xFQVNFRBABEhMKD1RBU0tfU1RBVEVfRE9ORRACEhUKEVRBU0tfU1RBVEVfRkFJTEVEEAMSGAoUVEFTS19TVEFURV9DQU5DRUxMRUQQBCoyCghUYXNrS2luZBISCg5UQVNLX0tJTkRfUElORxAAEhIKDlRBU0tfS0lORF9TQ0FOEAEyjREKA0FwaRIuCgVMb2dpbhIRLmFwaS5Mb2dpblJlcXVlc3QaEi5hcGkuTG9naW5SZXNwb25zZRIcCgJNZRIKLmFwaS5FbXB0eRoKLmFwaS5FbXB0eRIqCghHZXRTdGF0cxIKLmFwaS5FbXB0eRoSLmFwaS5TdGF0c1Jlc3BvbnNlEj4KD0dldFN0YXRzSGlzdG9yeRIYLmFwaS5TdGF0c0hpc3RvcnlSZXF1ZXN0GhEuYXBpLlN0YXRzSGlzdG9yeRInCg1UcmlnZ2VyVXBkYXRlEgouYXBpLkVtcHR5GgouYXBpLkVtcHR5Ej4KC0xpc3RTZXJ2ZXJzEhYuYXBpLlNlcnZlckxpc3RSZXF1ZXN0GhcuYXBpLlNlcnZlckxpc3RSZXNwb25zZRI4Cg1HZXRTZXJ2ZXJJbmZvEhYuYXBpLlNlcnZlckluZm9SZXF1ZXN0Gg8uYXBpLlNlcnZlckluZm8SPQoQU3RyZWFtU2VydmVySW5mbxIWLmFwaS5TZXJ2ZXJJbmZvUmVxdWVzdBoPLmFwaS5TZXJ2ZXJJbmZvMAESTwoSR2V0U2VydmVyU25hcHNob3RzEhsuYXBpLlNlcnZlclNuYXBzaG90c1JlcXVlc3QaHC5hcGkuU2VydmVyU25hcHNob3RzUmVzcG9uc2USQQoQR2V0U2VydmVySGlzdG9yeRIZLmFwaS5TZXJ2ZXJIaXN0b3J5UmVxdWVzdBoSLmFwaS5TZXJ2ZXJIaXN0b3J5EjQKDFVwZGF0ZVNlcnZlchIYLmFwaS5VcGRhdGVTZXJ2ZXJSZXF1ZXN0GgouYXBpLkVtcHR5EjoKD092ZXJ3cml0ZVNlcnZlchIbLmFwaS5PdmVyd3JpdGVTZXJ2ZXJSZXF1ZXN0GgouYXBpLkVtcHR5EjQKDERlbGV0ZVNlcnZlchIYLmFwaS5TZXJ2ZXJEZWxldGVSZXF1ZXN0GgouYXBpLkVtcHR5EjAKClBpbmdTZXJ2ZXISFi5hcGkuUGluZ1NlcnZlclJlcXVlc3QaCi5hcGkuRW1wdHkSLAoJQWRkVGFyZ2V0EhMuYXBpLkFkZEFkZHJSZXF1ZXN0GgouYXBpLkVtcHR5EjoKDUFkZFRhcmdldExpc3QSGS5hcGkuQWRkVGFyZ2V0TGlzdFJlcXVlc3QaDi5hcGkuVGFyZ2V0Sm9iEjAKDkxpc3RUYXJnZXRKb2JzEgouYXBpLkVtcHR5GhIuYXBpLlRhcmdldEpvYkxpc3QSPgoRU3RyZWFtSm9iUHJvZ3Jlc3MSFy5hcGkuSm9iUHJvZ3Jlc3NSZXF1ZXN0Gg4uYXBpLlRhcmdldEpvYjABEjoKD0RlbGV0ZVRhcmdldEpvYhIbLmFwaS5EZWxldGVUYXJnZXRKb2JSZXF1ZXN0GgouYXBpLkVtcHR5EjEKCUxpc3RUYXNrcxIVLmFwaS5MaXN0VGFza3NSZXF1ZXN0Gg0uYXBpLlRhc2tMaXN0EjYKC0NhbmNlbFRhc2tzEhMuYXBpLlRhc2tJZHNSZXF1ZXN0GhIuYXBpLlRhc2tzQWZmZWN0ZWQSNQoKUmV0cnlUYXNrcxITLmFwaS5UYXNrSWRzUmVxdWVzdBoSLmFwaS5UYXNrc0FmZmVjdGVkEjgKDUNyZWF0ZVNjYW5Kb2ISGS5hcGkuQ3JlYXRlU2NhbkpvYlJlcXVlc3QaDC5hcGkuU2NhbkpvYhIsCgxMaXN0U2NhbkpvYnMSCi5hcGkuRW1wdHkaEC5hcGkuU2NhbkpvYkxpc3QSNgoNRGVsZXRlU2NhbkpvYhIZLmFwaS5EZWxldGVTY2FuSm9iUmVxdWVzdBoKLmFwaS5FbXB0eRIwCg5MaXN0RXhjbHVzaW9ucxIKLmFwaS5FbXB0eRoSLmFwaS5FeGNsdXNpb25MaXN0EjgKDEFkZEV4Y2x1c2lvbhIYLmFwaS5BZGRFeGNsdXNpb25SZXF1ZXN0Gg4uYXBpLkV4Y2x1c2lvbhI6Cg9EZWxldGVFeGNsdXNpb24SGy5hcGkuRGVsZXRlRXhjbHVzaW9uUmVxdWVzdBoKLmFwaS5FbXB0eRJFChRQdXJnZUV4Y2x1ZGVkU2VydmVycxIKLmFwaS5FbXB0eRohLmFwaS5QdXJnZUV4Y2x1ZGVkU2VydmVyc1Jlc3BvbnNlEj4KC0xpc3RQbGF5ZXJzEhYuYXBpLlBsYXllckxpc3RSZXF1ZXN0GhcuYXBpLlBsYXllckxpc3RSZXNwb25zZRJECg1TZWFyY2hQbGF5ZXJzEhguYXBpLlBsYXllclNlYXJjaFJlcXVlc3QaGS5hcGkuUGxheWVyU2VhcmNoUmVzcG9uc2USNAoMVXBkYXRlUGxheWVyEhguYXBpLlVwZGF0ZVBsYXllclJlcXVlc3QaCi5hcGkuRW1wdHkSNAoMRGVsZXRlUGxheWVyEhguYXBpLkRlbGV0ZVBsYXllclJlcXVlc3QaCi5hcGkuRW1wdHkSKgoLTGlzdFdvcmtlcnMSCi5hcGkuRW1wdHkaDy5hcGkuV29ya2VyTGlzdBIuCg1TdHJlYW1Xb3JrZXJzEgouYXBpLkVtcHR5Gg8uYXBpLldvcmtlckxpc3QwARIzCglHZXRXb3JrZXISFS5hcGkuR2V0V29ya2VyUmVxdWVzdBoPLmFwaS5Xb3JrZXJJbmZvEkAKElVwZGF0ZVdvcmtlckNvbmZpZxIeLmFwaS5VcGRhdGVXb3JrZXJDb25maWdSZXF1ZXN0GgouYXBpLkVtcHR5EjYKDVNldFdvcmtlck5hbWUSGS5hcGkuU2V0V29ya2VyTmFtZVJlcXVlc3QaCi5hcGkuRW1wdHkSNgoNQ29udHJvbFdvcmtlchIZLmFwaS5Db250cm9sV29ya2VyUmVxdWVzdBoKLmFwaS5FbXB0eWIGcHJvdG8z", [file_worker]);
 
 /**
  * @generated from message api.Empty
@@ -159,6 +159,157 @@ export type StatsResponse = Message<"api.StatsResponse"> & {
 export const StatsResponseSchema: GenMessage<StatsResponse> = /*@__PURE__*/
   messageDesc(file_api, 4);
 
+/**
+ * @generated from message api.StatsHistoryRequest
+ */
+export type StatsHistoryRequest = Message<"api.StatsHistoryRequest"> & {
+  /**
+   * RFC3339, inclusive; default: 7 days ago
+   *
+   * @generated from field: optional string since = 1;
+   */
+  since?: string | undefined;
+
+  /**
+   * RFC3339, exclusive; default: now
+   *
+   * @generated from field: optional string until = 2;
+   */
+  until?: string | undefined;
+
+  /**
+   * Width of each point; 0 = one hour. At least the sampling interval, and the
+   * range may span at most 5000 buckets.
+   *
+   * @generated from field: int64 bucket_secs = 3;
+   */
+  bucketSecs: bigint;
+};
+
+/**
+ * Describes the message api.StatsHistoryRequest.
+ * Use `create(StatsHistoryRequestSchema)` to create a new message.
+ */
+export const StatsHistoryRequestSchema: GenMessage<StatsHistoryRequest> = /*@__PURE__*/
+  messageDesc(file_api, 5);
+
+/**
+ * @generated from message api.WorkerThroughput
+ */
+export type WorkerThroughput = Message<"api.WorkerThroughput"> & {
+  /**
+   * @generated from field: string worker_id = 1;
+   */
+  workerId: string;
+
+  /**
+   * Mean probes/sec over the worker's samples in the bucket.
+   *
+   * @generated from field: double scan_rate = 2;
+   */
+  scanRate: number;
+
+  /**
+   * @generated from field: double update_rate = 3;
+   */
+  updateRate: number;
+};
+
+/**
+ * Describes the message api.WorkerThroughput.
+ * Use `create(WorkerThroughputSchema)` to create a new message.
+ */
+export const WorkerThroughputSchema: GenMessage<WorkerThroughput> = /*@__PURE__*/
+  messageDesc(file_api, 6);
+
+/**
+ * @generated from message api.StatsPoint
+ */
+export type StatsPoint = Message<"api.StatsPoint"> & {
+  /**
+   * RFC3339, start of the bucket (aligned to the Unix epoch)
+   *
+   * @generated from field: string bucket = 1;
+   */
+  bucket: string;
+
+  /**
+   * As of the last sample in the bucket.
+   *
+   * @generated from field: int64 total_servers = 2;
+   */
+  totalServers: bigint;
+
+  /**
+   * @generated from field: int64 online_servers = 3;
+   */
+  onlineServers: bigint;
+
+  /**
+   * @generated from field: int64 cracked_servers = 4;
+   */
+  crackedServers: bigint;
+
+  /**
+   * known players, as in StatsResponse
+   *
+   * @generated from field: int64 total_players = 5;
+   */
+  totalPlayers: bigint;
+
+  /**
+   * summed over the online servers' latest counts
+   *
+   * @generated from field: int64 players_online = 6;
+   */
+  playersOnline: bigint;
+
+  /**
+   * @generated from field: repeated api.VersionStat version_distribution = 7;
+   */
+  versionDistribution: VersionStat[];
+
+  /**
+   * Discovered during the bucket. Each sample counts the servers added since
+   * the one before it, so after the backend was down the whole gap lands in
+   * the first bucket sampled after it.
+   *
+   * @generated from field: int64 new_servers = 8;
+   */
+  newServers: bigint;
+
+  /**
+   * @generated from field: repeated api.WorkerThroughput workers = 9;
+   */
+  workers: WorkerThroughput[];
+};
+
+/**
+ * Describes the message api.StatsPoint.
+ * Use `create(StatsPointSchema)` to create a new message.
+ */
+export const StatsPointSchema: GenMessage<StatsPoint> = /*@__PURE__*/
+  messageDesc(file_api, 7);
+
+/**
+ * @generated from message api.StatsHistory
+ */
+export type StatsHistory = Message<"api.StatsHistory"> & {
+  /**
+   * buckets without samples are left out
+   *
+   * @generated from field: repeated api.StatsPoint points = 1;
+   */
+  points: StatsPoint[];
+};
+
+/**
+ * Describes the message api.StatsHistory.
+ * Use `create(StatsHistorySchema)` to create a new message.
+ */
+export const StatsHistorySchema: GenMessage<StatsHistory> = /*@__PURE__*/
+  messageDesc(file_api, 8);
+
 /**
  * ----- Servers -----
  *
@@ -221,6 +372,39 @@ export type ServerListRequest = Message<"api.ServerListRequest"> & {
    * @generated from field: optional string query = 11;
    */
   query?: string | undefined;
+
+  /**
+   * @generated from field: optional worker.Edition edition = 12;
+   */
+  edition?: Edition | undefined;
+
+  /**
+   * Matches the classifier's suggestion, not the manual `join_status`.
+   *
+   * @generated from field: optional api.JoinStatus suggested_join_status = 13;
+   */
+  suggestedJoinStatus?: JoinStatus | undefined;
+
+  /**
+   * Only servers that answered this target job's probes; with `job_new`, only
+   * those the job added (true) or those already known (false).
+   *
+   * @generated from field: optional int64 job_id = 14;
+   */
+  jobId?: bigint | undefined;
+
+  /**
+   * @generated from field: optional bool job_new = 15;
+   */
+  jobNew?: boolean | undefined;
+
+  /**
+   * RFC3339; only servers with a field tracked by GetServerHistory changed
+   * since then.
+   *
+   * @generated from field: optional string changed_since = 16;
+   */
+  changedSince?: string | undefined;
 };
 
 /**
@@ -228,7 +412,7 @@ export type ServerListRequest = Message<"api.ServerListRequest"> & {
  * Use `create(ServerListRequestSchema)` to create a new message.
  */
 export const ServerListRequestSchema: GenMessage<ServerListRequest> = /*@__PURE__*/
-  messageDesc(file_api, 5);
+  messageDesc(file_api, 9);
 
 /**
  * @generated from message api.ServerInfo
@@ -320,6 +504,94 @@ export type ServerInfo = Message<"api.ServerInfo"> & {
    * @generated from field: optional int64 ping = 17;
    */
   ping?: bigint | undefined;
+
+  /**
+   * @generated from field: int32 port = 18;
+   */
+  port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 19;
+   */
+  edition: Edition;
+
+  /**
+   * Bedrock only
+   *
+   * @generated from field: optional string game_mode = 20;
+   */
+  gameMode?: string | undefined;
+
+  /**
+   * From the last answered GameSpy4 query; unset if the server never answered one.
+   *
+   * @generated from field: optional string software = 21;
+   */
+  software?: string | undefined;
+
+  /**
+   * @generated from field: repeated string plugins = 22;
+   */
+  plugins: string[];
+
+  /**
+   * @generated from field: optional string map = 23;
+   */
+  map?: string | undefined;
+
+  /**
+   * Oldest/newest client protocol the server accepted when last negotiated
+   * (e.g. 47 / 767 = "joinable from 1.8 to 1.21"); unset if never negotiated.
+   *
+   * @generated from field: optional int32 min_client_protocol = 24;
+   */
+  minClientProtocol?: number | undefined;
+
+  /**
+   * @generated from field: optional int32 max_client_protocol = 25;
+   */
+  maxClientProtocol?: number | undefined;
+
+  /**
+   * Outcome of the last deep login probe; unset if the server was never
+   * probed that deep.
+   *
+   * @generated from field: optional worker.LoginProbe login_probe = 26;
+   */
+  loginProbe?: LoginProbe | undefined;
+
+  /**
+   * Join status suggested from the last login attempt (disconnect reason and
+   * deep login probe), with its confidence in 0..1. Never overwrites
+   * `join_status`; unset when nothing pointed anywhere.
+   *
+   * @generated from field: optional api.JoinStatus suggested_join_status = 27;
+   */
+  suggestedJoinStatus?: JoinStatus | undefined;
+
+  /**
+   * @generated from field: optional float suggestion_confidence = 28;
+   */
+  suggestionConfidence?: number | undefined;
+
+  /**
+   * The hostname the server was added by, if it was not an address.
+   *
+   * @generated from field: optional string hostname = 29;
+   */
+  hostname?: string | undefined;
+
+  /**
+   * @generated from field: api.ProbePriority probe_priority = 30;
+   */
+  probePriority: ProbePriority;
+
+  /**
+   * When the update cycle next re-probes the server (RFC3339); unset = due now.
+   *
+   * @generated from field: optional string next_probe_at = 31;
+   */
+  nextProbeAt?: string | undefined;
 };
 
 /**
@@ -327,7 +599,7 @@ export type ServerInfo = Message<"api.ServerInfo"> & {
  * Use `create(ServerInfoSchema)` to create a new message.
  */
 export const ServerInfoSchema: GenMessage<ServerInfo> = /*@__PURE__*/
-  messageDesc(file_api, 6);
+  messageDesc(file_api, 10);
 
 /**
  * @generated from message api.ServerListResponse
@@ -344,9 +616,13 @@ export type ServerListResponse = Message<"api.ServerListResponse"> & {
  * Use `create(ServerListResponseSchema)` to create a new message.
  */
 export const ServerListResponseSchema: GenMessage<ServerListResponse> = /*@__PURE__*/
-  messageDesc(file_api, 7);
+  messageDesc(file_api, 11);
 
 /**
+ * A server is identified by its (ip, port, edition) triple: a host may answer
+ * as a Java and a Bedrock server on the same port number (TCP vs UDP). An unset
+ * port (0) means the edition's default port, 25565 or 19132.
+ *
  * @generated from message api.ServerInfoRequest
  */
 export type ServerInfoRequest = Message<"api.ServerInfoRequest"> & {
@@ -354,6 +630,16 @@ export type ServerInfoRequest = Message<"api.ServerInfoRequest"> & {
    * @generated from field: string ip = 1;
    */
   ip: string;
+
+  /**
+   * @generated from field: int32 port = 2;
+   */
+  port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 3;
+   */
+  edition: Edition;
 };
 
 /**
@@ -361,7 +647,7 @@ export type ServerInfoRequest = Message<"api.ServerInfoRequest"> & {
  * Use `create(ServerInfoRequestSchema)` to create a new message.
  */
 export const ServerInfoRequestSchema: GenMessage<ServerInfoRequest> = /*@__PURE__*/
-  messageDesc(file_api, 8);
+  messageDesc(file_api, 12);
 
 /**
  * @generated from message api.ServerSnapshotsRequest
@@ -376,6 +662,25 @@ export type ServerSnapshotsRequest = Message<"api.ServerSnapshotsRequest"> & {
    * @generated from field: int64 limit = 2;
    */
   limit: bigint;
+
+  /**
+   * @generated from field: api.SnapshotResolution resolution = 3;
+   */
+  resolution: SnapshotResolution;
+
+  /**
+   * RFC3339, inclusive
+   *
+   * @generated from field: optional string since = 4;
+   */
+  since?: string | undefined;
+
+  /**
+   * RFC3339, exclusive
+   *
+   * @generated from field: optional string until = 5;
+   */
+  until?: string | undefined;
 };
 
 /**
@@ -383,7 +688,7 @@ export type ServerSnapshotsRequest = Message<"api.ServerSnapshotsRequest"> & {
  * Use `create(ServerSnapshotsRequestSchema)` to create a new message.
  */
 export const ServerSnapshotsRequestSchema: GenMessage<ServerSnapshotsRequest> = /*@__PURE__*/
-  messageDesc(file_api, 9);
+  messageDesc(file_api, 13);
 
 /**
  * @generated from message api.ServerSnapshot
@@ -395,6 +700,8 @@ export type ServerSnapshot = Message<"api.ServerSnapshot"> & {
   serverId: number;
 
   /**
+   * rollups: the average, rounded
+   *
    * @generated from field: int32 players_online = 2;
    */
   playersOnline: number;
@@ -405,11 +712,36 @@ export type ServerSnapshot = Message<"api.ServerSnapshot"> & {
   playersMax: number;
 
   /**
-   * RFC3339
+   * RFC3339; rollups: start of the hour or day (UTC)
    *
    * @generated from field: string recorded_at = 4;
    */
   recordedAt: string;
+
+  /**
+   * Rollups only: the range and mean of `players_online` over the bucket's
+   * `samples` raw samples.
+   *
+   * @generated from field: optional int32 online_min = 5;
+   */
+  onlineMin?: number | undefined;
+
+  /**
+   * @generated from field: optional int32 online_max = 6;
+   */
+  onlineMax?: number | undefined;
+
+  /**
+   * @generated from field: optional double online_avg = 7;
+   */
+  onlineAvg?: number | undefined;
+
+  /**
+   * 1 for a raw sample
+   *
+   * @generated from field: int32 samples = 8;
+   */
+  samples: number;
 };
 
 /**
@@ -417,7 +749,7 @@ export type ServerSnapshot = Message<"api.ServerSnapshot"> & {
  * Use `create(ServerSnapshotSchema)` to create a new message.
  */
 export const ServerSnapshotSchema: GenMessage<ServerSnapshot> = /*@__PURE__*/
-  messageDesc(file_api, 10);
+  messageDesc(file_api, 14);
 
 /**
  * @generated from message api.ServerSnapshotsResponse
@@ -434,73 +766,196 @@ export type ServerSnapshotsResponse = Message<"api.ServerSnapshotsResponse"> & {
  * Use `create(ServerSnapshotsResponseSchema)` to create a new message.
  */
 export const ServerSnapshotsResponseSchema: GenMessage<ServerSnapshotsResponse> = /*@__PURE__*/
-  messageDesc(file_api, 11);
+  messageDesc(file_api, 15);
 
 /**
- * @generated from message api.UpdateServerRequest
+ * @generated from message api.ServerHistoryRequest
  */
-export type UpdateServerRequest = Message<"api.UpdateServerRequest"> & {
+export type ServerHistoryRequest = Message<"api.ServerHistoryRequest"> & {
   /**
-   * @generated from field: string server_ip = 1;
+   * @generated from field: int32 server_id = 1;
    */
-  serverIp: string;
+  serverId: number;
 
   /**
-   * @generated from field: optional bool is_checked = 2;
+   * page: changes older than this one
+   *
+   * @generated from field: optional int64 before_id = 2;
    */
-  isChecked?: boolean | undefined;
+  beforeId?: bigint | undefined;
 
   /**
-   * @generated from field: optional api.JoinStatus join_status = 3;
+   * @generated from field: int64 limit = 3;
    */
-  joinStatus?: JoinStatus | undefined;
+  limit: bigint;
 
   /**
-   * @generated from field: optional bool is_crashed = 4;
+   * only changes to this field
+   *
+   * @generated from field: optional api.ServerField field = 4;
    */
-  isCrashed?: boolean | undefined;
+  field?: ServerField | undefined;
 };
 
 /**
- * Describes the message api.UpdateServerRequest.
- * Use `create(UpdateServerRequestSchema)` to create a new message.
+ * Describes the message api.ServerHistoryRequest.
+ * Use `create(ServerHistoryRequestSchema)` to create a new message.
  */
-export const UpdateServerRequestSchema: GenMessage<UpdateServerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 12);
+export const ServerHistoryRequestSchema: GenMessage<ServerHistoryRequest> = /*@__PURE__*/
+  messageDesc(file_api, 16);
 
 /**
- * @generated from message api.OverwriteServerRequest
+ * One field changed by one re-probe. Changes made by the same re-probe share
+ * `changed_at`.
+ *
+ * @generated from message api.ServerChange
  */
-export type OverwriteServerRequest = Message<"api.OverwriteServerRequest"> & {
+export type ServerChange = Message<"api.ServerChange"> & {
   /**
-   * @generated from field: int32 server_id = 1;
+   * @generated from field: int64 id = 1;
    */
-  serverId: number;
+  id: bigint;
 
   /**
-   * @generated from field: optional int32 port = 2;
+   * @generated from field: int32 server_id = 2;
    */
-  port?: number | undefined;
+  serverId: number;
 
   /**
-   * @generated from field: optional string version_name = 3;
+   * @generated from field: api.ServerField field = 3;
    */
-  versionName?: string | undefined;
+  field: ServerField;
 
   /**
-   * @generated from field: optional int32 protocol = 4;
+   * unset: the field had no value
+   *
+   * @generated from field: optional string old_value = 4;
    */
-  protocol?: number | undefined;
+  oldValue?: string | undefined;
 
   /**
-   * @generated from field: optional bool is_online_mode = 5;
+   * @generated from field: optional string new_value = 5;
    */
-  isOnlineMode?: boolean | undefined;
+  newValue?: string | undefined;
 
   /**
-   * @generated from field: optional bool requires_mods = 6;
+   * RFC3339
+   *
+   * @generated from field: string changed_at = 6;
    */
-  requiresMods?: boolean | undefined;
+  changedAt: string;
+};
+
+/**
+ * Describes the message api.ServerChange.
+ * Use `create(ServerChangeSchema)` to create a new message.
+ */
+export const ServerChangeSchema: GenMessage<ServerChange> = /*@__PURE__*/
+  messageDesc(file_api, 17);
+
+/**
+ * @generated from message api.ServerHistory
+ */
+export type ServerHistory = Message<"api.ServerHistory"> & {
+  /**
+   * newest first
+   *
+   * @generated from field: repeated api.ServerChange changes = 1;
+   */
+  changes: ServerChange[];
+};
+
+/**
+ * Describes the message api.ServerHistory.
+ * Use `create(ServerHistorySchema)` to create a new message.
+ */
+export const ServerHistorySchema: GenMessage<ServerHistory> = /*@__PURE__*/
+  messageDesc(file_api, 18);
+
+/**
+ * @generated from message api.UpdateServerRequest
+ */
+export type UpdateServerRequest = Message<"api.UpdateServerRequest"> & {
+  /**
+   * @generated from field: string server_ip = 1;
+   */
+  serverIp: string;
+
+  /**
+   * @generated from field: optional bool is_checked = 2;
+   */
+  isChecked?: boolean | undefined;
+
+  /**
+   * @generated from field: optional api.JoinStatus join_status = 3;
+   */
+  joinStatus?: JoinStatus | undefined;
+
+  /**
+   * @generated from field: optional bool is_crashed = 4;
+   */
+  isCrashed?: boolean | undefined;
+
+  /**
+   * 0 = the edition's default port, as in ServerInfoRequest
+   *
+   * @generated from field: int32 server_port = 5;
+   */
+  serverPort: number;
+
+  /**
+   * Changing it makes the server due at once, to be rescheduled by its probe.
+   *
+   * @generated from field: optional api.ProbePriority probe_priority = 6;
+   */
+  probePriority?: ProbePriority | undefined;
+
+  /**
+   * @generated from field: worker.Edition server_edition = 7;
+   */
+  serverEdition: Edition;
+};
+
+/**
+ * Describes the message api.UpdateServerRequest.
+ * Use `create(UpdateServerRequestSchema)` to create a new message.
+ */
+export const UpdateServerRequestSchema: GenMessage<UpdateServerRequest> = /*@__PURE__*/
+  messageDesc(file_api, 19);
+
+/**
+ * @generated from message api.OverwriteServerRequest
+ */
+export type OverwriteServerRequest = Message<"api.OverwriteServerRequest"> & {
+  /**
+   * @generated from field: int32 server_id = 1;
+   */
+  serverId: number;
+
+  /**
+   * @generated from field: optional int32 port = 2;
+   */
+  port?: number | undefined;
+
+  /**
+   * @generated from field: optional string version_name = 3;
+   */
+  versionName?: string | undefined;
+
+  /**
+   * @generated from field: optional int32 protocol = 4;
+   */
+  protocol?: number | undefined;
+
+  /**
+   * @generated from field: optional bool is_online_mode = 5;
+   */
+  isOnlineMode?: boolean | undefined;
+
+  /**
+   * @generated from field: optional bool requires_mods = 6;
+   */
+  requiresMods?: boolean | undefined;
 
   /**
    * @generated from field: optional bool is_online = 7;
@@ -538,7 +993,7 @@ export type OverwriteServerRequest = Message<"api.OverwriteServerRequest"> & {
  * Use `create(OverwriteServerRequestSchema)` to create a new message.
  */
 export const OverwriteServerRequestSchema: GenMessage<OverwriteServerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 13);
+  messageDesc(file_api, 20);
 
 /**
  * @generated from message api.ServerDeleteRequest
@@ -555,7 +1010,31 @@ export type ServerDeleteRequest = Message<"api.ServerDeleteRequest"> & {
  * Use `create(ServerDeleteRequestSchema)` to create a new message.
  */
 export const ServerDeleteRequestSchema: GenMessage<ServerDeleteRequest> = /*@__PURE__*/
-  messageDesc(file_api, 14);
+  messageDesc(file_api, 21);
+
+/**
+ * @generated from message api.WorkerSelector
+ */
+export type WorkerSelector = Message<"api.WorkerSelector"> & {
+  /**
+   * @generated from field: api.WorkerPick pick = 1;
+   */
+  pick: WorkerPick;
+
+  /**
+   * only workers with this `region` label
+   *
+   * @generated from field: optional string region = 2;
+   */
+  region?: string | undefined;
+};
+
+/**
+ * Describes the message api.WorkerSelector.
+ * Use `create(WorkerSelectorSchema)` to create a new message.
+ */
+export const WorkerSelectorSchema: GenMessage<WorkerSelector> = /*@__PURE__*/
+  messageDesc(file_api, 22);
 
 /**
  * @generated from message api.PingServerRequest
@@ -572,72 +1051,772 @@ export type PingServerRequest = Message<"api.PingServerRequest"> & {
   withConnection: boolean;
 
   /**
-   * worker chosen by the user to run this ping
+   * worker chosen by the user to run this ping; empty = any
+   *
+   * @generated from field: string worker_id = 3;
+   */
+  workerId: string;
+
+  /**
+   * used when `worker_id` is empty
+   *
+   * @generated from field: api.WorkerSelector any_worker = 4;
+   */
+  anyWorker?: WorkerSelector | undefined;
+};
+
+/**
+ * Describes the message api.PingServerRequest.
+ * Use `create(PingServerRequestSchema)` to create a new message.
+ */
+export const PingServerRequestSchema: GenMessage<PingServerRequest> = /*@__PURE__*/
+  messageDesc(file_api, 23);
+
+/**
+ * @generated from message api.AddAddrRequest
+ */
+export type AddAddrRequest = Message<"api.AddAddrRequest"> & {
+  /**
+   * ip, [ipv6] or hostname, with an optional :port
+   *
+   * @generated from field: string addr = 1;
+   */
+  addr: string;
+
+  /**
+   * @generated from field: bool quick = 2;
+   */
+  quick: boolean;
+
+  /**
+   * worker chosen by the user to run this scan; empty = any
+   *
+   * @generated from field: string worker_id = 3;
+   */
+  workerId: string;
+
+  /**
+   * which prober to scan the address with
+   *
+   * @generated from field: worker.Edition edition = 4;
+   */
+  edition: Edition;
+
+  /**
+   * used when `worker_id` is empty
+   *
+   * @generated from field: api.WorkerSelector any_worker = 5;
+   */
+  anyWorker?: WorkerSelector | undefined;
+};
+
+/**
+ * Describes the message api.AddAddrRequest.
+ * Use `create(AddAddrRequestSchema)` to create a new message.
+ */
+export const AddAddrRequestSchema: GenMessage<AddAddrRequest> = /*@__PURE__*/
+  messageDesc(file_api, 24);
+
+/**
+ * @generated from message api.AddTargetListRequest
+ */
+export type AddTargetListRequest = Message<"api.AddTargetListRequest"> & {
+  /**
+   * @generated from field: repeated api.AddAddrRequest targets = 1;
+   */
+  targets: AddAddrRequest[];
+
+  /**
+   * Worker chosen by the user to run the whole batch; empty = any, picked per
+   * target so the batch spreads over the workers.
+   *
+   * @generated from field: string worker_id = 2;
+   */
+  workerId: string;
+
+  /**
+   * used when `worker_id` is empty
+   *
+   * @generated from field: api.WorkerSelector any_worker = 3;
+   */
+  anyWorker?: WorkerSelector | undefined;
+
+  /**
+   * of the target job; empty = named after the first target
+   *
+   * @generated from field: string name = 4;
+   */
+  name: string;
+};
+
+/**
+ * Describes the message api.AddTargetListRequest.
+ * Use `create(AddTargetListRequestSchema)` to create a new message.
+ */
+export const AddTargetListRequestSchema: GenMessage<AddTargetListRequest> = /*@__PURE__*/
+  messageDesc(file_api, 25);
+
+/**
+ * ----- Target jobs -----
+ * Every AddTargetList batch is a target job: its targets are queued as tasks
+ * under the job's id, and the job counts how they went.
+ *
+ * @generated from message api.TargetJob
+ */
+export type TargetJob = Message<"api.TargetJob"> & {
+  /**
+   * @generated from field: int64 id = 1;
+   */
+  id: bigint;
+
+  /**
+   * @generated from field: string name = 2;
+   */
+  name: string;
+
+  /**
+   * RFC3339
+   *
+   * @generated from field: string created_at = 3;
+   */
+  createdAt: string;
+
+  /**
+   * targets queued
+   *
+   * @generated from field: int64 total = 4;
+   */
+  total: bigint;
+
+  /**
+   * not finished yet, leased ones included
+   *
+   * @generated from field: int64 queued = 5;
+   */
+  queued: bigint;
+
+  /**
+   * probed by a worker, and the server answered
+   *
+   * @generated from field: int64 probed = 6;
+   */
+  probed: bigint;
+
+  /**
+   * no answer, or the worker rejected the target
+   *
+   * @generated from field: int64 failed = 7;
+   */
+  failed: bigint;
+
+  /**
+   * @generated from field: int64 cancelled = 8;
+   */
+  cancelled: bigint;
+
+  /**
+   * Servers that answered, each counted once, split by whether the job added
+   * them to the server list.
+   *
+   * @generated from field: int64 responded = 9;
+   */
+  responded: bigint;
+
+  /**
+   * @generated from field: int64 new_servers = 10;
+   */
+  newServers: bigint;
+
+  /**
+   * @generated from field: int64 updated_servers = 11;
+   */
+  updatedServers: bigint;
+};
+
+/**
+ * Describes the message api.TargetJob.
+ * Use `create(TargetJobSchema)` to create a new message.
+ */
+export const TargetJobSchema: GenMessage<TargetJob> = /*@__PURE__*/
+  messageDesc(file_api, 26);
+
+/**
+ * @generated from message api.TargetJobList
+ */
+export type TargetJobList = Message<"api.TargetJobList"> & {
+  /**
+   * newest first
+   *
+   * @generated from field: repeated api.TargetJob jobs = 1;
+   */
+  jobs: TargetJob[];
+};
+
+/**
+ * Describes the message api.TargetJobList.
+ * Use `create(TargetJobListSchema)` to create a new message.
+ */
+export const TargetJobListSchema: GenMessage<TargetJobList> = /*@__PURE__*/
+  messageDesc(file_api, 27);
+
+/**
+ * @generated from message api.JobProgressRequest
+ */
+export type JobProgressRequest = Message<"api.JobProgressRequest"> & {
+  /**
+   * @generated from field: int64 job_id = 1;
+   */
+  jobId: bigint;
+};
+
+/**
+ * Describes the message api.JobProgressRequest.
+ * Use `create(JobProgressRequestSchema)` to create a new message.
+ */
+export const JobProgressRequestSchema: GenMessage<JobProgressRequest> = /*@__PURE__*/
+  messageDesc(file_api, 28);
+
+/**
+ * @generated from message api.DeleteTargetJobRequest
+ */
+export type DeleteTargetJobRequest = Message<"api.DeleteTargetJobRequest"> & {
+  /**
+   * its unfinished tasks are dropped with it
+   *
+   * @generated from field: int64 id = 1;
+   */
+  id: bigint;
+};
+
+/**
+ * Describes the message api.DeleteTargetJobRequest.
+ * Use `create(DeleteTargetJobRequestSchema)` to create a new message.
+ */
+export const DeleteTargetJobRequestSchema: GenMessage<DeleteTargetJobRequest> = /*@__PURE__*/
+  messageDesc(file_api, 29);
+
+/**
+ * @generated from message api.Task
+ */
+export type Task = Message<"api.Task"> & {
+  /**
+   * @generated from field: int64 id = 1;
+   */
+  id: bigint;
+
+  /**
+   * @generated from field: api.TaskKind kind = 2;
+   */
+  kind: TaskKind;
+
+  /**
+   * empty for a scan by hostname
+   *
+   * @generated from field: string ip = 3;
+   */
+  ip: string;
+
+  /**
+   * @generated from field: int32 port = 4;
+   */
+  port: number;
+
+  /**
+   * @generated from field: optional string host = 5;
+   */
+  host?: string | undefined;
+
+  /**
+   * @generated from field: worker.Edition edition = 6;
+   */
+  edition: Edition;
+
+  /**
+   * pings only
+   *
+   * @generated from field: bool with_connection = 7;
+   */
+  withConnection: boolean;
+
+  /**
+   * chosen worker; unset = any
+   *
+   * @generated from field: optional string worker_id = 8;
+   */
+  workerId?: string | undefined;
+
+  /**
+   * @generated from field: api.WorkerSelector any_worker = 9;
+   */
+  anyWorker?: WorkerSelector | undefined;
+
+  /**
+   * @generated from field: api.TaskState state = 10;
+   */
+  state: TaskState;
+
+  /**
+   * the worker holding or last holding it
+   *
+   * @generated from field: optional string leased_to = 11;
+   */
+  leasedTo?: string | undefined;
+
+  /**
+   * @generated from field: int32 attempts = 12;
+   */
+  attempts: number;
+
+  /**
+   * @generated from field: optional string last_error = 13;
+   */
+  lastError?: string | undefined;
+
+  /**
+   * RFC3339
+   *
+   * @generated from field: string created_at = 14;
+   */
+  createdAt: string;
+
+  /**
+   * RFC3339
+   *
+   * @generated from field: string updated_at = 15;
+   */
+  updatedAt: string;
+
+  /**
+   * RFC3339; while leased
+   *
+   * @generated from field: optional string lease_expires_at = 16;
+   */
+  leaseExpiresAt?: string | undefined;
+
+  /**
+   * the target job it belongs to
+   *
+   * @generated from field: optional int64 job_id = 17;
+   */
+  jobId?: bigint | undefined;
+};
+
+/**
+ * Describes the message api.Task.
+ * Use `create(TaskSchema)` to create a new message.
+ */
+export const TaskSchema: GenMessage<Task> = /*@__PURE__*/
+  messageDesc(file_api, 30);
+
+/**
+ * @generated from message api.ListTasksRequest
+ */
+export type ListTasksRequest = Message<"api.ListTasksRequest"> & {
+  /**
+   * @generated from field: optional api.TaskState state = 1;
+   */
+  state?: TaskState | undefined;
+
+  /**
+   * chosen by or leased to this worker
+   *
+   * @generated from field: optional string worker_id = 2;
+   */
+  workerId?: string | undefined;
+
+  /**
+   * @generated from field: int64 limit = 3;
+   */
+  limit: bigint;
+
+  /**
+   * page backwards from the last id listed
+   *
+   * @generated from field: optional int64 before_id = 4;
+   */
+  beforeId?: bigint | undefined;
+
+  /**
+   * @generated from field: optional int64 job_id = 5;
+   */
+  jobId?: bigint | undefined;
+};
+
+/**
+ * Describes the message api.ListTasksRequest.
+ * Use `create(ListTasksRequestSchema)` to create a new message.
+ */
+export const ListTasksRequestSchema: GenMessage<ListTasksRequest> = /*@__PURE__*/
+  messageDesc(file_api, 31);
+
+/**
+ * @generated from message api.TaskCounts
+ */
+export type TaskCounts = Message<"api.TaskCounts"> & {
+  /**
+   * @generated from field: int64 queued = 1;
+   */
+  queued: bigint;
+
+  /**
+   * @generated from field: int64 leased = 2;
+   */
+  leased: bigint;
+
+  /**
+   * @generated from field: int64 done = 3;
+   */
+  done: bigint;
+
+  /**
+   * @generated from field: int64 failed = 4;
+   */
+  failed: bigint;
+
+  /**
+   * @generated from field: int64 cancelled = 5;
+   */
+  cancelled: bigint;
+};
+
+/**
+ * Describes the message api.TaskCounts.
+ * Use `create(TaskCountsSchema)` to create a new message.
+ */
+export const TaskCountsSchema: GenMessage<TaskCounts> = /*@__PURE__*/
+  messageDesc(file_api, 32);
+
+/**
+ * @generated from message api.TaskList
+ */
+export type TaskList = Message<"api.TaskList"> & {
+  /**
+   * newest first
+   *
+   * @generated from field: repeated api.Task tasks = 1;
+   */
+  tasks: Task[];
+
+  /**
+   * over the whole queue, ignoring the filters
+   *
+   * @generated from field: api.TaskCounts counts = 2;
+   */
+  counts?: TaskCounts | undefined;
+};
+
+/**
+ * Describes the message api.TaskList.
+ * Use `create(TaskListSchema)` to create a new message.
+ */
+export const TaskListSchema: GenMessage<TaskList> = /*@__PURE__*/
+  messageDesc(file_api, 33);
+
+/**
+ * @generated from message api.TaskIdsRequest
+ */
+export type TaskIdsRequest = Message<"api.TaskIdsRequest"> & {
+  /**
+   * @generated from field: repeated int64 ids = 1;
+   */
+  ids: bigint[];
+
+  /**
+   * Every task the RPC applies to instead of `ids`: all queued and leased ones
+   * for CancelTasks, all failed and cancelled ones for RetryTasks.
+   *
+   * @generated from field: bool all = 2;
+   */
+  all: boolean;
+};
+
+/**
+ * Describes the message api.TaskIdsRequest.
+ * Use `create(TaskIdsRequestSchema)` to create a new message.
+ */
+export const TaskIdsRequestSchema: GenMessage<TaskIdsRequest> = /*@__PURE__*/
+  messageDesc(file_api, 34);
+
+/**
+ * @generated from message api.TasksAffected
+ */
+export type TasksAffected = Message<"api.TasksAffected"> & {
+  /**
+   * @generated from field: int64 affected = 1;
+   */
+  affected: bigint;
+};
+
+/**
+ * Describes the message api.TasksAffected.
+ * Use `create(TasksAffectedSchema)` to create a new message.
+ */
+export const TasksAffectedSchema: GenMessage<TasksAffected> = /*@__PURE__*/
+  messageDesc(file_api, 35);
+
+/**
+ * ----- Scan jobs -----
+ * A scan job covers operator-submitted CIDR ranges. The backend merges them,
+ * splits them into shards and hands shards to online workers; progress is kept
+ * per shard so an interrupted job resumes where it stopped.
+ *
+ * @generated from message api.CreateScanJobRequest
+ */
+export type CreateScanJobRequest = Message<"api.CreateScanJobRequest"> & {
+  /**
+   * @generated from field: string name = 1;
+   */
+  name: string;
+
+  /**
+   * "a.b.c.d/n" or a single address
+   *
+   * @generated from field: repeated string ranges = 2;
+   */
+  ranges: string[];
+
+  /**
+   * Contents of a ranges file: one range per line, `#` starts a comment.
+   * Combined with `ranges`.
+   *
+   * @generated from field: string ranges_file = 3;
+   */
+  rangesFile: string;
+
+  /**
+   * 0 means the edition's default port
+   *
+   * @generated from field: int32 port = 4;
+   */
+  port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 5;
+   */
+  edition: Edition;
+};
+
+/**
+ * Describes the message api.CreateScanJobRequest.
+ * Use `create(CreateScanJobRequestSchema)` to create a new message.
+ */
+export const CreateScanJobRequestSchema: GenMessage<CreateScanJobRequest> = /*@__PURE__*/
+  messageDesc(file_api, 36);
+
+/**
+ * @generated from message api.ScanJob
+ */
+export type ScanJob = Message<"api.ScanJob"> & {
+  /**
+   * @generated from field: int32 id = 1;
+   */
+  id: number;
+
+  /**
+   * @generated from field: string name = 2;
+   */
+  name: string;
+
+  /**
+   * @generated from field: int32 port = 3;
+   */
+  port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 4;
+   */
+  edition: Edition;
+
+  /**
+   * RFC3339
+   *
+   * @generated from field: string created_at = 5;
+   */
+  createdAt: string;
+
+  /**
+   * RFC3339; set once every shard is done
+   *
+   * @generated from field: optional string finished_at = 6;
+   */
+  finishedAt?: string | undefined;
+
+  /**
+   * @generated from field: int32 shards_total = 7;
+   */
+  shardsTotal: number;
+
+  /**
+   * @generated from field: int32 shards_done = 8;
+   */
+  shardsDone: number;
+
+  /**
+   * @generated from field: int64 addresses_total = 9;
+   */
+  addressesTotal: bigint;
+
+  /**
+   * @generated from field: int64 addresses_scanned = 10;
+   */
+  addressesScanned: bigint;
+};
+
+/**
+ * Describes the message api.ScanJob.
+ * Use `create(ScanJobSchema)` to create a new message.
+ */
+export const ScanJobSchema: GenMessage<ScanJob> = /*@__PURE__*/
+  messageDesc(file_api, 37);
+
+/**
+ * @generated from message api.ScanJobList
+ */
+export type ScanJobList = Message<"api.ScanJobList"> & {
+  /**
+   * @generated from field: repeated api.ScanJob jobs = 1;
+   */
+  jobs: ScanJob[];
+};
+
+/**
+ * Describes the message api.ScanJobList.
+ * Use `create(ScanJobListSchema)` to create a new message.
+ */
+export const ScanJobListSchema: GenMessage<ScanJobList> = /*@__PURE__*/
+  messageDesc(file_api, 38);
+
+/**
+ * @generated from message api.DeleteScanJobRequest
+ */
+export type DeleteScanJobRequest = Message<"api.DeleteScanJobRequest"> & {
+  /**
+   * @generated from field: int32 id = 1;
+   */
+  id: number;
+};
+
+/**
+ * Describes the message api.DeleteScanJobRequest.
+ * Use `create(DeleteScanJobRequestSchema)` to create a new message.
+ */
+export const DeleteScanJobRequestSchema: GenMessage<DeleteScanJobRequest> = /*@__PURE__*/
+  messageDesc(file_api, 39);
+
+/**
+ * ----- Exclusions -----
+ * Operator-managed blocks no worker may probe. Pushed to every worker and
+ * enforced there on search, update cycles, scans and pings.
+ *
+ * @generated from message api.Exclusion
+ */
+export type Exclusion = Message<"api.Exclusion"> & {
+  /**
+   * @generated from field: int32 id = 1;
+   */
+  id: number;
+
+  /**
+   * normalized, e.g. "203.0.113.0/24"
+   *
+   * @generated from field: string cidr = 2;
+   */
+  cidr: string;
+
+  /**
+   * @generated from field: string reason = 3;
+   */
+  reason: string;
+
+  /**
+   * RFC3339
    *
-   * @generated from field: string worker_id = 3;
+   * @generated from field: string created_at = 4;
    */
-  workerId: string;
+  createdAt: string;
 };
 
 /**
- * Describes the message api.PingServerRequest.
- * Use `create(PingServerRequestSchema)` to create a new message.
+ * Describes the message api.Exclusion.
+ * Use `create(ExclusionSchema)` to create a new message.
  */
-export const PingServerRequestSchema: GenMessage<PingServerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 15);
+export const ExclusionSchema: GenMessage<Exclusion> = /*@__PURE__*/
+  messageDesc(file_api, 40);
 
 /**
- * @generated from message api.AddAddrRequest
+ * @generated from message api.ExclusionList
  */
-export type AddAddrRequest = Message<"api.AddAddrRequest"> & {
+export type ExclusionList = Message<"api.ExclusionList"> & {
   /**
-   * @generated from field: string addr = 1;
+   * @generated from field: repeated api.Exclusion exclusions = 1;
    */
-  addr: string;
+  exclusions: Exclusion[];
+};
+
+/**
+ * Describes the message api.ExclusionList.
+ * Use `create(ExclusionListSchema)` to create a new message.
+ */
+export const ExclusionListSchema: GenMessage<ExclusionList> = /*@__PURE__*/
+  messageDesc(file_api, 41);
 
+/**
+ * @generated from message api.AddExclusionRequest
+ */
+export type AddExclusionRequest = Message<"api.AddExclusionRequest"> & {
   /**
-   * @generated from field: bool quick = 2;
+   * "a.b.c.d/n", an IPv6 prefix, or a single address
+   *
+   * @generated from field: string cidr = 1;
    */
-  quick: boolean;
+  cidr: string;
 
   /**
-   * worker chosen by the user to run this scan
-   *
-   * @generated from field: string worker_id = 3;
+   * @generated from field: string reason = 2;
    */
-  workerId: string;
+  reason: string;
 };
 
 /**
- * Describes the message api.AddAddrRequest.
- * Use `create(AddAddrRequestSchema)` to create a new message.
+ * Describes the message api.AddExclusionRequest.
+ * Use `create(AddExclusionRequestSchema)` to create a new message.
  */
-export const AddAddrRequestSchema: GenMessage<AddAddrRequest> = /*@__PURE__*/
-  messageDesc(file_api, 16);
+export const AddExclusionRequestSchema: GenMessage<AddExclusionRequest> = /*@__PURE__*/
+  messageDesc(file_api, 42);
 
 /**
- * @generated from message api.AddTargetListRequest
+ * @generated from message api.DeleteExclusionRequest
  */
-export type AddTargetListRequest = Message<"api.AddTargetListRequest"> & {
+export type DeleteExclusionRequest = Message<"api.DeleteExclusionRequest"> & {
   /**
-   * @generated from field: repeated api.AddAddrRequest targets = 1;
+   * @generated from field: int32 id = 1;
    */
-  targets: AddAddrRequest[];
+  id: number;
+};
+
+/**
+ * Describes the message api.DeleteExclusionRequest.
+ * Use `create(DeleteExclusionRequestSchema)` to create a new message.
+ */
+export const DeleteExclusionRequestSchema: GenMessage<DeleteExclusionRequest> = /*@__PURE__*/
+  messageDesc(file_api, 43);
 
+/**
+ * @generated from message api.PurgeExcludedServersResponse
+ */
+export type PurgeExcludedServersResponse = Message<"api.PurgeExcludedServersResponse"> & {
   /**
-   * worker chosen by the user to run the whole batch
-   *
-   * @generated from field: string worker_id = 2;
+   * @generated from field: int64 deleted = 1;
    */
-  workerId: string;
+  deleted: bigint;
 };
 
 /**
- * Describes the message api.AddTargetListRequest.
- * Use `create(AddTargetListRequestSchema)` to create a new message.
+ * Describes the message api.PurgeExcludedServersResponse.
+ * Use `create(PurgeExcludedServersResponseSchema)` to create a new message.
  */
-export const AddTargetListRequestSchema: GenMessage<AddTargetListRequest> = /*@__PURE__*/
-  messageDesc(file_api, 17);
+export const PurgeExcludedServersResponseSchema: GenMessage<PurgeExcludedServersResponse> = /*@__PURE__*/
+  messageDesc(file_api, 44);
 
 /**
  * ----- Players -----
@@ -656,7 +1835,7 @@ export type PlayerListRequest = Message<"api.PlayerListRequest"> & {
  * Use `create(PlayerListRequestSchema)` to create a new message.
  */
 export const PlayerListRequestSchema: GenMessage<PlayerListRequest> = /*@__PURE__*/
-  messageDesc(file_api, 18);
+  messageDesc(file_api, 45);
 
 /**
  * @generated from message api.Player
@@ -695,7 +1874,7 @@ export type Player = Message<"api.Player"> & {
  * Use `create(PlayerSchema)` to create a new message.
  */
 export const PlayerSchema: GenMessage<Player> = /*@__PURE__*/
-  messageDesc(file_api, 19);
+  messageDesc(file_api, 46);
 
 /**
  * @generated from message api.PlayerListResponse
@@ -712,7 +1891,7 @@ export type PlayerListResponse = Message<"api.PlayerListResponse"> & {
  * Use `create(PlayerListResponseSchema)` to create a new message.
  */
 export const PlayerListResponseSchema: GenMessage<PlayerListResponse> = /*@__PURE__*/
-  messageDesc(file_api, 20);
+  messageDesc(file_api, 47);
 
 /**
  * @generated from message api.PlayerSearchRequest
@@ -749,7 +1928,7 @@ export type PlayerSearchRequest = Message<"api.PlayerSearchRequest"> & {
  * Use `create(PlayerSearchRequestSchema)` to create a new message.
  */
 export const PlayerSearchRequestSchema: GenMessage<PlayerSearchRequest> = /*@__PURE__*/
-  messageDesc(file_api, 21);
+  messageDesc(file_api, 48);
 
 /**
  * @generated from message api.PlayerSearchResult
@@ -793,6 +1972,16 @@ export type PlayerSearchResult = Message<"api.PlayerSearchResult"> & {
    * @generated from field: bool licensed = 7;
    */
   licensed: boolean;
+
+  /**
+   * @generated from field: int32 server_port = 8;
+   */
+  serverPort: number;
+
+  /**
+   * @generated from field: worker.Edition server_edition = 9;
+   */
+  serverEdition: Edition;
 };
 
 /**
@@ -800,7 +1989,7 @@ export type PlayerSearchResult = Message<"api.PlayerSearchResult"> & {
  * Use `create(PlayerSearchResultSchema)` to create a new message.
  */
 export const PlayerSearchResultSchema: GenMessage<PlayerSearchResult> = /*@__PURE__*/
-  messageDesc(file_api, 22);
+  messageDesc(file_api, 49);
 
 /**
  * @generated from message api.PlayerSearchResponse
@@ -817,7 +2006,7 @@ export type PlayerSearchResponse = Message<"api.PlayerSearchResponse"> & {
  * Use `create(PlayerSearchResponseSchema)` to create a new message.
  */
 export const PlayerSearchResponseSchema: GenMessage<PlayerSearchResponse> = /*@__PURE__*/
-  messageDesc(file_api, 23);
+  messageDesc(file_api, 50);
 
 /**
  * @generated from message api.UpdatePlayerRequest
@@ -839,7 +2028,7 @@ export type UpdatePlayerRequest = Message<"api.UpdatePlayerRequest"> & {
  * Use `create(UpdatePlayerRequestSchema)` to create a new message.
  */
 export const UpdatePlayerRequestSchema: GenMessage<UpdatePlayerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 24);
+  messageDesc(file_api, 51);
 
 /**
  * @generated from message api.DeletePlayerRequest
@@ -856,7 +2045,7 @@ export type DeletePlayerRequest = Message<"api.DeletePlayerRequest"> & {
  * Use `create(DeletePlayerRequestSchema)` to create a new message.
  */
 export const DeletePlayerRequestSchema: GenMessage<DeletePlayerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 25);
+  messageDesc(file_api, 52);
 
 /**
  * ----- Worker management -----
@@ -902,6 +2091,13 @@ export type WorkerInfo = Message<"api.WorkerInfo"> & {
    * @generated from field: worker.WorkerMetrics metrics = 7;
    */
   metrics?: WorkerMetrics | undefined;
+
+  /**
+   * operator label from the worker's config
+   *
+   * @generated from field: optional string region = 8;
+   */
+  region?: string | undefined;
 };
 
 /**
@@ -909,7 +2105,7 @@ export type WorkerInfo = Message<"api.WorkerInfo"> & {
  * Use `create(WorkerInfoSchema)` to create a new message.
  */
 export const WorkerInfoSchema: GenMessage<WorkerInfo> = /*@__PURE__*/
-  messageDesc(file_api, 26);
+  messageDesc(file_api, 53);
 
 /**
  * @generated from message api.WorkerList
@@ -926,7 +2122,7 @@ export type WorkerList = Message<"api.WorkerList"> & {
  * Use `create(WorkerListSchema)` to create a new message.
  */
 export const WorkerListSchema: GenMessage<WorkerList> = /*@__PURE__*/
-  messageDesc(file_api, 27);
+  messageDesc(file_api, 54);
 
 /**
  * @generated from message api.GetWorkerRequest
@@ -943,7 +2139,7 @@ export type GetWorkerRequest = Message<"api.GetWorkerRequest"> & {
  * Use `create(GetWorkerRequestSchema)` to create a new message.
  */
 export const GetWorkerRequestSchema: GenMessage<GetWorkerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 28);
+  messageDesc(file_api, 55);
 
 /**
  * @generated from message api.UpdateWorkerConfigRequest
@@ -965,7 +2161,7 @@ export type UpdateWorkerConfigRequest = Message<"api.UpdateWorkerConfigRequest">
  * Use `create(UpdateWorkerConfigRequestSchema)` to create a new message.
  */
 export const UpdateWorkerConfigRequestSchema: GenMessage<UpdateWorkerConfigRequest> = /*@__PURE__*/
-  messageDesc(file_api, 29);
+  messageDesc(file_api, 56);
 
 /**
  * @generated from message api.SetWorkerNameRequest
@@ -989,7 +2185,7 @@ export type SetWorkerNameRequest = Message<"api.SetWorkerNameRequest"> & {
  * Use `create(SetWorkerNameRequestSchema)` to create a new message.
  */
 export const SetWorkerNameRequestSchema: GenMessage<SetWorkerNameRequest> = /*@__PURE__*/
-  messageDesc(file_api, 30);
+  messageDesc(file_api, 57);
 
 /**
  * @generated from message api.ControlWorkerRequest
@@ -1011,7 +2207,7 @@ export type ControlWorkerRequest = Message<"api.ControlWorkerRequest"> & {
  * Use `create(ControlWorkerRequestSchema)` to create a new message.
  */
 export const ControlWorkerRequestSchema: GenMessage<ControlWorkerRequest> = /*@__PURE__*/
-  messageDesc(file_api, 31);
+  messageDesc(file_api, 58);
 
 /**
  * @generated from enum api.PlayerStatus
@@ -1093,6 +2289,210 @@ export enum JoinStatus {
 export const JoinStatusSchema: GenEnum<JoinStatus> = /*@__PURE__*/
   enumDesc(file_api, 1);
 
+/**
+ * Operator weight on how often the update cycle re-probes a server. The backend
+ * schedules each server from its recent activity; this scales the result.
+ *
+ * @generated from enum api.ProbePriority
+ */
+export enum ProbePriority {
+  /**
+   * @generated from enum value: PROBE_PRIORITY_NORMAL = 0;
+   */
+  NORMAL = 0,
+
+  /**
+   * re-probed several times as often
+   *
+   * @generated from enum value: PROBE_PRIORITY_HIGH = 1;
+   */
+  HIGH = 1,
+
+  /**
+   * re-probed several times less often
+   *
+   * @generated from enum value: PROBE_PRIORITY_LOW = 2;
+   */
+  LOW = 2,
+}
+
+/**
+ * Describes the enum api.ProbePriority.
+ */
+export const ProbePrioritySchema: GenEnum<ProbePriority> = /*@__PURE__*/
+  enumDesc(file_api, 2);
+
+/**
+ * Raw samples are kept for `[backend].snapshot_raw_days`; older history is
+ * only available as hourly and daily rollups.
+ *
+ * @generated from enum api.SnapshotResolution
+ */
+export enum SnapshotResolution {
+  /**
+   * @generated from enum value: SNAPSHOT_RESOLUTION_RAW = 0;
+   */
+  RAW = 0,
+
+  /**
+   * @generated from enum value: SNAPSHOT_RESOLUTION_HOURLY = 1;
+   */
+  HOURLY = 1,
+
+  /**
+   * @generated from enum value: SNAPSHOT_RESOLUTION_DAILY = 2;
+   */
+  DAILY = 2,
+}
+
+/**
+ * Describes the enum api.SnapshotResolution.
+ */
+export const SnapshotResolutionSchema: GenEnum<SnapshotResolution> = /*@__PURE__*/
+  enumDesc(file_api, 3);
+
+/**
+ * Fields whose changes are kept in a server's history.
+ *
+ * @generated from enum api.ServerField
+ */
+export enum ServerField {
+  /**
+   * @generated from enum value: SERVER_FIELD_VERSION_NAME = 0;
+   */
+  VERSION_NAME = 0,
+
+  /**
+   * JSON text
+   *
+   * @generated from enum value: SERVER_FIELD_DESCRIPTION = 1;
+   */
+  DESCRIPTION = 1,
+
+  /**
+   * @generated from enum value: SERVER_FIELD_MOTD = 2;
+   */
+  MOTD = 2,
+
+  /**
+   * @generated from enum value: SERVER_FIELD_FAVICON = 3;
+   */
+  FAVICON = 3,
+
+  /**
+   * "true" / "false"
+   *
+   * @generated from enum value: SERVER_FIELD_IS_ONLINE_MODE = 4;
+   */
+  IS_ONLINE_MODE = 4,
+
+  /**
+   * JSON text
+   *
+   * @generated from enum value: SERVER_FIELD_DISCONNECT_REASON = 5;
+   */
+  DISCONNECT_REASON = 5,
+}
+
+/**
+ * Describes the enum api.ServerField.
+ */
+export const ServerFieldSchema: GenEnum<ServerField> = /*@__PURE__*/
+  enumDesc(file_api, 4);
+
+/**
+ * How the backend picks the worker for a ping or scan whose request leaves
+ * `worker_id` empty. Tasks so placed move to another worker if theirs
+ * disconnects before finishing them; those for a chosen worker wait for it.
+ *
+ * @generated from enum api.WorkerPick
+ */
+export enum WorkerPick {
+  /**
+   * fewest pending tasks, then fewest search threads
+   *
+   * @generated from enum value: WORKER_PICK_LEAST_LOADED = 0;
+   */
+  LEAST_LOADED = 0,
+
+  /**
+   * @generated from enum value: WORKER_PICK_ROUND_ROBIN = 1;
+   */
+  ROUND_ROBIN = 1,
+}
+
+/**
+ * Describes the enum api.WorkerPick.
+ */
+export const WorkerPickSchema: GenEnum<WorkerPick> = /*@__PURE__*/
+  enumDesc(file_api, 5);
+
+/**
+ * ----- Tasks -----
+ * Every ping and scan is queued as a task and survives worker and backend
+ * restarts. Each worker holds a few leased tasks at a time; a lease that times
+ * out or loses its worker is retried, up to a few attempts.
+ *
+ * @generated from enum api.TaskState
+ */
+export enum TaskState {
+  /**
+   * @generated from enum value: TASK_STATE_QUEUED = 0;
+   */
+  QUEUED = 0,
+
+  /**
+   * sent to `leased_to`, not reported done yet
+   *
+   * @generated from enum value: TASK_STATE_LEASED = 1;
+   */
+  LEASED = 1,
+
+  /**
+   * @generated from enum value: TASK_STATE_DONE = 2;
+   */
+  DONE = 2,
+
+  /**
+   * out of attempts, or rejected by the worker
+   *
+   * @generated from enum value: TASK_STATE_FAILED = 3;
+   */
+  FAILED = 3,
+
+  /**
+   * @generated from enum value: TASK_STATE_CANCELLED = 4;
+   */
+  CANCELLED = 4,
+}
+
+/**
+ * Describes the enum api.TaskState.
+ */
+export const TaskStateSchema: GenEnum<TaskState> = /*@__PURE__*/
+  enumDesc(file_api, 6);
+
+/**
+ * @generated from enum api.TaskKind
+ */
+export enum TaskKind {
+  /**
+   * @generated from enum value: TASK_KIND_PING = 0;
+   */
+  PING = 0,
+
+  /**
+   * @generated from enum value: TASK_KIND_SCAN = 1;
+   */
+  SCAN = 1,
+}
+
+/**
+ * Describes the enum api.TaskKind.
+ */
+export const TaskKindSchema: GenEnum<TaskKind> = /*@__PURE__*/
+  enumDesc(file_api, 7);
+
 /**
  * Frontend ⇆ backend API.
  * Served as gRPC-web (browser) via tonic-web. All RPCs except Login require a
@@ -1129,6 +2529,16 @@ export const Api: GenService<{
     input: typeof EmptySchema;
     output: typeof StatsResponseSchema;
   },
+  /**
+   * Statistics recorded every 15 minutes, folded into buckets, oldest first.
+   *
+   * @generated from rpc api.Api.GetStatsHistory
+   */
+  getStatsHistory: {
+    methodKind: "unary";
+    input: typeof StatsHistoryRequestSchema;
+    output: typeof StatsHistorySchema;
+  },
   /**
    * System
    * Triggers watchtower to pull new images and recreate the stack's containers now.
@@ -1174,6 +2584,16 @@ export const Api: GenService<{
     input: typeof ServerSnapshotsRequestSchema;
     output: typeof ServerSnapshotsResponseSchema;
   },
+  /**
+   * What re-probes changed about the server, newest first.
+   *
+   * @generated from rpc api.Api.GetServerHistory
+   */
+  getServerHistory: {
+    methodKind: "unary";
+    input: typeof ServerHistoryRequestSchema;
+    output: typeof ServerHistorySchema;
+  },
   /**
    * @generated from rpc api.Api.UpdateServer
    */
@@ -1215,13 +2635,132 @@ export const Api: GenService<{
     output: typeof EmptySchema;
   },
   /**
+   * Queues the batch as a target job and returns it.
+   *
    * @generated from rpc api.Api.AddTargetList
    */
   addTargetList: {
     methodKind: "unary";
     input: typeof AddTargetListRequestSchema;
+    output: typeof TargetJobSchema;
+  },
+  /**
+   * Target jobs
+   *
+   * @generated from rpc api.Api.ListTargetJobs
+   */
+  listTargetJobs: {
+    methodKind: "unary";
+    input: typeof EmptySchema;
+    output: typeof TargetJobListSchema;
+  },
+  /**
+   * The job now, then again every few seconds until nothing of it is queued.
+   *
+   * @generated from rpc api.Api.StreamJobProgress
+   */
+  streamJobProgress: {
+    methodKind: "server_streaming";
+    input: typeof JobProgressRequestSchema;
+    output: typeof TargetJobSchema;
+  },
+  /**
+   * @generated from rpc api.Api.DeleteTargetJob
+   */
+  deleteTargetJob: {
+    methodKind: "unary";
+    input: typeof DeleteTargetJobRequestSchema;
+    output: typeof EmptySchema;
+  },
+  /**
+   * Task queue: the pings and scans queued by PingServer, AddTarget and
+   * AddTargetList.
+   *
+   * @generated from rpc api.Api.ListTasks
+   */
+  listTasks: {
+    methodKind: "unary";
+    input: typeof ListTasksRequestSchema;
+    output: typeof TaskListSchema;
+  },
+  /**
+   * @generated from rpc api.Api.CancelTasks
+   */
+  cancelTasks: {
+    methodKind: "unary";
+    input: typeof TaskIdsRequestSchema;
+    output: typeof TasksAffectedSchema;
+  },
+  /**
+   * @generated from rpc api.Api.RetryTasks
+   */
+  retryTasks: {
+    methodKind: "unary";
+    input: typeof TaskIdsRequestSchema;
+    output: typeof TasksAffectedSchema;
+  },
+  /**
+   * Scan jobs
+   *
+   * @generated from rpc api.Api.CreateScanJob
+   */
+  createScanJob: {
+    methodKind: "unary";
+    input: typeof CreateScanJobRequestSchema;
+    output: typeof ScanJobSchema;
+  },
+  /**
+   * @generated from rpc api.Api.ListScanJobs
+   */
+  listScanJobs: {
+    methodKind: "unary";
+    input: typeof EmptySchema;
+    output: typeof ScanJobListSchema;
+  },
+  /**
+   * @generated from rpc api.Api.DeleteScanJob
+   */
+  deleteScanJob: {
+    methodKind: "unary";
+    input: typeof DeleteScanJobRequestSchema;
+    output: typeof EmptySchema;
+  },
+  /**
+   * Exclusion list (abuse complaints, opt-outs): never probed by any worker.
+   *
+   * @generated from rpc api.Api.ListExclusions
+   */
+  listExclusions: {
+    methodKind: "unary";
+    input: typeof EmptySchema;
+    output: typeof ExclusionListSchema;
+  },
+  /**
+   * @generated from rpc api.Api.AddExclusion
+   */
+  addExclusion: {
+    methodKind: "unary";
+    input: typeof AddExclusionRequestSchema;
+    output: typeof ExclusionSchema;
+  },
+  /**
+   * @generated from rpc api.Api.DeleteExclusion
+   */
+  deleteExclusion: {
+    methodKind: "unary";
+    input: typeof DeleteExclusionRequestSchema;
     output: typeof EmptySchema;
   },
+  /**
+   * Deletes every server whose address falls inside an exclusion.
+   *
+   * @generated from rpc api.Api.PurgeExcludedServers
+   */
+  purgeExcludedServers: {
+    methodKind: "unary";
+    input: typeof EmptySchema;
+    output: typeof PurgeExcludedServersResponseSchema;
+  },
   /**
    * Players
    *
@@ -10,14 +10,15 @@ import type { Message } from "@bufbuild/protobuf";
  * Describes the file worker.proto.
  */
 export const file_worker: GenFile = /*@__PURE__*/
-  fileDesc("Cgx3b3JrZXIucHJvdG8SBndvcmtlciLqAgoMU2VydmVyRmlsdGVyEhMKBm9ubGluZRgBIAEoCEgAiAEBEhUKCGxpY2Vuc2VkGAIgASgISAGIAQESFAoHY2hlY2tlZBgDIAEoCEgCiAEBEhQKB2NyYXNoZWQYBCABKAhIA4gBARIaCg1yZXF1aXJlc19tb2RzGAUgASgISASIAQESGAoLaGFzX3BsYXllcnMYBiABKAhIBYgBARIdChBoYXNfbm9uZV9wbGF5ZXJzGAcgASgISAaIAQESGAoLam9pbl9zdGF0dXMYCCABKAlIB4gBARISCgVxdWVyeRgJIAEoCUgIiAEBQgkKB19vbmxpbmVCCwoJX2xpY2Vuc2VkQgoKCF9jaGVja2VkQgoKCF9jcmFzaGVkQhAKDl9yZXF1aXJlc19tb2RzQg4KDF9oYXNfcGxheWVyc0ITChFfaGFzX25vbmVfcGxheWVyc0IOCgxfam9pbl9zdGF0dXNCCAoGX3F1ZXJ5IrkCCgxXb3JrZXJDb25maWcSDwoHdGhyZWFkcxgBIAEoBRIVCg1zZWFyY2hfbW9kdWxlGAIgASgIEhUKDXVwZGF0ZV9tb2R1bGUYAyABKAgSHgoWdXBkYXRlX3dpdGhfY29ubmVjdGlvbhgEIAEoCBIcChR1cGRhdGVfaW50ZXJ2YWxfc2VjcxgHIAEoDRIaChJ1cGRhdGVfY29uY3VycmVuY3kYCCABKA0SKwoNdXBkYXRlX2ZpbHRlchgJIAEoCzIULndvcmtlci5TZXJ2ZXJGaWx0ZXISKwoNc2VhcmNoX2ZpbHRlchgKIAEoCzIULndvcmtlci5TZXJ2ZXJGaWx0ZXJKBAgFEAZKBAgGEAdSFW9ubHlfdXBkYXRlX3Nwb29mYWJsZVITb25seV91cGRhdGVfY3JhY2tlZCKLAQoNV29ya2VyTWVzc2FnZRIkCghyZWdpc3RlchgBIAEoCzIQLndvcmtlci5SZWdpc3RlckgAEiYKCWhlYXJ0YmVhdBgCIAEoCzIRLndvcmtlci5IZWFydGJlYXRIABIkCgZyZXN1bHQYAyABKAsyEi53b3JrZXIuU2NhblJlc3VsdEgAQgYKBGtpbmQicAoIUmVnaXN0ZXISEQoJd29ya2VyX2lkGAEgASgJEhEKBG5hbWUYAiABKAlIAIgBARIkCgZjb25maWcYAyABKAsyFC53b3JrZXIuV29ya2VyQ29uZmlnEg8KB3ZlcnNpb24YBCABKAlCBwoFX25hbWUiMwoJSGVhcnRiZWF0EiYKB21ldHJpY3MYASABKAsyFS53b3JrZXIuV29ya2VyTWV0cmljcyL6AQoNV29ya2VyTWV0cmljcxIVCg1zZXJ2ZXJzX2ZvdW5kGAEgASgEEhMKC2lwc19zY2FubmVkGAIgASgEEhEKCXNjYW5fcmF0ZRgDIAEoARITCgt1cHRpbWVfc2VjcxgEIAEoBBIRCglzZWFyY2hpbmcYBSABKAgSEAoIdXBkYXRpbmcYBiABKAgSFgoOYWN0aXZlX3RocmVhZHMYByABKA0SEwoLdXBkYXRlX2RvbmUYCCABKAQSFAoMdXBkYXRlX3RvdGFsGAkgASgEEhMKC3VwZGF0ZV9yYXRlGAogASgBEhgKEGxhc3RfdXBkYXRlX3VuaXgYCyABKAMiqQEKClNjYW5SZXN1bHQSKgoKZGlzY292ZXJlZBgBIAEoCzIULndvcmtlci5TZXJ2ZXJSZXBvcnRIABInCgd1cGRhdGVkGAIgASgLMhQud29ya2VyLlNlcnZlclJlcG9ydEgAEigKB29mZmxpbmUYAyABKAsyFS53b3JrZXIuU2VydmVyT2ZmbGluZUgAEhEKCXJlc3VsdF9pZBgEIAEoCUIJCgdvdXRjb21lIrUCCgxTZXJ2ZXJSZXBvcnQSCgoCaXAYASABKAkSDAoEcG9ydBgCIAEoBRIUCgx2ZXJzaW9uX25hbWUYAyABKAkSEAoIcHJvdG9jb2wYBCABKAUSGAoQZGVzY3JpcHRpb25fanNvbhgFIAEoCRIWCg5wbGF5ZXJzX29ubGluZRgGIAEoBRITCgtwbGF5ZXJzX21heBgHIAEoBRIUCgxwbGF5ZXJfbmFtZXMYCCADKAkSFQoNcmVxdWlyZXNfbW9kcxgJIAEoCBIUCgdmYXZpY29uGAogASgJSACIAQESEQoEcGluZxgLIAEoA0gBiAEBEicKBWV4dHJhGAwgASgLMhMud29ya2VyLlNlcnZlckV4dHJhSAKIAQFCCgoIX2Zhdmljb25CBwoFX3BpbmdCCAoGX2V4dHJhImUKC1NlcnZlckV4dHJhEhYKDmlzX29ubGluZV9tb2RlGAEgASgIEiMKFmRpc2Nvbm5lY3RfcmVhc29uX2pzb24YAiABKAlIAIgBAUIZChdfZGlzY29ubmVjdF9yZWFzb25fanNvbiIbCg1TZXJ2ZXJPZmZsaW5lEgoKAmlwGAEgASgJIusBCg1TZXJ2ZXJDb21tYW5kEiAKBHBpbmcYASABKAsyEC53b3JrZXIuUGluZ1Rhc2tIABIgCgRzY2FuGAIgASgLMhAud29ya2VyLlNjYW5UYXNrSAASKgoKc2V0X2NvbmZpZxgDIAEoCzIULndvcmtlci5Xb3JrZXJDb25maWdIABIiCgdjb250cm9sGAQgASgOMg8ud29ya2VyLkNvbnRyb2xIABIjCghzZXRfbmFtZRgFIAEoCzIPLndvcmtlci5TZXROYW1lSAASGgoDYWNrGAYgASgLMgsud29ya2VyLkFja0gAQgUKA2NtZCIYCgNBY2sSEQoJcmVzdWx0X2lkGAEgASgJIiUKB1NldE5hbWUSEQoEbmFtZRgBIAEoCUgAiAEBQgcKBV9uYW1lIj0KCFBpbmdUYXNrEgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUSFwoPd2l0aF9jb25uZWN0aW9uGAMgASgIIiQKCFNjYW5UYXNrEgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUifgoZRmV0Y2hVcGRhdGVUYXJnZXRzUmVxdWVzdBIRCgl3b3JrZXJfaWQYASABKAkSJAoGZmlsdGVyGAQgASgLMhQud29ya2VyLlNlcnZlckZpbHRlckoECAIQA0oECAMQBFIOb25seV9zcG9vZmFibGVSDG9ubHlfY3JhY2tlZCJBCgxVcGRhdGVUYXJnZXQSCgoCaXAYASABKAkSDAoEcG9ydBgCIAEoBRIXCg93aXRoX2Nvbm5lY3Rpb24YAyABKAgiXQoaRmV0Y2hVcGRhdGVUYXJnZXRzUmVzcG9uc2USDwoFdG90YWwYASABKARIABImCgZ0YXJnZXQYAiABKAsyFC53b3JrZXIuVXBkYXRlVGFyZ2V0SABCBgoEa2luZCqjAQoHQ29udHJvbBIXChNDT05UUk9MX1VOU1BFQ0lGSUVEEAASGAoUQ09OVFJPTF9QQVVTRV9TRUFSQ0gQARIZChVDT05UUk9MX1JFU1VNRV9TRUFSQ0gQAhIUChBDT05UUk9MX1NIVVRET1dOEAMSGAoUQ09OVFJPTF9BQk9SVF9VUERBVEUQBBIaChZDT05UUk9MX1RSSUdHRVJfVVBEQVRFEAUyqwEKDVdvcmtlckNvbnRyb2wSOwoHU2Vzc2lvbhIVLndvcmtlci5Xb3JrZXJNZXNzYWdlGhUud29ya2VyLlNlcnZlckNvbW1hbmQoATABEl0KEkZldGNoVXBkYXRlVGFyZ2V0cxIhLndvcmtlci5GZXRjaFVwZGF0ZVRhcmdldHNSZXF1ZXN0GiIud29ya2VyLkZldGNoVXBkYXRlVGFyZ2V0c1Jlc3BvbnNlMAFiBnByb3RvMw");
+  fileDesc("Cgx3b3JrZXIucHJvdG8SBndvcmtlciLKAwoMU2VydmVyRmlsdGVyEhMKBm9ubGluZRgBIAEoCEgAiAEBEhUKCGxpY2Vuc2VkGAIgASgISAGIAQESFAoHY2hlY2tlZBgDIAEoCEgCiAEBEhQKB2NyYXNoZWQYBCABKAhIA4gBARIaCg1yZXF1aXJlc19tb2RzGAUgASgISASIAQESGAoLaGFzX3BsYXllcnMYBiABKAhIBYgBARIdChBoYXNfbm9uZV9wbGF5ZXJzGAcgASgISAaIAQESGAoLam9pbl9zdGF0dXMYCCABKAlIB4gBARISCgVxdWVyeRgJIAEoCUgIiAEBEhQKB2VkaXRpb24YCiABKAlICYgBARIiChVzdWdnZXN0ZWRfam9pbl9zdGF0dXMYCyABKAlICogBAUIJCgdfb25saW5lQgsKCV9saWNlbnNlZEIKCghfY2hlY2tlZEIKCghfY3Jhc2hlZEIQCg5fcmVxdWlyZXNfbW9kc0IOCgxfaGFzX3BsYXllcnNCEwoRX2hhc19ub25lX3BsYXllcnNCDgoMX2pvaW5fc3RhdHVzQggKBl9xdWVyeUIKCghfZWRpdGlvbkIYChZfc3VnZ2VzdGVkX2pvaW5fc3RhdHVzIo8GCgxXb3JrZXJDb25maWcSDwoHdGhyZWFkcxgBIAEoBRIVCg1zZWFyY2hfbW9kdWxlGAIgASgIEhUKDXVwZGF0ZV9tb2R1bGUYAyABKAgSHgoWdXBkYXRlX3dpdGhfY29ubmVjdGlvbhgEIAEoCBIcChR1cGRhdGVfaW50ZXJ2YWxfc2VjcxgHIAEoDRIaChJ1cGRhdGVfY29uY3VycmVuY3kYCCABKA0SKwoNdXBkYXRlX2ZpbHRlchgJIAEoCzIULndvcmtlci5TZXJ2ZXJGaWx0ZXISKwoNc2VhcmNoX2ZpbHRlchgKIAEoCzIULndvcmtlci5TZXJ2ZXJGaWx0ZXISJwoMc2VhcmNoX3BvcnRzGAsgAygLMhEud29ya2VyLlBvcnRSYW5nZRIVCg1xdWVyeV9lbmFibGVkGAwgASgIEhoKEm5lZ290aWF0ZV9wcm90b2NvbBgNIAEoCBISCgpkZWVwX2xvZ2luGA4gASgIEiUKBWxvZ2luGA8gASgLMhEud29ya2VyLkxvZ2luUG9vbEgAiAEBEhoKEmNvbm5lY3RfdGltZW91dF9tcxgQIAEoDRIXCg9yZWFkX3RpbWVvdXRfbXMYESABKA0SHAoUaGFuZHNoYWtlX3RpbWVvdXRfbXMYEiABKA0SGAoQcHJvYmVfdGltZW91dF9tcxgTIAEoDRIxCgtwZXJtdXRhdGlvbhgUIAEoCzIXLndvcmtlci5QZXJtdXRhdGlvblNjYW5IAYgBARIbChNtYXhfcGFja2V0c19wZXJfc2VjGBUgASgNEh8KF21heF9jb25uZWN0aW9uc19wZXJfc2VjGBYgASgNEikKDXJhdGVfc2NoZWR1bGUYFyADKAsyEi53b3JrZXIuUmF0ZVdpbmRvdxIaChJ1cGRhdGVfcGVyX25ldHdvcmsYGCABKA1CCAoGX2xvZ2luQg4KDF9wZXJtdXRhdGlvbkoECAUQBkoECAYQB1IVb25seV91cGRhdGVfc3Bvb2ZhYmxlUhNvbmx5X3VwZGF0ZV9jcmFja2VkImYKClJhdGVXaW5kb3cSDQoFc3RhcnQYASABKAkSCwoDZW5kGAIgASgJEhsKE21heF9wYWNrZXRzX3Blcl9zZWMYAyABKA0SHwoXbWF4X2Nvbm5lY3Rpb25zX3Blcl9zZWMYBCABKA0iRgoPUGVybXV0YXRpb25TY2FuEgwKBHNlZWQYASABKA0SEQoJcGFydGl0aW9uGAIgASgNEhIKCnBhcnRpdGlvbnMYAyABKA0iRwoMU2VhcmNoQ3Vyc29yEiUKBHNjYW4YASABKAsyFy53b3JrZXIuUGVybXV0YXRpb25TY2FuEhAKCHBvc2l0aW9uGAIgASgEIkwKCUxvZ2luUG9vbBINCgVuYW1lcxgBIAMoCRIUCgdwYXR0ZXJuGAIgASgJSACIAQESDgoGc3RpY2t5GAMgASgIQgoKCF9wYXR0ZXJuIlkKCVBvcnRSYW5nZRINCgVzdGFydBgBIAEoDRILCgNlbmQYAiABKA0SDgoGd2VpZ2h0GAMgASgNEiAKB2VkaXRpb24YBCABKA4yDy53b3JrZXIuRWRpdGlvbiLjAQoNV29ya2VyTWVzc2FnZRIkCghyZWdpc3RlchgBIAEoCzIQLndvcmtlci5SZWdpc3RlckgAEiYKCWhlYXJ0YmVhdBgCIAEoCzIRLndvcmtlci5IZWFydGJlYXRIABIkCgZyZXN1bHQYAyABKAsyEi53b3JrZXIuU2NhblJlc3VsdEgAEi8KDnNoYXJkX3Byb2dyZXNzGAQgASgLMhUud29ya2VyLlNoYXJkUHJvZ3Jlc3NIABIlCgl0YXNrX2RvbmUYBSABKAsyEC53b3JrZXIuVGFza0RvbmVIAEIGCgRraW5kIpABCghSZWdpc3RlchIRCgl3b3JrZXJfaWQYASABKAkSEQoEbmFtZRgCIAEoCUgAiAEBEiQKBmNvbmZpZxgDIAEoCzIULndvcmtlci5Xb3JrZXJDb25maWcSDwoHdmVyc2lvbhgEIAEoCRITCgZyZWdpb24YBSABKAlIAYgBAUIHCgVfbmFtZUIJCgdfcmVnaW9uIjMKCUhlYXJ0YmVhdBImCgdtZXRyaWNzGAEgASgLMhUud29ya2VyLldvcmtlck1ldHJpY3MikwUKDVdvcmtlck1ldHJpY3MSFQoNc2VydmVyc19mb3VuZBgBIAEoBBITCgtpcHNfc2Nhbm5lZBgCIAEoBBIRCglzY2FuX3JhdGUYAyABKAESEwoLdXB0aW1lX3NlY3MYBCABKAQSEQoJc2VhcmNoaW5nGAUgASgIEhAKCHVwZGF0aW5nGAYgASgIEhYKDmFjdGl2ZV90aHJlYWRzGAcgASgNEhMKC3VwZGF0ZV9kb25lGAggASgEEhQKDHVwZGF0ZV90b3RhbBgJIAEoBBITCgt1cGRhdGVfcmF0ZRgKIAEoARIYChBsYXN0X3VwZGF0ZV91bml4GAsgASgDEjYKCXBvcnRfaGl0cxgMIAMoCzIjLndvcmtlci5Xb3JrZXJNZXRyaWNzLlBvcnRIaXRzRW50cnkSFgoOcHJvYmVfdGltZW91dHMYDSABKAQSFQoNcHJvYmVfcmVmdXNlZBgOIAEoBBIUCgxwcm9iZV9yZXNldHMYDyABKAQSHAoPc2VhcmNoX2NvdmVyYWdlGBAgASgBSACIAQESMAoNc2VhcmNoX2N1cnNvchgRIAEoCzIULndvcmtlci5TZWFyY2hDdXJzb3JIAYgBARITCgtwYWNrZXRfcmF0ZRgSIAEoARIXCg9jb25uZWN0aW9uX3JhdGUYEyABKAESFAoMcGFja2V0X2xpbWl0GBQgASgNEhgKEGNvbm5lY3Rpb25fbGltaXQYFSABKA0SFQoNcGVuZGluZ190YXNrcxgWIAEoDRovCg1Qb3J0SGl0c0VudHJ5EgsKA2tleRgBIAEoDRINCgV2YWx1ZRgCIAEoBDoCOAFCEgoQX3NlYXJjaF9jb3ZlcmFnZUIQCg5fc2VhcmNoX2N1cnNvciLkAQoKU2NhblJlc3VsdBIqCgpkaXNjb3ZlcmVkGAEgASgLMhQud29ya2VyLlNlcnZlclJlcG9ydEgAEicKB3VwZGF0ZWQYAiABKAsyFC53b3JrZXIuU2VydmVyUmVwb3J0SAASKAoHb2ZmbGluZRgDIAEoCzIVLndvcmtlci5TZXJ2ZXJPZmZsaW5lSAASKAoHc2tpcHBlZBgGIAEoCzIVLndvcmtlci5TZXJ2ZXJTa2lwcGVkSAASEQoJcmVzdWx0X2lkGAQgASgJEg8KB3Rhc2tfaWQYBSABKARCCQoHb3V0Y29tZSLwAwoMU2VydmVyUmVwb3J0EgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUSFAoMdmVyc2lvbl9uYW1lGAMgASgJEhAKCHByb3RvY29sGAQgASgFEhgKEGRlc2NyaXB0aW9uX2pzb24YBSABKAkSFgoOcGxheWVyc19vbmxpbmUYBiABKAUSEwoLcGxheWVyc19tYXgYByABKAUSFAoMcGxheWVyX25hbWVzGAggAygJEhUKDXJlcXVpcmVzX21vZHMYCSABKAgSFAoHZmF2aWNvbhgKIAEoCUgAiAEBEhEKBHBpbmcYCyABKANIAYgBARInCgVleHRyYRgMIAEoCzITLndvcmtlci5TZXJ2ZXJFeHRyYUgCiAEBEiAKB2VkaXRpb24YDSABKA4yDy53b3JrZXIuRWRpdGlvbhIWCglnYW1lX21vZGUYDiABKAlIA4gBARInCgVxdWVyeRgPIAEoCzITLndvcmtlci5TZXJ2ZXJRdWVyeUgEiAEBEhoKEmFjY2VwdGVkX3Byb3RvY29scxgQIAMoBRIVCghob3N0bmFtZRgRIAEoCUgFiAEBQgoKCF9mYXZpY29uQgcKBV9waW5nQggKBl9leHRyYUIMCgpfZ2FtZV9tb2RlQggKBl9xdWVyeUILCglfaG9zdG5hbWUibQoLU2VydmVyUXVlcnkSFQoIc29mdHdhcmUYASABKAlIAIgBARIPCgdwbHVnaW5zGAIgAygJEhAKA21hcBgDIAEoCUgBiAEBEg8KB3BsYXllcnMYBCADKAlCCwoJX3NvZnR3YXJlQgYKBF9tYXAilwEKC1NlcnZlckV4dHJhEhYKDmlzX29ubGluZV9tb2RlGAEgASgIEiMKFmRpc2Nvbm5lY3RfcmVhc29uX2pzb24YAiABKAlIAIgBARImCgVsb2dpbhgDIAEoCzISLndvcmtlci5Mb2dpblByb2JlSAGIAQFCGQoXX2Rpc2Nvbm5lY3RfcmVhc29uX2pzb25CCAoGX2xvZ2luImYKCkxvZ2luUHJvYmUSFAoMcmVhY2hlZF9wbGF5GAEgASgIEhMKC2F1dGhfcHJvbXB0GAIgASgIEhYKDndoaXRlbGlzdF9raWNrGAMgASgIEhUKDW1vZF9oYW5kc2hha2UYBCABKAgiSwoNU2VydmVyT2ZmbGluZRIKCgJpcBgBIAEoCRIMCgRwb3J0GAIgASgFEiAKB2VkaXRpb24YAyABKA4yDy53b3JrZXIuRWRpdGlvbiJLCg1TZXJ2ZXJTa2lwcGVkEgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUSIAoHZWRpdGlvbhgDIAEoDjIPLndvcmtlci5FZGl0aW9uIuwCCg1TZXJ2ZXJDb21tYW5kEiAKBHBpbmcYASABKAsyEC53b3JrZXIuUGluZ1Rhc2tIABIgCgRzY2FuGAIgASgLMhAud29ya2VyLlNjYW5UYXNrSAASKgoKc2V0X2NvbmZpZxgDIAEoCzIULndvcmtlci5Xb3JrZXJDb25maWdIABIiCgdjb250cm9sGAQgASgOMg8ud29ya2VyLkNvbnRyb2xIABIjCghzZXRfbmFtZRgFIAEoCzIPLndvcmtlci5TZXROYW1lSAASGgoDYWNrGAYgASgLMgsud29ya2VyLkFja0gAEiIKBXNoYXJkGAcgASgLMhEud29ya2VyLlNjYW5TaGFyZEgAEi0KDXJlc3VtZV9jdXJzb3IYCCABKAsyFC53b3JrZXIuU2VhcmNoQ3Vyc29ySAASLAoOc2V0X2V4Y2x1c2lvbnMYCSABKAsyEi53b3JrZXIuRXhjbHVzaW9uc0gAQgUKA2NtZCIbCgpFeGNsdXNpb25zEg0KBWNpZHJzGAEgAygJIhgKA0FjaxIRCglyZXN1bHRfaWQYASABKAkiJQoHU2V0TmFtZRIRCgRuYW1lGAEgASgJSACIAQFCBwoFX25hbWUijAEKCFBpbmdUYXNrEgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUSFwoPd2l0aF9jb25uZWN0aW9uGAMgASgIEiAKB2VkaXRpb24YBCABKA4yDy53b3JrZXIuRWRpdGlvbhIRCgRob3N0GAUgASgJSACIAQESDwoHdGFza19pZBgGIAEoBEIHCgVfaG9zdCJzCghTY2FuVGFzaxIKCgJpcBgBIAEoCRIMCgRwb3J0GAIgASgFEiAKB2VkaXRpb24YAyABKA4yDy53b3JrZXIuRWRpdGlvbhIRCgRob3N0GAQgASgJSACIAQESDwoHdGFza19pZBgFIAEoBEIHCgVfaG9zdCI5CghUYXNrRG9uZRIPCgd0YXNrX2lkGAEgASgEEhIKBWVycm9yGAIgASgJSACIAQFCCAoGX2Vycm9yImoKCVNjYW5TaGFyZBIQCghzaGFyZF9pZBgBIAEoBRIMCgRjaWRyGAIgASgJEg0KBXN0YXJ0GAMgASgNEgwKBHBvcnQYBCABKAUSIAoHZWRpdGlvbhgFIAEoDjIPLndvcmtlci5FZGl0aW9uIj0KDVNoYXJkUHJvZ3Jlc3MSEAoIc2hhcmRfaWQYASABKAUSDAoEbmV4dBgCIAEoDRIMCgRkb25lGAMgASgIIn4KGUZldGNoVXBkYXRlVGFyZ2V0c1JlcXVlc3QSEQoJd29ya2VyX2lkGAEgASgJEiQKBmZpbHRlchgEIAEoCzIULndvcmtlci5TZXJ2ZXJGaWx0ZXJKBAgCEANKBAgDEARSDm9ubHlfc3Bvb2ZhYmxlUgxvbmx5X2NyYWNrZWQifwoMVXBkYXRlVGFyZ2V0EgoKAmlwGAEgASgJEgwKBHBvcnQYAiABKAUSFwoPd2l0aF9jb25uZWN0aW9uGAMgASgIEiAKB2VkaXRpb24YBCABKA4yDy53b3JrZXIuRWRpdGlvbhIRCgRob3N0GAUgASgJSACIAQFCBwoFX2hvc3QiXQoaRmV0Y2hVcGRhdGVUYXJnZXRzUmVzcG9uc2USDwoFdG90YWwYASABKARIABImCgZ0YXJnZXQYAiABKAsyFC53b3JrZXIuVXBkYXRlVGFyZ2V0SABCBgoEa2luZCowCgdFZGl0aW9uEhAKDEVESVRJT05fSkFWQRAAEhMKD0VESVRJT05fQkVEUk9DSxABKqMBCgdDb250cm9sEhcKE0NPTlRST0xfVU5TUEVDSUZJRUQQABIYChRDT05UUk9MX1BBVVNFX1NFQVJDSBABEhkKFUNPTlRST0xfUkVTVU1FX1NFQVJDSBACEhQKEENPTlRST0xfU0hVVERPV04QAxIYChRDT05UUk9MX0FCT1JUX1VQREFURRAEEhoKFkNPTlRST0xfVFJJR0dFUl9VUERBVEUQBTKrAQoNV29ya2VyQ29udHJvbBI7CgdTZXNzaW9uEhUud29ya2VyLldvcmtlck1lc3NhZ2UaFS53b3JrZXIuU2VydmVyQ29tbWFuZCgBMAESXQoSRmV0Y2hVcGRhdGVUYXJnZXRzEiEud29ya2VyLkZldGNoVXBkYXRlVGFyZ2V0c1JlcXVlc3QaIi53b3JrZXIuRmV0Y2hVcGRhdGVUYXJnZXRzUmVzcG9uc2UwAWIGcHJvdG8z");
 
 /**
  * Mirrors the dashboard's server filters. Every field is tri-state via `optional`:
  * unset = "any". Reused for both the update target query (server-side WHERE) and the
- * search acceptance filter (worker-side, on freshly discovered servers). `join_status`
- * carries the DB enum text ("spoofable", "whitelist", ...); `query` is free-text over
- * ip/version/motd and only applies to the update path.
+ * search acceptance filter (worker-side, on freshly discovered servers). `join_status`,
+ * `suggested_join_status` and `edition` carry the DB enum text ("spoofable",
+ * "whitelist", ... / "java", "bedrock"); `query` and `suggested_join_status` only
+ * apply to the update path.
  *
  * @generated from message worker.ServerFilter
  */
@@ -68,6 +69,18 @@ export type ServerFilter = Message<"worker.ServerFilter"> & {
    * @generated from field: optional string query = 9;
    */
   query?: string | undefined;
+
+  /**
+   * @generated from field: optional string edition = 10;
+   */
+  edition?: string | undefined;
+
+  /**
+   * the classifier's suggestion
+   *
+   * @generated from field: optional string suggested_join_status = 11;
+   */
+  suggestedJoinStatus?: string | undefined;
 };
 
 /**
@@ -130,6 +143,105 @@ export type WorkerConfig = Message<"worker.WorkerConfig"> & {
    * @generated from field: worker.ServerFilter search_filter = 10;
    */
   searchFilter?: ServerFilter | undefined;
+
+  /**
+   * ports the search pool samples from; empty = 25565 only
+   *
+   * @generated from field: repeated worker.PortRange search_ports = 11;
+   */
+  searchPorts: PortRange[];
+
+  /**
+   * follow Java probes with a GameSpy4 query (full player list, plugins)
+   *
+   * @generated from field: bool query_enabled = 12;
+   */
+  queryEnabled: boolean;
+
+  /**
+   * negotiate the status protocol and record accepted client protocols
+   *
+   * @generated from field: bool negotiate_protocol = 13;
+   */
+  negotiateProtocol: boolean;
+
+  /**
+   * carry offline-mode logins into the play state (auth/whitelist/mod detection)
+   *
+   * @generated from field: bool deep_login = 14;
+   */
+  deepLogin: boolean;
+
+  /**
+   * usernames login probes present; unset = worker default
+   *
+   * @generated from field: optional worker.LoginPool login = 15;
+   */
+  login?: LoginPool | undefined;
+
+  /**
+   * Probe budgets in milliseconds; 0 = worker default.
+   *
+   * TCP connect, or the wait for a Bedrock pong
+   *
+   * @generated from field: uint32 connect_timeout_ms = 16;
+   */
+  connectTimeoutMs: number;
+
+  /**
+   * any single read or query answer
+   *
+   * @generated from field: uint32 read_timeout_ms = 17;
+   */
+  readTimeoutMs: number;
+
+  /**
+   * login handshake, connect to online/offline verdict
+   *
+   * @generated from field: uint32 handshake_timeout_ms = 18;
+   */
+  handshakeTimeoutMs: number;
+
+  /**
+   * a whole Java probe
+   *
+   * @generated from field: uint32 probe_timeout_ms = 19;
+   */
+  probeTimeoutMs: number;
+
+  /**
+   * Walk the IPv4 space once in a keyed order instead of sampling at random;
+   * unset = random search.
+   *
+   * @generated from field: optional worker.PermutationScan permutation = 20;
+   */
+  permutation?: PermutationScan | undefined;
+
+  /**
+   * Probe rate caps shared by search, scan jobs and update cycles; 0 = unlimited.
+   *
+   * @generated from field: uint32 max_packets_per_sec = 21;
+   */
+  maxPacketsPerSec: number;
+
+  /**
+   * @generated from field: uint32 max_connections_per_sec = 22;
+   */
+  maxConnectionsPerSec: number;
+
+  /**
+   * Time-of-day overrides of the two caps; the first matching window wins.
+   *
+   * @generated from field: repeated worker.RateWindow rate_schedule = 23;
+   */
+  rateSchedule: RateWindow[];
+
+  /**
+   * max concurrent re-probes per /24 (IPv6 /64); 0 = worker default
+   *
+   * @generated from field: uint32 update_per_network = 24;
+   */
+  updatePerNetwork: number;
 };
 
 /**
@@ -139,6 +251,167 @@ export type WorkerConfig = Message<"worker.WorkerConfig"> & {
 export const WorkerConfigSchema: GenMessage<WorkerConfig> = /*@__PURE__*/
   messageDesc(file_worker, 1);
 
+/**
+ * A time-of-day window (worker local time, "HH:MM") with its own rate caps.
+ * `end` before `start` runs past midnight; `end` equal to `start` is all day.
+ *
+ * @generated from message worker.RateWindow
+ */
+export type RateWindow = Message<"worker.RateWindow"> & {
+  /**
+   * @generated from field: string start = 1;
+   */
+  start: string;
+
+  /**
+   * @generated from field: string end = 2;
+   */
+  end: string;
+
+  /**
+   * @generated from field: uint32 max_packets_per_sec = 3;
+   */
+  maxPacketsPerSec: number;
+
+  /**
+   * @generated from field: uint32 max_connections_per_sec = 4;
+   */
+  maxConnectionsPerSec: number;
+};
+
+/**
+ * Describes the message worker.RateWindow.
+ * Use `create(RateWindowSchema)` to create a new message.
+ */
+export const RateWindowSchema: GenMessage<RateWindow> = /*@__PURE__*/
+  messageDesc(file_worker, 2);
+
+/**
+ * A full-coverage search walk (`[worker.permutation]`). Workers sharing `seed`
+ * split one keyed permutation of the IPv4 space into `partitions` equal index
+ * ranges; this worker walks range `partition` (0-based).
+ *
+ * @generated from message worker.PermutationScan
+ */
+export type PermutationScan = Message<"worker.PermutationScan"> & {
+  /**
+   * @generated from field: uint32 seed = 1;
+   */
+  seed: number;
+
+  /**
+   * @generated from field: uint32 partition = 2;
+   */
+  partition: number;
+
+  /**
+   * @generated from field: uint32 partitions = 3;
+   */
+  partitions: number;
+};
+
+/**
+ * Describes the message worker.PermutationScan.
+ * Use `create(PermutationScanSchema)` to create a new message.
+ */
+export const PermutationScanSchema: GenMessage<PermutationScan> = /*@__PURE__*/
+  messageDesc(file_worker, 3);
+
+/**
+ * How far a worker has walked its PermutationScan partition. Reported in the
+ * heartbeat, persisted by the backend, and sent back after registration so a
+ * restarted worker resumes the walk.
+ *
+ * @generated from message worker.SearchCursor
+ */
+export type SearchCursor = Message<"worker.SearchCursor"> & {
+  /**
+   * @generated from field: worker.PermutationScan scan = 1;
+   */
+  scan?: PermutationScan | undefined;
+
+  /**
+   * indices of the partition already walked
+   *
+   * @generated from field: uint64 position = 2;
+   */
+  position: bigint;
+};
+
+/**
+ * Describes the message worker.SearchCursor.
+ * Use `create(SearchCursorSchema)` to create a new message.
+ */
+export const SearchCursorSchema: GenMessage<SearchCursor> = /*@__PURE__*/
+  messageDesc(file_worker, 4);
+
+/**
+ * Usernames the worker's login probes draw from (`[worker.login]`). `pattern`
+ * generates names (`#` = digit, `?` = letter) and counts as one more pool entry.
+ * `sticky` keeps the choice fixed per server address.
+ *
+ * @generated from message worker.LoginPool
+ */
+export type LoginPool = Message<"worker.LoginPool"> & {
+  /**
+   * @generated from field: repeated string names = 1;
+   */
+  names: string[];
+
+  /**
+   * @generated from field: optional string pattern = 2;
+   */
+  pattern?: string | undefined;
+
+  /**
+   * @generated from field: bool sticky = 3;
+   */
+  sticky: boolean;
+};
+
+/**
+ * Describes the message worker.LoginPool.
+ * Use `create(LoginPoolSchema)` to create a new message.
+ */
+export const LoginPoolSchema: GenMessage<LoginPool> = /*@__PURE__*/
+  messageDesc(file_worker, 5);
+
+/**
+ * Inclusive port range the search pool samples from. `weight` is the range's
+ * relative share of search probes (0 = 1); a port within the range is then
+ * picked uniformly and probed with the prober for `edition`.
+ *
+ * @generated from message worker.PortRange
+ */
+export type PortRange = Message<"worker.PortRange"> & {
+  /**
+   * @generated from field: uint32 start = 1;
+   */
+  start: number;
+
+  /**
+   * @generated from field: uint32 end = 2;
+   */
+  end: number;
+
+  /**
+   * @generated from field: uint32 weight = 3;
+   */
+  weight: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 4;
+   */
+  edition: Edition;
+};
+
+/**
+ * Describes the message worker.PortRange.
+ * Use `create(PortRangeSchema)` to create a new message.
+ */
+export const PortRangeSchema: GenMessage<PortRange> = /*@__PURE__*/
+  messageDesc(file_worker, 6);
+
 /**
  * Worker -> backend, multiplexed over the Session stream.
  *
@@ -166,6 +439,18 @@ export type WorkerMessage = Message<"worker.WorkerMessage"> & {
      */
     value: ScanResult;
     case: "result";
+  } | {
+    /**
+     * @generated from field: worker.ShardProgress shard_progress = 4;
+     */
+    value: ShardProgress;
+    case: "shardProgress";
+  } | {
+    /**
+     * @generated from field: worker.TaskDone task_done = 5;
+     */
+    value: TaskDone;
+    case: "taskDone";
   } | { case: undefined; value?: undefined };
 };
 
@@ -174,7 +459,7 @@ export type WorkerMessage = Message<"worker.WorkerMessage"> & {
  * Use `create(WorkerMessageSchema)` to create a new message.
  */
 export const WorkerMessageSchema: GenMessage<WorkerMessage> = /*@__PURE__*/
-  messageDesc(file_worker, 2);
+  messageDesc(file_worker, 7);
 
 /**
  * @generated from message worker.Register
@@ -201,6 +486,13 @@ export type Register = Message<"worker.Register"> & {
    * @generated from field: string version = 4;
    */
   version: string;
+
+  /**
+   * operator label, for "any worker" selection
+   *
+   * @generated from field: optional string region = 5;
+   */
+  region?: string | undefined;
 };
 
 /**
@@ -208,7 +500,7 @@ export type Register = Message<"worker.Register"> & {
  * Use `create(RegisterSchema)` to create a new message.
  */
 export const RegisterSchema: GenMessage<Register> = /*@__PURE__*/
-  messageDesc(file_worker, 3);
+  messageDesc(file_worker, 8);
 
 /**
  * @generated from message worker.Heartbeat
@@ -225,7 +517,7 @@ export type Heartbeat = Message<"worker.Heartbeat"> & {
  * Use `create(HeartbeatSchema)` to create a new message.
  */
 export const HeartbeatSchema: GenMessage<Heartbeat> = /*@__PURE__*/
-  messageDesc(file_worker, 4);
+  messageDesc(file_worker, 9);
 
 /**
  * @generated from message worker.WorkerMetrics
@@ -299,6 +591,74 @@ export type WorkerMetrics = Message<"worker.WorkerMetrics"> & {
    * @generated from field: int64 last_update_unix = 11;
    */
   lastUpdateUnix: bigint;
+
+  /**
+   * servers found per probed port, process lifetime
+   *
+   * @generated from field: map<uint32, uint64> port_hits = 12;
+   */
+  portHits: { [key: number]: bigint };
+
+  /**
+   * Failed probes of known servers / accepted search connections, by cause,
+   * process lifetime.
+   *
+   * @generated from field: uint64 probe_timeouts = 13;
+   */
+  probeTimeouts: bigint;
+
+  /**
+   * @generated from field: uint64 probe_refused = 14;
+   */
+  probeRefused: bigint;
+
+  /**
+   * @generated from field: uint64 probe_resets = 15;
+   */
+  probeResets: bigint;
+
+  /**
+   * Permutation search only: share of this worker's partition walked (0-100),
+   * and the cursor behind it.
+   *
+   * @generated from field: optional double search_coverage = 16;
+   */
+  searchCoverage?: number | undefined;
+
+  /**
+   * @generated from field: optional worker.SearchCursor search_cursor = 17;
+   */
+  searchCursor?: SearchCursor | undefined;
+
+  /**
+   * Probe packets and new connections sent per second, recent window, and the
+   * caps currently in force (after the schedule; 0 = unlimited).
+   *
+   * @generated from field: double packet_rate = 18;
+   */
+  packetRate: number;
+
+  /**
+   * @generated from field: double connection_rate = 19;
+   */
+  connectionRate: number;
+
+  /**
+   * @generated from field: uint32 packet_limit = 20;
+   */
+  packetLimit: number;
+
+  /**
+   * @generated from field: uint32 connection_limit = 21;
+   */
+  connectionLimit: number;
+
+  /**
+   * On-demand pings, scans and scan job shards running now.
+   *
+   * @generated from field: uint32 pending_tasks = 22;
+   */
+  pendingTasks: number;
 };
 
 /**
@@ -306,7 +666,7 @@ export type WorkerMetrics = Message<"worker.WorkerMetrics"> & {
  * Use `create(WorkerMetricsSchema)` to create a new message.
  */
 export const WorkerMetricsSchema: GenMessage<WorkerMetrics> = /*@__PURE__*/
-  messageDesc(file_worker, 5);
+  messageDesc(file_worker, 10);
 
 /**
  * Worker -> backend: the outcome of probing a single address.
@@ -319,8 +679,8 @@ export type ScanResult = Message<"worker.ScanResult"> & {
    */
   outcome: {
     /**
-     * Newly probed via the search/scan path (upsert-by-ip, conflict only bumps
-     * updated_at/is_online/favicon).
+     * Newly probed via the search/scan path (upsert-by-address, i.e. by the
+     * (ip, port) pair; conflict only bumps updated_at/is_online/favicon).
      *
      * @generated from field: worker.ServerReport discovered = 1;
      */
@@ -329,7 +689,7 @@ export type ScanResult = Message<"worker.ScanResult"> & {
   } | {
     /**
      * Re-probed during an update cycle / on-demand ping (full field update by
-     * ip).
+     * (ip, port)).
      *
      * @generated from field: worker.ServerReport updated = 2;
      */
@@ -343,6 +703,16 @@ export type ScanResult = Message<"worker.ScanResult"> & {
      */
     value: ServerOffline;
     case: "offline";
+  } | {
+    /**
+     * Update target the worker would not probe (excluded or not probeable from
+     * it): the backend pushes its next probe back instead of serving it again
+     * every cycle.
+     *
+     * @generated from field: worker.ServerSkipped skipped = 6;
+     */
+    value: ServerSkipped;
+    case: "skipped";
   } | { case: undefined; value?: undefined };
 
   /**
@@ -353,6 +723,13 @@ export type ScanResult = Message<"worker.ScanResult"> & {
    * @generated from field: string result_id = 4;
    */
   resultId: string;
+
+  /**
+   * The backend task (PingTask/ScanTask.task_id) the probe ran for; 0 = none.
+   *
+   * @generated from field: uint64 task_id = 5;
+   */
+  taskId: bigint;
 };
 
 /**
@@ -360,7 +737,7 @@ export type ScanResult = Message<"worker.ScanResult"> & {
  * Use `create(ScanResultSchema)` to create a new message.
  */
 export const ScanResultSchema: GenMessage<ScanResult> = /*@__PURE__*/
-  messageDesc(file_worker, 6);
+  messageDesc(file_worker, 11);
 
 /**
  * @generated from message worker.ServerReport
@@ -424,12 +801,48 @@ export type ServerReport = Message<"worker.ServerReport"> & {
   ping?: bigint | undefined;
 
   /**
-   * Present for every `discovered` report; present for `updated` only when the
-   * worker was asked to probe with a login connection (`with_connection`).
+   * Present for every Java `discovered` report; present for `updated` only when
+   * the worker was asked to probe with a login connection (`with_connection`).
+   * Never present for Bedrock, which has no login probe.
    *
    * @generated from field: optional worker.ServerExtra extra = 12;
    */
   extra?: ServerExtra | undefined;
+
+  /**
+   * @generated from field: worker.Edition edition = 13;
+   */
+  edition: Edition;
+
+  /**
+   * Bedrock only: default game mode from the pong
+   *
+   * @generated from field: optional string game_mode = 14;
+   */
+  gameMode?: string | undefined;
+
+  /**
+   * Present when the worker has query enabled and the server answered it.
+   *
+   * @generated from field: optional worker.ServerQuery query = 15;
+   */
+  query?: ServerQuery | undefined;
+
+  /**
+   * Client protocols the server accepted, ascending. Empty unless the worker
+   * negotiates protocols (`WorkerConfig.negotiate_protocol`).
+   *
+   * @generated from field: repeated int32 accepted_protocols = 16;
+   */
+  acceptedProtocols: number[];
+
+  /**
+   * The hostname the probe was sent to (and announced in its handshake), when
+   * the target was entered as one rather than as an address.
+   *
+   * @generated from field: optional string hostname = 17;
+   */
+  hostname?: string | undefined;
 };
 
 /**
@@ -437,7 +850,44 @@ export type ServerReport = Message<"worker.ServerReport"> & {
  * Use `create(ServerReportSchema)` to create a new message.
  */
 export const ServerReportSchema: GenMessage<ServerReport> = /*@__PURE__*/
-  messageDesc(file_worker, 7);
+  messageDesc(file_worker, 12);
+
+/**
+ * GameSpy4 "Query" full stat (`enable-query=true`). Unlike the status sample,
+ * `players` is the complete online list.
+ *
+ * @generated from message worker.ServerQuery
+ */
+export type ServerQuery = Message<"worker.ServerQuery"> & {
+  /**
+   * e.g. "Paper on 1.20.4"; unset for vanilla
+   *
+   * @generated from field: optional string software = 1;
+   */
+  software?: string | undefined;
+
+  /**
+   * @generated from field: repeated string plugins = 2;
+   */
+  plugins: string[];
+
+  /**
+   * @generated from field: optional string map = 3;
+   */
+  map?: string | undefined;
+
+  /**
+   * @generated from field: repeated string players = 4;
+   */
+  players: string[];
+};
+
+/**
+ * Describes the message worker.ServerQuery.
+ * Use `create(ServerQuerySchema)` to create a new message.
+ */
+export const ServerQuerySchema: GenMessage<ServerQuery> = /*@__PURE__*/
+  messageDesc(file_worker, 13);
 
 /**
  * @generated from message worker.ServerExtra
@@ -452,6 +902,13 @@ export type ServerExtra = Message<"worker.ServerExtra"> & {
    * @generated from field: optional string disconnect_reason_json = 2;
    */
   disconnectReasonJson?: string | undefined;
+
+  /**
+   * Set only when the worker ran the deep login probe (`WorkerConfig.deep_login`).
+   *
+   * @generated from field: optional worker.LoginProbe login = 3;
+   */
+  login?: LoginProbe | undefined;
 };
 
 /**
@@ -459,7 +916,47 @@ export type ServerExtra = Message<"worker.ServerExtra"> & {
  * Use `create(ServerExtraSchema)` to create a new message.
  */
 export const ServerExtraSchema: GenMessage<ServerExtra> = /*@__PURE__*/
-  messageDesc(file_worker, 8);
+  messageDesc(file_worker, 14);
+
+/**
+ * What an offline-mode login ran into past `LoginFinished`.
+ *
+ * @generated from message worker.LoginProbe
+ */
+export type LoginProbe = Message<"worker.LoginProbe"> & {
+  /**
+   * the server sent join game
+   *
+   * @generated from field: bool reached_play = 1;
+   */
+  reachedPlay: boolean;
+
+  /**
+   * chat asked for /login or /register (AuthMe-style)
+   *
+   * @generated from field: bool auth_prompt = 2;
+   */
+  authPrompt: boolean;
+
+  /**
+   * @generated from field: bool whitelist_kick = 3;
+   */
+  whitelistKick: boolean;
+
+  /**
+   * a mod loader's login/configuration channel
+   *
+   * @generated from field: bool mod_handshake = 4;
+   */
+  modHandshake: boolean;
+};
+
+/**
+ * Describes the message worker.LoginProbe.
+ * Use `create(LoginProbeSchema)` to create a new message.
+ */
+export const LoginProbeSchema: GenMessage<LoginProbe> = /*@__PURE__*/
+  messageDesc(file_worker, 15);
 
 /**
  * @generated from message worker.ServerOffline
@@ -469,6 +966,21 @@ export type ServerOffline = Message<"worker.ServerOffline"> & {
    * @generated from field: string ip = 1;
    */
   ip: string;
+
+  /**
+   * 0 only in results queued by a worker that predates per-port identity; the
+   * backend then marks every server on `ip` offline (the old behaviour).
+   *
+   * @generated from field: int32 port = 2;
+   */
+  port: number;
+
+  /**
+   * Java in results from workers that predate it, which only probed Java.
+   *
+   * @generated from field: worker.Edition edition = 3;
+   */
+  edition: Edition;
 };
 
 /**
@@ -476,7 +988,34 @@ export type ServerOffline = Message<"worker.ServerOffline"> & {
  * Use `create(ServerOfflineSchema)` to create a new message.
  */
 export const ServerOfflineSchema: GenMessage<ServerOffline> = /*@__PURE__*/
-  messageDesc(file_worker, 9);
+  messageDesc(file_worker, 16);
+
+/**
+ * @generated from message worker.ServerSkipped
+ */
+export type ServerSkipped = Message<"worker.ServerSkipped"> & {
+  /**
+   * @generated from field: string ip = 1;
+   */
+  ip: string;
+
+  /**
+   * @generated from field: int32 port = 2;
+   */
+  port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 3;
+   */
+  edition: Edition;
+};
+
+/**
+ * Describes the message worker.ServerSkipped.
+ * Use `create(ServerSkippedSchema)` to create a new message.
+ */
+export const ServerSkippedSchema: GenMessage<ServerSkipped> = /*@__PURE__*/
+  messageDesc(file_worker, 17);
 
 /**
  * Backend -> worker, over the Session stream.
@@ -533,6 +1072,30 @@ export type ServerCommand = Message<"worker.ServerCommand"> & {
      */
     value: Ack;
     case: "ack";
+  } | {
+    /**
+     * one block of an operator-submitted scan job
+     *
+     * @generated from field: worker.ScanShard shard = 7;
+     */
+    value: ScanShard;
+    case: "shard";
+  } | {
+    /**
+     * where the permutation search left off
+     *
+     * @generated from field: worker.SearchCursor resume_cursor = 8;
+     */
+    value: SearchCursor;
+    case: "resumeCursor";
+  } | {
+    /**
+     * operator exclusion list; replaces the previous one
+     *
+     * @generated from field: worker.Exclusions set_exclusions = 9;
+     */
+    value: Exclusions;
+    case: "setExclusions";
   } | { case: undefined; value?: undefined };
 };
 
@@ -541,7 +1104,30 @@ export type ServerCommand = Message<"worker.ServerCommand"> & {
  * Use `create(ServerCommandSchema)` to create a new message.
  */
 export const ServerCommandSchema: GenMessage<ServerCommand> = /*@__PURE__*/
-  messageDesc(file_worker, 10);
+  messageDesc(file_worker, 18);
+
+/**
+ * Backend -> worker: the full operator exclusion list, sent on register and
+ * whenever it changes. The worker never probes an address inside any of these
+ * blocks, whatever asked for it.
+ *
+ * @generated from message worker.Exclusions
+ */
+export type Exclusions = Message<"worker.Exclusions"> & {
+  /**
+   * "203.0.113.0/24", "2001:db8::/32"
+   *
+   * @generated from field: repeated string cidrs = 1;
+   */
+  cidrs: string[];
+};
+
+/**
+ * Describes the message worker.Exclusions.
+ * Use `create(ExclusionsSchema)` to create a new message.
+ */
+export const ExclusionsSchema: GenMessage<Exclusions> = /*@__PURE__*/
+  messageDesc(file_worker, 19);
 
 /**
  * Backend -> worker: confirms a ScanResult (by its `result_id`) is durably
@@ -561,7 +1147,7 @@ export type Ack = Message<"worker.Ack"> & {
  * Use `create(AckSchema)` to create a new message.
  */
 export const AckSchema: GenMessage<Ack> = /*@__PURE__*/
-  messageDesc(file_worker, 11);
+  messageDesc(file_worker, 20);
 
 /**
  * Operator-supplied display name pushed from the frontend. The worker persists
@@ -582,7 +1168,7 @@ export type SetName = Message<"worker.SetName"> & {
  * Use `create(SetNameSchema)` to create a new message.
  */
 export const SetNameSchema: GenMessage<SetName> = /*@__PURE__*/
-  messageDesc(file_worker, 12);
+  messageDesc(file_worker, 21);
 
 /**
  * @generated from message worker.PingTask
@@ -602,6 +1188,25 @@ export type PingTask = Message<"worker.PingTask"> & {
    * @generated from field: bool with_connection = 3;
    */
   withConnection: boolean;
+
+  /**
+   * @generated from field: worker.Edition edition = 4;
+   */
+  edition: Edition;
+
+  /**
+   * Virtual host announced in the handshake instead of `ip`; not re-resolved.
+   *
+   * @generated from field: optional string host = 5;
+   */
+  host?: string | undefined;
+
+  /**
+   * as ScanTask.task_id
+   *
+   * @generated from field: uint64 task_id = 6;
+   */
+  taskId: bigint;
 };
 
 /**
@@ -609,7 +1214,7 @@ export type PingTask = Message<"worker.PingTask"> & {
  * Use `create(PingTaskSchema)` to create a new message.
  */
 export const PingTaskSchema: GenMessage<PingTask> = /*@__PURE__*/
-  messageDesc(file_worker, 13);
+  messageDesc(file_worker, 22);
 
 /**
  * @generated from message worker.ScanTask
@@ -624,6 +1229,28 @@ export type ScanTask = Message<"worker.ScanTask"> & {
    * @generated from field: int32 port = 2;
    */
   port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 3;
+   */
+  edition: Edition;
+
+  /**
+   * When set, the worker resolves this hostname itself and `ip` is ignored. A
+   * `port` of 0 then means none was given: the SRV record (Java) or the
+   * edition's default port applies.
+   *
+   * @generated from field: optional string host = 4;
+   */
+  host?: string | undefined;
+
+  /**
+   * The backend's task id (see api.Task), echoed in TaskDone; 0 = no TaskDone
+   * wanted.
+   *
+   * @generated from field: uint64 task_id = 5;
+   */
+  taskId: bigint;
 };
 
 /**
@@ -631,7 +1258,110 @@ export type ScanTask = Message<"worker.ScanTask"> & {
  * Use `create(ScanTaskSchema)` to create a new message.
  */
 export const ScanTaskSchema: GenMessage<ScanTask> = /*@__PURE__*/
-  messageDesc(file_worker, 14);
+  messageDesc(file_worker, 23);
+
+/**
+ * Worker -> backend: a ping or scan task has finished, or was rejected. A
+ * ping is done whatever its outcome; a scan that found no server failed.
+ *
+ * @generated from message worker.TaskDone
+ */
+export type TaskDone = Message<"worker.TaskDone"> & {
+  /**
+   * @generated from field: uint64 task_id = 1;
+   */
+  taskId: bigint;
+
+  /**
+   * why the task failed or the worker rejected it
+   *
+   * @generated from field: optional string error = 2;
+   */
+  error?: string | undefined;
+};
+
+/**
+ * Describes the message worker.TaskDone.
+ * Use `create(TaskDoneSchema)` to create a new message.
+ */
+export const TaskDoneSchema: GenMessage<TaskDone> = /*@__PURE__*/
+  messageDesc(file_worker, 24);
+
+/**
+ * Backend -> worker: scan every address of `cidr` on `port`, starting from
+ * offset `start` within the block (non-zero when resuming a shard that a
+ * previous worker or session left unfinished). Addresses are walked in order
+ * and progress is reported back with `ShardProgress`.
+ *
+ * @generated from message worker.ScanShard
+ */
+export type ScanShard = Message<"worker.ScanShard"> & {
+  /**
+   * @generated from field: int32 shard_id = 1;
+   */
+  shardId: number;
+
+  /**
+   * IPv4 block, e.g. "203.0.113.0/24"
+   *
+   * @generated from field: string cidr = 2;
+   */
+  cidr: string;
+
+  /**
+   * offset of the first address to scan
+   *
+   * @generated from field: uint32 start = 3;
+   */
+  start: number;
+
+  /**
+   * @generated from field: int32 port = 4;
+   */
+  port: number;
+
+  /**
+   * @generated from field: worker.Edition edition = 5;
+   */
+  edition: Edition;
+};
+
+/**
+ * Describes the message worker.ScanShard.
+ * Use `create(ScanShardSchema)` to create a new message.
+ */
+export const ScanShardSchema: GenMessage<ScanShard> = /*@__PURE__*/
+  messageDesc(file_worker, 25);
+
+/**
+ * Worker -> backend: every address of shard `shard_id` before offset `next`
+ * has been scanned; `done` once the whole block has.
+ *
+ * @generated from message worker.ShardProgress
+ */
+export type ShardProgress = Message<"worker.ShardProgress"> & {
+  /**
+   * @generated from field: int32 shard_id = 1;
+   */
+  shardId: number;
+
+  /**
+   * @generated from field: uint32 next = 2;
+   */
+  next: number;
+
+  /**
+   * @generated from field: bool done = 3;
+   */
+  done: boolean;
+};
+
+/**
+ * Describes the message worker.ShardProgress.
+ * Use `create(ShardProgressSchema)` to create a new message.
+ */
+export const ShardProgressSchema: GenMessage<ShardProgress> = /*@__PURE__*/
+  messageDesc(file_worker, 26);
 
 /**
  * @generated from message worker.FetchUpdateTargetsRequest
@@ -653,7 +1383,7 @@ export type FetchUpdateTargetsRequest = Message<"worker.FetchUpdateTargetsReques
  * Use `create(FetchUpdateTargetsRequestSchema)` to create a new message.
  */
 export const FetchUpdateTargetsRequestSchema: GenMessage<FetchUpdateTargetsRequest> = /*@__PURE__*/
-  messageDesc(file_worker, 15);
+  messageDesc(file_worker, 27);
 
 /**
  * @generated from message worker.UpdateTarget
@@ -673,6 +1403,18 @@ export type UpdateTarget = Message<"worker.UpdateTarget"> & {
    * @generated from field: bool with_connection = 3;
    */
   withConnection: boolean;
+
+  /**
+   * @generated from field: worker.Edition edition = 4;
+   */
+  edition: Edition;
+
+  /**
+   * as PingTask.host
+   *
+   * @generated from field: optional string host = 5;
+   */
+  host?: string | undefined;
 };
 
 /**
@@ -680,13 +1422,15 @@ export type UpdateTarget = Message<"worker.UpdateTarget"> & {
  * Use `create(UpdateTargetSchema)` to create a new message.
  */
 export const UpdateTargetSchema: GenMessage<UpdateTarget> = /*@__PURE__*/
-  messageDesc(file_worker, 16);
+  messageDesc(file_worker, 28);
 
 /**
  * One frame of the FetchUpdateTargets stream. The backend sends exactly one
- * `total` frame first (the count of servers matching the filter), then one
- * `target` frame per server. Splitting total from the targets lets the worker
- * display a fixed denominator instead of a number that climbs as rows arrive.
+ * `total` frame first, then one `target` frame per server. Workers on the same
+ * filter split the servers that are due for a re-probe, as scheduled by the
+ * backend, so `total` estimates this worker's share. Splitting total from the
+ * targets lets the worker display a fixed denominator instead of a number that
+ * climbs as rows arrive.
  *
  * @generated from message worker.FetchUpdateTargetsResponse
  */
@@ -714,7 +1458,33 @@ export type FetchUpdateTargetsResponse = Message<"worker.FetchUpdateTargetsRespo
  * Use `create(FetchUpdateTargetsResponseSchema)` to create a new message.
  */
 export const FetchUpdateTargetsResponseSchema: GenMessage<FetchUpdateTargetsResponse> = /*@__PURE__*/
-  messageDesc(file_worker, 17);
+  messageDesc(file_worker, 29);
+
+/**
+ * Which Minecraft edition a server speaks, and so which prober reaches it:
+ * Java over TCP (handshake + status), Bedrock over UDP (RakNet unconnected
+ * ping). Java is the zero value so results and tasks from workers that predate
+ * Bedrock support decode as Java.
+ *
+ * @generated from enum worker.Edition
+ */
+export enum Edition {
+  /**
+   * @generated from enum value: EDITION_JAVA = 0;
+   */
+  JAVA = 0,
+
+  /**
+   * @generated from enum value: EDITION_BEDROCK = 1;
+   */
+  BEDROCK = 1,
+}
+
+/**
+ * Describes the enum worker.Edition.
+ */
+export const EditionSchema: GenEnum<Edition> = /*@__PURE__*/
+  enumDesc(file_worker, 0);
 
 /**
  * @generated from enum worker.Control
@@ -759,7 +1529,7 @@ export enum Control {
  * Describes the enum worker.Control.
  */
 export const ControlSchema: GenEnum<Control> = /*@__PURE__*/
-  enumDesc(file_worker, 0);
+  enumDesc(file_worker, 1);
 
 /**
  * Control plane between a worker and the backend.
@@ -787,6 +1557,8 @@ export const WorkerControl: GenService<{
    * buffering the whole `servers` table into a single message (which would blow
    * the gRPC message-size limit as the table grows). The very first message
    * carries the total row count so the worker can show real progress up front.
+   * Concurrent workers with the same update filter are served disjoint parts
+   * of the set, so adding workers divides the load.
    *
    * @generated from rpc worker.WorkerControl.FetchUpdateTargets
    */
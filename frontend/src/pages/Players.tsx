@@ -11,6 +11,7 @@ import { useTranslation } from "@/i18n";
 import { CopyButton, Spinner, StatusBlock } from "@/components";
 import { PLAYER_STATUSES, PLAYER_STATUS_COLOR } from "@/constants/serverDetail";
 import { cn } from "@/cn";
+import { serverPath } from "@/utils/serverDetailHelpers";
 
 const LIMIT = 50;
 
@@ -337,7 +338,11 @@ export const Players = () => {
                       </div>
                       <div className="flex-1 min-w-0 sm:py-2.5 sm:px-3">
                         <Link
-                          to={`/server/${player.server_ip}`}
+                          to={serverPath(
+                            player.server_ip,
+                            player.server_port,
+                            player.server_edition,
+                          )}
                           className="text-indigo-400 hover:text-indigo-300 hover:underline text-xs transition-colors truncate block"
                         >
                           {player.server_ip}
@@ -4,6 +4,7 @@ import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import type { InfiniteData } from "@tanstack/react-query";
 import { ArrowLeft } from "lucide-react";
 import type {
+  Edition,
   PlayerStatus,
   PlayerResponse,
   ServerInfoResponse,
@@ -28,7 +29,12 @@ import { Spinner } from "@/components";
 
 export const ServerDetail = () => {
   const { t } = useTranslation();
-  const { ip } = useParams<{ ip: string }>();
+  const params = useParams<{ ip: string; port?: string; edition?: string }>();
+  const ip = params.ip;
+  // Links from before servers were keyed by port omit it: the default port.
+  const port = Number(params.port ?? 0) || 0;
+  const edition: Edition = params.edition === "bedrock" ? "bedrock" : "java";
+  const serverKey = ["server", ip, port, edition];
   const navigate = useNavigate();
   const queryClient = useQueryClient();
 
@@ -52,15 +58,20 @@ export const ServerDetail = () => {
   const findCachedServer = () => {
     for (const query of queryClient.getQueryCache().findAll({ queryKey: ["servers"] })) {
       const data = query.state.data as InfiniteData<ServerInfoResponse[]> | undefined;
-      const hit = data?.pages.flat().find((s) => s.ip === ip);
+      const hit = data?.pages.flat().find(
+        (s) =>
+          s.ip === ip &&
+          s.edition === edition &&
+          s.port === (port || (edition === "java" ? 25565 : 19132)),
+      );
       if (hit) return { server: hit, updatedAt: query.state.dataUpdatedAt };
     }
     return undefined;
   };
 
   const { data: server, isLoading: isInfoLoading } = useQuery({
-    queryKey: ["server", ip],
-    queryFn: () => serverApi.fetchServerInfo(ip!),
+    queryKey: serverKey,
+    queryFn: () => serverApi.fetchServerInfo({ ip: ip!, port, edition }),
     enabled: !!ip,
     staleTime: 10 * 60 * 1000,
     initialData: () => findCachedServer()?.server,
@@ -94,9 +105,9 @@ export const ServerDetail = () => {
   const updateMutation = useMutation({
     mutationFn: (body: UpdateServerRequest) => serverApi.updateServer(body),
     onMutate: async (body: UpdateServerRequest) => {
-      await queryClient.cancelQueries({ queryKey: ["server", ip] });
-      const previousServer = queryClient.getQueryData<ServerInfoResponse>(["server", ip]);
-      queryClient.setQueryData<ServerInfoResponse>(["server", ip], (old) => {
+      await queryClient.cancelQueries({ queryKey: serverKey });
+      const previousServer = queryClient.getQueryData<ServerInfoResponse>(serverKey);
+      queryClient.setQueryData<ServerInfoResponse>(serverKey, (old) => {
         if (!old) return old;
         return {
           ...old,
@@ -109,7 +120,7 @@ export const ServerDetail = () => {
     },
     onError: (_err, _body, context) => {
       if (context?.previousServer) {
-        queryClient.setQueryData(["server", ip], context.previousServer);
+        queryClient.setQueryData(serverKey, context.previousServer);
       }
     },
   });
@@ -163,7 +174,7 @@ export const ServerDetail = () => {
     onSuccess: () => {
       setIsEditing(false);
       setEditError(null);
-      queryClient.invalidateQueries({ queryKey: ["server", ip] });
+      queryClient.invalidateQueries({ queryKey: serverKey });
     },
     onError: () => {
       setEditError(t.serverInfo.editError);
@@ -183,8 +194,9 @@ export const ServerDetail = () => {
     (async () => {
       while (!cancelled) {
         try {
-          for await (const info of serverApi.streamServerInfo(ip, controller.signal)) {
-            queryClient.setQueryData<ServerInfoResponse>(["server", ip], info);
+          const request = { ip, port, edition };
+          for await (const info of serverApi.streamServerInfo(request, controller.signal)) {
+            queryClient.setQueryData<ServerInfoResponse>(["server", ip, port, edition], info);
             queryClient.invalidateQueries({ queryKey: ["serverData", info.id] });
             queryClient.invalidateQueries({ queryKey: ["playerList", info.id] });
             if (pingTimeoutRef.current) {
@@ -210,18 +222,18 @@ export const ServerDetail = () => {
         pingTimeoutRef.current = null;
       }
     };
-  }, [ip, queryClient]);
+  }, [ip, port, edition, queryClient]);
 
   // -- Handlers --------------------------------------------------------------
 
   const handleFlagToggle = (field: ServerFlagField) => {
     if (!server) return;
-    updateMutation.mutate(buildFlagUpdate(server.ip, field, server[field]));
+    updateMutation.mutate(buildFlagUpdate(server, field, server[field]));
   };
 
   const handleJoinStatusChange = (status: JoinStatus) => {
     if (!server) return;
-    updateMutation.mutate(buildJoinStatusUpdate(server.ip, status));
+    updateMutation.mutate(buildJoinStatusUpdate(server, status));
   };
 
   const handlePingRequest = () => {
@@ -41,6 +41,8 @@ export type PlayerSearchResponse = {
   id: number;
   server_id: number;
   server_ip: string;
+  server_port: number;
+  server_edition: Edition;
   name: string;
   status: PlayerStatus;
   last_seen_at: string;
@@ -63,11 +65,18 @@ export type AddAddrRequest = { addr: string; quick: boolean };
 
 export type ServerDeleteRequest = { id: number };
 
-export type ServerInfoRequest = { ip: string };
+// Which prober a server answers: Java over TCP, Bedrock over UDP.
+export type Edition = "java" | "bedrock";
+
+// A server is identified by its address and edition (a host may run both on
+// one port number); port 0 means the edition's default, 25565 or 19132.
+export type ServerInfoRequest = { ip: string; port: number; edition: Edition };
 
 export type ServerInfoResponse = {
   id: number;
   ip: string;
+  port: number;
+  edition: Edition;
   online: number;
   max: number;
   version_name: string;
@@ -102,6 +111,8 @@ export type ServerListRequest = {
 
 export type UpdateServerRequest = {
   server_ip: string;
+  server_port: number;
+  server_edition: Edition;
   is_checked: boolean | null;
   join_status: JoinStatus | null;
   is_crashed: boolean | null;
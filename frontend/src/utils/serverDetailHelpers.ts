@@ -1,4 +1,4 @@
-import type { JoinStatus, UpdateServerRequest } from "@/types";
+import type { Edition, JoinStatus, ServerInfoResponse, UpdateServerRequest } from "@/types";
 import type { ServerFlagField } from "@/constants/serverDetail";
 
 /**
@@ -8,12 +8,14 @@ import type { ServerFlagField } from "@/constants/serverDetail";
  * independent — toggling one no longer clears the others.
  */
 export function buildFlagUpdate(
-  serverIp: string,
+  server: ServerInfoResponse,
   field: ServerFlagField,
   currentValue: boolean,
 ): UpdateServerRequest {
   return {
-    server_ip: serverIp,
+    server_ip: server.ip,
+    server_port: server.port,
+    server_edition: server.edition,
     is_checked: field === "is_checked" ? !currentValue : null,
     join_status: null,
     is_crashed: field === "is_crashed" ? !currentValue : null,
@@ -22,17 +24,24 @@ export function buildFlagUpdate(
 
 /** Builds an UpdateServerRequest that sets only the join_status enum. */
 export function buildJoinStatusUpdate(
-  serverIp: string,
+  server: ServerInfoResponse,
   status: JoinStatus,
 ): UpdateServerRequest {
   return {
-    server_ip: serverIp,
+    server_ip: server.ip,
+    server_port: server.port,
+    server_edition: server.edition,
     is_checked: null,
     join_status: status,
     is_crashed: null,
   };
 }
 
+/** Detail page route of a server; the edition segment is left out for Java. */
+export function serverPath(ip: string, port: number, edition: Edition): string {
+  return edition === "java" ? `/server/${ip}/${port}` : `/server/${ip}/${port}/${edition}`;
+}
+
 export function buildChartData(
   history: Array<{ recorded_at: string; players_online: number }>,
 ) {
@@ -4,11 +4,71 @@
 //! - [`api`] — frontend ⇆ backend service.
 //! - [`worker`] — worker ⇆ backend control plane (workers dial in, register,
 //!   stream scan results + heartbeats, and receive commands/config).
+//!
+//! Also home to the little validation both ends of a message must agree on.
 
 pub mod api {
     tonic::include_proto!("api");
 }
 
+// `ServerCommand.cmd` carries a whole `WorkerConfig` next to small tasks; the
+// enum is generated, and commands are rare enough not to be worth boxing.
+#[allow(clippy::large_enum_variant)]
 pub mod worker {
     tonic::include_proto!("worker");
 }
+
+/// Whether `s` is a DNS hostname: dot-separated labels of letters, digits,
+/// hyphens and underscores (no leading/trailing hyphen), at most 253 bytes, and
+/// not an address (an all-numeric last label never names a host). The backend
+/// validates scan targets with it and workers re-check the tasks they receive,
+/// so both ends accept the same names.
+pub fn is_hostname(s: &str) -> bool {
+    let s = s.strip_suffix('.').unwrap_or(s);
+    !s.is_empty()
+        && s.len() <= 253
+        && s.parse::<std::net::IpAddr>().is_err()
+        && !s
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        && s.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hostname_syntax() {
+        for ok in [
+            "play.example.net",
+            "localhost",
+            "mc-1.example.co.uk",
+            "example.com.",
+        ] {
+            assert!(is_hostname(ok), "{ok}");
+        }
+        for bad in [
+            "",
+            "1.2.3.4",
+            "1.2.3",
+            "::1",
+            "bad_host-.com",
+            "a..b",
+            "-x.com",
+            "a b.com",
+        ] {
+            assert!(!is_hostname(bad), "{bad}");
+        }
+    }
+}
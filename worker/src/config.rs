@@ -2,7 +2,11 @@
 //! (it streams to the backend over gRPC), so this only needs to read a TOML file.
 
 use serde::Deserialize;
-use std::{env, fs, path::PathBuf};
+use std::{env, fs, net::SocketAddr, path::PathBuf};
+
+use crate::{
+    login_pool::LoginPool, permutation::PermutationScan, ports::PortRange, ratelimit::RateWindow,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -23,6 +27,10 @@ pub struct ServerFilter {
     pub has_none_players: Option<bool>,
     pub join_status: Option<String>,
     pub query: Option<String>,
+    /// "java" or "bedrock".
+    pub edition: Option<String>,
+    /// The backend classifier's suggested join status (update path only).
+    pub suggested_join_status: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -39,12 +47,55 @@ pub struct WorkerConfig {
     pub update_interval_secs: u32,
     #[serde(default = "default_update_concurrency")]
     pub update_concurrency: u32,
+    // Max concurrent re-probes per /24 (IPv6 /64); 0 or absent = 4.
+    #[serde(default)]
+    pub update_per_network: u32,
     // Which existing servers the update cycle re-probes.
     #[serde(default)]
     pub update_filter: ServerFilter,
     // Acceptance filter applied to freshly discovered servers before reporting.
     #[serde(default)]
     pub search_filter: ServerFilter,
+    // Ports the search pool samples from, e.g. ["25565-25575@4", "25580"].
+    // Empty = 25565 only.
+    #[serde(default)]
+    pub search_ports: Vec<PortRange>,
+    // Follow Java probes with a GameSpy4 query (servers with `enable-query=true`)
+    // for the full player list, plugins, map and software.
+    #[serde(default)]
+    pub query_enabled: bool,
+    // Negotiate the status protocol per server and record which client
+    // protocols it accepts (one extra status ping per probed version).
+    #[serde(default)]
+    pub negotiate_protocol: bool,
+    // Continue login handshakes (offline mode) into the play state to detect
+    // auth plugins, whitelist kicks and mod handshakes.
+    #[serde(default)]
+    pub deep_login: bool,
+    // `[worker.login]`: usernames (and their offline UUIDs) login probes present.
+    #[serde(default)]
+    pub login: LoginPool,
+    // Probe budgets in milliseconds; 0 or absent = the built-in default.
+    #[serde(default)]
+    pub connect_timeout_ms: u32,
+    #[serde(default)]
+    pub read_timeout_ms: u32,
+    #[serde(default)]
+    pub handshake_timeout_ms: u32,
+    #[serde(default)]
+    pub probe_timeout_ms: u32,
+    // `[worker.permutation]`: walk the IPv4 space once in a keyed order instead
+    // of sampling at random. Absent = random search.
+    #[serde(default)]
+    pub permutation: Option<PermutationScan>,
+    // Probe rate caps (packets and new connections per second); 0 or absent =
+    // unlimited. `[[worker.rate_schedule]]` windows override them by time of day.
+    #[serde(default)]
+    pub max_packets_per_sec: u32,
+    #[serde(default)]
+    pub max_connections_per_sec: u32,
+    #[serde(default)]
+    pub rate_schedule: Vec<RateWindow>,
     pub log_level: Option<String>,
 
     // gRPC mode
@@ -52,8 +103,15 @@ pub struct WorkerConfig {
     pub token: Option<String>,
     pub id: Option<String>,
     pub name: Option<String>,
+    // Label the backend matches when an operator sends work to "any worker" in
+    // a region, e.g. "eu".
+    pub region: Option<String>,
     pub tls_ca: Option<String>,
     pub insecure: Option<bool>,
+    // DNS servers for hostname targets ("1.1.1.1:53"); empty = the system
+    // resolver.
+    #[serde(default)]
+    pub dns_servers: Vec<SocketAddr>,
 }
 
 impl Config {
@@ -81,10 +139,10 @@ fn default_update_concurrency() -> u32 {
 /// `config.toml`.
 pub fn config_path() -> PathBuf {
     let args: Vec<String> = env::args().collect();
-    if let Some(pos) = args.iter().position(|a| a == "--config") {
-        if let Some(val) = args.get(pos + 1) {
-            return PathBuf::from(val);
-        }
+    if let Some(pos) = args.iter().position(|a| a == "--config")
+        && let Some(val) = args.get(pos + 1)
+    {
+        return PathBuf::from(val);
     }
     if let Ok(val) = env::var("CONFIG_PATH") {
         return PathBuf::from(val);
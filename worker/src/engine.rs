@@ -4,28 +4,39 @@
 //! [`GrpcSink`], and pulls re-probe targets through a [`GrpcTargetSource`].
 
 use std::{
+    collections::HashMap,
     net::IpAddr,
     sync::{
-        Arc,
-        atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, Ordering},
     },
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use anyhow::bail;
+use ipnet::Ipv4Net;
+use proto::worker::{Edition, ScanShard};
 use rand::{SeedableRng, rngs::SysRng};
 use rand_chacha::ChaCha8Rng;
 use tokio::{
     sync::{Notify, Semaphore, watch},
     task::JoinSet,
-    time::timeout,
 };
 use tokio_stream::StreamExt;
-use tracing::{debug, error, info};
-use worker::generate_random_ip;
+use tracing::{debug, error, info, warn};
+use worker::{Exclusions, generate_random_ip};
 
 use crate::{
     grpc_backend::{GrpcSink, GrpcTargetSource},
-    report::{ScanReport, check_server, probe},
+    login_pool::LoginPool,
+    netqueue::{NetworkQueue, network_of},
+    permutation::{Cursor, Next, PermutationScan, Walk},
+    ports::{PortRange, PortSampler},
+    ratelimit::{RateLimiter, RateLimits, RateWindow, effective_limits},
+    report::{ProbeOptions, ScanReport, check_server, probe, probe_bedrock, probe_edition},
+    resolve::TargetResolver,
+    server_actions::query::get_query_stat,
+    timeouts::{FailureCounts, ProbeTimeouts},
 };
 
 /// Live-tunable subset of the worker config (mirrors `[worker]` and the gRPC
@@ -44,10 +55,33 @@ pub struct RuntimeConfig {
     pub update_with_connection: bool,
     pub update_interval_secs: u32,
     pub update_concurrency: u32,
+    /// Max concurrent re-probes per /24 (IPv6 /64) during an update cycle.
+    pub update_per_network: u32,
     /// Which existing servers the update cycle re-probes (sent to the backend).
     pub update_filter: proto::worker::ServerFilter,
     /// Acceptance filter applied to freshly discovered servers before reporting.
     pub search_filter: proto::worker::ServerFilter,
+    /// Weighted port ranges the search threads sample from (empty = 25565).
+    pub search_ports: Vec<PortRange>,
+    /// Whether Java probes are followed by a GameSpy4 query for the full player
+    /// list, plugins, map and software.
+    pub query_enabled: bool,
+    /// Whether Java probes negotiate the status protocol and record the client
+    /// protocols the server accepts.
+    pub negotiate_protocol: bool,
+    /// Whether login handshakes continue into the play state to detect auth
+    /// plugins, whitelist kicks and mod handshakes.
+    pub deep_login: bool,
+    /// Usernames login handshakes present.
+    pub login: LoginPool,
+    /// Per-phase probe budgets.
+    pub timeouts: ProbeTimeouts,
+    /// Full-coverage walk the search threads take instead of random sampling.
+    pub permutation: Option<PermutationScan>,
+    /// Probe rate caps outside any schedule window.
+    pub rate_limits: RateLimits,
+    /// Time-of-day overrides of `rate_limits`.
+    pub rate_schedule: Vec<RateWindow>,
 }
 
 impl From<&crate::config::WorkerConfig> for RuntimeConfig {
@@ -59,8 +93,26 @@ impl From<&crate::config::WorkerConfig> for RuntimeConfig {
             update_with_connection: c.update_with_connection,
             update_interval_secs: c.update_interval_secs,
             update_concurrency: c.update_concurrency,
+            update_per_network: c.update_per_network,
             update_filter: crate::grpc_backend::filter_to_proto(&c.update_filter),
             search_filter: crate::grpc_backend::filter_to_proto(&c.search_filter),
+            search_ports: c.search_ports.clone(),
+            query_enabled: c.query_enabled,
+            negotiate_protocol: c.negotiate_protocol,
+            deep_login: c.deep_login,
+            login: c.login.clone(),
+            timeouts: ProbeTimeouts::from_millis(
+                c.connect_timeout_ms,
+                c.read_timeout_ms,
+                c.handshake_timeout_ms,
+                c.probe_timeout_ms,
+            ),
+            permutation: c.permutation,
+            rate_limits: RateLimits {
+                packets_per_sec: c.max_packets_per_sec,
+                connections_per_sec: c.max_connections_per_sec,
+            },
+            rate_schedule: c.rate_schedule.clone(),
         }
     }
 }
@@ -68,7 +120,8 @@ impl From<&crate::config::WorkerConfig> for RuntimeConfig {
 /// Whether a freshly discovered server passes the search-module acceptance
 /// filter. Only fields observable at discovery time are checked: online-mode
 /// (`licensed`, from the login handshake that discovery always performs),
-/// `requires_mods`, and `has_players`. An unset filter field accepts anything.
+/// `requires_mods`, `has_players`, and `edition`. An unset filter field accepts
+/// anything.
 fn accept_discovery(report: &ScanReport, f: &proto::worker::ServerFilter) -> bool {
     if let Some(want) = f.licensed {
         let is_online_mode = report.extra.as_ref().is_some_and(|e| e.is_online_mode);
@@ -76,13 +129,22 @@ fn accept_discovery(report: &ScanReport, f: &proto::worker::ServerFilter) -> boo
             return false;
         }
     }
-    if let Some(want) = f.requires_mods {
-        if report.requires_mods != want {
-            return false;
-        }
+    if let Some(want) = f.requires_mods
+        && report.requires_mods != want
+    {
+        return false;
     }
-    if let Some(want) = f.has_players {
-        if (report.players_online > 0) != want {
+    if let Some(want) = f.has_players
+        && (report.players_online > 0) != want
+    {
+        return false;
+    }
+    if let Some(want) = f.edition.as_deref() {
+        let name = match report.edition {
+            Edition::Java => "java",
+            Edition::Bedrock => "bedrock",
+        };
+        if name != want {
             return false;
         }
     }
@@ -95,6 +157,9 @@ pub struct UpdateTarget {
     pub ip: String,
     pub port: u16,
     pub with_connection: bool,
+    pub edition: Edition,
+    /// Hostname to announce in the handshake, for servers added by name.
+    pub host: Option<String>,
 }
 
 /// One frame of the update-target stream: either the leading total (count of
@@ -105,22 +170,35 @@ pub enum UpdateTargetItem {
     Target(UpdateTarget),
 }
 
-const SEARCH_PORT: u16 = 25565;
 const DEFAULT_UPDATE_INTERVAL_SECS: u64 = 600;
 const DEFAULT_UPDATE_CONCURRENCY: usize = 50;
+/// Update-cycle probes in flight per /24 (or IPv6 /64) when unset.
+const DEFAULT_UPDATE_PER_NETWORK: usize = 4;
+/// Update targets held back behind busy networks, as a multiple of the
+/// cycle's concurrency; past it the stream is not read until some finish.
+const UPDATE_WAITING_FACTOR: usize = 4;
 /// Wait before retrying a cycle whose target fetch failed (e.g. the backend link
 /// was momentarily down, as can happen on a cold start when the update loop
 /// spins up before the session). Much shorter than a normal interval so updates
 /// begin promptly once the link is up, instead of stalling for a full interval.
 const RETRY_INTERVAL_SECS: u64 = 10;
-/// Per-server budget for a full probe (status + optional login handshake).
-/// The underlying socket reads have no timeout of their own, so this is the
-/// only thing bounding a server that accepts the TCP connection but stalls.
-const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Addresses of a scan job shard probed between two progress reports. Also the
+/// granularity at which a shard notices a search pause.
+const SHARD_CHUNK: u32 = 256;
+/// Widest shard the worker accepts. The backend cuts jobs into /22s; anything
+/// much wider is a misbehaving backend, and would hold a session for hours.
+const MIN_SHARD_PREFIX: u8 = 16;
+/// How often the rate schedule is re-evaluated against the clock.
+const RATE_SCHEDULE_TICK: Duration = Duration::from_secs(15);
+/// Pause before a search thread samples again after the exclusion list left it
+/// nothing to draw from.
+const EXCLUDED_BACKOFF: Duration = Duration::from_secs(30);
 
 pub struct Engine {
     pub sink: GrpcSink,
     pub targets: GrpcTargetSource,
+    /// Resolves hostname scan targets.
+    pub resolver: TargetResolver,
     /// Process start, used for the heartbeat's uptime. Lives with the engine (not
     /// the session) so uptime reflects the worker, not the current connection.
     pub started: Instant,
@@ -139,21 +217,44 @@ pub struct Engine {
     /// Fired to interrupt the running update cycle.
     abort_update: Notify,
     pub servers_found: AtomicU64,
+    /// Servers found per probed port, for the heartbeat's `port_hits`.
+    pub port_hits: Mutex<HashMap<u16, u64>>,
     pub ips_scanned: AtomicU64,
     pub updating: AtomicBool,
     pub update_done: AtomicU64,
     pub update_total: AtomicU64,
     pub last_update_unix: AtomicI64,
+    /// On-demand pings, scans and shards running, for the heartbeat.
+    pub pending_tasks: AtomicU32,
+    /// Failed probes by cause, for the heartbeat.
+    pub failures: FailureCounts,
+    /// Position of the permutation search walk.
+    pub cursor: Cursor,
+    /// One shard is scanned at a time; the backend's next assignment waits
+    /// here, so shards never run more than `threads` probes between them.
+    shard_slot: Semaphore,
+    /// Operator exclusion list, replaced whole when the backend pushes a new
+    /// one. Checked before every probe.
+    exclusions: RwLock<Arc<Exclusions>>,
+    /// Paces every probe; its limits follow the config and its schedule.
+    pub limiter: Arc<RateLimiter>,
 }
 
 #[allow(dead_code)] // some methods are only driven by the gRPC command loop
 impl Engine {
-    pub fn new(sink: GrpcSink, targets: GrpcTargetSource, cfg: RuntimeConfig) -> Arc<Self> {
+    pub fn new(
+        sink: GrpcSink,
+        targets: GrpcTargetSource,
+        resolver: TargetResolver,
+        cfg: RuntimeConfig,
+        exclusions: Exclusions,
+    ) -> Arc<Self> {
         let (cfg_tx, _) = watch::channel(cfg);
         let (pause_tx, _) = watch::channel(false);
         Arc::new(Self {
             sink,
             targets,
+            resolver,
             started: Instant::now(),
             cfg_tx,
             pause_tx,
@@ -162,11 +263,18 @@ impl Engine {
             trigger_update: Notify::new(),
             abort_update: Notify::new(),
             servers_found: AtomicU64::new(0),
+            port_hits: Mutex::new(HashMap::new()),
             ips_scanned: AtomicU64::new(0),
             updating: AtomicBool::new(false),
             update_done: AtomicU64::new(0),
             update_total: AtomicU64::new(0),
             last_update_unix: AtomicI64::new(0),
+            pending_tasks: AtomicU32::new(0),
+            failures: FailureCounts::default(),
+            cursor: Cursor::default(),
+            shard_slot: Semaphore::new(1),
+            exclusions: RwLock::new(Arc::new(exclusions)),
+            limiter: Arc::new(RateLimiter::default()),
         })
     }
 
@@ -211,6 +319,49 @@ impl Engine {
         self.refresh_pause();
     }
 
+    /// Counts a discovered server, overall and against the port it answered on.
+    fn record_found(&self, port: u16) {
+        self.servers_found.fetch_add(1, Ordering::Relaxed);
+        *self.port_hits.lock().unwrap().entry(port).or_default() += 1;
+    }
+
+    /// Resumes the permutation walk from a backend-persisted position, if it
+    /// is for the walk currently configured (see [`Cursor::resume`]).
+    pub fn resume_cursor(&self, scan: PermutationScan, position: u64) {
+        if self.config().permutation == Some(scan) {
+            self.cursor.start(scan);
+            self.cursor.resume(scan, position);
+        }
+    }
+
+    /// The permutation walk's cursor for the heartbeat, held back to the
+    /// oldest probe still in flight (see [`Cursor::settled`]), and the share of
+    /// the partition walked. `None` in random search.
+    pub fn cursor_report(&self) -> Option<(PermutationScan, u64, f64)> {
+        let (scan, position) = self.cursor.position()?;
+        if self.config().permutation != Some(scan) {
+            return None;
+        }
+        let len = scan.range().end - scan.range().start;
+        let coverage = position as f64 / len as f64 * 100.0;
+        let (_, settled) = self.cursor.settled()?;
+        Some((scan, settled, coverage))
+    }
+
+    pub fn exclusions(&self) -> Arc<Exclusions> {
+        self.exclusions.read().unwrap().clone()
+    }
+
+    pub fn set_exclusions(&self, exclusions: Exclusions) {
+        info!("applying exclusion list: {} blocks", exclusions.len());
+        *self.exclusions.write().unwrap() = Arc::new(exclusions);
+    }
+
+    /// Whether `ip` is on the operator's exclusion list.
+    pub fn is_excluded(&self, ip: &str) -> bool {
+        self.exclusions.read().unwrap().contains_str(ip)
+    }
+
     /// Start an update cycle now, cutting short the inter-cycle interval wait.
     pub fn trigger_update(&self) {
         self.trigger_update.notify_one();
@@ -221,43 +372,243 @@ impl Engine {
         self.abort_update.notify_one();
     }
 
-    /// Launches the search supervisor and the update loop, returning their task
-    /// handles so the caller can abort them on shutdown/reconnect (otherwise the
-    /// tasks keep the `Engine` alive forever, leaking the whole search pool).
+    /// Launches the search supervisor, the update loop and the rate schedule,
+    /// returning their task handles so the caller can abort them on
+    /// shutdown/reconnect (otherwise the tasks keep the `Engine` alive forever,
+    /// leaking the whole search pool).
     pub fn start(self: &Arc<Self>) -> Vec<tokio::task::JoinHandle<()>> {
         vec![
             tokio::spawn(search_supervisor(self.clone())),
             tokio::spawn(update_loop(self.clone())),
+            tokio::spawn(rate_schedule_loop(self.clone())),
         ]
     }
 
-    /// On-demand scan (discovery semantics).
+    /// On-demand scan (discovery semantics). `task_id` is the backend task it
+    /// runs for (0 for none), echoed in the result. An excluded address or a
+    /// failed probe is an error, for the task to fail with.
     ///
-    /// Bounded by [`PROBE_TIMEOUT`] like [`Engine::ping`]: a server that accepts
-    /// the TCP connection but never replies must not leak the task forever (the
-    /// underlying socket reads have no timeout of their own).
-    pub async fn scan(&self, ip: String, port: u16) {
+    /// Bounded by the configured probe timeouts like [`Engine::ping`]: a server
+    /// that accepts the TCP connection but never replies must not leak the task.
+    pub async fn scan(
+        &self,
+        ip: String,
+        port: u16,
+        edition: Edition,
+        host: Option<String>,
+        task_id: u64,
+    ) -> anyhow::Result<()> {
+        if self.is_excluded(&ip) {
+            info!("not scanning excluded address {ip}");
+            bail!("excluded address");
+        }
         self.ips_scanned.fetch_add(1, Ordering::Relaxed);
-        if let Ok(Ok(report)) = timeout(PROBE_TIMEOUT, probe(&ip, port, None, true, true)).await {
-            self.servers_found.fetch_add(1, Ordering::Relaxed);
-            self.sink.discovered(report).await;
+        let opts = ProbeOptions {
+            host,
+            ..self.probe_options(true, true)
+        };
+        self.pace(edition).await;
+        match probe_edition(edition, &ip, port, opts).await {
+            Ok(mut report) => {
+                self.attach_query(&mut report).await;
+                self.record_found(port);
+                self.sink.discovered(report, task_id).await;
+                Ok(())
+            }
+            Err(e) => {
+                self.failures.record(&e);
+                Err(e)
+            }
+        }
+    }
+
+    /// On-demand scan of a hostname target: resolves it (SRV when `port` is 0)
+    /// to a probeable address, then scans that while announcing the hostname.
+    pub async fn scan_host(
+        &self,
+        host: String,
+        port: u16,
+        edition: Edition,
+        task_id: u64,
+    ) -> anyhow::Result<()> {
+        match self.resolver.resolve(&host, port, edition).await {
+            Ok((ip, port)) => self.scan(ip, port, edition, Some(host), task_id).await,
+            Err(e) => {
+                warn!("not scanning {host}: {e}");
+                Err(e)
+            }
+        }
+    }
+
+    /// Scans every address of a scan job shard from offset `start`, in order,
+    /// reporting progress after each [`SHARD_CHUNK`]. Each chunk runs `threads`
+    /// probes at a time with discovery semantics (search filter included), and
+    /// the walk holds while search is paused. Non-probeable and excluded
+    /// addresses are skipped but count as scanned. Waits for the shard before
+    /// it to finish.
+    pub async fn scan_shard(self: &Arc<Self>, shard: ScanShard) {
+        let net = match shard.cidr.parse::<Ipv4Net>() {
+            Ok(net) if net.prefix_len() >= MIN_SHARD_PREFIX => net.trunc(),
+            _ => {
+                warn!("ignoring scan shard with invalid block {:?}", shard.cidr);
+                return;
+            }
+        };
+        let Ok(port) = u16::try_from(shard.port) else {
+            warn!("ignoring scan shard with invalid port {}", shard.port);
+            return;
+        };
+        let edition = shard.edition();
+        let base = u32::from(net.network());
+        let size = 1u32 << (32 - net.prefix_len());
+        let Ok(_slot) = self.shard_slot.acquire().await else {
+            return;
+        };
+        info!(
+            "scanning shard {} ({net}) from offset {}",
+            shard.shard_id, shard.start
+        );
+
+        let mut pause_rx = self.pause_tx.subscribe();
+        let mut next = shard.start.min(size);
+        while next < size {
+            if *pause_rx.borrow_and_update() {
+                if pause_rx.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            }
+
+            let end = next.saturating_add(SHARD_CHUNK).min(size);
+            let cfg = self.config();
+            let timeouts = self.timeouts(&cfg);
+            let semaphore = Arc::new(Semaphore::new(cfg.threads.max(1) as usize));
+            let exclusions = self.exclusions();
+            let mut set = JoinSet::new();
+            for offset in next..end {
+                let ip = IpAddr::V4((base + offset).into()).to_string();
+                if !worker::is_probeable_ip(&ip) || exclusions.contains_str(&ip) {
+                    continue;
+                }
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    break;
+                };
+                let engine = self.clone();
+                let timeouts = timeouts.clone();
+                set.spawn(async move {
+                    let _permit = permit;
+                    probe_candidate(&engine, ip, port, edition, &timeouts).await;
+                });
+            }
+            while set.join_next().await.is_some() {}
+
+            next = end;
+            self.sink
+                .shard_progress(shard.shard_id, next, next == size)
+                .await;
         }
+        if shard.start >= size {
+            self.sink.shard_progress(shard.shard_id, size, true).await;
+        }
+        info!("shard {} finished", shard.shard_id);
     }
 
     /// On-demand ping / update-cycle probe (update semantics).
     ///
-    /// Bounded by a per-server [`PROBE_TIMEOUT`]: an unresponsive server (TCP
+    /// Bounded by the configured probe timeouts: an unresponsive server (TCP
     /// accepts but never replies) must not block its update slot until the OS
-    /// connection timeout fires. A timeout is treated as offline.
-    pub async fn ping(&self, ip: String, port: u16, with_connection: bool) {
-        match timeout(
-            PROBE_TIMEOUT,
-            probe(&ip, port, None, with_connection, false),
-        )
-        .await
-        {
-            Ok(Ok(report)) => self.sink.updated(report).await,
-            Ok(Err(_)) | Err(_) => self.sink.offline(&ip).await,
+    /// connection timeout fires. A timeout is treated as offline. An excluded
+    /// address is not probed, and reported neither way. `task_id` is as for
+    /// [`Engine::scan`].
+    pub async fn ping(
+        &self,
+        ip: String,
+        port: u16,
+        with_connection: bool,
+        edition: Edition,
+        host: Option<String>,
+        task_id: u64,
+    ) {
+        if self.is_excluded(&ip) {
+            debug!("not pinging excluded address {ip}");
+            return;
+        }
+        let opts = ProbeOptions {
+            host,
+            ..self.probe_options(with_connection, false)
+        };
+        self.pace(edition).await;
+        match probe_edition(edition, &ip, port, opts).await {
+            Ok(mut report) => {
+                self.attach_query(&mut report).await;
+                self.sink.updated(report, task_id).await
+            }
+            Err(e) => {
+                self.failures.record(&e);
+                self.sink.offline(&ip, port, edition, task_id).await
+            }
+        }
+    }
+
+    /// Waits for the rate limiter to allow a Bedrock ping's datagram. Java
+    /// probes wait per connection instead, in their metered connect.
+    async fn pace(&self, edition: Edition) {
+        if edition == Edition::Bedrock {
+            self.limiter.packet().await;
+        }
+    }
+
+    /// The configured probe budgets, metered by the engine's limiter.
+    fn timeouts(&self, cfg: &RuntimeConfig) -> ProbeTimeouts {
+        cfg.timeouts.metered(&self.limiter)
+    }
+
+    /// Probe options for the current config; the arguments are as for
+    /// [`ProbeOptions`].
+    fn probe_options(&self, fetch_extra: bool, require_extra: bool) -> ProbeOptions {
+        let cfg = self.config();
+        ProbeOptions {
+            fetch_extra,
+            require_extra,
+            negotiate: cfg.negotiate_protocol,
+            deep_login: cfg.deep_login,
+            timeouts: self.timeouts(&cfg),
+            login: cfg.login,
+            host: None,
+        }
+    }
+
+    /// Attaches the GameSpy4 full stat to a Java report when `query_enabled`
+    /// is on. Best-effort: most servers leave query disabled and never answer,
+    /// which just leaves `query` unset after the read budget.
+    async fn attach_query(&self, report: &mut ScanReport) {
+        let cfg = self.config();
+        if cfg.query_enabled && report.edition == Edition::Java {
+            let timeouts = self.timeouts(&cfg);
+            report.query = get_query_stat(&report.ip, report.port as u16, &timeouts)
+                .await
+                .ok();
+        }
+    }
+}
+
+/// Keeps the rate limiter on the limits the config and its schedule call for,
+/// re-checking the clock every [`RATE_SCHEDULE_TICK`] and on config changes.
+async fn rate_schedule_loop(engine: Arc<Engine>) {
+    let mut cfg_rx = engine.cfg_tx.subscribe();
+    loop {
+        let cfg = cfg_rx.borrow_and_update().clone();
+        let now = chrono::Local::now().time();
+        engine
+            .limiter
+            .set_limits(effective_limits(cfg.rate_limits, &cfg.rate_schedule, now));
+        tokio::select! {
+            changed = cfg_rx.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+            _ = tokio::time::sleep(RATE_SCHEDULE_TICK) => {}
         }
     }
 }
@@ -268,11 +619,28 @@ async fn search_supervisor(engine: Arc<Engine>) {
         let cfg = cfg_rx.borrow_and_update().clone();
 
         if cfg.search_module && cfg.threads > 0 {
+            let ports = Arc::new(PortSampler::new(&cfg.search_ports));
+            let walk = cfg.permutation.map(|scan| {
+                engine.cursor.start(scan);
+                Arc::new(Walk::new(scan))
+            });
             let mut set = JoinSet::new();
             for _ in 0..cfg.threads {
-                set.spawn(search_thread(engine.clone(), engine.pause_tx.subscribe()));
+                set.spawn(search_thread(
+                    engine.clone(),
+                    engine.pause_tx.subscribe(),
+                    ports.clone(),
+                    walk.clone(),
+                    engine.timeouts(&cfg),
+                ));
+            }
+            match cfg.permutation {
+                Some(p) => info!(
+                    "search: {} threads walking permutation partition {}/{}",
+                    cfg.threads, p.partition, p.partitions
+                ),
+                None => info!("search: {} threads running", cfg.threads),
             }
-            info!("search: {} threads running", cfg.threads);
 
             // Block until the config changes, then rebuild the pool.
             if cfg_rx.changed().await.is_err() {
@@ -288,7 +656,13 @@ async fn search_supervisor(engine: Arc<Engine>) {
     }
 }
 
-async fn search_thread(engine: Arc<Engine>, mut pause_rx: watch::Receiver<bool>) {
+async fn search_thread(
+    engine: Arc<Engine>,
+    mut pause_rx: watch::Receiver<bool>,
+    ports: Arc<PortSampler>,
+    walk: Option<Arc<Walk>>,
+    timeouts: ProbeTimeouts,
+) {
     let mut rng =
         ChaCha8Rng::try_from_rng(&mut SysRng).expect("Failed to seed RNG from system entropy");
 
@@ -300,41 +674,92 @@ async fn search_thread(engine: Arc<Engine>, mut pause_rx: watch::Receiver<bool>)
             continue;
         }
 
-        let ip = IpAddr::V4(generate_random_ip(&mut rng)).to_string();
-        engine.ips_scanned.fetch_add(1, Ordering::Relaxed);
-
-        if let Ok(stream) = check_server(&ip, SEARCH_PORT).await {
-            debug!("Potential server found at {}:{}", ip, SEARCH_PORT);
-            let engine = engine.clone();
-            let _ = timeout(PROBE_TIMEOUT, async move {
-                match probe(&ip, SEARCH_PORT, Some(stream), true, true).await {
-                    Ok(report) => {
-                        // Drop discoveries that don't match the search-module
-                        // acceptance filter before counting or reporting them.
-                        if !accept_discovery(&report, &engine.config().search_filter) {
-                            debug!("Discovery {}:{} filtered out", ip, SEARCH_PORT);
-                            return;
-                        }
-                        engine.servers_found.fetch_add(1, Ordering::Relaxed);
-                        info!(
-                            target: "server_found",
-                            ip = %report.ip,
-                            port = report.port,
-                            version = %report.version_name,
-                            online = report.players_online,
-                            max = report.players_max,
-                            "New server detected"
-                        );
-                        engine.sink.discovered(report).await;
+        let exclusions = engine.exclusions();
+        // Held until the probe is done, so the cursor report waits for it.
+        let (ip, _claim) = match &walk {
+            None => match generate_random_ip(&mut rng, &exclusions) {
+                Some(ip) => (ip, None),
+                None => {
+                    warn!("search: exclusions cover the public address space, backing off");
+                    tokio::time::sleep(EXCLUDED_BACKOFF).await;
+                    continue;
+                }
+            },
+            Some(walk) => match engine.cursor.next_address(walk, &exclusions) {
+                Next::Address(ip, claim) => (ip, Some(claim)),
+                // A long excluded stretch; let the other threads at the cursor.
+                Next::Skipped => {
+                    tokio::task::yield_now().await;
+                    continue;
+                }
+                // Partition walked: this thread is done until the config changes.
+                Next::Walked => return,
+            },
+        };
+        let ip = IpAddr::V4(ip).to_string();
+        let (port, edition) = ports.sample(&mut rng);
+        probe_candidate(&engine, ip, port, edition, &timeouts).await;
+    }
+}
+
+/// Probes one address that may or may not host a server (a random search pick
+/// or a shard address), reporting it as a discovery if it does.
+async fn probe_candidate(
+    engine: &Engine,
+    ip: String,
+    port: u16,
+    edition: Edition,
+    timeouts: &ProbeTimeouts,
+) {
+    engine.ips_scanned.fetch_add(1, Ordering::Relaxed);
+    engine.pace(edition).await;
+
+    match edition {
+        Edition::Java => {
+            if let Ok(stream) = check_server(&ip, port, timeouts).await {
+                debug!("Potential server found at {}:{}", ip, port);
+                let opts = engine.probe_options(true, true);
+                match probe(&ip, port, Some(stream), opts).await {
+                    Ok(report) => report_discovery(engine, report).await,
+                    Err(e) => {
+                        engine.failures.record(&e);
+                        debug!("Failed to process {}:{} | {}", ip, port, e)
                     }
-                    Err(e) => debug!("Failed to process {}:{} | {}", ip, SEARCH_PORT, e),
                 }
-            })
-            .await;
+            }
+        }
+        // The pong is both the liveness check and the whole status, and its
+        // wait has the connect budget; no answer is the common (empty IP) case.
+        Edition::Bedrock => {
+            if let Ok(report) = probe_bedrock(&ip, port, timeouts).await {
+                report_discovery(engine, report).await;
+            }
         }
     }
 }
 
+/// Counts and reports a search discovery, unless it doesn't match the
+/// search-module acceptance filter.
+async fn report_discovery(engine: &Engine, mut report: ScanReport) {
+    if !accept_discovery(&report, &engine.config().search_filter) {
+        debug!("Discovery {}:{} filtered out", report.ip, report.port);
+        return;
+    }
+    engine.attach_query(&mut report).await;
+    engine.record_found(report.port as u16);
+    info!(
+        target: "server_found",
+        ip = %report.ip,
+        port = report.port,
+        edition = report.edition.as_str_name(),
+        version = %report.version_name,
+        online = report.players_online,
+        max = report.players_max,
+        "New server detected"
+    );
+    engine.sink.discovered(report, 0).await;
+}
+
 /// Blocks while the update module is disabled, returning only when a cycle should
 /// run anyway: either a manual trigger fired (an operator forcing a one-off
 /// update), or the module was just switched on. Returns `false` when the config
@@ -446,6 +871,11 @@ async fn run_update_cycle(engine: &Arc<Engine>) -> bool {
     } else {
         cfg.update_concurrency as usize
     };
+    let per_network = if cfg.update_per_network == 0 {
+        DEFAULT_UPDATE_PER_NETWORK
+    } else {
+        cfg.update_per_network as usize
+    };
 
     let fetched = match engine
         .targets
@@ -453,48 +883,83 @@ async fn run_update_cycle(engine: &Arc<Engine>) -> bool {
         .await
     {
         Ok(stream) => {
-            // Consume the target stream through a fixed-size pool. We acquire a
-            // permit *before* pulling the next target, so at most `concurrency`
-            // probes are in flight and at most `concurrency` tasks are ever
-            // alive — no matter how many servers the backend streams (the old
-            // code spawned one task per target up front). Reserved/invalid
-            // addresses are dropped so a compromised backend can't aim the
-            // worker at internal hosts. `update_total` is set once from the
-            // stream's leading frame (the backend's row count) so progress has a
-            // fixed denominator; skipped addresses still count as done so
-            // `update_done` reaches `update_total` at the end of the cycle.
+            // Consume the target stream through a bounded pool: the stream is
+            // only read while fewer than `concurrency` probes are in flight, so
+            // at most that many tasks are ever alive — no matter how many
+            // servers the backend streams (the old code spawned one task per
+            // target up front). Within the pool each /24 gets at most
+            // `per_network` probes ([`NetworkQueue`]); targets of a busy network
+            // wait while the ones behind them go ahead, up to
+            // [`UPDATE_WAITING_FACTOR`] × `concurrency` waiting targets.
+            // Reserved/invalid addresses are dropped so a compromised backend
+            // can't aim the worker at internal hosts, as are excluded ones.
+            // Skipped targets are reported so the backend backs them off.
+            // `update_total` is set once from the stream's leading frame (the
+            // backend's row count) so progress has a fixed denominator; skipped
+            // addresses still count as done so `update_done` reaches
+            // `update_total` at the end of the cycle.
             let dispatch_engine = engine.clone();
             let dispatcher = tokio::spawn(async move {
-                let semaphore = Arc::new(Semaphore::new(concurrency));
+                let mut queue = NetworkQueue::new(per_network);
+                let mut networks = HashMap::new();
                 let mut set = JoinSet::new();
+                let mut open = true;
                 tokio::pin!(stream);
-                while let Some(item) = stream.next().await {
-                    let t = match item {
-                        Ok(UpdateTargetItem::Total(n)) => {
-                            dispatch_engine.update_total.store(n, Ordering::Relaxed);
-                            continue;
+                loop {
+                    let room = set.len() < concurrency
+                        && queue.waiting() < concurrency * UPDATE_WAITING_FACTOR;
+                    tokio::select! {
+                        item = stream.next(), if open && room => {
+                            let t = match item {
+                                Some(Ok(UpdateTargetItem::Total(n))) => {
+                                    dispatch_engine.update_total.store(n, Ordering::Relaxed);
+                                    continue;
+                                }
+                                Some(Ok(UpdateTargetItem::Target(t))) => t,
+                                Some(Err(e)) => {
+                                    error!(target: "updater", "target stream error: {}", e);
+                                    open = false;
+                                    continue;
+                                }
+                                None => {
+                                    open = false;
+                                    continue;
+                                }
+                            };
+                            if !worker::is_probeable_ip(&t.ip)
+                                || dispatch_engine.is_excluded(&t.ip)
+                            {
+                                dispatch_engine.sink.skipped(&t.ip, t.port, t.edition).await;
+                                dispatch_engine.update_done.fetch_add(1, Ordering::Relaxed);
+                                continue;
+                            }
+                            match network_of(&t.ip) {
+                                Some(net) => {
+                                    if let Some(t) = queue.push(net, t) {
+                                        let id = spawn_update_probe(&mut set, &dispatch_engine, t);
+                                        networks.insert(id, net);
+                                    }
+                                }
+                                None => {
+                                    spawn_update_probe(&mut set, &dispatch_engine, t);
+                                }
+                            }
                         }
-                        Ok(UpdateTargetItem::Target(t)) => t,
-                        Err(e) => {
-                            error!(target: "updater", "target stream error: {}", e);
-                            break;
+                        Some(done) = set.join_next_with_id() => {
+                            let id = match done {
+                                Ok((id, ())) => id,
+                                Err(e) => e.id(),
+                            };
+                            if let Some(net) = networks.remove(&id)
+                                && let Some(t) = queue.finish(net)
+                            {
+                                let id = spawn_update_probe(&mut set, &dispatch_engine, t);
+                                networks.insert(id, net);
+                            }
                         }
-                    };
-                    if !worker::is_probeable_ip(&t.ip) {
-                        dispatch_engine.update_done.fetch_add(1, Ordering::Relaxed);
-                        continue;
+                        else => break,
                     }
-                    let Ok(permit) = semaphore.clone().acquire_owned().await else {
-                        break;
-                    };
-                    let engine = dispatch_engine.clone();
-                    set.spawn(async move {
-                        let _permit = permit;
-                        engine.ping(t.ip, t.port, t.with_connection).await;
-                        engine.update_done.fetch_add(1, Ordering::Relaxed);
-                    });
                 }
-                while set.join_next().await.is_some() {}
             });
 
             let abort = dispatcher.abort_handle();
@@ -529,6 +994,22 @@ async fn run_update_cycle(engine: &Arc<Engine>) -> bool {
     fetched
 }
 
+/// Spawns one update-cycle re-probe into `set`, returning its task id.
+fn spawn_update_probe(
+    set: &mut JoinSet<()>,
+    engine: &Arc<Engine>,
+    t: UpdateTarget,
+) -> tokio::task::Id {
+    let engine = engine.clone();
+    set.spawn(async move {
+        engine
+            .ping(t.ip, t.port, t.with_connection, t.edition, t.host, 0)
+            .await;
+        engine.update_done.fetch_add(1, Ordering::Relaxed);
+    })
+    .id()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -542,8 +1023,18 @@ mod tests {
             update_with_connection: false,
             update_interval_secs: 600,
             update_concurrency: 50,
+            update_per_network: 0,
             update_filter: proto::worker::ServerFilter::default(),
             search_filter: proto::worker::ServerFilter::default(),
+            search_ports: Vec::new(),
+            query_enabled: false,
+            negotiate_protocol: false,
+            deep_login: false,
+            login: LoginPool::default(),
+            timeouts: ProbeTimeouts::default(),
+            permutation: None,
+            rate_limits: RateLimits::default(),
+            rate_schedule: Vec::new(),
         }
     }
 
@@ -3,14 +3,15 @@
 //! pushed back over the same bidirectional `Session` stream.
 
 use std::{
-    path::Path,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex, atomic::Ordering},
     time::Duration,
 };
 
 use anyhow::anyhow;
 use proto::worker::{
-    Heartbeat, Register, ScanResult, ServerExtra, ServerFilter as PbFilter, ServerReport,
+    Edition, Heartbeat, LoginProbe, Register, ScanResult, SearchCursor, ServerExtra,
+    ServerFilter as PbFilter, ServerQuery, ServerReport, ShardProgress, TaskDone,
     WorkerConfig as PbConfig, WorkerMessage, WorkerMetrics, scan_result, server_command,
     worker_control_client::WorkerControlClient, worker_message,
 };
@@ -26,9 +27,16 @@ use tracing::{debug, error, info, warn};
 use crate::{
     config::WorkerConfig,
     engine::{Engine, RuntimeConfig, UpdateTarget, UpdateTargetItem},
+    login_pool::LoginPool,
     outbox::Outbox,
+    permutation::PermutationScan,
+    ports::{MAX_PORT_RANGES, PortRange},
+    ratelimit::{MAX_RATE_WINDOWS, RateLimits, RateWindow},
     report::ScanReport,
+    resolve::TargetResolver,
+    timeouts::ProbeTimeouts,
 };
+use worker::Exclusions;
 
 /// How often the replay sweep runs, and how long a result may go un-acked before
 /// it is re-sent. Covers the "link up but backend can't persist" case.
@@ -67,6 +75,8 @@ pub(crate) fn filter_to_proto(f: &crate::config::ServerFilter) -> PbFilter {
         has_none_players: f.has_none_players,
         join_status: f.join_status.clone(),
         query: f.query.clone(),
+        edition: f.edition.clone(),
+        suggested_join_status: f.suggested_join_status.clone(),
     }
 }
 
@@ -78,8 +88,22 @@ fn config_to_proto(c: &WorkerConfig) -> PbConfig {
         update_with_connection: c.update_with_connection,
         update_interval_secs: c.update_interval_secs,
         update_concurrency: c.update_concurrency,
+        update_per_network: c.update_per_network,
         update_filter: Some(filter_to_proto(&c.update_filter)),
         search_filter: Some(filter_to_proto(&c.search_filter)),
+        search_ports: c.search_ports.iter().map(|r| r.to_proto()).collect(),
+        query_enabled: c.query_enabled,
+        negotiate_protocol: c.negotiate_protocol,
+        deep_login: c.deep_login,
+        login: Some(c.login.to_proto()),
+        connect_timeout_ms: c.connect_timeout_ms,
+        read_timeout_ms: c.read_timeout_ms,
+        handshake_timeout_ms: c.handshake_timeout_ms,
+        probe_timeout_ms: c.probe_timeout_ms,
+        permutation: c.permutation.map(PermutationScan::to_proto),
+        max_packets_per_sec: c.max_packets_per_sec,
+        max_connections_per_sec: c.max_connections_per_sec,
+        rate_schedule: c.rate_schedule.iter().map(|w| w.to_proto()).collect(),
     }
 }
 
@@ -115,7 +139,25 @@ fn filter_to_toml(f: &Option<PbFilter>) -> toml_edit::Table {
         if let Some(v) = &f.query {
             t["query"] = toml_edit::value(v.as_str());
         }
+        if let Some(v) = &f.edition {
+            t["edition"] = toml_edit::value(v.as_str());
+        }
+        if let Some(v) = &f.suggested_join_status {
+            t["suggested_join_status"] = toml_edit::value(v.as_str());
+        }
+    }
+    t
+}
+
+/// Renders a [`LoginPool`] into a `toml_edit` table in the `[worker.login]`
+/// syntax it is parsed from.
+fn login_to_toml(p: &LoginPool) -> toml_edit::Table {
+    let mut t = toml_edit::Table::new();
+    t["names"] = toml_edit::value(p.names.iter().collect::<toml_edit::Array>());
+    if let Some(pattern) = &p.pattern {
+        t["pattern"] = toml_edit::value(pattern.as_str());
     }
+    t["sticky"] = toml_edit::value(p.sticky);
     t
 }
 
@@ -140,9 +182,60 @@ fn persist_config(path: &Path, c: &PbConfig) {
     worker["update_with_connection"] = toml_edit::value(c.update_with_connection);
     worker["update_interval_secs"] = toml_edit::value(c.update_interval_secs as i64);
     worker["update_concurrency"] = toml_edit::value(c.update_concurrency as i64);
+    worker["update_per_network"] = toml_edit::value(c.update_per_network as i64);
     // Replace the whole subtable each time so clearing a filter drops its key.
     worker["update_filter"] = toml_edit::Item::Table(filter_to_toml(&c.update_filter));
     worker["search_filter"] = toml_edit::Item::Table(filter_to_toml(&c.search_filter));
+    // Written in the same `"start-end@weight"` syntax the file is parsed from.
+    worker["search_ports"] = toml_edit::value(
+        c.search_ports
+            .iter()
+            .filter_map(PortRange::from_proto)
+            .map(|r| r.to_string())
+            .collect::<toml_edit::Array>(),
+    );
+    worker["query_enabled"] = toml_edit::value(c.query_enabled);
+    worker["negotiate_protocol"] = toml_edit::value(c.negotiate_protocol);
+    worker["deep_login"] = toml_edit::value(c.deep_login);
+    worker["login"] = toml_edit::Item::Table(login_to_toml(&runtime_login(c)));
+    worker["connect_timeout_ms"] = toml_edit::value(c.connect_timeout_ms as i64);
+    worker["read_timeout_ms"] = toml_edit::value(c.read_timeout_ms as i64);
+    worker["handshake_timeout_ms"] = toml_edit::value(c.handshake_timeout_ms as i64);
+    worker["probe_timeout_ms"] = toml_edit::value(c.probe_timeout_ms as i64);
+    match c.permutation.as_ref().and_then(PermutationScan::from_proto) {
+        Some(p) => {
+            let mut t = toml_edit::Table::new();
+            t["seed"] = toml_edit::value(p.seed as i64);
+            t["partition"] = toml_edit::value(p.partition as i64);
+            t["partitions"] = toml_edit::value(p.partitions as i64);
+            worker["permutation"] = toml_edit::Item::Table(t);
+        }
+        None => {
+            if let Some(t) = worker.as_table_mut() {
+                t.remove("permutation");
+            }
+        }
+    }
+    worker["max_packets_per_sec"] = toml_edit::value(c.max_packets_per_sec as i64);
+    worker["max_connections_per_sec"] = toml_edit::value(c.max_connections_per_sec as i64);
+    // Written as `[[worker.rate_schedule]]` tables, the syntax it is parsed from.
+    let schedule = runtime_schedule(c);
+    if schedule.is_empty() {
+        if let Some(t) = worker.as_table_mut() {
+            t.remove("rate_schedule");
+        }
+    } else {
+        let mut windows = toml_edit::ArrayOfTables::new();
+        for w in schedule.into_iter().map(RateWindow::to_proto) {
+            let mut t = toml_edit::Table::new();
+            t["start"] = toml_edit::value(w.start);
+            t["end"] = toml_edit::value(w.end);
+            t["max_packets_per_sec"] = toml_edit::value(w.max_packets_per_sec as i64);
+            t["max_connections_per_sec"] = toml_edit::value(w.max_connections_per_sec as i64);
+            windows.push(t);
+        }
+        worker["rate_schedule"] = toml_edit::Item::ArrayOfTables(windows);
+    }
 
     if let Err(e) = std::fs::write(path, doc.to_string()) {
         warn!("could not persist config to {}: {e}", path.display());
@@ -207,6 +300,38 @@ fn persist_name(path: &Path, name: &Option<String>) {
     }
 }
 
+/// Local copy of the operator exclusion list, next to the config file. Loaded
+/// at startup so the list holds before the first session delivers it.
+fn exclusions_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("exclusions.txt")
+}
+
+/// The exclusion list saved by [`persist_exclusions`]; empty if there is none.
+pub fn load_exclusions(config_path: &Path) -> Exclusions {
+    let path = exclusions_path(config_path);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => Exclusions::parse(text.lines()),
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("could not read {}: {e}", path.display());
+            }
+            Exclusions::default()
+        }
+    }
+}
+
+/// Saves the exclusion list, one block per line.
+fn persist_exclusions(config_path: &Path, exclusions: &Exclusions) {
+    let path = exclusions_path(config_path);
+    let text: String = exclusions.blocks().map(|n| format!("{n}\n")).collect();
+    if let Err(e) = std::fs::write(&path, text) {
+        warn!(
+            "could not persist exclusion list to {}: {e}",
+            path.display()
+        );
+    }
+}
+
 /// Upper bounds on backend-supplied config. A malicious or buggy `SetConfig`
 /// with a huge `threads`/`update_concurrency` would otherwise spawn that many
 /// tasks and OOM the worker, so both are clamped here where backend config
@@ -222,11 +347,53 @@ fn proto_to_runtime(c: &PbConfig) -> RuntimeConfig {
         update_with_connection: c.update_with_connection,
         update_interval_secs: c.update_interval_secs,
         update_concurrency: c.update_concurrency.min(MAX_UPDATE_CONCURRENCY),
+        update_per_network: c.update_per_network.min(MAX_UPDATE_CONCURRENCY),
         update_filter: c.update_filter.clone().unwrap_or_default(),
         search_filter: c.search_filter.clone().unwrap_or_default(),
+        search_ports: c
+            .search_ports
+            .iter()
+            .filter_map(PortRange::from_proto)
+            .take(MAX_PORT_RANGES)
+            .collect(),
+        query_enabled: c.query_enabled,
+        negotiate_protocol: c.negotiate_protocol,
+        deep_login: c.deep_login,
+        login: runtime_login(c),
+        timeouts: ProbeTimeouts::from_millis(
+            c.connect_timeout_ms,
+            c.read_timeout_ms,
+            c.handshake_timeout_ms,
+            c.probe_timeout_ms,
+        ),
+        permutation: c.permutation.as_ref().and_then(PermutationScan::from_proto),
+        rate_limits: RateLimits {
+            packets_per_sec: c.max_packets_per_sec,
+            connections_per_sec: c.max_connections_per_sec,
+        },
+        rate_schedule: runtime_schedule(c),
     }
 }
 
+/// The valid windows of a proto config's rate schedule, at most
+/// [`MAX_RATE_WINDOWS`].
+fn runtime_schedule(c: &PbConfig) -> Vec<RateWindow> {
+    c.rate_schedule
+        .iter()
+        .filter_map(RateWindow::from_proto)
+        .take(MAX_RATE_WINDOWS)
+        .collect()
+}
+
+/// The login pool a proto config describes; an unset one (older backend) is
+/// the worker default.
+fn runtime_login(c: &PbConfig) -> LoginPool {
+    c.login
+        .as_ref()
+        .map(LoginPool::from_proto)
+        .unwrap_or_default()
+}
+
 fn report_to_proto(report: ScanReport) -> ServerReport {
     ServerReport {
         ip: report.ip,
@@ -243,7 +410,23 @@ fn report_to_proto(report: ScanReport) -> ServerReport {
         extra: report.extra.map(|e| ServerExtra {
             is_online_mode: e.is_online_mode,
             disconnect_reason_json: e.disconnect_reason.map(|v| v.to_string()),
+            login: e.login.map(|l| LoginProbe {
+                reached_play: l.reached_play,
+                auth_prompt: l.auth_prompt,
+                whitelist_kick: l.whitelist_kick,
+                mod_handshake: l.mod_handshake,
+            }),
         }),
+        edition: report.edition as i32,
+        game_mode: report.game_mode,
+        query: report.query.map(|q| ServerQuery {
+            software: q.software,
+            plugins: q.plugins,
+            map: q.map,
+            players: q.players,
+        }),
+        accepted_protocols: report.accepted_protocols,
+        hostname: report.hostname,
     }
 }
 
@@ -308,12 +491,13 @@ pub struct GrpcSink {
 }
 
 impl GrpcSink {
-    async fn send(&self, outcome: scan_result::Outcome) {
+    async fn send(&self, outcome: scan_result::Outcome, task_id: u64) {
         let result_id = uuid::Uuid::new_v4().to_string();
         let msg = WorkerMessage {
             kind: Some(worker_message::Kind::Result(ScanResult {
                 outcome: Some(outcome),
                 result_id: result_id.clone(),
+                task_id,
             })),
         };
         // Persist before sending: if there is no live session (or the result is
@@ -331,23 +515,64 @@ impl GrpcSink {
         }
     }
 
-    /// A server found via the search/scan path (upsert-by-ip).
-    pub async fn discovered(&self, report: ScanReport) {
-        self.send(scan_result::Outcome::Discovered(report_to_proto(report)))
-            .await;
+    /// A server found via the search/scan path (upsert by `(ip, port, edition)`). A
+    /// non-zero `task_id` here and below names the backend task the probe ran
+    /// for.
+    pub async fn discovered(&self, report: ScanReport, task_id: u64) {
+        self.send(
+            scan_result::Outcome::Discovered(report_to_proto(report)),
+            task_id,
+        )
+        .await;
     }
     /// A server re-probed during an update cycle / on-demand ping (full update).
-    pub async fn updated(&self, report: ScanReport) {
-        self.send(scan_result::Outcome::Updated(report_to_proto(report)))
-            .await;
+    pub async fn updated(&self, report: ScanReport, task_id: u64) {
+        self.send(
+            scan_result::Outcome::Updated(report_to_proto(report)),
+            task_id,
+        )
+        .await;
     }
     /// A server that failed re-probing: mark offline.
-    pub async fn offline(&self, ip: &str) {
-        self.send(scan_result::Outcome::Offline(
-            proto::worker::ServerOffline { ip: ip.to_string() },
-        ))
+    pub async fn offline(&self, ip: &str, port: u16, edition: Edition, task_id: u64) {
+        self.send(
+            scan_result::Outcome::Offline(proto::worker::ServerOffline {
+                ip: ip.to_string(),
+                port: port as i32,
+                edition: edition as i32,
+            }),
+            task_id,
+        )
+        .await;
+    }
+    /// An update target that was not probed (excluded or not probeable), so
+    /// the backend stops serving it every cycle.
+    pub async fn skipped(&self, ip: &str, port: u16, edition: Edition) {
+        self.send(
+            scan_result::Outcome::Skipped(proto::worker::ServerSkipped {
+                ip: ip.to_string(),
+                port: port as i32,
+                edition: edition as i32,
+            }),
+            0,
+        )
         .await;
     }
+    /// How far a scan job shard got. Sent straight to the current session, not
+    /// through the outbox: a shard only runs within the session it was handed
+    /// out on, and the backend resumes it from the last report that arrived.
+    pub async fn shard_progress(&self, shard_id: i32, next: u32, done: bool) {
+        let msg = WorkerMessage {
+            kind: Some(worker_message::Kind::ShardProgress(ShardProgress {
+                shard_id,
+                next,
+                done,
+            })),
+        };
+        if let Some(tx) = self.link.sender() {
+            let _ = tx.send(msg).await;
+        }
+    }
 }
 
 /// Asks the backend which servers to re-probe during an update cycle. Pulls the
@@ -388,9 +613,11 @@ impl GrpcTargetSource {
             match msg.kind {
                 Some(Kind::Total(n)) => Ok(UpdateTargetItem::Total(n)),
                 Some(Kind::Target(t)) => Ok(UpdateTargetItem::Target(UpdateTarget {
+                    edition: t.edition(),
                     ip: t.ip,
                     port: t.port as u16,
                     with_connection: t.with_connection,
+                    host: t.host,
                 })),
                 None => Err(anyhow!("empty FetchUpdateTargets frame")),
             }
@@ -421,6 +648,7 @@ pub fn build_engine(
     cfg: &WorkerConfig,
     worker_id: &str,
     outbox: Arc<Outbox>,
+    exclusions: Exclusions,
 ) -> (Arc<Engine>, SessionLink) {
     let link = SessionLink::default();
     let sink = GrpcSink {
@@ -431,7 +659,14 @@ pub fn build_engine(
         link: link.clone(),
         worker_id: worker_id.to_string(),
     };
-    let engine = Engine::new(sink, targets, RuntimeConfig::from(cfg));
+    let resolver = TargetResolver::new(&cfg.dns_servers);
+    let engine = Engine::new(
+        sink,
+        targets,
+        resolver,
+        RuntimeConfig::from(cfg),
+        exclusions,
+    );
     // Start the search supervisor and update loop once. Dropping the handles
     // detaches the tasks: they must outlive any single session and are only
     // stopped when the process exits.
@@ -476,6 +711,7 @@ pub async fn run(
                 name: cfg.name.clone(),
                 config: Some(config_to_proto(cfg)),
                 version: env!("CARGO_PKG_VERSION").to_string(),
+                region: cfg.region.clone(),
             })),
         })
         .await?;
@@ -550,23 +786,63 @@ pub async fn run(
             Some(server_command::Cmd::Ping(p)) => {
                 if !worker::is_probeable_ip(&p.ip) {
                     warn!("ignoring ping to non-probeable address {}", p.ip);
+                    report_task_done(&msg_tx, p.task_id, Some("non-probeable address")).await;
                     continue;
                 }
-                let engine = engine.clone();
-                cmd_tasks.spawn(async move {
-                    engine.ping(p.ip, p.port as u16, p.with_connection).await;
+                let task = engine.clone();
+                spawn_task(&mut cmd_tasks, engine, &msg_tx, p.task_id, async move {
+                    let edition = p.edition();
+                    let (port, with_connection) = (p.port as u16, p.with_connection);
+                    task.ping(p.ip, port, with_connection, edition, p.host, p.task_id)
+                        .await;
+                    Ok(())
                 });
             }
             Some(server_command::Cmd::Scan(s)) => {
+                let edition = s.edition();
+                let task = engine.clone();
+                // A hostname is checked against the guard once resolved.
+                if let Some(host) = s.host {
+                    if !proto::is_hostname(&host) {
+                        warn!("ignoring scan of invalid hostname {host:?}");
+                        report_task_done(&msg_tx, s.task_id, Some("invalid hostname")).await;
+                        continue;
+                    }
+                    spawn_task(&mut cmd_tasks, engine, &msg_tx, s.task_id, async move {
+                        task.scan_host(host, s.port as u16, edition, s.task_id)
+                            .await
+                    });
+                    continue;
+                }
                 if !worker::is_probeable_ip(&s.ip) {
                     warn!("ignoring scan of non-probeable address {}", s.ip);
+                    report_task_done(&msg_tx, s.task_id, Some("non-probeable address")).await;
                     continue;
                 }
-                let engine = engine.clone();
-                cmd_tasks.spawn(async move {
-                    engine.scan(s.ip, s.port as u16).await;
+                spawn_task(&mut cmd_tasks, engine, &msg_tx, s.task_id, async move {
+                    task.scan(s.ip, s.port as u16, edition, None, s.task_id)
+                        .await
                 });
             }
+            Some(server_command::Cmd::Shard(shard)) => {
+                let task = engine.clone();
+                spawn_task(&mut cmd_tasks, engine, &msg_tx, 0, async move {
+                    task.scan_shard(shard).await;
+                    Ok(())
+                });
+            }
+            Some(server_command::Cmd::ResumeCursor(c)) => {
+                if let Some(scan) = c.scan.as_ref().and_then(PermutationScan::from_proto) {
+                    engine.resume_cursor(scan, c.position);
+                }
+            }
+            Some(server_command::Cmd::SetExclusions(list)) => {
+                let exclusions = Exclusions::parse(list.cidrs.iter().map(String::as_str));
+                if *engine.exclusions() != exclusions {
+                    persist_exclusions(config_path, &exclusions);
+                    engine.set_exclusions(exclusions);
+                }
+            }
             Some(server_command::Cmd::Control(ctrl)) => {
                 match proto::worker::Control::try_from(ctrl)
                     .unwrap_or(proto::worker::Control::Unspecified)
@@ -589,6 +865,58 @@ pub async fn run(
     Err(anyhow!("session stream closed by backend"))
 }
 
+/// Runs an on-demand ping, scan or shard in `tasks`, counted in the engine's
+/// `pending_tasks` while it runs. A non-zero `task_id` is reported back with
+/// `TaskDone` once it finished, carrying the error it failed with.
+fn spawn_task(
+    tasks: &mut JoinSet<()>,
+    engine: &Arc<Engine>,
+    msg_tx: &mpsc::Sender<WorkerMessage>,
+    task_id: u64,
+    task: impl Future<Output = anyhow::Result<()>> + Send + 'static,
+) {
+    let pending = PendingTask::start(engine);
+    let msg_tx = msg_tx.clone();
+    tasks.spawn(async move {
+        let error = task.await.err().map(|e| format!("{e:#}"));
+        drop(pending);
+        report_task_done(&msg_tx, task_id, error.as_deref()).await;
+    });
+}
+
+/// Counts one running on-demand task; also uncounted when the task is aborted
+/// with its session.
+struct PendingTask(Arc<Engine>);
+
+impl PendingTask {
+    fn start(engine: &Arc<Engine>) -> Self {
+        engine.pending_tasks.fetch_add(1, Ordering::Relaxed);
+        Self(engine.clone())
+    }
+}
+
+impl Drop for PendingTask {
+    fn drop(&mut self) {
+        self.0.pending_tasks.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Tells the backend a task it queued is finished, or with `error` why it was
+/// rejected; `task_id` 0 is untracked.
+async fn report_task_done(tx: &mpsc::Sender<WorkerMessage>, task_id: u64, error: Option<&str>) {
+    if task_id != 0 {
+        let done = TaskDone {
+            task_id,
+            error: error.map(str::to_string),
+        };
+        let _ = tx
+            .send(WorkerMessage {
+                kind: Some(worker_message::Kind::TaskDone(done)),
+            })
+            .await;
+    }
+}
+
 /// Periodically re-sends outbox results that have gone too long without an ack,
 /// so a result is eventually delivered even if the link stays up while the
 /// backend cannot persist. Exits (via the channel error) when the session ends.
@@ -611,6 +939,8 @@ async fn heartbeat(engine: Arc<Engine>, tx: mpsc::Sender<WorkerMessage>) {
     // the first heartbeat of a reconnected session would report a huge spike.
     let mut prev_scanned = engine.ips_scanned.load(Ordering::Relaxed);
     let mut prev_update_done = engine.update_done.load(Ordering::Relaxed);
+    let mut prev_packets = engine.limiter.packets_sent.load(Ordering::Relaxed);
+    let mut prev_connections = engine.limiter.connections_opened.load(Ordering::Relaxed);
     let period = Duration::from_secs(5);
     let mut interval = tokio::time::interval(period);
 
@@ -625,8 +955,18 @@ async fn heartbeat(engine: Arc<Engine>, tx: mpsc::Sender<WorkerMessage>) {
             update_done.saturating_sub(prev_update_done) as f64 / period.as_secs_f64();
         prev_update_done = update_done;
 
+        let packets = engine.limiter.packets_sent.load(Ordering::Relaxed);
+        let packet_rate = packets.saturating_sub(prev_packets) as f64 / period.as_secs_f64();
+        prev_packets = packets;
+        let connections = engine.limiter.connections_opened.load(Ordering::Relaxed);
+        let connection_rate =
+            connections.saturating_sub(prev_connections) as f64 / period.as_secs_f64();
+        prev_connections = connections;
+        let limits = engine.limiter.limits();
+
         let cfg = engine.config();
         let searching = engine.searching();
+        let cursor = engine.cursor_report();
         let metrics = WorkerMetrics {
             servers_found: engine.servers_found.load(Ordering::Relaxed),
             ips_scanned: scanned,
@@ -643,6 +983,26 @@ async fn heartbeat(engine: Arc<Engine>, tx: mpsc::Sender<WorkerMessage>) {
             update_total: engine.update_total.load(Ordering::Relaxed),
             update_rate,
             last_update_unix: engine.last_update_unix.load(Ordering::Relaxed),
+            port_hits: engine
+                .port_hits
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(&port, &hits)| (port as u32, hits))
+                .collect(),
+            probe_timeouts: engine.failures.timeouts.load(Ordering::Relaxed),
+            probe_refused: engine.failures.refused.load(Ordering::Relaxed),
+            probe_resets: engine.failures.resets.load(Ordering::Relaxed),
+            search_coverage: cursor.map(|(_, _, coverage)| coverage),
+            search_cursor: cursor.map(|(scan, position, _)| SearchCursor {
+                scan: Some(scan.to_proto()),
+                position,
+            }),
+            packet_rate,
+            connection_rate,
+            packet_limit: limits.packets_per_sec,
+            connection_limit: limits.connections_per_sec,
+            pending_tasks: engine.pending_tasks.load(Ordering::Relaxed),
         };
 
         let msg = WorkerMessage {
@@ -670,18 +1030,45 @@ mod tests {
             update_with_connection: true,
             update_interval_secs: 300,
             update_concurrency: 25,
+            update_per_network: 2,
             update_filter: FileFilter {
                 licensed: Some(true),
                 ..FileFilter::default()
             },
             search_filter: FileFilter::default(),
+            search_ports: vec!["25565-25575@4".parse().unwrap(), "25580".parse().unwrap()],
+            query_enabled: true,
+            negotiate_protocol: true,
+            deep_login: true,
+            login: LoginPool {
+                names: vec!["Steve".into(), "Alex".into()],
+                pattern: Some("Player####".into()),
+                sticky: false,
+            },
+            connect_timeout_ms: 2000,
+            read_timeout_ms: 0,
+            handshake_timeout_ms: 8000,
+            probe_timeout_ms: 20_000,
             log_level: None,
             backend_url: Some("http://backend:50051".into()),
             token: Some("secret".into()),
             id: Some("worker-1".into()),
             name: Some("Alpha".into()),
+            region: Some("eu".into()),
             tls_ca: None,
             insecure: None,
+            dns_servers: Vec::new(),
+            permutation: Some(PermutationScan {
+                seed: 9,
+                partition: 1,
+                partitions: 3,
+            }),
+            max_packets_per_sec: 5000,
+            max_connections_per_sec: 0,
+            rate_schedule: vec![
+                toml::from_str("start = \"08:00\"\nend = \"18:00\"\nmax_packets_per_sec = 800")
+                    .unwrap(),
+            ],
         }
     }
 
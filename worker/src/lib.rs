@@ -1,22 +1,109 @@
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use rand::RngExt;
 use rand_chacha::ChaCha8Rng;
 
-pub fn generate_random_ip(rng: &mut ChaCha8Rng) -> Ipv4Addr {
-    loop {
+/// Draws [`generate_random_ip`] makes before giving up. Only an exclusion list
+/// covering (nearly) all of the public space misses that often.
+pub const MAX_RANDOM_DRAWS: u32 = 4096;
+
+/// A random public IPv4 address outside `exclusions`, or `None` if
+/// [`MAX_RANDOM_DRAWS`] draws all missed. The search stays IPv4-only: the IPv6
+/// space is far too sparse to sample, so IPv6 servers come in as explicit
+/// targets.
+pub fn generate_random_ip(rng: &mut ChaCha8Rng, exclusions: &Exclusions) -> Option<Ipv4Addr> {
+    (0..MAX_RANDOM_DRAWS).find_map(|_| {
         let raw_ip: u32 = rng.random();
         let ip = Ipv4Addr::from(raw_ip);
-        let octets = ip.octets();
+        (is_public_ipv4(ip) && !exclusions.contains(IpAddr::V4(ip))).then_some(ip)
+    })
+}
+
+/// Most blocks an exclusion list holds. The list comes from the backend, which
+/// is untrusted; past this the rest is dropped.
+pub const MAX_EXCLUSIONS: usize = 100_000;
 
-        if octets[0] == 0 || octets[0] > 223 {
-            continue;
+/// The operator's exclusion list (abuse complaints, opt-outs): networks no
+/// probe may touch, on top of the built-in reserved ranges. Held merged and
+/// sorted, so a lookup is a binary search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Exclusions {
+    v4: Vec<Ipv4Net>,
+    v6: Vec<Ipv6Net>,
+}
+
+impl Exclusions {
+    /// Parses CIDR blocks or single addresses. Entries that are neither are
+    /// skipped, as is everything past [`MAX_EXCLUSIONS`].
+    pub fn parse<'a>(cidrs: impl IntoIterator<Item = &'a str>) -> Self {
+        let (mut v4, mut v6) = (Vec::new(), Vec::new());
+        for cidr in cidrs.into_iter().take(MAX_EXCLUSIONS) {
+            let cidr = cidr.trim();
+            let net = match cidr.parse::<IpNet>() {
+                Ok(net) => net.trunc(),
+                Err(_) => match cidr.parse::<IpAddr>() {
+                    Ok(ip) => IpNet::from(ip),
+                    Err(_) => continue,
+                },
+            };
+            match net {
+                IpNet::V4(net) => v4.push(net),
+                IpNet::V6(net) => v6.push(net),
+            }
+        }
+        Self {
+            v4: Ipv4Net::aggregate(&v4),
+            v6: Ipv6Net::aggregate(&v6),
         }
+    }
+
+    /// Number of blocks after merging.
+    pub fn len(&self) -> usize {
+        self.v4.len() + self.v6.len()
+    }
 
-        if !is_reserved_ip(octets) {
-            return ip;
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether `ip` lies inside an excluded block. IPv4-mapped IPv6 addresses
+    /// are judged as the IPv4 address they carry.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match ip {
+            IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+                Some(v4) => self.contains(IpAddr::V4(v4)),
+                None => {
+                    let i = self.v6.partition_point(|n| n.network() <= v6);
+                    i > 0 && self.v6[i - 1].contains(&v6)
+                }
+            },
+            IpAddr::V4(v4) => {
+                let i = self.v4.partition_point(|n| n.network() <= v4);
+                i > 0 && self.v4[i - 1].contains(&v4)
+            }
         }
     }
+
+    /// [`Exclusions::contains`] for an address string; hostnames are never
+    /// excluded (they are checked once resolved).
+    pub fn contains_str(&self, ip: &str) -> bool {
+        ip.parse().is_ok_and(|ip| self.contains(ip))
+    }
+
+    /// The merged blocks, IPv4 first.
+    pub fn blocks(&self) -> impl Iterator<Item = IpNet> + '_ {
+        let v4 = self.v4.iter().copied().map(IpNet::V4);
+        v4.chain(self.v6.iter().copied().map(IpNet::V6))
+    }
+}
+
+/// Whether the search may pick `ip`: outside the 0/>223 first-octet bands and
+/// the reserved ranges. The address space [`generate_random_ip`] samples from
+/// and the permutation search walks.
+pub fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    octets[0] != 0 && octets[0] <= 223 && !is_reserved_ip(octets)
 }
 
 /// Whether a caller-supplied address is safe to probe. Applies the same guards
@@ -24,16 +111,38 @@ pub fn generate_random_ip(rng: &mut ChaCha8Rng) -> Ipv4Addr {
 /// reserved ranges — so backend-directed targets (update cycle, on-demand
 /// ping/scan) cannot aim the worker at internal or bogon hosts. This closes the
 /// SSRF-proxy vector where a compromised backend points the worker at private
-/// infrastructure. Non-IPv4 inputs (hostnames, IPv6) are rejected.
+/// infrastructure. IPv6 addresses must be global unicast outside the ranges
+/// [`is_reserved_ipv6`] rejects; IPv4-mapped ones are judged as the IPv4
+/// address they carry. Hostnames are rejected.
 pub fn is_probeable_ip(ip: &str) -> bool {
-    let Ok(addr) = ip.parse::<Ipv4Addr>() else {
-        return false;
+    let addr = match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V6(v6)) => match v6.to_ipv4_mapped() {
+            Some(v4) => v4,
+            None => return !is_reserved_ipv6(v6),
+        },
+        Ok(IpAddr::V4(v4)) => v4,
+        Err(_) => return false,
     };
-    let octets = addr.octets();
-    if octets[0] == 0 || octets[0] > 223 {
-        return false;
+    is_public_ipv4(addr)
+}
+
+/// `host:port` for a socket connect, bracketing IPv6 literals (`[::1]:25565`).
+pub fn host_port(ip: &str, port: u16) -> String {
+    if ip.contains(':') {
+        format!("[{}]:{}", ip, port)
+    } else {
+        format!("{}:{}", ip, port)
+    }
+}
+
+/// Wildcard local address for a UDP socket that will talk to `ip`: the socket
+/// family has to match the target's.
+pub fn udp_bind_addr(ip: &str) -> &'static str {
+    if ip.contains(':') {
+        "[::]:0"
+    } else {
+        "0.0.0.0:0"
     }
-    !is_reserved_ip(octets)
 }
 
 #[inline(always)]
@@ -53,3 +162,124 @@ fn is_reserved_ip(octets: [u8; 4]) -> bool {
         _ => false,
     }
 }
+
+/// The IPv6 counterpart of [`is_reserved_ip`]. Only global unicast (2000::/3)
+/// is probeable, which already excludes unspecified, loopback, IPv4-mapped and
+/// NAT64 prefixes, ULA (fc00::/7), link-local (fe80::/10) and multicast; within
+/// it, the special-purpose blocks are rejected too.
+fn is_reserved_ipv6(addr: Ipv6Addr) -> bool {
+    let s = addr.segments();
+    if s[0] & 0xe000 != 0x2000 {
+        return true;
+    }
+    match s[0] {
+        // 2001::/23 (IETF: Teredo, ORCHID, benchmarking) + 2001:db8::/32 (documentation)
+        0x2001 => s[1] < 0x0200 || s[1] == 0x0db8,
+        0x2002 => true,          // 2002::/16 (6to4, embeds an arbitrary IPv4 host)
+        0x3fff => s[1] < 0x1000, // 3fff::/20 (documentation)
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv4_guard_is_unchanged() {
+        assert!(is_probeable_ip("8.8.8.8"));
+        for bad in [
+            "10.1.2.3",
+            "127.0.0.1",
+            "192.168.1.1",
+            "0.1.2.3",
+            "224.0.0.1",
+        ] {
+            assert!(!is_probeable_ip(bad), "{bad}");
+        }
+        assert!(!is_probeable_ip("example.com"));
+    }
+
+    #[test]
+    fn ipv6_accepts_global_unicast_only() {
+        assert!(is_probeable_ip("2a01:4f8:c17:1234::1"));
+        assert!(is_probeable_ip("2606:4700::6810:84e5"));
+        for bad in [
+            "::",
+            "::1",
+            "fd12:3456:789a::1",                    // ULA
+            "fe80::1",                              // link-local
+            "ff02::1",                              // multicast
+            "64:ff9b::808:808",                     // NAT64
+            "2001:db8::1",                          // documentation
+            "3fff:0abc::1",                         // documentation (RFC 9637)
+            "2001:0:4136:e378:8000:63bf:3fff:fdd2", // Teredo
+            "2001:2::1",                            // benchmarking
+            "2002:c0a8:0101::1",                    // 6to4
+            "[2a01:4f8::1]", // brackets belong to the address syntax, not the IP
+        ] {
+            assert!(!is_probeable_ip(bad), "{bad}");
+        }
+    }
+
+    #[test]
+    fn ipv4_mapped_addresses_use_the_ipv4_rules() {
+        assert!(is_probeable_ip("::ffff:8.8.8.8"));
+        assert!(!is_probeable_ip("::ffff:192.168.0.1"));
+    }
+
+    #[test]
+    fn exclusions_merge_and_match() {
+        let ex = Exclusions::parse([
+            "198.51.100.7/24",
+            "8.8.8.8",
+            "8.8.8.9",
+            "2a01:4f8::/32",
+            "not an address",
+        ]);
+        assert_eq!(ex.len(), 3); // 8.8.8.8/31 merged
+        assert!(ex.contains_str("198.51.100.200"));
+        assert!(!ex.contains_str("198.51.101.1"));
+        assert!(ex.contains_str("8.8.8.9"));
+        assert!(!ex.contains_str("8.8.8.10"));
+        assert!(ex.contains_str("::ffff:8.8.8.8"));
+        assert!(ex.contains_str("2a01:4f8:c17::1"));
+        assert!(!ex.contains_str("2a01:4f9::1"));
+        assert!(!ex.contains_str("example.com"));
+        assert!(!Exclusions::default().contains_str("8.8.8.8"));
+
+        let blocks: Vec<String> = ex.blocks().map(|n| n.to_string()).collect();
+        assert_eq!(blocks, ["8.8.8.8/31", "198.51.100.0/24", "2a01:4f8::/32"]);
+    }
+
+    #[test]
+    fn random_search_skips_exclusions() {
+        use rand::SeedableRng;
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        // Everything but 8.0.0.0/8 is excluded.
+        let ex = Exclusions::parse([
+            "0.0.0.0/5",
+            "9.0.0.0/8",
+            "10.0.0.0/7",
+            "12.0.0.0/6",
+            "16.0.0.0/4",
+            "32.0.0.0/3",
+            "64.0.0.0/2",
+            "128.0.0.0/1",
+        ]);
+        for _ in 0..100 {
+            assert_eq!(generate_random_ip(&mut rng, &ex).unwrap().octets()[0], 8);
+        }
+        // Nothing left to draw from: give up instead of spinning.
+        let all = Exclusions::parse(["0.0.0.0/0"]);
+        assert_eq!(generate_random_ip(&mut rng, &all), None);
+    }
+
+    #[test]
+    fn socket_addresses_bracket_ipv6() {
+        assert_eq!(host_port("1.2.3.4", 25565), "1.2.3.4:25565");
+        assert_eq!(host_port("2001:db8::1", 25565), "[2001:db8::1]:25565");
+        assert_eq!(udp_bind_addr("2001:db8::1"), "[::]:0");
+        assert_eq!(udp_bind_addr("1.2.3.4"), "0.0.0.0:0");
+    }
+}
@@ -0,0 +1,257 @@
+//! Identities the login probe presents. `[worker.login]` (and the gRPC
+//! `WorkerConfig.login`) holds a pool of usernames and/or a generator pattern;
+//! every login draws one through [`LoginPool::identity`] and pairs it with the
+//! offline-mode UUID a cracked client would send for that name.
+
+use md5::{Digest, Md5};
+use proto::worker::LoginPool as PbLoginPool;
+use rand::{RngExt, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use uuid::{Builder, Uuid};
+
+/// Name used when the pool is empty and no pattern is set.
+pub const DEFAULT_LOGIN_NAME: &str = "Notch";
+
+/// Upper bound on pool names. Backend-supplied config is untrusted, so a huge
+/// list is truncated rather than allocated (like `MAX_PORT_RANGES`).
+pub const MAX_POOL_NAMES: usize = 1024;
+
+/// A username and the UUID sent with it in `LoginStart`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginIdentity {
+    pub name: String,
+    pub uuid: Uuid,
+}
+
+impl LoginIdentity {
+    pub fn offline(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            uuid: offline_uuid(name),
+        }
+    }
+}
+
+/// The UUID an offline-mode server assigns `name`: Java's
+/// `UUID.nameUUIDFromBytes("OfflinePlayer:<name>")`, a version 3 UUID over the
+/// MD5 of that string.
+pub fn offline_uuid(name: &str) -> Uuid {
+    let digest: [u8; 16] = Md5::digest(format!("OfflinePlayer:{name}")).into();
+    Builder::from_md5_bytes(digest).into_uuid()
+}
+
+/// Usernames are 3–16 characters of `[A-Za-z0-9_]`.
+fn is_valid_name(name: &str) -> bool {
+    (3..=16).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// A pattern is a name in which every `#` stands for a random digit and every
+/// `?` for a random lowercase letter (`"Player####"`, `"??????_##"`).
+fn is_valid_pattern(pattern: &str) -> bool {
+    (3..=16).contains(&pattern.len())
+        && pattern
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'#' | b'?'))
+}
+
+/// Where login probes get their usernames. Written in the config file as
+///
+/// ```toml
+/// [worker.login]
+/// names = ["Steve", "Alex"]
+/// pattern = "Player####"
+/// sticky = true
+/// ```
+///
+/// With both `names` and `pattern` set, the pattern counts as one more pool
+/// entry. `sticky` (the default) derives the choice, including generated
+/// characters, from the server address, so every probe of one server logs in
+/// as the same player; otherwise each probe draws afresh.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawLoginPool")]
+pub struct LoginPool {
+    pub names: Vec<String>,
+    pub pattern: Option<String>,
+    pub sticky: bool,
+}
+
+impl Default for LoginPool {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            pattern: None,
+            sticky: true,
+        }
+    }
+}
+
+/// File form of [`LoginPool`], validated on conversion so a typo fails the
+/// config load instead of silently shrinking the pool.
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawLoginPool {
+    names: Vec<String>,
+    pattern: Option<String>,
+    sticky: bool,
+}
+
+impl Default for RawLoginPool {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            pattern: None,
+            sticky: true,
+        }
+    }
+}
+
+impl TryFrom<RawLoginPool> for LoginPool {
+    type Error = String;
+
+    fn try_from(raw: RawLoginPool) -> Result<Self, Self::Error> {
+        if let Some(bad) = raw.names.iter().find(|n| !is_valid_name(n)) {
+            return Err(format!("invalid login name {bad:?}"));
+        }
+        if let Some(bad) = raw.pattern.as_deref().filter(|p| !is_valid_pattern(p)) {
+            return Err(format!("invalid login name pattern {bad:?}"));
+        }
+        Ok(Self {
+            names: raw.names,
+            pattern: raw.pattern,
+            sticky: raw.sticky,
+        })
+    }
+}
+
+impl LoginPool {
+    pub fn to_proto(&self) -> PbLoginPool {
+        PbLoginPool {
+            names: self.names.clone(),
+            pattern: self.pattern.clone(),
+            sticky: self.sticky,
+        }
+    }
+
+    /// Converts a backend-supplied pool, dropping invalid names and an invalid
+    /// pattern rather than rejecting the whole config.
+    pub fn from_proto(p: &PbLoginPool) -> Self {
+        Self {
+            names: p
+                .names
+                .iter()
+                .filter(|n| is_valid_name(n))
+                .take(MAX_POOL_NAMES)
+                .cloned()
+                .collect(),
+            pattern: p.pattern.clone().filter(|p| is_valid_pattern(p)),
+            sticky: p.sticky,
+        }
+    }
+
+    /// Picks the identity to log in to `ip:port` with.
+    pub fn identity(&self, ip: &str, port: u16) -> LoginIdentity {
+        let mut rng = if self.sticky {
+            let digest = Md5::digest(format!("{ip}:{port}"));
+            let seed = u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"));
+            ChaCha8Rng::seed_from_u64(seed)
+        } else {
+            ChaCha8Rng::seed_from_u64(rand::random())
+        };
+
+        let slots = self.names.len() + usize::from(self.pattern.is_some());
+        if slots == 0 {
+            return LoginIdentity::offline(DEFAULT_LOGIN_NAME);
+        }
+        let i = rng.random_range(0..slots);
+        let name = match self.names.get(i) {
+            Some(name) => name.clone(),
+            None => generate(self.pattern.as_deref().expect("pattern slot"), &mut rng),
+        };
+        LoginIdentity::offline(&name)
+    }
+}
+
+fn generate(pattern: &str, rng: &mut ChaCha8Rng) -> String {
+    pattern
+        .chars()
+        .map(|c| match c {
+            '#' => char::from(b'0' + rng.random_range(0..10u8)),
+            '?' => char::from(b'a' + rng.random_range(0..26u8)),
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_uuid_matches_java() {
+        // UUID.nameUUIDFromBytes("OfflinePlayer:Notch".getBytes(UTF_8))
+        assert_eq!(
+            offline_uuid("Notch").to_string(),
+            "b50ad385-829d-3141-a216-7e7d7539ba7f"
+        );
+        assert_eq!(offline_uuid("Notch").get_version_num(), 3);
+    }
+
+    #[test]
+    fn empty_pool_falls_back_to_default_name() {
+        let id = LoginPool::default().identity("1.2.3.4", 25565);
+        assert_eq!(id, LoginIdentity::offline(DEFAULT_LOGIN_NAME));
+    }
+
+    #[test]
+    fn sticky_pools_repeat_per_server() {
+        let pool = LoginPool {
+            names: vec!["Steve".into(), "Alex".into(), "Herobrine".into()],
+            pattern: Some("Player####".into()),
+            sticky: true,
+        };
+        let first = pool.identity("1.2.3.4", 25565);
+        assert!((0..20).all(|_| pool.identity("1.2.3.4", 25565) == first));
+        // Different servers spread across the pool.
+        let distinct: std::collections::HashSet<_> = (0..200)
+            .map(|i| {
+                pool.identity(&format!("10.0.{}.{}", i / 256, i % 256), 25565)
+                    .name
+            })
+            .collect();
+        assert!(distinct.len() > 4, "only {distinct:?}");
+    }
+
+    #[test]
+    fn patterns_generate_valid_names() {
+        let pool = LoginPool {
+            names: Vec::new(),
+            pattern: Some("??_Player###".into()),
+            sticky: false,
+        };
+        for _ in 0..50 {
+            let name = pool.identity("1.2.3.4", 25565).name;
+            assert!(is_valid_name(&name), "{name:?}");
+            assert!(name[2..].starts_with("_Player"));
+            assert!(name[9..].bytes().all(|b| b.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn file_config_rejects_bad_names_but_proto_drops_them() {
+        let parse = |s: &str| toml::from_str::<LoginPool>(s);
+        assert_eq!(parse("").unwrap(), LoginPool::default());
+        assert!(parse(r#"names = ["ok_name", "no spaces"]"#).is_err());
+        assert!(parse(r#"names = ["ab"]"#).is_err());
+        assert!(parse(r#"pattern = "Player-##""#).is_err());
+        assert!(!parse("sticky = false").unwrap().sticky);
+
+        let pool = LoginPool::from_proto(&PbLoginPool {
+            names: vec!["Steve".into(), "x".into(), "ThisNameIsFarTooLong".into()],
+            pattern: Some("bad pattern".into()),
+            sticky: true,
+        });
+        assert_eq!(pool.names, vec!["Steve".to_string()]);
+        assert_eq!(pool.pattern, None);
+    }
+}
@@ -3,10 +3,17 @@ use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberI
 mod config;
 mod engine;
 mod grpc_backend;
+mod login_pool;
+mod netqueue;
 mod outbox;
 mod packets;
+mod permutation;
+mod ports;
+mod ratelimit;
 mod report;
+mod resolve;
 mod server_actions;
+mod timeouts;
 
 #[tokio::main]
 async fn main() {
@@ -40,7 +47,9 @@ async fn main() {
     // The engine (search pool + update loop) is built once and runs for the whole
     // process, so its state survives reconnects. Each session only swaps its
     // transport into `link`; a dropped link never restarts the engine.
-    let (engine, link) = grpc_backend::build_engine(&worker_cfg, &worker_id, outbox.clone());
+    let exclusions = grpc_backend::load_exclusions(&config_path);
+    let (engine, link) =
+        grpc_backend::build_engine(&worker_cfg, &worker_id, outbox.clone(), exclusions);
 
     loop {
         match grpc_backend::run(
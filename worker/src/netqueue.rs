@@ -0,0 +1,126 @@
+//! Per-network politeness for the update cycle. Re-probe targets arrive in id
+//! order, which often clusters many ports of one host or /24 together; without
+//! a cap the cycle would hit them all at once. [`NetworkQueue`] admits at most
+//! `update_per_network` probes per /24 (per /64 for IPv6) and holds the rest
+//! back until one of their network's probes finishes, so a crowded network
+//! waits without stalling the targets behind it.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+};
+
+use ipnet::IpNet;
+
+/// The network a target counts against: its /24, or its /64 for IPv6.
+/// IPv4-mapped addresses count as the IPv4 address. `None` for anything that
+/// does not parse as an address.
+pub fn network_of(ip: &str) -> Option<IpNet> {
+    let net = match ip.parse::<IpAddr>().ok()?.to_canonical() {
+        IpAddr::V4(v4) => IpNet::new(v4.into(), 24),
+        IpAddr::V6(v6) => IpNet::new(v6.into(), 64),
+    };
+    net.ok().map(|n| n.trunc())
+}
+
+/// Admission per network: items of a network beyond `per_network` in flight
+/// wait in that network's queue, first in first out.
+pub struct NetworkQueue<T> {
+    per_network: usize,
+    active: HashMap<IpNet, usize>,
+    waiting: HashMap<IpNet, VecDeque<T>>,
+    waiting_len: usize,
+}
+
+impl<T> NetworkQueue<T> {
+    pub fn new(per_network: usize) -> Self {
+        Self {
+            per_network: per_network.max(1),
+            active: HashMap::new(),
+            waiting: HashMap::new(),
+            waiting_len: 0,
+        }
+    }
+
+    /// Offers `item` of network `net`. Returns it if it may start now (it then
+    /// counts as in flight); otherwise it is queued.
+    pub fn push(&mut self, net: IpNet, item: T) -> Option<T> {
+        let active = self.active.entry(net).or_default();
+        if *active < self.per_network {
+            *active += 1;
+            return Some(item);
+        }
+        self.waiting.entry(net).or_default().push_back(item);
+        self.waiting_len += 1;
+        None
+    }
+
+    /// Records that an item of `net` finished, and returns the next queued item
+    /// of that network to start in its place, if any.
+    pub fn finish(&mut self, net: IpNet) -> Option<T> {
+        if let Some(queue) = self.waiting.get_mut(&net)
+            && let Some(item) = queue.pop_front()
+        {
+            if queue.is_empty() {
+                self.waiting.remove(&net);
+            }
+            self.waiting_len -= 1;
+            return Some(item);
+        }
+        if let Some(active) = self.active.get_mut(&net) {
+            *active -= 1;
+            if *active == 0 {
+                self.active.remove(&net);
+            }
+        }
+        None
+    }
+
+    /// Items held back, over all networks.
+    pub fn waiting(&self) -> usize {
+        self.waiting_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn networks_are_slash_24_and_slash_64() {
+        let net = |ip| network_of(ip).map(|n| n.to_string());
+        assert_eq!(net("203.0.113.77").as_deref(), Some("203.0.113.0/24"));
+        assert_eq!(
+            net("::ffff:203.0.113.77").as_deref(),
+            Some("203.0.113.0/24")
+        );
+        assert_eq!(
+            net("2a01:4f8:c17:1234:5::1").as_deref(),
+            Some("2a01:4f8:c17:1234::/64")
+        );
+        assert_eq!(net("example.com"), None);
+    }
+
+    #[test]
+    fn queue_caps_each_network_and_releases_in_order() {
+        let a = network_of("198.51.100.1").unwrap();
+        let b = network_of("198.51.101.1").unwrap();
+        let mut q = NetworkQueue::new(2);
+        assert_eq!(q.push(a, 1), Some(1));
+        assert_eq!(q.push(a, 2), Some(2));
+        assert_eq!(q.push(a, 3), None);
+        assert_eq!(q.push(a, 4), None);
+        // Another network is not held up by a full one.
+        assert_eq!(q.push(b, 10), Some(10));
+        assert_eq!(q.waiting(), 2);
+
+        assert_eq!(q.finish(a), Some(3));
+        assert_eq!(q.finish(a), Some(4));
+        assert_eq!(q.finish(a), None);
+        assert_eq!(q.finish(a), None);
+        assert_eq!(q.waiting(), 0);
+        // Both slots of `a` are free again.
+        assert_eq!(q.push(a, 5), Some(5));
+        assert_eq!(q.push(a, 6), Some(6));
+    }
+}
@@ -88,12 +88,13 @@ impl Outbox {
         let mut st = self.state.lock().await;
         let now = SystemTime::now();
 
-        if st.entries.len() >= MAX_ENTRIES && !st.entries.contains_key(id) {
-            if let Some(oldest) = oldest_id(&st.entries) {
-                st.entries.remove(&oldest);
-                let _ = append(&mut st.file, OP_ACK, 0, &oldest, &[]);
-                warn!("outbox: full ({MAX_ENTRIES}); dropped oldest un-acked result");
-            }
+        if st.entries.len() >= MAX_ENTRIES
+            && !st.entries.contains_key(id)
+            && let Some(oldest) = oldest_id(&st.entries)
+        {
+            st.entries.remove(&oldest);
+            let _ = append(&mut st.file, OP_ACK, 0, &oldest, &[]);
+            warn!("outbox: full ({MAX_ENTRIES}); dropped oldest un-acked result");
         }
 
         let bytes = msg.encode_to_vec();
@@ -215,10 +216,10 @@ fn compact_to_disk(path: &Path, entries: &HashMap<String, Entry>) -> io::Result<
     // fsync the parent directory so the rename itself survives power loss.
     // Best-effort and platform-dependent (opening a directory as a file fails on
     // Windows); the durability target is the Linux/Docker deployment.
-    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
-        if let Ok(d) = File::open(dir) {
-            let _ = d.sync_all();
-        }
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty())
+        && let Ok(d) = File::open(dir)
+    {
+        let _ = d.sync_all();
     }
     OpenOptions::new().append(true).create(true).open(path)
 }
@@ -275,8 +276,11 @@ fn load_entries(path: &Path) -> HashMap<String, Entry> {
     entries
 }
 
+/// One decoded log record: `(op, created, result_id, payload)`.
+type Record = (u8, SystemTime, String, Vec<u8>);
+
 /// Reads one record. Returns `Ok(None)` on a clean EOF at a record boundary.
-fn read_record<R: Read>(r: &mut R) -> io::Result<Option<(u8, SystemTime, String, Vec<u8>)>> {
+fn read_record<R: Read>(r: &mut R) -> io::Result<Option<Record>> {
     let mut op = [0u8; 1];
     match r.read_exact(&mut op) {
         Ok(()) => {}
@@ -35,6 +35,20 @@ pub mod c2s {
         pub uuid: Uuid,
     }
 
+    /// Answer to [`s2c::LoginPluginRequest`]; `successful: false` means "channel
+    /// not understood", which is all a vanilla client ever sends.
+    #[derive(Packet, Debug)]
+    #[packet(0x02)]
+    pub struct LoginPluginResponse {
+        pub message_id: VarInt,
+        pub successful: bool,
+    }
+
+    /// Moves the connection into the configuration state (1.20.2+).
+    #[derive(Packet, Debug)]
+    #[packet(0x03)]
+    pub struct LoginAcknowledged {}
+
     impl LoginStart {
         pub fn raw_by_protocol(&self, protocol: i32) -> Result<RawPacket, PacketError> {
             if protocol >= 764 {
@@ -47,7 +61,7 @@ pub mod c2s {
                 mc_protocol::ser::Serialize::serialize(&Some(self.uuid), &mut payload)?;
 
                 mc_protocol::packet::UncompressedPacket {
-                    packet_id: Self::PACKET_ID.clone(),
+                    packet_id: Self::PACKET_ID,
                     payload,
                 }
                 .to_raw_packet()
@@ -59,7 +73,7 @@ pub mod c2s {
                 mc_protocol::ser::Serialize::serialize(&Some(self.uuid), &mut payload)?; // has_uuid + UUID
 
                 mc_protocol::packet::UncompressedPacket {
-                    packet_id: Self::PACKET_ID.clone(),
+                    packet_id: Self::PACKET_ID,
                     payload,
                 }
                 .to_raw_packet()
@@ -70,7 +84,7 @@ pub mod c2s {
                 mc_protocol::ser::Serialize::serialize(&false, &mut payload)?; // has_uuid
 
                 mc_protocol::packet::UncompressedPacket {
-                    packet_id: Self::PACKET_ID.clone(),
+                    packet_id: Self::PACKET_ID,
                     payload,
                 }
                 .to_raw_packet()
@@ -80,7 +94,7 @@ pub mod c2s {
                 mc_protocol::ser::Serialize::serialize(&self.name, &mut payload)?;
 
                 mc_protocol::packet::UncompressedPacket {
-                    packet_id: Self::PACKET_ID.clone(),
+                    packet_id: Self::PACKET_ID,
                     payload,
                 }
                 .to_raw_packet()
@@ -119,4 +133,12 @@ pub mod s2c {
     pub struct SetCompression {
         pub threshold: VarInt,
     }
+
+    /// Only the header is decoded; the channel-specific data is ignored.
+    #[derive(Packet, Debug)]
+    #[packet(0x04)]
+    pub struct LoginPluginRequest {
+        pub message_id: VarInt,
+        pub channel: String,
+    }
 }
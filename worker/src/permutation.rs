@@ -0,0 +1,452 @@
+//! Full-coverage search. With `[worker.permutation]` set, the search threads
+//! stop sampling addresses at random and walk the IPv4 space instead: every
+//! address exactly once, in an order keyed by `seed` that still spreads the
+//! probes across networks. Workers configured with the same seed split the
+//! sequence by index into `partitions` equal ranges and each walks its own
+//! `partition`.
+//!
+//! How far a worker got is its [`Cursor`] position, reported in every heartbeat
+//! (`WorkerMetrics.search_cursor`). The backend persists it and hands it back
+//! when the worker reconnects, so a restart resumes the walk. The position
+//! reported is the oldest address still in flight, so a crash repeats a few
+//! probes rather than skipping them.
+//!
+//! The order is the cyclic group of integers modulo the prime 2^32 + 15, as in
+//! zmap: multiplying by a generator `g` visits every element of `1..p` once
+//! before repeating. Element `x` stands for address `x - 1`; the 14 elements
+//! past the IPv4 space, reserved addresses and the operator's exclusions are
+//! stepped over.
+
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, Ipv4Addr},
+    ops::Range,
+    sync::Mutex,
+};
+
+use proto::worker::PermutationScan as PbPermutationScan;
+use rand::{RngExt, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use tracing::info;
+use worker::Exclusions;
+
+/// Smallest prime above 2^32.
+const PRIME: u64 = 4_294_967_311;
+/// Distinct prime factors of the group order `PRIME - 1` (2 · 3² · 5 · 131 · 364289).
+const ORDER_FACTORS: [u64; 5] = [2, 3, 5, 131, 364_289];
+/// Length of the whole sequence, i.e. the group order.
+pub const SEQUENCE_LEN: u64 = PRIME - 1;
+
+/// Upper bound on `partitions`. Backend-supplied config is untrusted; beyond
+/// this a partition would be too small to be worth a worker.
+pub const MAX_PARTITIONS: u32 = 65_536;
+
+/// Indices [`Cursor::next_address`] steps over in one call. Bounds how long a
+/// stretch of excluded space holds the cursor lock.
+const MAX_SKIPPED_INDICES: u64 = 65_536;
+
+/// Which walk a worker takes. Written in the config file as
+///
+/// ```toml
+/// [worker.permutation]
+/// seed = 1
+/// partition = 0
+/// partitions = 4
+/// ```
+///
+/// Changing any of the three starts a different walk from its beginning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawPermutationScan")]
+pub struct PermutationScan {
+    pub seed: u32,
+    pub partition: u32,
+    pub partitions: u32,
+}
+
+/// File form of [`PermutationScan`], validated on conversion.
+#[derive(Deserialize)]
+struct RawPermutationScan {
+    #[serde(default)]
+    seed: u32,
+    #[serde(default)]
+    partition: u32,
+    #[serde(default = "one")]
+    partitions: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl TryFrom<RawPermutationScan> for PermutationScan {
+    type Error = String;
+
+    fn try_from(raw: RawPermutationScan) -> Result<Self, Self::Error> {
+        Self::new(raw.seed, raw.partition, raw.partitions).ok_or_else(|| {
+            format!(
+                "permutation partition {} of {} is out of range (1..={MAX_PARTITIONS} partitions)",
+                raw.partition, raw.partitions
+            )
+        })
+    }
+}
+
+impl PermutationScan {
+    fn new(seed: u32, partition: u32, partitions: u32) -> Option<Self> {
+        ((1..=MAX_PARTITIONS).contains(&partitions) && partition < partitions).then_some(Self {
+            seed,
+            partition,
+            partitions,
+        })
+    }
+
+    pub fn to_proto(self) -> PbPermutationScan {
+        PbPermutationScan {
+            seed: self.seed,
+            partition: self.partition,
+            partitions: self.partitions,
+        }
+    }
+
+    /// Converts a backend-supplied walk; an out-of-range partition is dropped
+    /// (leaving random search) rather than guessed at.
+    pub fn from_proto(p: &PbPermutationScan) -> Option<Self> {
+        Self::new(p.seed, p.partition, p.partitions)
+    }
+
+    /// The indices of the sequence this partition walks.
+    pub fn range(self) -> Range<u64> {
+        let bound = |k: u32| (SEQUENCE_LEN as u128 * k as u128 / self.partitions as u128) as u64;
+        bound(self.partition)..bound(self.partition + 1)
+    }
+}
+
+/// The keyed order itself: a generator and a starting element, both derived
+/// from the seed.
+#[derive(Debug, Clone, Copy)]
+pub struct Permutation {
+    generator: u64,
+    first: u64,
+}
+
+impl Permutation {
+    pub fn new(seed: u32) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+        let generator = loop {
+            let g = rng.random_range(2..PRIME - 1);
+            if is_generator(g) {
+                break g;
+            }
+        };
+        let first = rng.random_range(1..PRIME);
+        Self { generator, first }
+    }
+
+    /// The address at `index`, or `None` where the element lies past the IPv4
+    /// space.
+    pub fn at(&self, index: u64) -> Option<Ipv4Addr> {
+        let x = mul_mod(self.first, pow_mod(self.generator, index % SEQUENCE_LEN));
+        u32::try_from(x - 1).ok().map(Ipv4Addr::from)
+    }
+}
+
+fn mul_mod(a: u64, b: u64) -> u64 {
+    (a as u128 * b as u128 % PRIME as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64) -> u64 {
+    let mut acc = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base);
+        }
+        base = mul_mod(base, base);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// `g` generates the whole group iff no `g^((p-1)/q)` is 1 for a prime factor
+/// `q` of the order.
+fn is_generator(g: u64) -> bool {
+    ORDER_FACTORS
+        .iter()
+        .all(|q| pow_mod(g, SEQUENCE_LEN / q) != 1)
+}
+
+/// One partition's walk, shared by the search threads of a pool.
+pub struct Walk {
+    scan: PermutationScan,
+    permutation: Permutation,
+    range: Range<u64>,
+}
+
+impl Walk {
+    pub fn new(scan: PermutationScan) -> Self {
+        Self {
+            scan,
+            permutation: Permutation::new(scan.seed),
+            range: scan.range(),
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.range.end - self.range.start
+    }
+}
+
+/// The position of the current walk within its partition. Outlives search
+/// pools (and sessions), so a pool rebuilt for an unrelated config change picks
+/// up where the last one stopped.
+#[derive(Default)]
+pub struct Cursor {
+    scan: Mutex<Option<PermutationScan>>,
+    progress: Mutex<Progress>,
+}
+
+#[derive(Default)]
+struct Progress {
+    /// Indices handed out so far.
+    next: u64,
+    /// Handed-out indices whose probe has not finished.
+    in_flight: BTreeSet<u64>,
+    /// Bumped when the cursor moves to another walk, so claims on the old one
+    /// are not settled against the new one.
+    walk: u64,
+}
+
+/// What [`Cursor::next_address`] came up with.
+pub enum Next<'a> {
+    /// An address to probe, in flight until the claim is dropped.
+    Address(Ipv4Addr, Claim<'a>),
+    /// [`MAX_SKIPPED_INDICES`] reserved or excluded addresses in a row; ask
+    /// again.
+    Skipped,
+    /// The partition has been walked.
+    Walked,
+}
+
+/// An address of the walk being probed. Dropping it marks the probe finished.
+pub struct Claim<'a> {
+    cursor: &'a Cursor,
+    walk: u64,
+    index: u64,
+}
+
+impl Drop for Claim<'_> {
+    fn drop(&mut self) {
+        let mut progress = self.cursor.progress.lock().unwrap();
+        if progress.walk == self.walk {
+            progress.in_flight.remove(&self.index);
+        }
+    }
+}
+
+impl Cursor {
+    /// Points the cursor at `scan`, restarting from 0 if it was on another.
+    pub fn start(&self, scan: PermutationScan) {
+        let mut current = self.scan.lock().unwrap();
+        if *current != Some(scan) {
+            *current = Some(scan);
+            let mut progress = self.progress.lock().unwrap();
+            *progress = Progress {
+                walk: progress.walk + 1,
+                ..Progress::default()
+            };
+        }
+    }
+
+    /// Moves forward to `position` of `scan`, as persisted by the backend. A
+    /// position for another walk, or behind the current one, is ignored.
+    pub fn resume(&self, scan: PermutationScan, position: u64) {
+        let current = self.scan.lock().unwrap();
+        if *current == Some(scan) {
+            let mut progress = self.progress.lock().unwrap();
+            if position > progress.next {
+                progress.next = position;
+                info!("search: resuming permutation walk at {position}");
+            }
+        }
+    }
+
+    /// The walk the cursor is on and how many of its indices have been handed
+    /// out.
+    pub fn position(&self) -> Option<(PermutationScan, u64)> {
+        let scan = (*self.scan.lock().unwrap())?;
+        let len = scan.range().end - scan.range().start;
+        Some((scan, self.progress.lock().unwrap().next.min(len)))
+    }
+
+    /// The walk the cursor is on and the index below which every address has
+    /// been probed: the oldest claim still in flight, or the position if none
+    /// is.
+    pub fn settled(&self) -> Option<(PermutationScan, u64)> {
+        let scan = (*self.scan.lock().unwrap())?;
+        let len = scan.range().end - scan.range().start;
+        let progress = self.progress.lock().unwrap();
+        let oldest = progress.in_flight.first().copied();
+        Some((scan, oldest.unwrap_or(progress.next).min(len)))
+    }
+
+    /// Claims the next public address of `walk` outside `exclusions`. Steps
+    /// over at most [`MAX_SKIPPED_INDICES`] addresses that are not, so an
+    /// exclusion list covering most of the space cannot stall the walk.
+    pub fn next_address(&self, walk: &Walk, exclusions: &Exclusions) -> Next<'_> {
+        let mut progress = self.progress.lock().unwrap();
+        for _ in 0..MAX_SKIPPED_INDICES {
+            let i = progress.next;
+            progress.next += 1;
+            if i >= walk.len() {
+                if i == walk.len() {
+                    info!(
+                        "search: permutation partition {}/{} complete",
+                        walk.scan.partition, walk.scan.partitions
+                    );
+                }
+                return Next::Walked;
+            }
+            if let Some(ip) = walk.permutation.at(walk.range.start + i)
+                && worker::is_public_ipv4(ip)
+                && !exclusions.contains(IpAddr::V4(ip))
+            {
+                progress.in_flight.insert(i);
+                let claim = Claim {
+                    cursor: self,
+                    walk: progress.walk,
+                    index: i,
+                };
+                return Next::Address(ip, claim);
+            }
+        }
+        Next::Skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn claim<'a>(cursor: &'a Cursor, walk: &Walk, ex: &Exclusions) -> (Ipv4Addr, Claim<'a>) {
+        match cursor.next_address(walk, ex) {
+            Next::Address(ip, claim) => (ip, claim),
+            _ => panic!("no address claimed"),
+        }
+    }
+
+    #[test]
+    fn keyed_generators_generate_the_group() {
+        assert_eq!(pow_mod(3, 5), 243);
+        assert_eq!(mul_mod(PRIME - 1, PRIME - 1), 1);
+        for seed in [0, 1, 7, u32::MAX] {
+            let g = Permutation::new(seed).generator;
+            assert!(is_generator(g), "seed {seed}");
+            assert_eq!(pow_mod(g, SEQUENCE_LEN), 1);
+            // The order is even, so a square never generates the group.
+            assert!(!is_generator(mul_mod(g, g)));
+        }
+    }
+
+    #[test]
+    fn sequence_is_keyed_and_distinct() {
+        let a = Permutation::new(1);
+        let b = Permutation::new(2);
+        let first: Vec<_> = (0..2000).filter_map(|i| a.at(i)).collect();
+        let distinct: HashSet<_> = first.iter().collect();
+        assert_eq!(distinct.len(), first.len());
+        assert!(first.len() >= 1990);
+        let again: Vec<_> = (0..2000)
+            .filter_map(|i| Permutation::new(1).at(i))
+            .collect();
+        assert_eq!(first, again);
+        let other: Vec<_> = (0..2000).filter_map(|i| b.at(i)).collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn partitions_tile_the_sequence() {
+        let parts = 7;
+        let ranges: Vec<_> = (0..parts)
+            .map(|k| PermutationScan::new(0, k, parts).unwrap().range())
+            .collect();
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges[parts as usize - 1].end, SEQUENCE_LEN);
+        assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(PermutationScan::new(0, 3, 3).is_none());
+        assert!(PermutationScan::new(0, 0, 0).is_none());
+    }
+
+    #[test]
+    fn cursor_resumes_only_its_own_walk() {
+        let scan = PermutationScan::new(5, 1, 2).unwrap();
+        let cursor = Cursor::default();
+        cursor.start(scan);
+        cursor.resume(PermutationScan::new(6, 1, 2).unwrap(), 1000);
+        assert_eq!(cursor.position(), Some((scan, 0)));
+        cursor.resume(scan, 1000);
+        cursor.resume(scan, 10);
+        assert_eq!(cursor.position(), Some((scan, 1000)));
+
+        let walk = Walk::new(scan);
+        let (ip, _claim) = claim(&cursor, &walk, &Exclusions::default());
+        assert!(worker::is_public_ipv4(ip));
+        // An excluded address is stepped over like a reserved one.
+        let next = walk
+            .permutation
+            .at(walk.range.start + cursor.position().unwrap().1);
+        let excluded = Exclusions::parse(next.map(|ip| ip.to_string()).as_deref());
+        assert_ne!(Some(claim(&cursor, &walk, &excluded).0), next);
+        assert!(cursor.position().unwrap().1 > 1000);
+        // Excluded space is stepped over a bounded stretch at a time.
+        let before = cursor.position().unwrap().1;
+        let all = Exclusions::parse(["0.0.0.0/0"]);
+        assert!(matches!(cursor.next_address(&walk, &all), Next::Skipped));
+        assert_eq!(cursor.position().unwrap().1, before + MAX_SKIPPED_INDICES);
+        // Re-pointing at the same walk keeps the position; another resets it.
+        cursor.start(scan);
+        assert!(cursor.position().unwrap().1 > 1000);
+        cursor.start(PermutationScan::new(5, 0, 2).unwrap());
+        assert_eq!(cursor.position().unwrap().1, 0);
+    }
+
+    #[test]
+    fn settled_position_waits_for_the_oldest_claim() {
+        let scan = PermutationScan::new(9, 0, 4).unwrap();
+        let walk = Walk::new(scan);
+        let cursor = Cursor::default();
+        cursor.start(scan);
+        let none = Exclusions::default();
+        let (_, slow) = claim(&cursor, &walk, &none);
+        let slow_index = slow.index;
+        let later: Vec<_> = (0..50).map(|_| claim(&cursor, &walk, &none)).collect();
+        drop(later);
+        // Everything after the slow probe finished; it alone holds the cursor.
+        assert_eq!(cursor.settled(), Some((scan, slow_index)));
+        assert!(cursor.position().unwrap().1 > slow_index + 50);
+        drop(slow);
+        assert_eq!(cursor.settled(), cursor.position());
+
+        // A claim on a walk the cursor left no longer holds it back.
+        let (_, stale) = claim(&cursor, &walk, &none);
+        cursor.start(PermutationScan::new(9, 1, 4).unwrap());
+        assert_eq!(cursor.settled().unwrap().1, 0);
+        drop(stale);
+        assert_eq!(cursor.settled().unwrap().1, 0);
+    }
+
+    #[test]
+    fn file_config_validates_partitions() {
+        let parse = |s: &str| toml::from_str::<PermutationScan>(s);
+        assert_eq!(
+            parse("seed = 3").unwrap(),
+            PermutationScan {
+                seed: 3,
+                partition: 0,
+                partitions: 1
+            }
+        );
+        assert!(parse("partition = 2\npartitions = 2").is_err());
+        assert!(parse("partitions = 0").is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! Ports the random-search pool probes. `[worker].search_ports` (and the gRPC
+//! `WorkerConfig.search_ports`) holds weighted, inclusive port ranges; every
+//! search iteration draws one port from them through a [`PortSampler`], along
+//! with the edition (and so the prober) that port is searched for.
+
+use std::{fmt, str::FromStr};
+
+use proto::worker::{Edition, PortRange as PbPortRange};
+use rand::{Rng, RngExt};
+use serde::Deserialize;
+
+/// Port probed when no ranges are configured (the vanilla Java default).
+pub const DEFAULT_SEARCH_PORT: u16 = 25565;
+
+/// Upper bound on configured ranges. Backend-supplied config is untrusted, so a
+/// huge list is truncated rather than allocated (like `MAX_THREADS`).
+pub const MAX_PORT_RANGES: usize = 256;
+
+/// An inclusive port range with a relative sampling weight. Written in the
+/// config file as `"25565"`, `"25565-25575"`, or either with an `@weight`
+/// suffix (`"25565-25575@4"`); the weight defaults to 1 and applies to the
+/// range as a whole, not to each port in it. A `bedrock:` prefix
+/// (`"bedrock:19132@2"`) searches the range with the Bedrock (RakNet) prober
+/// instead of the Java one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+    pub weight: u32,
+    pub edition: Edition,
+}
+
+impl FromStr for PortRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (edition, rest) = match s.split_once(':') {
+            Some(("java", r)) => (Edition::Java, r),
+            Some(("bedrock", r)) => (Edition::Bedrock, r),
+            Some(_) => return Err(format!("unknown edition in port range {s:?}")),
+            None => (Edition::Java, s),
+        };
+        let (range, weight) = match rest.split_once('@') {
+            Some((r, w)) => {
+                let w = w
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| format!("invalid weight in port range {s:?}"))?;
+                (r.trim(), w)
+            }
+            None => (rest, 1),
+        };
+        if weight == 0 {
+            return Err(format!("port range {s:?} has a zero weight"));
+        }
+        let parse_port = |p: &str| {
+            p.trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|&p| p != 0)
+                .ok_or_else(|| format!("invalid port in port range {s:?}"))
+        };
+        let (start, end) = match range.split_once('-') {
+            Some((a, b)) => (parse_port(a)?, parse_port(b)?),
+            None => {
+                let p = parse_port(range)?;
+                (p, p)
+            }
+        };
+        if start > end {
+            return Err(format!("port range {s:?} is reversed"));
+        }
+        Ok(Self {
+            start,
+            end,
+            weight,
+            edition,
+        })
+    }
+}
+
+impl TryFrom<String> for PortRange {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Renders back into the config-file syntax (omitting a weight of 1), so
+/// [`PortRange::from_str`] round-trips it.
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.edition == Edition::Bedrock {
+            f.write_str("bedrock:")?;
+        }
+        if self.start == self.end {
+            write!(f, "{}", self.start)?;
+        } else {
+            write!(f, "{}-{}", self.start, self.end)?;
+        }
+        if self.weight != 1 {
+            write!(f, "@{}", self.weight)?;
+        }
+        Ok(())
+    }
+}
+
+impl PortRange {
+    pub fn to_proto(self) -> PbPortRange {
+        PbPortRange {
+            start: self.start as u32,
+            end: self.end as u32,
+            weight: self.weight,
+            edition: self.edition as i32,
+        }
+    }
+
+    /// Converts a backend-supplied range, rejecting ones that don't describe
+    /// real ports or name an unknown edition. An unset (0) weight means 1.
+    pub fn from_proto(p: &PbPortRange) -> Option<Self> {
+        let start = u16::try_from(p.start).ok().filter(|&s| s != 0)?;
+        let end = u16::try_from(p.end).ok()?;
+        let edition = Edition::try_from(p.edition).ok()?;
+        (start <= end).then_some(Self {
+            start,
+            end,
+            weight: p.weight.max(1),
+            edition,
+        })
+    }
+}
+
+/// Weighted sampler over a set of [`PortRange`]s: picks a range with
+/// probability proportional to its weight, then a port uniformly within it.
+/// An empty set samples [`DEFAULT_SEARCH_PORT`] for Java.
+#[derive(Debug, Clone)]
+pub struct PortSampler {
+    ranges: Vec<PortRange>,
+    /// Running weight totals, parallel to `ranges`.
+    cumulative: Vec<u64>,
+}
+
+impl PortSampler {
+    pub fn new(ranges: &[PortRange]) -> Self {
+        let ranges = if ranges.is_empty() {
+            vec![PortRange {
+                start: DEFAULT_SEARCH_PORT,
+                end: DEFAULT_SEARCH_PORT,
+                weight: 1,
+                edition: Edition::Java,
+            }]
+        } else {
+            ranges.to_vec()
+        };
+        let cumulative = ranges
+            .iter()
+            .scan(0u64, |acc, r| {
+                *acc += r.weight as u64;
+                Some(*acc)
+            })
+            .collect();
+        Self { ranges, cumulative }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (u16, Edition) {
+        let total = *self
+            .cumulative
+            .last()
+            .expect("sampler has at least one range");
+        let pick = rng.random_range(0..total);
+        let idx = self.cumulative.partition_point(|&c| c <= pick);
+        let r = self.ranges[idx];
+        (rng.random_range(r.start..=r.end), r.edition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn range(start: u16, end: u16, weight: u32) -> PortRange {
+        PortRange {
+            start,
+            end,
+            weight,
+            edition: Edition::Java,
+        }
+    }
+
+    fn bedrock(start: u16, end: u16, weight: u32) -> PortRange {
+        PortRange {
+            edition: Edition::Bedrock,
+            ..range(start, end, weight)
+        }
+    }
+
+    #[test]
+    fn parses_single_ports_ranges_and_weights() {
+        assert_eq!("25580".parse(), Ok(range(25580, 25580, 1)));
+        assert_eq!(" 25565 - 25575 ".parse(), Ok(range(25565, 25575, 1)));
+        assert_eq!("25565-25575@4".parse(), Ok(range(25565, 25575, 4)));
+        assert_eq!("19132@2".parse(), Ok(range(19132, 19132, 2)));
+        assert_eq!("java:25565".parse(), Ok(range(25565, 25565, 1)));
+        assert_eq!(
+            "bedrock:19132-19133@2".parse(),
+            Ok(bedrock(19132, 19133, 2))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        let bad = [
+            "",
+            "0",
+            "70000",
+            "25575-25565",
+            "25565@0",
+            "25565@x",
+            "a-b",
+            "pe:19132",
+        ];
+        for bad in bad {
+            assert!(
+                bad.parse::<PortRange>().is_err(),
+                "{bad:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in ["25565", "25565-25575", "25565-25575@4", "bedrock:19132@2"] {
+            assert_eq!(s.parse::<PortRange>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn from_proto_drops_invalid_and_defaults_weight() {
+        let pb = |start, end, weight| PbPortRange {
+            start,
+            end,
+            weight,
+            edition: Edition::Java as i32,
+        };
+        assert_eq!(
+            PortRange::from_proto(&pb(25565, 25570, 0)),
+            Some(range(25565, 25570, 1))
+        );
+        let unknown_edition = PbPortRange {
+            edition: 7,
+            ..pb(25565, 25565, 1)
+        };
+        assert_eq!(PortRange::from_proto(&unknown_edition), None);
+        assert_eq!(PortRange::from_proto(&pb(0, 10, 1)), None);
+        assert_eq!(PortRange::from_proto(&pb(20, 10, 1)), None);
+        assert_eq!(PortRange::from_proto(&pb(1, 70000, 1)), None);
+    }
+
+    #[test]
+    fn empty_sampler_uses_default_port() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let sampler = PortSampler::new(&[]);
+        assert!((0..100).all(|_| sampler.sample(&mut rng) == (DEFAULT_SEARCH_PORT, Edition::Java)));
+    }
+
+    #[test]
+    fn sampler_stays_in_range_and_honours_weights() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let sampler = PortSampler::new(&[range(25565, 25575, 3), bedrock(19132, 19132, 1)]);
+        let mut bedrock = 0;
+        for _ in 0..4000 {
+            match sampler.sample(&mut rng) {
+                (p, Edition::Java) => {
+                    assert!((25565..=25575).contains(&p), "port {p} out of range")
+                }
+                (p, Edition::Bedrock) => {
+                    assert_eq!(p, 19132);
+                    bedrock += 1;
+                }
+            }
+        }
+        // Expected share is 1/4; allow generous slack for a fixed seed.
+        assert!(
+            (800..1200).contains(&bedrock),
+            "19132 drawn {bedrock} times"
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Probe rate limiting. `[worker]` (and the gRPC `WorkerConfig`) caps how many
+//! packets and new connections per second the worker sends, so a search pool
+//! of thousands of threads cannot burst past what the hosting provider
+//! tolerates. The search threads, scan job shards, the update cycle and
+//! on-demand probes all draw from the engine's one [`RateLimiter`].
+//!
+//! Every TCP connection a probe opens costs a connection token (and the packet
+//! its connect sends): the search's connect, and each reconnect of a Java probe
+//! for negotiation, login and legacy pings, through the metered
+//! [`crate::timeouts::ProbeTimeouts::connect`]. A Bedrock ping and a GameSpy4
+//! query cost one packet each. Traffic on an open connection is not metered.
+//!
+//! `rate_schedule` overrides the limits by time of day (worker local time),
+//! e.g. to slow down during a provider's business hours.
+
+use std::{
+    fmt,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveTime;
+use proto::worker::RateWindow as PbRateWindow;
+use serde::Deserialize;
+use tracing::info;
+
+/// Upper bound on schedule windows. Backend-supplied config is untrusted, so a
+/// huge list is truncated rather than allocated (like `MAX_PORT_RANGES`).
+pub const MAX_RATE_WINDOWS: usize = 48;
+
+/// Share of a second's tokens a bucket holds, i.e. the largest burst: a tenth
+/// of a second's worth keeps the output smooth.
+const BURST_SECS: f64 = 0.1;
+
+/// Per-second caps; 0 means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RateLimits {
+    pub packets_per_sec: u32,
+    pub connections_per_sec: u32,
+}
+
+/// A time-of-day window with its own limits. Written in the config file as
+///
+/// ```toml
+/// [[worker.rate_schedule]]
+/// start = "08:00"
+/// end = "18:00"
+/// max_packets_per_sec = 500
+/// max_connections_per_sec = 200
+/// ```
+///
+/// A window whose `end` is before its `start` runs past midnight; one whose
+/// `end` equals its `start` covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawRateWindow")]
+pub struct RateWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub limits: RateLimits,
+}
+
+/// File form of [`RateWindow`], validated on conversion.
+#[derive(Deserialize)]
+struct RawRateWindow {
+    start: String,
+    end: String,
+    #[serde(default)]
+    max_packets_per_sec: u32,
+    #[serde(default)]
+    max_connections_per_sec: u32,
+}
+
+impl TryFrom<RawRateWindow> for RateWindow {
+    type Error = String;
+
+    fn try_from(raw: RawRateWindow) -> Result<Self, Self::Error> {
+        let time = |s: &str| {
+            NaiveTime::parse_from_str(s.trim(), "%H:%M")
+                .map_err(|_| format!("invalid time {s:?} in rate schedule (expected HH:MM)"))
+        };
+        Ok(Self {
+            start: time(&raw.start)?,
+            end: time(&raw.end)?,
+            limits: RateLimits {
+                packets_per_sec: raw.max_packets_per_sec,
+                connections_per_sec: raw.max_connections_per_sec,
+            },
+        })
+    }
+}
+
+impl RateWindow {
+    pub fn contains(&self, t: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= t && t < self.end
+        } else if self.start > self.end {
+            t >= self.start || t < self.end
+        } else {
+            true
+        }
+    }
+
+    pub fn to_proto(self) -> PbRateWindow {
+        PbRateWindow {
+            start: self.start.format("%H:%M").to_string(),
+            end: self.end.format("%H:%M").to_string(),
+            max_packets_per_sec: self.limits.packets_per_sec,
+            max_connections_per_sec: self.limits.connections_per_sec,
+        }
+    }
+
+    /// Converts a backend-supplied window; one with an unparsable time is
+    /// dropped.
+    pub fn from_proto(p: &PbRateWindow) -> Option<Self> {
+        RateWindow::try_from(RawRateWindow {
+            start: p.start.clone(),
+            end: p.end.clone(),
+            max_packets_per_sec: p.max_packets_per_sec,
+            max_connections_per_sec: p.max_connections_per_sec,
+        })
+        .ok()
+    }
+}
+
+/// The limits in force at time of day `now`: those of the first schedule
+/// window containing it, else `base`.
+pub fn effective_limits(base: RateLimits, schedule: &[RateWindow], now: NaiveTime) -> RateLimits {
+    schedule
+        .iter()
+        .find(|w| w.contains(now))
+        .map_or(base, |w| w.limits)
+}
+
+/// Token bucket state. Callers take a token even when none is left and wait
+/// out the debt, so waiters are served in arrival order without polling.
+struct Bucket {
+    rate: u32,
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new() -> Self {
+        Self {
+            rate: 0,
+            tokens: 0.0,
+            last: Instant::now(),
+        }
+    }
+
+    /// Takes one token at `now`, returning how long the caller must wait
+    /// before sending; `None` to send right away.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        if self.rate == 0 {
+            return None;
+        }
+        let rate = self.rate as f64;
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min((rate * BURST_SECS).max(1.0));
+        self.last = now;
+        self.tokens -= 1.0;
+        (self.tokens < 0.0).then(|| Duration::from_secs_f64(-self.tokens / rate))
+    }
+
+    /// Changes the rate, starting from an empty bucket: neither a burst nor
+    /// the debt run up under the old rate carries over.
+    fn set_rate(&mut self, rate: u32, now: Instant) {
+        self.rate = rate;
+        self.tokens = 0.0;
+        self.last = now;
+    }
+}
+
+/// The engine's shared limiter. Counts what it lets through, for the
+/// heartbeat's rates, whether or not a limit is set.
+pub struct RateLimiter {
+    limits: Mutex<RateLimits>,
+    packets: Mutex<Bucket>,
+    connections: Mutex<Bucket>,
+    pub packets_sent: AtomicU64,
+    pub connections_opened: AtomicU64,
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("limits", &self.limits())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            limits: Mutex::new(RateLimits::default()),
+            packets: Mutex::new(Bucket::new()),
+            connections: Mutex::new(Bucket::new()),
+            packets_sent: AtomicU64::new(0),
+            connections_opened: AtomicU64::new(0),
+        }
+    }
+}
+
+impl RateLimiter {
+    pub fn limits(&self) -> RateLimits {
+        *self.limits.lock().unwrap()
+    }
+
+    pub fn set_limits(&self, limits: RateLimits) {
+        let mut current = self.limits.lock().unwrap();
+        if *current == limits {
+            return;
+        }
+        *current = limits;
+        let now = Instant::now();
+        self.packets
+            .lock()
+            .unwrap()
+            .set_rate(limits.packets_per_sec, now);
+        self.connections
+            .lock()
+            .unwrap()
+            .set_rate(limits.connections_per_sec, now);
+        info!(
+            "rate limits: {} packets/s, {} connections/s (0 = unlimited)",
+            limits.packets_per_sec, limits.connections_per_sec
+        );
+    }
+
+    /// Waits for a packet token.
+    pub async fn packet(&self) {
+        let wait = self.packets.lock().unwrap().take(Instant::now());
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Waits for a connection token, and the packet token its connect costs.
+    pub async fn connection(&self) {
+        let wait = self.connections.lock().unwrap().take(Instant::now());
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+        self.connections_opened.fetch_add(1, Ordering::Relaxed);
+        self.packet().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hm(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn bucket_paces_to_its_rate() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new();
+        assert_eq!(bucket.take(start), None, "unlimited");
+        bucket.set_rate(100, start);
+        // Empty after a rate change: the first token is 10 ms away, and every
+        // further one another 10 ms.
+        let waits: Vec<_> = (0..5).map(|_| bucket.take(start).unwrap()).collect();
+        for (i, wait) in waits.iter().enumerate() {
+            let expected = (i + 1) as f64 * 0.01;
+            assert!((wait.as_secs_f64() - expected).abs() < 1e-9, "{waits:?}");
+        }
+        // Idle time refills up to the burst (a tenth of a second's tokens).
+        let later = start + Duration::from_secs(10);
+        let sent = (0..20).take_while(|_| bucket.take(later).is_none()).count();
+        assert_eq!(sent, 10);
+    }
+
+    #[test]
+    fn schedule_windows_wrap_midnight() {
+        let day = RateWindow::try_from(RawRateWindow {
+            start: "08:00".into(),
+            end: "18:00".into(),
+            max_packets_per_sec: 500,
+            max_connections_per_sec: 0,
+        })
+        .unwrap();
+        let night = RateWindow {
+            start: hm("22:00"),
+            end: hm("02:00"),
+            limits: RateLimits {
+                packets_per_sec: 9000,
+                connections_per_sec: 3000,
+            },
+        };
+        let base = RateLimits {
+            packets_per_sec: 2000,
+            connections_per_sec: 1000,
+        };
+        let schedule = [day, night];
+        let at = |t| effective_limits(base, &schedule, hm(t));
+        assert_eq!(at("12:00").packets_per_sec, 500);
+        assert_eq!(at("18:00"), base);
+        assert_eq!(at("23:30"), night.limits);
+        assert_eq!(at("01:59"), night.limits);
+        assert_eq!(at("02:00"), base);
+        assert_eq!(RateWindow::from_proto(&day.to_proto()), Some(day));
+    }
+
+    #[test]
+    fn file_config_validates_times() {
+        let parse = |s: &str| toml::from_str::<RateWindow>(s);
+        let w = parse("start = \"00:00\"\nend = \"00:00\"").unwrap();
+        assert!(w.contains(hm("13:37")));
+        assert_eq!(w.limits, RateLimits::default());
+        assert!(parse("start = \"8am\"\nend = \"10:00\"").is_err());
+        assert!(parse("start = \"25:00\"\nend = \"10:00\"").is_err());
+    }
+}
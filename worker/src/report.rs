@@ -5,10 +5,22 @@
 
 use std::time::Duration;
 
+use proto::worker::Edition;
 use serde_json::Value;
-use tokio::{net::TcpStream, time::timeout};
+use tokio::{net::TcpStream, time::Instant};
 
-use crate::server_actions::{with_connection::get_extra_data, without_connection::get_status};
+use crate::login_pool::LoginPool;
+use crate::server_actions::{
+    bedrock::get_bedrock_status,
+    login::{DEEP_LOGIN_TIMEOUT, LoginDetails},
+    query::QueryStat,
+    with_connection::get_extra_data,
+    without_connection::{
+        DEFAULT_STATUS_PROTOCOL, accepted_protocols, get_legacy_status, get_status,
+        get_status_from_ladder,
+    },
+};
+use crate::timeouts::{Phase, ProbeTimeouts, within};
 
 #[derive(Debug, Clone)]
 pub struct ScanReport {
@@ -24,39 +36,156 @@ pub struct ScanReport {
     pub favicon: Option<String>,
     pub ping: Option<i64>,
     pub extra: Option<ScanExtra>,
+    pub edition: Edition,
+    pub game_mode: Option<String>,
+    /// GameSpy4 full stat, attached by the engine when `query_enabled` is on and
+    /// the server answers.
+    pub query: Option<QueryStat>,
+    /// Client protocols the server accepted, ascending; empty unless the probe
+    /// negotiated.
+    pub accepted_protocols: Vec<i32>,
+    /// The hostname the target was entered as, if it was not an address.
+    pub hostname: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ScanExtra {
     pub is_online_mode: bool,
     pub disconnect_reason: Option<Value>,
+    /// Outcome of the deep login probe; `None` unless it ran.
+    pub login: Option<LoginDetails>,
+}
+
+/// How far a Java [`probe`] goes beyond the status ping.
+#[derive(Debug, Clone, Default)]
+pub struct ProbeOptions {
+    /// Attempt the login handshake (online-mode detection).
+    pub fetch_extra: bool,
+    /// Make a failed handshake fatal (discovery path); otherwise handshake
+    /// failures are ignored (update path).
+    pub require_extra: bool,
+    /// Re-ask for the status as the server's own advertised protocol, fall
+    /// back to a ladder of client protocols when the default one is rejected,
+    /// and record which client protocols the server accepts.
+    pub negotiate: bool,
+    /// Carry an offline-mode login on into the play state (see
+    /// [`LoginDetails`]). Only applies when `fetch_extra` is set.
+    pub deep_login: bool,
+    /// Usernames the login handshake picks from.
+    pub login: LoginPool,
+    /// Connect/read/handshake budgets, and the whole probe's.
+    pub timeouts: ProbeTimeouts,
+    /// Hostname announced in the handshakes instead of the address, for
+    /// targets entered by name.
+    pub host: Option<String>,
 }
 
 /// Probes a server's status (and optionally its login handshake) and assembles a
-/// [`ScanReport`].
+/// [`ScanReport`], as far as `opts` asks.
+///
+/// A server that does not answer the modern status ping is retried with the
+/// legacy (pre-1.7) ping. Legacy servers cannot speak the modern login, so no
+/// handshake is attempted for them and `extra` is always `None`.
 ///
-/// - `fetch_extra` controls whether the login handshake (online-mode detection)
-///   is attempted.
-/// - `require_extra` makes a failed handshake fatal (discovery path); otherwise
-///   handshake failures are ignored (update path).
+/// Every connection and read is bounded by `opts.timeouts`, the login
+/// handshake by its handshake budget, and the whole probe by its total one.
+/// Protocol negotiation only takes what the total budget leaves after the
+/// phases that follow it: the legacy fallback after the ladder, the login
+/// handshake (and with `deep_login`, its continuation) after the
+/// accepted-protocol rungs. The deep continuation itself stops at the total
+/// deadline, so a slow one still leaves a report.
 pub async fn probe(
     ip: &str,
     port: u16,
     tcp_stream: Option<TcpStream>,
-    fetch_extra: bool,
-    require_extra: bool,
+    opts: ProbeOptions,
+) -> anyhow::Result<ScanReport> {
+    let total = opts.timeouts.total;
+    // Taken before the outer timer starts, so the phases' deadline never
+    // outlasts it.
+    let deadline = Instant::now() + total;
+    within(
+        Phase::Total,
+        total,
+        probe_phases(ip, port, tcp_stream, deadline, opts),
+    )
+    .await
+}
+
+async fn probe_phases(
+    ip: &str,
+    port: u16,
+    tcp_stream: Option<TcpStream>,
+    deadline: Instant,
+    opts: ProbeOptions,
 ) -> anyhow::Result<ScanReport> {
-    let (status, ping) = get_status(ip, port, tcp_stream).await?;
+    let negotiate = opts.negotiate;
+    let t = &opts.timeouts;
+    let leaving = |reserve| deadline.checked_sub(reserve).unwrap_or(deadline);
+    let host = opts.host.as_deref().unwrap_or(ip);
+    let modern = match get_status(ip, host, port, tcp_stream, DEFAULT_STATUS_PROTOCOL, t).await {
+        Ok((status, ping)) if negotiate => {
+            let native = status.version.protocol as i32;
+            if native > 0 && native != DEFAULT_STATUS_PROTOCOL {
+                // Keep the first answer if the re-handshake fails.
+                Ok(get_status(ip, host, port, None, native, t)
+                    .await
+                    .unwrap_or((status, ping)))
+            } else {
+                Ok((status, ping))
+            }
+        }
+        Err(err) if negotiate => {
+            get_status_from_ladder(ip, host, port, t, leaving(t.connect + t.read))
+                .await
+                .map_err(|_| err)
+        }
+        res => res,
+    };
+    let (status, ping, legacy) = match modern {
+        Ok((status, ping)) => (status, ping, false),
+        Err(err) => match get_legacy_status(ip, host, port, t).await {
+            Ok((status, ping)) => (status, ping, true),
+            // Report the modern failure: it is the expected protocol.
+            Err(_) => return Err(err),
+        },
+    };
     let requires_mods = status.requires_mods();
 
-    let extra = if fetch_extra {
-        match get_extra_data(ip.to_string(), port, status.version.protocol as i32).await {
+    let accepted_protocols = if negotiate && !legacy {
+        let native = status.version.protocol as i32;
+        let reserve = if opts.fetch_extra && opts.deep_login {
+            t.handshake + DEEP_LOGIN_TIMEOUT
+        } else if opts.fetch_extra {
+            t.handshake
+        } else {
+            Duration::ZERO
+        };
+        accepted_protocols(ip, host, port, native, t, leaving(reserve)).await
+    } else {
+        Vec::new()
+    };
+
+    let extra = if opts.fetch_extra && !legacy {
+        let protocol = status.version.protocol as i32;
+        let identity = opts.login.identity(ip, port);
+        let extra = get_extra_data(
+            ip.to_string(),
+            host.to_string(),
+            port,
+            protocol,
+            identity,
+            opts.deep_login.then_some(deadline),
+            t,
+        );
+        match extra.await {
             Ok(e) => Some(ScanExtra {
                 is_online_mode: e.is_online_mode,
                 disconnect_reason: e.disconnect_reason,
+                login: e.login,
             }),
             Err(err) => {
-                if require_extra {
+                if opts.require_extra {
                     return Err(err);
                 }
                 None
@@ -87,11 +216,73 @@ pub async fn probe(
         favicon: status.favicon,
         ping,
         extra,
+        edition: Edition::Java,
+        game_mode: None,
+        query: None,
+        accepted_protocols,
+        hostname: opts.host,
     })
 }
 
-/// Opens a TCP connection to a candidate address, with a short timeout.
-pub async fn check_server(ip: &str, port: u16) -> anyhow::Result<TcpStream> {
-    let addr = format!("{}:{}", ip, port);
-    Ok(timeout(Duration::from_millis(750), TcpStream::connect(&addr)).await??)
+/// Probes a Bedrock server with the RakNet unconnected ping. The pong carries no
+/// player sample, favicon or mod list, and there is no login probe, so those
+/// fields are always empty. Both MOTD lines go into `description`.
+pub async fn probe_bedrock(
+    ip: &str,
+    port: u16,
+    timeouts: &ProbeTimeouts,
+) -> anyhow::Result<ScanReport> {
+    let (status, ping) = get_bedrock_status(ip, port, timeouts).await?;
+    let description = match status.sub_motd {
+        Some(sub) => format!("{}\n{}", status.motd, sub),
+        None => status.motd,
+    };
+
+    Ok(ScanReport {
+        ip: ip.to_string(),
+        port: port as i32,
+        version_name: status.version,
+        protocol: status.protocol as i32,
+        description: Value::String(description),
+        players_online: status.players_online as i32,
+        players_max: status.players_max as i32,
+        player_names: Vec::new(),
+        requires_mods: false,
+        favicon: None,
+        ping,
+        extra: None,
+        edition: Edition::Bedrock,
+        game_mode: status.game_mode,
+        query: None,
+        accepted_protocols: Vec::new(),
+        hostname: None,
+    })
+}
+
+/// Probes a known server with the prober for its edition. `opts` is as for
+/// [`probe`]; Bedrock only takes its timeouts and the `host` to carry into the
+/// report.
+pub async fn probe_edition(
+    edition: Edition,
+    ip: &str,
+    port: u16,
+    opts: ProbeOptions,
+) -> anyhow::Result<ScanReport> {
+    match edition {
+        Edition::Java => probe(ip, port, None, opts).await,
+        Edition::Bedrock => {
+            let mut report = probe_bedrock(ip, port, &opts.timeouts).await?;
+            report.hostname = opts.host;
+            Ok(report)
+        }
+    }
+}
+
+/// Opens a TCP connection to a candidate address within the connect budget.
+pub async fn check_server(
+    ip: &str,
+    port: u16,
+    timeouts: &ProbeTimeouts,
+) -> anyhow::Result<TcpStream> {
+    timeouts.connect(ip, port).await
 }
@@ -0,0 +1,256 @@
+//! Hostname targets. A scan task may carry a hostname instead of an address;
+//! [`TargetResolver::resolve`] turns it into the address and port a client
+//! would connect to: the `_minecraft._tcp` SRV record when no port was given
+//! (Java only), then the A/AAAA records of the resulting host. Resolved
+//! addresses go through the same [`worker::is_probeable_ip`] guard as literal
+//! ones, so a name pointing at an internal host is refused like its address.
+//!
+//! Lookups go to the system resolver unless `[worker].dns_servers` names
+//! explicit ones.
+
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::{anyhow, bail};
+use hickory_resolver::{
+    TokioResolver,
+    config::{ConnectionConfig, NameServerConfig, ResolverConfig},
+    net::runtime::TokioRuntimeProvider,
+    proto::rr::RData,
+};
+use proto::worker::Edition;
+
+/// Port a Java client assumes when neither the address nor an SRV record
+/// names one.
+pub const DEFAULT_JAVA_PORT: u16 = 25565;
+/// Bedrock has no SRV convention; a bare name means this port.
+pub const DEFAULT_BEDROCK_PORT: u16 = 19132;
+
+/// Service label the Java client looks up for a bare hostname.
+const SRV_PREFIX: &str = "_minecraft._tcp.";
+
+/// Per-query wait and retries. Resolution happens once per on-demand scan, so
+/// there is no point waiting as long as the system default.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+const LOOKUP_ATTEMPTS: usize = 2;
+
+#[derive(Clone)]
+pub struct TargetResolver {
+    inner: TokioResolver,
+}
+
+impl TargetResolver {
+    /// Queries `servers` when any are given, otherwise the system resolver
+    /// (`/etc/resolv.conf`). A host without a usable system config gets a
+    /// resolver with no servers, whose lookups all fail.
+    pub fn new(servers: &[SocketAddr]) -> Self {
+        let mut builder = if servers.is_empty() {
+            TokioResolver::builder_tokio().unwrap_or_else(|e| {
+                tracing::warn!("no usable system DNS config ({e}); hostname targets will fail");
+                TokioResolver::builder_with_config(
+                    ResolverConfig::from_name_servers(Vec::new()),
+                    TokioRuntimeProvider::default(),
+                )
+            })
+        } else {
+            let name_servers = servers
+                .iter()
+                .map(|s| {
+                    let mut udp = ConnectionConfig::udp();
+                    udp.port = s.port();
+                    NameServerConfig::new(s.ip(), true, vec![udp])
+                })
+                .collect();
+            TokioResolver::builder_with_config(
+                ResolverConfig::from_name_servers(name_servers),
+                TokioRuntimeProvider::default(),
+            )
+        };
+        let opts = builder.options_mut();
+        opts.timeout = LOOKUP_TIMEOUT;
+        opts.attempts = LOOKUP_ATTEMPTS;
+        let inner = builder.build().expect("resolver config is valid");
+        Self { inner }
+    }
+
+    /// Resolves `host` to a probeable address and port. A `port` of 0 means
+    /// none was given: Java then follows the SRV record, falling back to
+    /// [`DEFAULT_JAVA_PORT`]; Bedrock uses [`DEFAULT_BEDROCK_PORT`].
+    pub async fn resolve(
+        &self,
+        host: &str,
+        port: u16,
+        edition: Edition,
+    ) -> anyhow::Result<(String, u16)> {
+        let (target, port) = match (port, edition) {
+            (0, Edition::Java) => match self.srv(host).await {
+                Some(found) => found,
+                None => (host.to_string(), DEFAULT_JAVA_PORT),
+            },
+            (0, Edition::Bedrock) => (host.to_string(), DEFAULT_BEDROCK_PORT),
+            (port, _) => (host.to_string(), port),
+        };
+
+        let ips = self.inner.lookup_ip(target.as_str()).await?;
+        let mut seen = false;
+        for ip in ips.iter() {
+            seen = true;
+            let ip = ip.to_string();
+            if worker::is_probeable_ip(&ip) {
+                return Ok((ip, port));
+            }
+        }
+        if seen {
+            bail!("{host} resolves only to non-probeable addresses");
+        }
+        Err(anyhow!("{host} has no A/AAAA records"))
+    }
+
+    /// The SRV target and port a Java client would connect to: the record with
+    /// the lowest priority, and among those the highest weight. `None` when the
+    /// name has no usable record (a target of `.` means "no service").
+    async fn srv(&self, host: &str) -> Option<(String, u16)> {
+        let lookup = self
+            .inner
+            .srv_lookup(format!("{SRV_PREFIX}{host}"))
+            .await
+            .ok()?;
+        lookup
+            .answers()
+            .iter()
+            .filter_map(|record| match &record.data {
+                RData::SRV(srv) if !srv.target.is_root() => Some(srv),
+                _ => None,
+            })
+            .min_by_key(|srv| (srv.priority, std::cmp::Reverse(srv.weight)))
+            .map(|srv| {
+                let target = srv.target.to_ascii();
+                (target.trim_end_matches('.').to_string(), srv.port)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_resolver::proto::{
+        op::{Message, OpCode},
+        rr::{
+            Name, Record,
+            rdata::{A, SRV},
+        },
+    };
+    use std::net::Ipv4Addr;
+    use tokio::net::UdpSocket;
+
+    /// Answers A and SRV queries from `records` over UDP on loopback; anything
+    /// else gets an empty answer.
+    async fn stub_dns(records: Vec<(&'static str, RData)>) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            loop {
+                let Ok((len, from)) = socket.recv_from(&mut buf).await else {
+                    return;
+                };
+                let Ok(query) = Message::from_vec(&buf[..len]) else {
+                    continue;
+                };
+                let mut reply = Message::response(query.metadata.id, OpCode::Query);
+                for q in &query.queries {
+                    reply.add_query(q.clone());
+                    let name = q.name().to_ascii();
+                    for (owner, data) in &records {
+                        if name.trim_end_matches('.') == *owner
+                            && data.record_type() == q.query_type()
+                        {
+                            reply.add_answer(Record::from_rdata(
+                                q.name().clone(),
+                                60,
+                                data.clone(),
+                            ));
+                        }
+                    }
+                }
+                let _ = socket.send_to(&reply.to_vec().unwrap(), from).await;
+            }
+        });
+        addr
+    }
+
+    fn srv(priority: u16, weight: u16, port: u16, target: &str) -> RData {
+        RData::SRV(SRV::new(
+            priority,
+            weight,
+            port,
+            Name::from_ascii(target).unwrap(),
+        ))
+    }
+
+    fn a(ip: [u8; 4]) -> RData {
+        RData::A(A(Ipv4Addr::from(ip)))
+    }
+
+    #[tokio::test]
+    async fn follows_the_preferred_srv_record() {
+        let dns = stub_dns(vec![
+            (
+                "_minecraft._tcp.play.example.net",
+                srv(10, 0, 25570, "backup.example.net."),
+            ),
+            (
+                "_minecraft._tcp.play.example.net",
+                srv(5, 1, 25566, "light.example.net."),
+            ),
+            (
+                "_minecraft._tcp.play.example.net",
+                srv(5, 9, 25567, "mc.example.net."),
+            ),
+            ("mc.example.net", a([8, 8, 4, 4])),
+            ("play.example.net", a([1, 1, 1, 1])),
+        ])
+        .await;
+        let resolver = TargetResolver::new(&[dns]);
+
+        let got = resolver.resolve("play.example.net", 0, Edition::Java).await;
+        assert_eq!(got.unwrap(), ("8.8.4.4".to_string(), 25567));
+        // An explicit port skips SRV, like the vanilla client.
+        let got = resolver
+            .resolve("play.example.net", 25565, Edition::Java)
+            .await;
+        assert_eq!(got.unwrap(), ("1.1.1.1".to_string(), 25565));
+        // Bedrock has no SRV convention.
+        let got = resolver
+            .resolve("play.example.net", 0, Edition::Bedrock)
+            .await;
+        assert_eq!(got.unwrap(), ("1.1.1.1".to_string(), DEFAULT_BEDROCK_PORT));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_default_port_without_srv() {
+        let dns = stub_dns(vec![("mc.example.org", a([9, 9, 9, 9]))]).await;
+        let resolver = TargetResolver::new(&[dns]);
+        let got = resolver.resolve("mc.example.org", 0, Edition::Java).await;
+        assert_eq!(got.unwrap(), ("9.9.9.9".to_string(), DEFAULT_JAVA_PORT));
+    }
+
+    #[tokio::test]
+    async fn refuses_names_resolving_to_reserved_addresses() {
+        let dns = stub_dns(vec![
+            ("internal.example.com", a([10, 0, 0, 5])),
+            ("loop.example.com", a([127, 0, 0, 1])),
+        ])
+        .await;
+        let resolver = TargetResolver::new(&[dns]);
+        for host in [
+            "internal.example.com",
+            "loop.example.com",
+            "missing.example.com",
+        ] {
+            assert!(
+                resolver.resolve(host, 25565, Edition::Java).await.is_err(),
+                "{host}"
+            );
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail};
+use tokio::net::UdpSocket;
+
+use crate::timeouts::{Phase, ProbeTimeouts, within};
+
+/// RakNet offline packet ids for the server list ping.
+const UNCONNECTED_PING: u8 = 0x01;
+const UNCONNECTED_PONG: u8 = 0x1C;
+/// RakNet's "offline message" magic, present in every unconnected packet.
+const OFFLINE_MAGIC: [u8; 16] = [
+    0x00, 0xFF, 0xFF, 0x00, 0xFE, 0xFE, 0xFE, 0xFE, 0xFD, 0xFD, 0xFD, 0xFD, 0x12, 0x34, 0x56, 0x78,
+];
+/// id (1) + time (8) + server guid (8) + magic (16) + string length (2).
+const PONG_HEADER_LEN: usize = 35;
+
+/// Status parsed from a Bedrock pong's server id string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BedrockStatus {
+    /// `MCPE` for Bedrock proper, `MCEE` for Education Edition.
+    pub edition: String,
+    pub motd: String,
+    pub protocol: i64,
+    pub version: String,
+    pub players_online: i64,
+    pub players_max: i64,
+    /// Second MOTD line; vanilla servers put the level name here.
+    pub sub_motd: Option<String>,
+    pub game_mode: Option<String>,
+}
+
+/// Bedrock server list ping: a RakNet unconnected ping answered by an
+/// unconnected pong whose payload is the `;`-separated server id string. The
+/// returned ping is the round trip of the exchange.
+///
+/// UDP has no connect phase to fail fast on, so waiting for the pong doubles as
+/// the "is anything listening" check, bounded by the connect budget.
+pub async fn get_bedrock_status(
+    ip: &str,
+    port: u16,
+    timeouts: &ProbeTimeouts,
+) -> anyhow::Result<(BedrockStatus, Option<i64>)> {
+    let socket = UdpSocket::bind(worker::udp_bind_addr(ip)).await?;
+    socket.connect(worker::host_port(ip, port)).await?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("SystemTime before UNIX_EPOCH")
+        .as_millis() as i64;
+    let started = Instant::now();
+    socket.send(&unconnected_ping(timestamp, timestamp)).await?;
+
+    // A pong is a single datagram; the id string is bounded by the MTU.
+    let mut buf = [0u8; 1500];
+    let len = within(Phase::Connect, timeouts.connect, socket.recv(&mut buf)).await?;
+    let ping_ms = started.elapsed().as_millis() as i64;
+
+    Ok((parse_pong(&buf[..len])?, Some(ping_ms)))
+}
+
+/// Encodes an unconnected ping: id, client time, magic, client GUID.
+fn unconnected_ping(timestamp: i64, client_guid: i64) -> Vec<u8> {
+    let mut out = vec![UNCONNECTED_PING];
+    out.extend(timestamp.to_be_bytes());
+    out.extend(OFFLINE_MAGIC);
+    out.extend(client_guid.to_be_bytes());
+    out
+}
+
+/// Parses an unconnected pong datagram (id, echoed time, server GUID, magic,
+/// `u16`-prefixed server id string).
+pub fn parse_pong(bytes: &[u8]) -> anyhow::Result<BedrockStatus> {
+    if bytes.len() < PONG_HEADER_LEN {
+        bail!("truncated bedrock pong");
+    }
+    if bytes[0] != UNCONNECTED_PONG {
+        bail!("unexpected bedrock pong packet id 0x{:02x}", bytes[0]);
+    }
+    if bytes[17..33] != OFFLINE_MAGIC {
+        bail!("bedrock pong is missing the offline magic");
+    }
+    let len = u16::from_be_bytes([bytes[33], bytes[34]]) as usize;
+    let id = bytes
+        .get(PONG_HEADER_LEN..PONG_HEADER_LEN + len)
+        .ok_or_else(|| anyhow!("truncated bedrock pong"))?;
+    parse_server_id(std::str::from_utf8(id)?)
+}
+
+/// Parses `edition;motd;protocol;version;online;max;guid;sub motd;game mode;...`.
+/// Only the first six fields are mandatory; older servers stop there, and the
+/// trailing ones (game mode id, ports) are ignored.
+fn parse_server_id(id: &str) -> anyhow::Result<BedrockStatus> {
+    let bad = || anyhow!("malformed bedrock server id: {id:?}");
+    let fields: Vec<&str> = id.split(';').collect();
+    let [edition, motd, protocol, version, online, max, ..] = fields[..] else {
+        return Err(bad());
+    };
+    let optional = |i: usize| {
+        fields
+            .get(i)
+            .filter(|f| !f.is_empty())
+            .map(|f| f.to_string())
+    };
+
+    Ok(BedrockStatus {
+        edition: edition.to_string(),
+        motd: motd.to_string(),
+        protocol: protocol.parse().map_err(|_| bad())?,
+        version: version.to_string(),
+        players_online: online.parse().map_err(|_| bad())?,
+        players_max: max.parse().map_err(|_| bad())?,
+        sub_motd: optional(7),
+        game_mode: optional(8),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVER_ID: &str = "MCPE;Dedicated Server;766;1.21.50;3;10;13253860892328930865;Bedrock level;Survival;1;19132;19133;";
+
+    /// Encodes the pong a server would send back for `id`.
+    fn pong(id: &str) -> Vec<u8> {
+        let mut out = vec![UNCONNECTED_PONG];
+        out.extend(0i64.to_be_bytes());
+        out.extend(42i64.to_be_bytes());
+        out.extend(OFFLINE_MAGIC);
+        out.extend((id.len() as u16).to_be_bytes());
+        out.extend(id.as_bytes());
+        out
+    }
+
+    #[test]
+    fn pong_parses_all_fields() {
+        let status = parse_pong(&pong(SERVER_ID)).unwrap();
+        assert_eq!(
+            status,
+            BedrockStatus {
+                edition: "MCPE".into(),
+                motd: "Dedicated Server".into(),
+                protocol: 766,
+                version: "1.21.50".into(),
+                players_online: 3,
+                players_max: 10,
+                sub_motd: Some("Bedrock level".into()),
+                game_mode: Some("Survival".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn pong_without_trailing_fields_parses() {
+        let status = parse_pong(&pong("MCPE;§aHello;390;1.14.60;0;20")).unwrap();
+        assert_eq!(status.motd, "§aHello");
+        assert_eq!(status.sub_motd, None);
+        assert_eq!(status.game_mode, None);
+    }
+
+    #[test]
+    fn pong_rejects_malformed_packets() {
+        let full = pong(SERVER_ID);
+        assert!(parse_pong(&full[..20]).is_err());
+        assert!(parse_pong(&full[..full.len() - 5]).is_err());
+        let mut wrong_id = full.clone();
+        wrong_id[0] = 0x1D;
+        assert!(parse_pong(&wrong_id).is_err());
+        let mut no_magic = full;
+        no_magic[20] ^= 0xFF;
+        assert!(parse_pong(&no_magic).is_err());
+        assert!(parse_pong(&pong("MCPE;motd;x;1.0;0;10")).is_err());
+        assert!(parse_pong(&pong("MCPE;motd")).is_err());
+    }
+
+    /// End to end against a fake responder on loopback: checks the ping it
+    /// receives and answers with a pong.
+    #[tokio::test]
+    async fn pings_a_local_responder() {
+        let responder = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = responder.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            let (len, from) = responder.recv_from(&mut buf).await.unwrap();
+            assert_eq!(len, 33);
+            assert_eq!(buf[0], UNCONNECTED_PING);
+            assert_eq!(buf[9..25], OFFLINE_MAGIC);
+            responder.send_to(&pong(SERVER_ID), from).await.unwrap();
+        });
+
+        let (status, ping) = get_bedrock_status("127.0.0.1", port, &ProbeTimeouts::default())
+            .await
+            .unwrap();
+        assert_eq!(status.version, "1.21.50");
+        assert_eq!(status.players_online, 3);
+        assert!(ping.is_some());
+    }
+
+    #[tokio::test]
+    async fn silent_port_times_out() {
+        // Bound but never answers.
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = silent.local_addr().unwrap().port();
+        assert!(
+            get_bedrock_status("127.0.0.1", port, &ProbeTimeouts::default())
+                .await
+                .is_err()
+        );
+    }
+}
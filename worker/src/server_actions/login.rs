@@ -0,0 +1,642 @@
+//! Deep login probe: carries an offline-mode login past `LoginFinished`,
+//! through the configuration state (1.20.2+) and into play, to see what a real
+//! client would run into on joining: an auth plugin asking for `/login`, a
+//! whitelist kick, or a mod loader's handshake.
+
+use std::io::{Cursor, Read};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use mc_protocol::prelude::*;
+use serde_json::{Map, Value};
+use tokio::{
+    net::TcpStream,
+    time::{Instant, timeout_at},
+};
+
+use crate::packets::*;
+
+/// Budget for the configuration state, the first play packet and the play
+/// window. Registry data is the bulk of it; a server that stalls past this is
+/// left as far as it got rather than failing the probe.
+pub const DEEP_LOGIN_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to keep listening after joining for a kick or an auth prompt.
+/// Auth plugins greet on join, so this only has to cover the first messages.
+const PLAY_WINDOW: Duration = Duration::from_secs(2);
+/// Vanilla's translation key for a whitelist kick.
+const NOT_WHITELISTED_KEY: &str = "multiplayer.disconnect.not_whitelisted";
+/// Commands auth plugins (AuthMe, nLogin, LibreLogin, ...) prompt for.
+const AUTH_COMMANDS: [&str; 2] = ["/login", "/register"];
+/// Plugin channel namespaces used by mod loaders' login/config handshakes.
+const MOD_CHANNEL_PREFIXES: [&str; 4] = ["fml:", "forge:", "neoforge:", "fabric:"];
+/// Text components are network NBT from 1.20.3 on, JSON before.
+const NBT_COMPONENTS_PROTOCOL: i32 = 765;
+/// First protocol with the configuration state (1.20.2).
+const CONFIGURATION_PROTOCOL: i32 = 764;
+/// Bounds recursion on untrusted NBT.
+const MAX_NBT_DEPTH: usize = 64;
+
+/// What the deep login probe saw after the login handshake.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoginDetails {
+    /// The server sent the join game packet.
+    pub reached_play: bool,
+    /// A chat message prompted for `/login` or `/register`.
+    pub auth_prompt: bool,
+    /// The server kicked us for not being whitelisted.
+    pub whitelist_kick: bool,
+    /// The server spoke a mod loader's handshake channel.
+    pub mod_handshake: bool,
+}
+
+/// Configuration-state packet ids; they shifted in 1.20.5 (cookies).
+struct ConfigIds {
+    plugin: i32,
+    disconnect: i32,
+    finish: i32,
+    keep_alive: i32,
+    ping: i32,
+    /// Known packs request (1.20.5+).
+    known_packs: Option<i32>,
+    ack_finish: i32,
+    keep_alive_reply: i32,
+    pong: i32,
+    known_packs_reply: i32,
+}
+
+fn config_ids(protocol: i32) -> ConfigIds {
+    if protocol >= 766 {
+        ConfigIds {
+            plugin: 0x01,
+            disconnect: 0x02,
+            finish: 0x03,
+            keep_alive: 0x04,
+            ping: 0x05,
+            known_packs: Some(0x0E),
+            ack_finish: 0x03,
+            keep_alive_reply: 0x04,
+            pong: 0x05,
+            known_packs_reply: 0x07,
+        }
+    } else {
+        ConfigIds {
+            plugin: 0x00,
+            disconnect: 0x01,
+            finish: 0x02,
+            keep_alive: 0x03,
+            ping: 0x04,
+            known_packs: None,
+            ack_finish: 0x02,
+            keep_alive_reply: 0x03,
+            pong: 0x04,
+            known_packs_reply: 0x07,
+        }
+    }
+}
+
+/// Play-state `(disconnect, system chat)` ids for the protocols we know. Before
+/// 1.19 the chat packet is the plain chat message one. Unknown protocols still
+/// get join detection, just no kick/prompt reading.
+fn play_ids(protocol: i32) -> Option<(i32, i32)> {
+    Some(match protocol {
+        47 => (0x40, 0x02),
+        340 => (0x1A, 0x0F),
+        751..=754 => (0x19, 0x0E),
+        755..=758 => (0x1A, 0x0F),
+        759 => (0x17, 0x5F),
+        760 => (0x19, 0x62),
+        761 => (0x17, 0x60),
+        762 | 763 => (0x1A, 0x64),
+        764 => (0x1B, 0x67),
+        765 => (0x1B, 0x69),
+        766 | 767 => (0x1D, 0x6C),
+        768 | 769 => (0x1D, 0x73),
+        770..=772 => (0x1C, 0x72),
+        _ => return None,
+    })
+}
+
+/// True for a login plugin or configuration plugin channel belonging to a mod
+/// loader.
+pub fn is_mod_channel(channel: &str) -> bool {
+    MOD_CHANNEL_PREFIXES.iter().any(|p| channel.starts_with(p))
+}
+
+/// True when a disconnect reason is a whitelist kick: vanilla's translation
+/// key, or a plugin's literal "not whitelisted" message.
+pub fn is_whitelist_kick(reason: &Value) -> bool {
+    if has_translate_key(reason, NOT_WHITELISTED_KEY) {
+        return true;
+    }
+    let text: String = plain_text(reason)
+        .to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .collect();
+    text.contains("whitelist")
+}
+
+/// True when a chat message asks the player to log in or register.
+pub fn is_auth_prompt(message: &Value) -> bool {
+    let text = plain_text(message).to_lowercase();
+    AUTH_COMMANDS.iter().any(|c| text.contains(c))
+}
+
+fn has_translate_key(component: &Value, key: &str) -> bool {
+    match component {
+        Value::Object(o) => {
+            o.get("translate").and_then(Value::as_str) == Some(key)
+                || o.values().any(|v| has_translate_key(v, key))
+        }
+        Value::Array(a) => a.iter().any(|v| has_translate_key(v, key)),
+        _ => false,
+    }
+}
+
+/// Flattens a text component to its visible text: `text`, then `with`
+/// arguments, then `extra` children. Translation keys are not resolved.
+pub fn plain_text(component: &Value) -> String {
+    let mut out = String::new();
+    push_text(component, &mut out);
+    out
+}
+
+fn push_text(component: &Value, out: &mut String) {
+    match component {
+        Value::String(s) => out.push_str(s),
+        Value::Array(a) => a.iter().for_each(|v| push_text(v, out)),
+        Value::Object(o) => {
+            // NBT lists of mixed types wrap each element under an empty key.
+            for key in ["", "text"] {
+                if let Some(v) = o.get(key) {
+                    push_text(v, out);
+                }
+            }
+            for key in ["with", "extra"] {
+                if let Some(v) = o.get(key) {
+                    push_text(v, out);
+                }
+            }
+        }
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::Bool(b) => out.push_str(&b.to_string()),
+        Value::Null => {}
+    }
+}
+
+/// Decodes a nameless network NBT tag (1.20.2+ framing) into JSON, which is
+/// how text components are sent from 1.20.3 on. Arrays of numbers become JSON
+/// arrays.
+pub fn nbt_to_json(bytes: &mut Cursor<&[u8]>) -> anyhow::Result<Value> {
+    let ty = read_u8(bytes)?;
+    read_nbt_payload(bytes, ty, 0)
+}
+
+fn read_nbt_payload(r: &mut Cursor<&[u8]>, ty: u8, depth: usize) -> anyhow::Result<Value> {
+    if depth > MAX_NBT_DEPTH {
+        bail!("nbt nested too deeply");
+    }
+    Ok(match ty {
+        1 => Value::from(read_array::<1>(r)?[0] as i8),
+        2 => Value::from(i16::from_be_bytes(read_array(r)?)),
+        3 => Value::from(i32::from_be_bytes(read_array(r)?)),
+        4 => Value::from(i64::from_be_bytes(read_array(r)?)),
+        5 => Value::from(f32::from_be_bytes(read_array(r)?)),
+        6 => Value::from(f64::from_be_bytes(read_array(r)?)),
+        7 => {
+            let len = read_len(r)?;
+            let mut bytes = vec![0u8; len];
+            r.read_exact(&mut bytes)?;
+            Value::from(bytes.into_iter().map(|b| b as i8).collect::<Vec<_>>())
+        }
+        8 => Value::String(read_nbt_string(r)?),
+        9 => {
+            let elem = read_u8(r)?;
+            let len = read_len(r)?;
+            let items = (0..len)
+                .map(|_| read_nbt_payload(r, elem, depth + 1))
+                .collect::<anyhow::Result<_>>()?;
+            Value::Array(items)
+        }
+        10 => {
+            let mut map = Map::new();
+            loop {
+                let ty = read_u8(r)?;
+                if ty == 0 {
+                    break;
+                }
+                let name = read_nbt_string(r)?;
+                map.insert(name, read_nbt_payload(r, ty, depth + 1)?);
+            }
+            Value::Object(map)
+        }
+        11 => {
+            let len = read_len(r)?;
+            let items = (0..len)
+                .map(|_| Ok(Value::from(i32::from_be_bytes(read_array(r)?))))
+                .collect::<anyhow::Result<_>>()?;
+            Value::Array(items)
+        }
+        12 => {
+            let len = read_len(r)?;
+            let items = (0..len)
+                .map(|_| Ok(Value::from(i64::from_be_bytes(read_array(r)?))))
+                .collect::<anyhow::Result<_>>()?;
+            Value::Array(items)
+        }
+        _ => bail!("unknown nbt tag type {ty}"),
+    })
+}
+
+fn read_u8(r: &mut Cursor<&[u8]>) -> anyhow::Result<u8> {
+    Ok(read_array::<1>(r)?[0])
+}
+
+fn read_array<const N: usize>(r: &mut Cursor<&[u8]>) -> anyhow::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Array/list lengths are `i32`; one that outruns the remaining bytes is
+/// rejected before anything is allocated for it.
+fn read_len(r: &mut Cursor<&[u8]>) -> anyhow::Result<usize> {
+    let len = i32::from_be_bytes(read_array(r)?);
+    let remaining = r.get_ref().len() as u64 - r.position();
+    usize::try_from(len)
+        .ok()
+        .filter(|&l| l as u64 <= remaining)
+        .ok_or_else(|| anyhow!("invalid nbt length {len}"))
+}
+
+/// NBT strings are `u16`-prefixed modified UTF-8; lossy UTF-8 is close enough
+/// for display text.
+fn read_nbt_string(r: &mut Cursor<&[u8]>) -> anyhow::Result<String> {
+    let len = u16::from_be_bytes(read_array(r)?) as usize;
+    let mut bytes = vec![0u8; len];
+    r.read_exact(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Reads the text component at the front of a play/configuration payload.
+fn read_component(payload: &[u8], protocol: i32) -> anyhow::Result<Value> {
+    let mut cursor = Cursor::new(payload);
+    if protocol >= NBT_COMPONENTS_PROTOCOL {
+        nbt_to_json(&mut cursor)
+    } else {
+        let json = String::deserialize(&mut cursor)?;
+        Ok(serde_json::from_str(&json).unwrap_or(Value::String(json)))
+    }
+}
+
+/// A logged-in connection past `LoginFinished`.
+struct Session<'a> {
+    conn: &'a mut TcpStream,
+    protocol: i32,
+    threshold: Option<i32>,
+}
+
+impl Session<'_> {
+    async fn read(&mut self) -> anyhow::Result<UncompressedPacket> {
+        Ok(RawPacket::read_async(self.conn)
+            .await?
+            .uncompress(self.threshold)?)
+    }
+
+    async fn send(&mut self, packet_id: i32, payload: Vec<u8>) -> anyhow::Result<()> {
+        UncompressedPacket::new(packet_id, payload)
+            .to_raw_packet_compressed(self.threshold)?
+            .write_async(self.conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn run(
+        &mut self,
+        details: &mut LoginDetails,
+        kick: &mut Option<Value>,
+    ) -> anyhow::Result<()> {
+        if self.protocol >= CONFIGURATION_PROTOCOL {
+            let login_ack = UncompressedPacket::from_packet(&c2s::LoginAcknowledged {})?;
+            self.send(login_ack.packet_id, login_ack.payload).await?;
+            if !self.configure(details, kick).await? {
+                return Ok(());
+            }
+        }
+        self.play(details, kick).await
+    }
+
+    /// Runs the configuration state; false when the server disconnected in it.
+    async fn configure(
+        &mut self,
+        details: &mut LoginDetails,
+        kick: &mut Option<Value>,
+    ) -> anyhow::Result<bool> {
+        let ids = config_ids(self.protocol);
+        loop {
+            let packet = self.read().await?;
+            let id = packet.packet_id;
+            if id == ids.plugin {
+                let channel: String = packet.deserialize_payload()?;
+                details.mod_handshake |= is_mod_channel(&channel);
+            } else if id == ids.disconnect {
+                let reason = read_component(&packet.payload, self.protocol)?;
+                details.whitelist_kick = is_whitelist_kick(&reason);
+                *kick = Some(reason);
+                return Ok(false);
+            } else if id == ids.finish {
+                self.send(ids.ack_finish, Vec::new()).await?;
+                return Ok(true);
+            } else if id == ids.keep_alive {
+                self.send(ids.keep_alive_reply, packet.payload).await?;
+            } else if id == ids.ping {
+                self.send(ids.pong, packet.payload).await?;
+            } else if Some(id) == ids.known_packs {
+                // Claim no packs, so the server sends its registries in full.
+                self.send(ids.known_packs_reply, vec![0]).await?;
+            }
+        }
+    }
+
+    /// The first play packet is join game unless it is a kick; after that,
+    /// listen briefly for a kick or an auth plugin's prompt.
+    async fn play(
+        &mut self,
+        details: &mut LoginDetails,
+        kick: &mut Option<Value>,
+    ) -> anyhow::Result<()> {
+        let ids = play_ids(self.protocol);
+        let first = self.read().await?;
+        if let Some((disconnect, _)) = ids
+            && first.packet_id == disconnect
+        {
+            let reason = read_component(&first.payload, self.protocol)?;
+            details.whitelist_kick = is_whitelist_kick(&reason);
+            *kick = Some(reason);
+            return Ok(());
+        }
+        details.reached_play = true;
+
+        let Some((disconnect, chat)) = ids else {
+            return Ok(());
+        };
+        let deadline = Instant::now() + PLAY_WINDOW;
+        while let Ok(packet) = timeout_at(deadline, self.read()).await {
+            let packet = packet?;
+            if packet.packet_id == disconnect {
+                let reason = read_component(&packet.payload, self.protocol)?;
+                details.whitelist_kick = is_whitelist_kick(&reason);
+                *kick = Some(reason);
+                return Ok(());
+            }
+            if packet.packet_id == chat
+                && let Ok(message) = read_component(&packet.payload, self.protocol)
+                && is_auth_prompt(&message)
+            {
+                details.auth_prompt = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Continues a login that just received `LoginFinished` into the play state,
+/// filling `details` and `kick` (the disconnect reason, if kicked) as it goes.
+/// Stops after [`DEEP_LOGIN_TIMEOUT`], or at `deadline` if that comes first.
+/// Best-effort: a stall or protocol error keeps whatever was learned so far.
+pub async fn continue_login(
+    conn: &mut TcpStream,
+    protocol: i32,
+    threshold: Option<i32>,
+    deadline: Instant,
+    details: &mut LoginDetails,
+    kick: &mut Option<Value>,
+) {
+    let mut session = Session {
+        conn,
+        protocol,
+        threshold,
+    };
+    let deadline = deadline.min(Instant::now() + DEEP_LOGIN_TIMEOUT);
+    let _ = timeout_at(deadline, session.run(details, kick)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::{
+        login_pool::LoginIdentity, server_actions::with_connection::get_extra_data,
+        timeouts::ProbeTimeouts,
+    };
+
+    /// Encodes `{"text": text}` as a nameless network NBT compound.
+    fn nbt_text(text: &str) -> Vec<u8> {
+        let mut out = vec![10, 8];
+        out.extend(4u16.to_be_bytes());
+        out.extend(b"text");
+        out.extend((text.len() as u16).to_be_bytes());
+        out.extend(text.as_bytes());
+        out.push(0);
+        out
+    }
+
+    #[test]
+    fn nbt_compounds_lists_and_numbers_decode() {
+        let mut bytes = vec![10];
+        // "extra": [ "a", "b" ]
+        bytes.extend([9, 0, 5]);
+        bytes.extend(b"extra");
+        bytes.extend([8, 0, 0, 0, 2, 0, 1, b'a', 0, 1, b'b']);
+        // "bold": 1b
+        bytes.extend([1, 0, 4]);
+        bytes.extend(b"bold");
+        bytes.push(1);
+        // "n": int array [7]
+        bytes.extend([11, 0, 1, b'n', 0, 0, 0, 1, 0, 0, 0, 7]);
+        bytes.push(0);
+        let value = nbt_to_json(&mut Cursor::new(&bytes[..])).unwrap();
+        assert_eq!(value, json!({"extra": ["a", "b"], "bold": 1, "n": [7]}));
+    }
+
+    #[test]
+    fn nbt_rejects_truncated_and_oversized_input() {
+        let full = nbt_text("hello");
+        assert!(nbt_to_json(&mut Cursor::new(&full[..full.len() - 3])).is_err());
+        // A list claiming 2^31-1 strings in a handful of bytes.
+        let huge = [9, 8, 0x7F, 0xFF, 0xFF, 0xFF];
+        assert!(nbt_to_json(&mut Cursor::new(&huge[..])).is_err());
+        assert!(nbt_to_json(&mut Cursor::new(&[13u8][..])).is_err());
+    }
+
+    #[test]
+    fn plain_text_flattens_extra_and_with() {
+        let component = json!({
+            "text": "Please ",
+            "extra": [{"text": "/login", "color": "red"}, " <password>"],
+        });
+        assert_eq!(plain_text(&component), "Please /login <password>");
+        assert_eq!(plain_text(&json!({"": "wrapped"})), "wrapped");
+        assert!(is_auth_prompt(&component));
+        assert!(is_auth_prompt(&json!("§cUse /REGISTER <pw> <pw>")));
+        assert!(!is_auth_prompt(&json!("Welcome back!")));
+    }
+
+    #[test]
+    fn whitelist_kicks_are_recognised() {
+        assert!(is_whitelist_kick(
+            &json!({"translate": NOT_WHITELISTED_KEY})
+        ));
+        assert!(is_whitelist_kick(&json!(
+            "You are not white-listed on this server!"
+        )));
+        assert!(is_whitelist_kick(
+            &json!({"text": "", "extra": ["Not on the white list"]})
+        ));
+        assert!(!is_whitelist_kick(
+            &json!({"translate": "multiplayer.disconnect.banned"})
+        ));
+    }
+
+    /// A fake offline-mode server: reads the handshake and login start, then
+    /// plays `script` (packets to send, with the number of client packets to
+    /// read first).
+    async fn fake_login_server(script: Vec<(usize, UncompressedPacket)>) -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            RawPacket::read_async(&mut stream).await?; // handshake
+            RawPacket::read_async(&mut stream).await?; // login start
+            for (reads, packet) in script {
+                for _ in 0..reads {
+                    RawPacket::read_async(&mut stream).await?;
+                }
+                packet.write_async(&mut stream).await?;
+            }
+            // Hold the connection open until the client leaves.
+            let _ = RawPacket::read_async(&mut stream).await;
+            anyhow::Ok(())
+        });
+        port
+    }
+
+    fn finished() -> UncompressedPacket {
+        let mut payload = Vec::new();
+        Uuid::nil().serialize(&mut payload).unwrap();
+        "Notch".to_string().serialize(&mut payload).unwrap();
+        UncompressedPacket::new(s2c::LoginFinished::PACKET_ID, payload)
+    }
+
+    #[tokio::test]
+    async fn configuration_and_auth_prompt_on_1_20_4() {
+        let ids = config_ids(765);
+        let (_, chat) = play_ids(765).unwrap();
+        let mut chat_payload = nbt_text("Please /login <password>");
+        chat_payload.push(0); // overlay
+        let port = fake_login_server(vec![
+            (0, finished()),
+            // Reads the login acknowledgement before configuring.
+            (
+                1,
+                UncompressedPacket::new(ids.keep_alive, 42i64.to_be_bytes().to_vec()),
+            ),
+            // Reads the keep alive reply.
+            (1, UncompressedPacket::new(ids.finish, Vec::new())),
+            // Reads the finish acknowledgement; then join game and the prompt.
+            (1, UncompressedPacket::new(0x29, vec![0; 16])),
+            (0, UncompressedPacket::new(chat, chat_payload)),
+        ])
+        .await;
+
+        let notch = LoginIdentity::offline("Notch");
+        let t = ProbeTimeouts::default();
+        let extra = get_extra_data(
+            "127.0.0.1".into(),
+            "127.0.0.1".into(),
+            port,
+            765,
+            notch,
+            Some(Instant::now() + t.total),
+            &t,
+        )
+        .await
+        .unwrap();
+        assert!(!extra.is_online_mode);
+        let login = extra.login.unwrap();
+        assert!(login.reached_play);
+        assert!(login.auth_prompt);
+        assert!(!login.whitelist_kick);
+    }
+
+    #[tokio::test]
+    async fn forge_handshake_and_whitelist_kick_on_1_20_1() {
+        let mut request = Vec::new();
+        VarInt(7).serialize(&mut request).unwrap();
+        "fml:loginwrapper"
+            .to_string()
+            .serialize(&mut request)
+            .unwrap();
+        let (disconnect, _) = play_ids(763).unwrap();
+        let mut reason = Vec::new();
+        json!({"translate": NOT_WHITELISTED_KEY})
+            .to_string()
+            .serialize(&mut reason)
+            .unwrap();
+        let port = fake_login_server(vec![
+            (
+                0,
+                UncompressedPacket::new(s2c::LoginPluginRequest::PACKET_ID, request),
+            ),
+            // Reads the plugin response.
+            (1, finished()),
+            (0, UncompressedPacket::new(disconnect, reason)),
+        ])
+        .await;
+
+        let notch = LoginIdentity::offline("Notch");
+        let t = ProbeTimeouts::default();
+        let extra = get_extra_data(
+            "127.0.0.1".into(),
+            "127.0.0.1".into(),
+            port,
+            763,
+            notch,
+            Some(Instant::now() + t.total),
+            &t,
+        )
+        .await
+        .unwrap();
+        let login = extra.login.unwrap();
+        assert!(login.mod_handshake);
+        assert!(login.whitelist_kick);
+        assert!(!login.reached_play);
+        assert!(extra.disconnect_reason.is_some());
+    }
+
+    #[tokio::test]
+    async fn stalled_continuation_stops_at_the_probe_deadline() {
+        // Finishes the login, then never sends a play packet.
+        let port = fake_login_server(vec![(0, finished())]).await;
+
+        let notch = LoginIdentity::offline("Notch");
+        let t = ProbeTimeouts::default();
+        let started = Instant::now();
+        let extra = get_extra_data(
+            "127.0.0.1".into(),
+            "127.0.0.1".into(),
+            port,
+            763,
+            notch,
+            Some(started + Duration::from_millis(100)),
+            &t,
+        )
+        .await
+        .unwrap();
+        assert!(started.elapsed() < DEEP_LOGIN_TIMEOUT);
+        assert!(!extra.is_online_mode);
+        assert!(!extra.login.unwrap().reached_play);
+    }
+}
@@ -1,2 +1,5 @@
+pub mod bedrock;
+pub mod login;
+pub mod query;
 pub mod with_connection;
 pub mod without_connection;
@@ -0,0 +1,310 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail};
+use tokio::net::UdpSocket;
+
+use crate::timeouts::ProbeTimeouts;
+
+/// Every client packet starts with this magic.
+const QUERY_MAGIC: [u8; 2] = [0xFE, 0xFD];
+const TYPE_HANDSHAKE: u8 = 0x09;
+const TYPE_STAT: u8 = 0x00;
+/// Only the low nibble of each session id byte is echoed back.
+const SESSION_ID_MASK: i32 = 0x0F0F_0F0F;
+/// Constant padding after the full stat's type + session id (`splitnum\0\x80\0`).
+const KV_PADDING: &[u8] = b"splitnum\0\x80\0";
+/// Constant padding between the key/value section and the player list.
+const PLAYER_PADDING: &[u8] = b"\x01player_\0\0";
+
+/// Everything the full stat reports beyond the status ping.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryStat {
+    /// Server software from the `plugins` key (`"Paper on 1.20.4"`); `None` for
+    /// vanilla, which leaves the key empty.
+    pub software: Option<String>,
+    pub plugins: Vec<String>,
+    pub map: Option<String>,
+    /// Every online player, not just the status ping's capped sample.
+    pub players: Vec<String>,
+}
+
+/// GameSpy4 "Query" full stat: a handshake for a challenge token, then a full
+/// stat request carrying it. Queries the given port, which is the game port
+/// unless the operator moved `query.port`. Each datagram takes a packet token
+/// from the limiter, if `timeouts` is metered. Each answer gets the read
+/// budget: a server without `enable-query=true` never answers, so that is what
+/// bounds the (common) disabled case.
+pub async fn get_query_stat(
+    ip: &str,
+    port: u16,
+    timeouts: &ProbeTimeouts,
+) -> anyhow::Result<QueryStat> {
+    let socket = UdpSocket::bind(worker::udp_bind_addr(ip)).await?;
+    socket.connect(worker::host_port(ip, port)).await?;
+
+    let session_id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("SystemTime before UNIX_EPOCH")
+        .subsec_nanos() as i32
+        & SESSION_ID_MASK;
+    // Full stat responses carry every player name; allow a maximal datagram.
+    let mut buf = vec![0u8; 65_535];
+
+    timeouts.packet().await;
+    socket.send(&handshake_request(session_id)).await?;
+    let len = timeouts.read(socket.recv(&mut buf)).await?;
+    let token = parse_handshake(&buf[..len], session_id)?;
+
+    timeouts.packet().await;
+    socket.send(&full_stat_request(session_id, token)).await?;
+    let len = timeouts.read(socket.recv(&mut buf)).await?;
+    parse_full_stat(&buf[..len], session_id)
+}
+
+fn handshake_request(session_id: i32) -> Vec<u8> {
+    let mut out = QUERY_MAGIC.to_vec();
+    out.push(TYPE_HANDSHAKE);
+    out.extend(session_id.to_be_bytes());
+    out
+}
+
+/// A stat request padded to 4 extra bytes is a *full* stat (a basic stat has
+/// none).
+fn full_stat_request(session_id: i32, token: i32) -> Vec<u8> {
+    let mut out = QUERY_MAGIC.to_vec();
+    out.push(TYPE_STAT);
+    out.extend(session_id.to_be_bytes());
+    out.extend(token.to_be_bytes());
+    out.extend([0u8; 4]);
+    out
+}
+
+/// Checks the type + session id header shared by every server packet and
+/// returns the payload after it.
+fn strip_header(bytes: &[u8], ty: u8, session_id: i32) -> anyhow::Result<&[u8]> {
+    let (header, payload) = bytes
+        .split_first_chunk::<5>()
+        .ok_or_else(|| anyhow!("truncated query response"))?;
+    if header[0] != ty {
+        bail!("unexpected query packet type 0x{:02x}", header[0]);
+    }
+    if i32::from_be_bytes([header[1], header[2], header[3], header[4]]) != session_id {
+        bail!("query response for another session");
+    }
+    Ok(payload)
+}
+
+/// The challenge token comes back as a NUL-terminated decimal string.
+fn parse_handshake(bytes: &[u8], session_id: i32) -> anyhow::Result<i32> {
+    let payload = strip_header(bytes, TYPE_HANDSHAKE, session_id)?;
+    let token = payload.split(|&b| b == 0).next().unwrap_or_default();
+    std::str::from_utf8(token)?
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("invalid query challenge token"))
+}
+
+/// Parses a full stat: padding, NUL-separated key/value pairs ending in an
+/// empty key, padding, then NUL-separated player names ending in an empty one.
+fn parse_full_stat(bytes: &[u8], session_id: i32) -> anyhow::Result<QueryStat> {
+    let payload = strip_header(bytes, TYPE_STAT, session_id)?;
+    let mut rest = payload
+        .strip_prefix(KV_PADDING)
+        .ok_or_else(|| anyhow!("malformed query full stat"))?;
+    let truncated = || anyhow!("truncated query full stat");
+
+    let mut stat = QueryStat::default();
+    loop {
+        let key = take_string(&mut rest).ok_or_else(truncated)?;
+        if key.is_empty() {
+            break;
+        }
+        let value = take_string(&mut rest).ok_or_else(truncated)?;
+        match key.as_str() {
+            "plugins" => (stat.software, stat.plugins) = parse_plugins(&value),
+            "map" => stat.map = Some(value).filter(|m| !m.is_empty()),
+            _ => {}
+        }
+    }
+
+    rest = rest
+        .strip_prefix(PLAYER_PADDING)
+        .ok_or_else(|| anyhow!("malformed query full stat"))?;
+    // Some servers omit the final terminator; a trailing unterminated name is
+    // still a name.
+    while !rest.is_empty() {
+        let name = take_string(&mut rest).unwrap_or_else(|| latin1(std::mem::take(&mut rest)));
+        if name.is_empty() {
+            break;
+        }
+        stat.players.push(name);
+    }
+    Ok(stat)
+}
+
+/// Takes one NUL-terminated string off the front of `rest`, or `None` when no
+/// terminator is left.
+fn take_string(rest: &mut &[u8]) -> Option<String> {
+    let end = rest.iter().position(|&b| b == 0)?;
+    let s = latin1(&rest[..end]);
+    *rest = &rest[end + 1..];
+    Some(s)
+}
+
+/// Splits `"<software>: <plugin>; <plugin>"`. Vanilla sends an empty value; a
+/// server that hides its plugins sends the software alone.
+fn parse_plugins(value: &str) -> (Option<String>, Vec<String>) {
+    let (software, plugins) = match value.split_once(':') {
+        Some((software, plugins)) => (software, plugins),
+        None => (value, ""),
+    };
+    let software = Some(software.trim().to_string()).filter(|s| !s.is_empty());
+    let plugins = plugins
+        .split(';')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+    (software, plugins)
+}
+
+/// Query strings are ISO-8859-1, which maps byte-for-byte onto the first 256
+/// code points.
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: i32 = 0x0102_0304;
+
+    /// Encodes the full stat a server sends for the given pairs and players.
+    fn full_stat(kv: &[(&str, &str)], players: &[&str]) -> Vec<u8> {
+        let mut out = vec![TYPE_STAT];
+        out.extend(SESSION.to_be_bytes());
+        out.extend(KV_PADDING);
+        for (k, v) in kv {
+            out.extend(k.as_bytes());
+            out.push(0);
+            out.extend(v.as_bytes());
+            out.push(0);
+        }
+        out.push(0);
+        out.extend(PLAYER_PADDING);
+        for p in players {
+            out.extend(p.as_bytes());
+            out.push(0);
+        }
+        out.push(0);
+        out
+    }
+
+    fn handshake_response(token: &str) -> Vec<u8> {
+        let mut out = vec![TYPE_HANDSHAKE];
+        out.extend(SESSION.to_be_bytes());
+        out.extend(token.as_bytes());
+        out.push(0);
+        out
+    }
+
+    const BUKKIT_KV: &[(&str, &str)] = &[
+        ("hostname", "A Minecraft Server"),
+        ("gametype", "SMP"),
+        ("game_id", "MINECRAFT"),
+        ("version", "1.20.4"),
+        (
+            "plugins",
+            "Paper on 1.20.4: WorldEdit 7.2.15; LuckPerms 5.4.102",
+        ),
+        ("map", "world"),
+        ("numplayers", "2"),
+        ("maxplayers", "20"),
+    ];
+
+    #[test]
+    fn full_stat_parses_plugins_map_and_every_player() {
+        let stat = parse_full_stat(&full_stat(BUKKIT_KV, &["Notch", "jeb_"]), SESSION).unwrap();
+        assert_eq!(
+            stat,
+            QueryStat {
+                software: Some("Paper on 1.20.4".into()),
+                plugins: vec!["WorldEdit 7.2.15".into(), "LuckPerms 5.4.102".into()],
+                map: Some("world".into()),
+                players: vec!["Notch".into(), "jeb_".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn vanilla_full_stat_has_no_software_or_plugins() {
+        let kv = [("plugins", ""), ("map", "world")];
+        let stat = parse_full_stat(&full_stat(&kv, &[]), SESSION).unwrap();
+        assert_eq!(stat.software, None);
+        assert!(stat.plugins.is_empty());
+        assert!(stat.players.is_empty());
+    }
+
+    #[test]
+    fn hidden_plugin_list_keeps_software() {
+        assert_eq!(
+            parse_plugins("CraftBukkit on Bukkit 1.2.5-R4.0"),
+            (Some("CraftBukkit on Bukkit 1.2.5-R4.0".into()), Vec::new())
+        );
+    }
+
+    #[test]
+    fn rejects_foreign_sessions_and_malformed_packets() {
+        let good = full_stat(BUKKIT_KV, &["Notch"]);
+        assert!(parse_full_stat(&good, SESSION + 1).is_err());
+        assert!(parse_full_stat(&good[..12], SESSION).is_err());
+        assert!(parse_full_stat(&good[..40], SESSION).is_err());
+        assert!(parse_handshake(&handshake_response("abc"), SESSION).is_err());
+        assert_eq!(
+            parse_handshake(&handshake_response("9513307"), SESSION).unwrap(),
+            9513307
+        );
+    }
+
+    #[test]
+    fn latin1_names_decode() {
+        let mut bytes = full_stat(&[], &[]);
+        // Replace the empty player list with "Jürgen" in ISO-8859-1.
+        bytes.truncate(bytes.len() - 1);
+        bytes.extend(b"J\xFCrgen\0\0");
+        let stat = parse_full_stat(&bytes, SESSION).unwrap();
+        assert_eq!(stat.players, vec!["Jürgen".to_string()]);
+    }
+
+    /// End to end against a fake query listener on loopback.
+    #[tokio::test]
+    async fn queries_a_local_responder() {
+        let responder = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = responder.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            let (len, from) = responder.recv_from(&mut buf).await.unwrap();
+            assert_eq!(&buf[..3], &[0xFE, 0xFD, TYPE_HANDSHAKE]);
+            assert_eq!(len, 7);
+            let session = i32::from_be_bytes([buf[3], buf[4], buf[5], buf[6]]);
+            let mut reply = vec![TYPE_HANDSHAKE];
+            reply.extend(session.to_be_bytes());
+            reply.extend(b"-1234567\0");
+            responder.send_to(&reply, from).await.unwrap();
+
+            let (len, from) = responder.recv_from(&mut buf).await.unwrap();
+            assert_eq!(len, 15, "full stat requests carry 4 bytes of padding");
+            assert_eq!(&buf[7..11], &(-1234567i32).to_be_bytes());
+            let mut reply = full_stat(BUKKIT_KV, &["Notch"]);
+            reply[1..5].copy_from_slice(&session.to_be_bytes());
+            responder.send_to(&reply, from).await.unwrap();
+        });
+
+        let stat = get_query_stat("127.0.0.1", port, &ProbeTimeouts::default())
+            .await
+            .unwrap();
+        assert_eq!(stat.players, vec!["Notch".to_string()]);
+        assert_eq!(stat.map.as_deref(), Some("world"));
+    }
+}
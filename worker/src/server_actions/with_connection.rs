@@ -1,22 +1,47 @@
 use mc_protocol::prelude::*;
 use serde_json::Value;
-use tokio::net::TcpStream;
-use uuid::Uuid;
+use tokio::time::Instant;
 
+use crate::login_pool::LoginIdentity;
 use crate::packets::*;
+use crate::server_actions::login::{
+    LoginDetails, continue_login, is_mod_channel, is_whitelist_kick,
+};
+use crate::timeouts::{Phase, ProbeTimeouts};
 
 #[derive(Debug)]
 pub struct ExtraData {
     pub is_online_mode: bool,
     pub disconnect_reason: Option<Value>,
+    /// Set when `deep` was requested (see [`continue_login`]).
+    pub login: Option<LoginDetails>,
 }
 
-pub async fn get_extra_data(ip: String, port: u16, protocol: i32) -> anyhow::Result<ExtraData> {
-    let mut conn = TcpStream::connect(&format!("{}:{}", ip, port)).await?;
+/// Logs in as `identity`, an offline-mode player, to tell online from offline
+/// mode. With `deep` set, an offline-mode login is carried on into the play
+/// state and login plugin requests are answered instead of failing the probe.
+///
+/// The handshake announces `host`, as in [`get_status`]. Everything up to the
+/// online/offline verdict shares the handshake budget in `timeouts`; the deep
+/// continuation has its own, cut short at the `deep` deadline (the whole
+/// probe's).
+///
+/// [`get_status`]: crate::server_actions::without_connection::get_status
+pub async fn get_extra_data(
+    ip: String,
+    host: String,
+    port: u16,
+    protocol: i32,
+    identity: LoginIdentity,
+    deep: Option<Instant>,
+    timeouts: &ProbeTimeouts,
+) -> anyhow::Result<ExtraData> {
+    let deadline = Instant::now() + timeouts.handshake;
+    let mut conn = timeouts.connect(&ip, port).await?;
 
     let handshake = c2s::Handshake {
         protocol_version: VarInt(protocol),
-        server_address: ip,
+        server_address: host,
         server_port: port,
         intent: VarInt(2),
     };
@@ -26,8 +51,8 @@ pub async fn get_extra_data(ip: String, port: u16, protocol: i32) -> anyhow::Res
         .await?;
 
     let login_start = c2s::LoginStart {
-        name: "Notch".to_string(),
-        uuid: Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5),
+        name: identity.name,
+        uuid: identity.uuid,
     };
 
     login_start
@@ -36,30 +61,51 @@ pub async fn get_extra_data(ip: String, port: u16, protocol: i32) -> anyhow::Res
         .await?;
 
     let mut threshold = None;
+    let mut login = deep.map(|_| LoginDetails::default());
 
     loop {
-        let packet = RawPacket::read_async(&mut conn)
+        let packet = timeouts
+            .read_until(Phase::Handshake, deadline, RawPacket::read_async(&mut conn))
             .await?
             .uncompress(threshold)?;
 
         match packet.packet_id {
             s2c::LoginDisconnect::PACKET_ID => {
                 let reason: String = packet.deserialize_payload::<s2c::LoginDisconnect>()?.reason;
+                let reason = serde_json::from_str::<Value>(&reason)?;
+                if let Some(login) = &mut login {
+                    login.whitelist_kick = is_whitelist_kick(&reason);
+                }
                 return Ok(ExtraData {
                     is_online_mode: false,
-                    disconnect_reason: Some(serde_json::from_str::<Value>(&reason)?),
+                    disconnect_reason: Some(reason),
+                    login,
                 });
             }
             s2c::EncryptionRequest::PACKET_ID => {
                 return Ok(ExtraData {
                     is_online_mode: true,
                     disconnect_reason: None,
+                    login,
                 });
             }
             s2c::LoginFinished::PACKET_ID => {
+                let mut disconnect_reason = None;
+                if let (Some(login), Some(deadline)) = (&mut login, deep) {
+                    continue_login(
+                        &mut conn,
+                        protocol,
+                        threshold,
+                        deadline,
+                        login,
+                        &mut disconnect_reason,
+                    )
+                    .await;
+                }
                 return Ok(ExtraData {
                     is_online_mode: false,
-                    disconnect_reason: None,
+                    disconnect_reason,
+                    login,
                 });
             }
             s2c::SetCompression::PACKET_ID => {
@@ -70,6 +116,20 @@ pub async fn get_extra_data(ip: String, port: u16, protocol: i32) -> anyhow::Res
                         .0,
                 );
             }
+            s2c::LoginPluginRequest::PACKET_ID if deep.is_some() => {
+                let request = packet.deserialize_payload::<s2c::LoginPluginRequest>()?;
+                if let Some(login) = &mut login {
+                    login.mod_handshake |= is_mod_channel(&request.channel);
+                }
+                let response = c2s::LoginPluginResponse {
+                    message_id: request.message_id,
+                    successful: false,
+                };
+                UncompressedPacket::from_packet(&response)?
+                    .to_raw_packet_compressed(threshold)?
+                    .write_async(&mut conn)
+                    .await?;
+            }
             _ => {
                 return Err(anyhow::anyhow!("error"));
             }
@@ -1,11 +1,18 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use anyhow::{anyhow, bail};
 use mc_protocol::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tokio::net::TcpStream;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    task::JoinSet,
+    time::{Instant, timeout_at},
+};
 
 use crate::packets::*;
+use crate::timeouts::ProbeTimeouts;
 
 #[derive(Deserialize, Debug)]
 pub struct Status {
@@ -89,19 +96,59 @@ pub struct Version {
     pub protocol: i64,
 }
 
+/// Client protocol announced in the status handshake unless negotiating (1.20.4).
+pub const DEFAULT_STATUS_PROTOCOL: i32 = 765;
+
+/// Client protocols probed when negotiating: the last protocol of each release
+/// line from 1.8 on, newest first.
+pub const PROTOCOL_LADDER: [i32; 14] = [
+    772, // 1.21.7
+    769, // 1.21.4
+    767, // 1.21.1
+    765, // 1.20.4
+    763, // 1.20.1
+    762, // 1.19.4
+    758, // 1.18.2
+    756, // 1.17.1
+    754, // 1.16.5
+    578, // 1.15.2
+    498, // 1.14.4
+    404, // 1.13.2
+    340, // 1.12.2
+    47,  // 1.8.9
+];
+
+/// Ladder rungs [`accepted_protocols`] probes at once, each on its own
+/// connection, so one probe does not burst a connection per rung.
+const LADDER_FAN_OUT: usize = 4;
+
+/// When a ladder rung started now must be done: its own budget (a connect and
+/// a read), cut short by the `deadline` of the whole walk.
+fn rung_deadline(timeouts: &ProbeTimeouts, deadline: Instant) -> Instant {
+    deadline.min(Instant::now() + timeouts.connect + timeouts.read)
+}
+
+/// Status ping announcing `protocol` as the client version. Servers behind
+/// ViaVersion (and version-aware MOTD plugins) may answer differently
+/// depending on it. Connects to `ip` and announces `host` (the hostname the
+/// target was entered as, or `ip` itself) in the handshake, which virtual-host
+/// proxies route on. Connects and reads within `timeouts`.
 pub async fn get_status(
     ip: &str,
+    host: &str,
     port: u16,
     tcp_stream: Option<TcpStream>,
+    protocol: i32,
+    timeouts: &ProbeTimeouts,
 ) -> anyhow::Result<(Status, Option<i64>)> {
     let mut tcp_stream = match tcp_stream {
         Some(t) => t,
-        None => TcpStream::connect(&format!("{}:{}", ip, port)).await?,
+        None => timeouts.connect(ip, port).await?,
     };
 
     let handshake = c2s::Handshake {
-        protocol_version: VarInt(765),
-        server_address: ip.to_string(),
+        protocol_version: VarInt(protocol),
+        server_address: host.to_string(),
         server_port: port,
         intent: VarInt(1),
     };
@@ -116,7 +163,8 @@ pub async fn get_status(
         .write_async(&mut tcp_stream)
         .await?;
 
-    let response: s2c::StatusResponse = RawPacket::read_async(&mut tcp_stream)
+    let response: s2c::StatusResponse = timeouts
+        .read(RawPacket::read_async(&mut tcp_stream))
         .await?
         .as_uncompressed()?
         .deserialize_payload()?;
@@ -134,7 +182,11 @@ pub async fn get_status(
         .write_async(&mut tcp_stream)
         .await;
 
-    if RawPacket::read_async(&mut tcp_stream).await.is_ok() {
+    if timeouts
+        .read(RawPacket::read_async(&mut tcp_stream))
+        .await
+        .is_ok()
+    {
         let ping_ms = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("SystemTime before UNIX_EPOCH")
@@ -147,9 +199,208 @@ pub async fn get_status(
     }
 }
 
+/// Walks [`PROTOCOL_LADDER`] newest first and returns the first status any
+/// rung gets, for servers that rejected the default protocol outright. Gives
+/// up on the rungs left at `deadline`.
+pub async fn get_status_from_ladder(
+    ip: &str,
+    host: &str,
+    port: u16,
+    timeouts: &ProbeTimeouts,
+    deadline: Instant,
+) -> anyhow::Result<(Status, Option<i64>)> {
+    let mut last_err = anyhow!("empty protocol ladder");
+    for protocol in PROTOCOL_LADDER {
+        if Instant::now() >= deadline {
+            break;
+        }
+        let attempt = get_status(ip, host, port, None, protocol, timeouts);
+        match timeout_at(rung_deadline(timeouts, deadline), attempt).await {
+            Ok(Ok(res)) => return Ok(res),
+            Ok(Err(e)) => last_err = e,
+            Err(e) => last_err = e.into(),
+        }
+    }
+    Err(last_err)
+}
+
+/// Client protocols the server accepts, ascending. A status answered with the
+/// very protocol the client announced means the server (or its ViaVersion
+/// layer) speaks that version; otherwise it answers with its own. `native` —
+/// the protocol the server advertises for itself — is included unless it is
+/// not a valid protocol (≤ 0). Up to [`LADDER_FAN_OUT`] rungs are probed at
+/// once; those not answered by `deadline` count as not accepted.
+pub async fn accepted_protocols(
+    ip: &str,
+    host: &str,
+    port: u16,
+    native: i32,
+    timeouts: &ProbeTimeouts,
+    deadline: Instant,
+) -> Vec<i32> {
+    let mut rungs = PROTOCOL_LADDER.into_iter().filter(|&p| p != native);
+    let mut set = JoinSet::new();
+    let mut accepted: Vec<i32> = (native > 0).then_some(native).into_iter().collect();
+    loop {
+        while set.len() < LADDER_FAN_OUT
+            && Instant::now() < deadline
+            && let Some(protocol) = rungs.next()
+        {
+            let (ip, host) = (ip.to_string(), host.to_string());
+            let timeouts = timeouts.clone();
+            set.spawn(async move {
+                let res = timeout_at(
+                    rung_deadline(&timeouts, deadline),
+                    get_status(&ip, &host, port, None, protocol, &timeouts),
+                )
+                .await;
+                matches!(res, Ok(Ok((status, _))) if status.version.protocol == protocol as i64)
+                    .then_some(protocol)
+            });
+        }
+        match set.join_next().await {
+            Some(Ok(Some(protocol))) => accepted.push(protocol),
+            Some(_) => {}
+            None => break,
+        }
+    }
+    accepted.sort_unstable();
+    accepted
+}
+
+/// Packet id of the kick a pre-1.7 server answers the legacy ping with.
+const LEGACY_KICK_ID: u8 = 0xFF;
+/// Protocol announced in the 1.6 `MC|PingHost` extension (1.6.4). Servers
+/// older than 1.6 ignore the extension and answer the bare `0xFE 0x01`.
+const LEGACY_PING_PROTOCOL: u8 = 78;
+/// Version name reported for beta 1.8 – 1.3 servers, whose kick payload
+/// carries no version information at all.
+const LEGACY_UNKNOWN_VERSION: &str = "pre-1.4";
+
+/// Legacy (pre-1.7) server list ping: `0xFE 0x01` plus the 1.6 `MC|PingHost`
+/// extension, answered by a `0xFF` kick whose reason encodes the status.
+/// Always opens its own connection: it is the fallback for servers that did not
+/// understand the modern handshake, which has already consumed the first one.
+/// The returned ping is the round trip of the exchange.
+pub async fn get_legacy_status(
+    ip: &str,
+    host: &str,
+    port: u16,
+    timeouts: &ProbeTimeouts,
+) -> anyhow::Result<(Status, Option<i64>)> {
+    let mut tcp_stream = timeouts.connect(ip, port).await?;
+
+    let started = Instant::now();
+    tcp_stream
+        .write_all(&legacy_ping_request(host, port))
+        .await?;
+
+    let mut header = [0u8; 3];
+    timeouts.read(tcp_stream.read_exact(&mut header)).await?;
+    let len = u16::from_be_bytes([header[1], header[2]]) as usize;
+    let mut response = header.to_vec();
+    response.resize(3 + len * 2, 0);
+    timeouts
+        .read(tcp_stream.read_exact(&mut response[3..]))
+        .await?;
+    let ping_ms = started.elapsed().as_millis() as i64;
+
+    Ok((parse_legacy_response(&response)?, Some(ping_ms)))
+}
+
+/// Encodes a UTF-16BE string the way the legacy protocol expects it on the
+/// wire (the `u16` length prefix is written separately).
+fn utf16_be(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+/// Builds `0xFE 0x01` followed by the 1.6 `0xFA "MC|PingHost"` plugin message
+/// carrying the protocol, host and port the client connected to.
+fn legacy_ping_request(host: &str, port: u16) -> Vec<u8> {
+    let channel = "MC|PingHost";
+    let host_len = host.encode_utf16().count();
+
+    let mut out = vec![0xFE, 0x01, 0xFA];
+    out.extend((channel.encode_utf16().count() as u16).to_be_bytes());
+    out.extend(utf16_be(channel));
+    // protocol (1) + host length (2) + host + port (4)
+    out.extend(((7 + host_len * 2) as u16).to_be_bytes());
+    out.push(LEGACY_PING_PROTOCOL);
+    out.extend((host_len as u16).to_be_bytes());
+    out.extend(utf16_be(host));
+    out.extend((port as i32).to_be_bytes());
+    out
+}
+
+/// Parses a complete legacy kick packet (`0xFF`, `u16` length in UTF-16 code
+/// units, UTF-16BE reason) into the same [`Status`] shape the modern ping
+/// produces.
+pub fn parse_legacy_response(bytes: &[u8]) -> anyhow::Result<Status> {
+    let (&id, rest) = bytes
+        .split_first()
+        .ok_or_else(|| anyhow!("empty legacy ping response"))?;
+    if id != LEGACY_KICK_ID {
+        bail!("unexpected legacy ping packet id 0x{id:02x}");
+    }
+    let (len, payload) = rest
+        .split_first_chunk::<2>()
+        .ok_or_else(|| anyhow!("truncated legacy ping response"))?;
+    let payload = payload
+        .get(..u16::from_be_bytes(*len) as usize * 2)
+        .ok_or_else(|| anyhow!("truncated legacy ping response"))?;
+    let units: Vec<u16> = payload
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect();
+    parse_legacy_kick(&String::from_utf16(&units)?)
+}
+
+/// Parses the kick reason. 1.4 – 1.6 answer `§1\0protocol\0version\0motd\0online\0max`;
+/// beta 1.8 – 1.3 answer `motd§online§max` (split from the right, as the MOTD
+/// may itself contain `§` colour codes).
+fn parse_legacy_kick(reason: &str) -> anyhow::Result<Status> {
+    let bad = || anyhow!("malformed legacy ping response: {reason:?}");
+
+    let (version, motd, online, max) = if let Some(fields) = reason.strip_prefix("§1\0") {
+        let [protocol, name, motd, online, max] = fields.split('\0').collect::<Vec<_>>()[..] else {
+            return Err(bad());
+        };
+        let version = Version {
+            name: name.to_string(),
+            protocol: protocol.parse().map_err(|_| bad())?,
+        };
+        (version, motd, online, max)
+    } else {
+        let mut fields = reason.rsplitn(3, '§');
+        let (Some(max), Some(online), Some(motd)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(bad());
+        };
+        let version = Version {
+            name: LEGACY_UNKNOWN_VERSION.to_string(),
+            protocol: 0,
+        };
+        (version, motd, online, max)
+    };
+
+    Ok(Status {
+        players: Players {
+            online: online.parse().map_err(|_| bad())?,
+            max: max.parse().map_err(|_| bad())?,
+            sample: None,
+        },
+        version,
+        description: Value::String(motd.to_string()),
+        forge_data: None,
+        modinfo: None,
+        favicon: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Status;
+    use super::{Status, parse_legacy_response};
+    use crate::timeouts::ProbeTimeouts;
     use serde_json::json;
 
     /// Builds a `Status` from a `version.name` and an optional raw status body
@@ -227,6 +478,191 @@ mod tests {
         assert!(!s.requires_mods());
     }
 
+    /// `0xFF` kick captured from a vanilla 1.6.4 server:
+    /// `§1\0 78 \0 1.6.4 \0 A Minecraft Server \0 3 \0 20`.
+    const LEGACY_1_6_4: [u8; 73] = [
+        0xff, 0x00, 0x23, 0x00, 0xa7, 0x00, 0x31, 0x00, 0x00, 0x00, 0x37, 0x00, 0x38, 0x00, 0x00,
+        0x00, 0x31, 0x00, 0x2e, 0x00, 0x36, 0x00, 0x2e, 0x00, 0x34, 0x00, 0x00, 0x00, 0x41, 0x00,
+        0x20, 0x00, 0x4d, 0x00, 0x69, 0x00, 0x6e, 0x00, 0x65, 0x00, 0x63, 0x00, 0x72, 0x00, 0x61,
+        0x00, 0x66, 0x00, 0x74, 0x00, 0x20, 0x00, 0x53, 0x00, 0x65, 0x00, 0x72, 0x00, 0x76, 0x00,
+        0x65, 0x00, 0x72, 0x00, 0x00, 0x00, 0x33, 0x00, 0x00, 0x00, 0x32, 0x00, 0x30,
+    ];
+
+    /// `0xFF` kick captured from a beta 1.8 server: `A Beta Server§5§20`.
+    const LEGACY_BETA_1_8: [u8; 39] = [
+        0xff, 0x00, 0x12, 0x00, 0x41, 0x00, 0x20, 0x00, 0x42, 0x00, 0x65, 0x00, 0x74, 0x00, 0x61,
+        0x00, 0x20, 0x00, 0x53, 0x00, 0x65, 0x00, 0x72, 0x00, 0x76, 0x00, 0x65, 0x00, 0x72, 0x00,
+        0xa7, 0x00, 0x35, 0x00, 0xa7, 0x00, 0x32, 0x00, 0x30,
+    ];
+
+    #[test]
+    fn legacy_1_6_response_parses_all_fields() {
+        let s = parse_legacy_response(&LEGACY_1_6_4).expect("valid 1.6 kick");
+        assert_eq!(s.version.name, "1.6.4");
+        assert_eq!(s.version.protocol, 78);
+        assert_eq!(s.description, json!("A Minecraft Server"));
+        assert_eq!((s.players.online, s.players.max), (3, 20));
+        assert!(s.players.sample.is_none());
+        assert!(!s.requires_mods());
+    }
+
+    #[test]
+    fn legacy_beta_response_parses_without_version() {
+        let s = parse_legacy_response(&LEGACY_BETA_1_8).expect("valid beta kick");
+        assert_eq!(s.version.name, super::LEGACY_UNKNOWN_VERSION);
+        assert_eq!(s.description, json!("A Beta Server"));
+        assert_eq!((s.players.online, s.players.max), (5, 20));
+    }
+
+    #[test]
+    fn legacy_response_rejects_truncated_and_foreign_packets() {
+        assert!(parse_legacy_response(&LEGACY_1_6_4[..40]).is_err());
+        assert!(parse_legacy_response(&[]).is_err());
+        let mut wrong_id = LEGACY_1_6_4;
+        wrong_id[0] = 0x00;
+        assert!(parse_legacy_response(&wrong_id).is_err());
+    }
+
+    #[test]
+    fn legacy_ping_request_carries_ping_host() {
+        let req = super::legacy_ping_request("a.b", 25565);
+        assert_eq!(&req[..3], &[0xFE, 0x01, 0xFA]);
+        // channel: 11 UTF-16 units of "MC|PingHost"
+        assert_eq!(&req[3..5], &[0x00, 0x0B]);
+        // remaining length: 7 + 2 * len("a.b")
+        assert_eq!(&req[27..29], &[0x00, 13]);
+        assert_eq!(req[29], super::LEGACY_PING_PROTOCOL);
+        assert_eq!(&req[req.len() - 4..], &25565i32.to_be_bytes());
+    }
+
+    /// Fake status server on loopback. `answer` maps the protocol the client
+    /// announced to the protocol the server reports back, or `None` to drop
+    /// the connection the way servers that reject a protocol do.
+    async fn fake_status_server(answer: fn(i32) -> Option<i32>) -> u16 {
+        use crate::packets::{c2s, s2c};
+        use mc_protocol::prelude::*;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let handshake: c2s::Handshake = RawPacket::read_async(&mut stream)
+                        .await?
+                        .as_uncompressed()?
+                        .deserialize_payload()?;
+                    RawPacket::read_async(&mut stream).await?; // status request
+                    let Some(protocol) = answer(handshake.protocol_version.0) else {
+                        return Ok(());
+                    };
+                    let response = json!({
+                        "version": { "name": "fake", "protocol": protocol },
+                        "players": { "online": 0, "max": 20 },
+                        "description": "fake",
+                    })
+                    .to_string();
+                    UncompressedPacket::from_packet(&s2c::StatusResponse { response })?
+                        .write_async(&mut stream)
+                        .await?;
+                    anyhow::Ok(())
+                });
+            }
+        });
+        port
+    }
+
+    fn in_10s() -> tokio::time::Instant {
+        tokio::time::Instant::now() + std::time::Duration::from_secs(10)
+    }
+
+    #[tokio::test]
+    async fn accepted_protocols_follow_via_echo() {
+        // 1.20.4 server with ViaVersion + ViaBackwards down to 1.12.
+        let port =
+            fake_status_server(|p| Some(if (340..=765).contains(&p) { p } else { 765 })).await;
+        let accepted = super::accepted_protocols(
+            "127.0.0.1",
+            "127.0.0.1",
+            port,
+            765,
+            &ProbeTimeouts::default(),
+            in_10s(),
+        )
+        .await;
+        let expected: Vec<i32> = super::PROTOCOL_LADDER
+            .into_iter()
+            .rev()
+            .filter(|p| (340..=765).contains(p))
+            .collect();
+        assert_eq!(accepted, expected);
+    }
+
+    #[tokio::test]
+    async fn vanilla_server_accepts_only_its_native_protocol() {
+        let port = fake_status_server(|_| Some(763)).await;
+        let t = ProbeTimeouts::default();
+        let accepted = |native| {
+            super::accepted_protocols("127.0.0.1", "127.0.0.1", port, native, &t, in_10s())
+        };
+        assert_eq!(accepted(763).await, vec![763]);
+        // A bogus advertised protocol is not reported as accepted.
+        assert_eq!(accepted(-1).await, vec![763]);
+    }
+
+    #[tokio::test]
+    async fn ladder_finds_a_protocol_the_server_answers() {
+        // Drops every client newer than 1.19.4.
+        let port = fake_status_server(|p| (p <= 762).then_some(762)).await;
+        assert!(
+            super::get_status(
+                "127.0.0.1",
+                "127.0.0.1",
+                port,
+                None,
+                super::DEFAULT_STATUS_PROTOCOL,
+                &ProbeTimeouts::default()
+            )
+            .await
+            .is_err()
+        );
+        let (status, _) = super::get_status_from_ladder(
+            "127.0.0.1",
+            "127.0.0.1",
+            port,
+            &ProbeTimeouts::default(),
+            in_10s(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status.version.protocol, 762);
+    }
+
+    #[tokio::test]
+    async fn ladder_stops_at_its_deadline() {
+        // Accepts connections and never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+        let started = tokio::time::Instant::now();
+        let deadline = started + std::time::Duration::from_millis(200);
+        let t = ProbeTimeouts::default();
+        assert!(
+            super::get_status_from_ladder("127.0.0.1", "127.0.0.1", port, &t, deadline)
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            super::accepted_protocols("127.0.0.1", "127.0.0.1", port, 763, &t, deadline).await,
+            vec![763]
+        );
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
     /// Live probe — pings a real server and dumps the fields the detector reads,
     /// so we can see *why* it gets flagged. Ignored by default (needs network).
     /// Run with: `cargo test -p worker probe_real_server -- --ignored --nocapture`
@@ -236,9 +672,16 @@ mod tests {
         let ip = "";
         let port = 25565;
 
-        let (status, ping) = super::get_status(ip, port, None)
-            .await
-            .expect("status ping failed");
+        let (status, ping) = super::get_status(
+            ip,
+            ip,
+            port,
+            None,
+            super::DEFAULT_STATUS_PROTOCOL,
+            &ProbeTimeouts::default(),
+        )
+        .await
+        .expect("status ping failed");
 
         eprintln!("=== {ip}:{port} (ping {ping:?}ms) ===");
         eprintln!("version.name     = {:?}", status.version.name);
@@ -0,0 +1,352 @@
+//! Per-phase probe budgets. `[worker]` (and the gRPC `WorkerConfig`) carries a
+//! connect, read, login handshake and total timeout in milliseconds; they are
+//! applied where each phase runs (see [`crate::report::probe`]). Failed probes
+//! are classified by [`FailureCounts`] so the heartbeat can tell a slow link
+//! (timeouts) from filtered ports (refusals) and middleboxes (resets).
+//!
+//! The engine hands probes its budgets [`ProbeTimeouts::metered`] by its rate
+//! limiter, so every TCP connection a probe opens waits for a connection token
+//! and every datagram it sends for a packet token.
+
+use std::{
+    fmt, io,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::{
+    net::TcpStream,
+    time::{Instant, error::Elapsed, timeout, timeout_at},
+};
+
+use crate::ratelimit::RateLimiter;
+
+/// TCP connect budget, also the wait for a Bedrock pong. Short by default: the
+/// random search connects to mostly dead addresses, and every pending connect
+/// holds a search thread.
+pub const DEFAULT_CONNECT_TIMEOUT_MS: u32 = 750;
+/// Budget for any single read (one packet, one legacy kick, one query answer).
+pub const DEFAULT_READ_TIMEOUT_MS: u32 = 3000;
+/// Budget for the login handshake, from connect to the online/offline verdict.
+/// The deep login continuation has its own budget on top.
+pub const DEFAULT_HANDSHAKE_TIMEOUT_MS: u32 = 5000;
+/// Budget for a whole Java probe (status, negotiation, login handshake).
+pub const DEFAULT_PROBE_TIMEOUT_MS: u32 = 10_000;
+
+/// Upper bound on any configured timeout. Backend-supplied config is untrusted;
+/// an absurd value would pin update slots (like `MAX_UPDATE_CONCURRENCY`).
+pub const MAX_TIMEOUT_MS: u32 = 120_000;
+
+/// The probe phase a timeout fired in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Connect,
+    Read,
+    Handshake,
+    Total,
+}
+
+/// Error for a phase that ran out of time.
+#[derive(Debug)]
+pub struct TimedOut(pub Phase);
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = match self.0 {
+            Phase::Connect => "connect",
+            Phase::Read => "read",
+            Phase::Handshake => "login handshake",
+            Phase::Total => "probe",
+        };
+        write!(f, "{phase} timed out")
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+#[derive(Debug, Clone)]
+pub struct ProbeTimeouts {
+    pub connect: Duration,
+    pub read: Duration,
+    pub handshake: Duration,
+    pub total: Duration,
+    /// Meters [`ProbeTimeouts::connect`]; unset in config, set by the engine.
+    pub limiter: Option<Arc<RateLimiter>>,
+}
+
+/// Compares the budgets and whether both share one limiter, so a config push
+/// that changes nothing is still recognised as such.
+impl PartialEq for ProbeTimeouts {
+    fn eq(&self, other: &Self) -> bool {
+        self.connect == other.connect
+            && self.read == other.read
+            && self.handshake == other.handshake
+            && self.total == other.total
+            && match (&self.limiter, &other.limiter) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (a, b) => a.is_none() && b.is_none(),
+            }
+    }
+}
+
+impl Eq for ProbeTimeouts {}
+
+impl Default for ProbeTimeouts {
+    fn default() -> Self {
+        Self::from_millis(0, 0, 0, 0)
+    }
+}
+
+impl ProbeTimeouts {
+    /// Builds the budgets from config milliseconds; 0 (unset) means the
+    /// default, and anything above [`MAX_TIMEOUT_MS`] is clamped.
+    pub fn from_millis(connect: u32, read: u32, handshake: u32, total: u32) -> Self {
+        let ms = |v: u32, default: u32| {
+            let v = if v == 0 {
+                default
+            } else {
+                v.min(MAX_TIMEOUT_MS)
+            };
+            Duration::from_millis(v as u64)
+        };
+        Self {
+            connect: ms(connect, DEFAULT_CONNECT_TIMEOUT_MS),
+            read: ms(read, DEFAULT_READ_TIMEOUT_MS),
+            handshake: ms(handshake, DEFAULT_HANDSHAKE_TIMEOUT_MS),
+            total: ms(total, DEFAULT_PROBE_TIMEOUT_MS),
+            limiter: None,
+        }
+    }
+
+    /// The same budgets, with every connect and datagram waiting on `limiter`.
+    pub fn metered(&self, limiter: &Arc<RateLimiter>) -> Self {
+        Self {
+            limiter: Some(limiter.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Opens a TCP connection within the connect budget, once the limiter (if
+    /// any) allows it. The wait for a token is not part of the budget.
+    pub async fn connect(&self, ip: &str, port: u16) -> anyhow::Result<TcpStream> {
+        if let Some(limiter) = &self.limiter {
+            limiter.connection().await;
+        }
+        within(
+            Phase::Connect,
+            self.connect,
+            TcpStream::connect(worker::host_port(ip, port)),
+        )
+        .await
+    }
+
+    /// Waits for a packet token before a datagram is sent, if metered.
+    pub async fn packet(&self) {
+        if let Some(limiter) = &self.limiter {
+            limiter.packet().await;
+        }
+    }
+
+    /// Runs one read within the read budget.
+    pub async fn read<T, E>(&self, fut: impl Future<Output = Result<T, E>>) -> anyhow::Result<T>
+    where
+        E: Into<anyhow::Error>,
+    {
+        within(Phase::Read, self.read, fut).await
+    }
+
+    /// Runs one read within the read budget, or until `deadline` (the end of an
+    /// enclosing phase) if that comes first.
+    pub async fn read_until<T, E>(
+        &self,
+        phase: Phase,
+        deadline: Instant,
+        fut: impl Future<Output = Result<T, E>>,
+    ) -> anyhow::Result<T>
+    where
+        E: Into<anyhow::Error>,
+    {
+        let read_deadline = Instant::now() + self.read;
+        let (at, phase) = if deadline < read_deadline {
+            (deadline, phase)
+        } else {
+            (read_deadline, Phase::Read)
+        };
+        match timeout_at(at, fut).await {
+            Ok(res) => res.map_err(Into::into),
+            Err(_) => Err(TimedOut(phase).into()),
+        }
+    }
+}
+
+/// Runs `fut` under `limit`, failing with [`TimedOut`] for `phase` on expiry.
+pub async fn within<T, E>(
+    phase: Phase,
+    limit: Duration,
+    fut: impl Future<Output = Result<T, E>>,
+) -> anyhow::Result<T>
+where
+    E: Into<anyhow::Error>,
+{
+    match timeout(limit, fut).await {
+        Ok(res) => res.map_err(Into::into),
+        Err(_) => Err(TimedOut(phase).into()),
+    }
+}
+
+/// What a failed probe ran into, as far as the heartbeat cares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    Timeout,
+    Refused,
+    Reset,
+    /// Protocol errors, unreachable hosts, anything else.
+    Other,
+}
+
+impl Failure {
+    /// Classifies by the first timeout or socket error in the error's chain.
+    pub fn of(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if cause.is::<TimedOut>() || cause.is::<Elapsed>() {
+                return Self::Timeout;
+            }
+            if let Some(io) = cause.downcast_ref::<io::Error>() {
+                return match io.kind() {
+                    io::ErrorKind::TimedOut => Self::Timeout,
+                    io::ErrorKind::ConnectionRefused => Self::Refused,
+                    io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe => Self::Reset,
+                    _ => Self::Other,
+                };
+            }
+        }
+        Self::Other
+    }
+}
+
+/// Failed probes by [`Failure`] class, for the heartbeat's `probe_timeouts`,
+/// `probe_refused` and `probe_resets`. Counts probes of known servers and of
+/// search candidates that accepted the connection; the search's own connect
+/// attempts to empty addresses are not failures.
+#[derive(Debug, Default)]
+pub struct FailureCounts {
+    pub timeouts: AtomicU64,
+    pub refused: AtomicU64,
+    pub resets: AtomicU64,
+}
+
+impl FailureCounts {
+    pub fn record(&self, err: &anyhow::Error) {
+        let counter = match Failure::of(err) {
+            Failure::Timeout => &self.timeouts,
+            Failure::Refused => &self.refused,
+            Failure::Reset => &self.resets,
+            Failure::Other => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn zero_means_default_and_large_values_clamp() {
+        let t = ProbeTimeouts::from_millis(0, 250, u32::MAX, 0);
+        assert_eq!(t.connect, Duration::from_millis(750));
+        assert_eq!(t.read, Duration::from_millis(250));
+        assert_eq!(t.handshake, Duration::from_millis(MAX_TIMEOUT_MS as u64));
+        assert_eq!(
+            t,
+            ProbeTimeouts {
+                read: t.read,
+                handshake: t.handshake,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn classifies_through_the_error_chain() {
+        let io = |kind| anyhow::Error::from(io::Error::from(kind));
+        assert_eq!(
+            Failure::of(&io(io::ErrorKind::ConnectionRefused)),
+            Failure::Refused
+        );
+        assert_eq!(
+            Failure::of(&io(io::ErrorKind::ConnectionReset)),
+            Failure::Reset
+        );
+        assert_eq!(
+            Failure::of(&io(io::ErrorKind::TimedOut).context("status")),
+            Failure::Timeout
+        );
+        assert_eq!(
+            Failure::of(&TimedOut(Phase::Handshake).into()),
+            Failure::Timeout
+        );
+        assert_eq!(Failure::of(&anyhow::anyhow!("bad packet")), Failure::Other);
+    }
+
+    #[tokio::test]
+    async fn stalled_reads_time_out_in_their_phase() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let t = ProbeTimeouts::from_millis(0, 50, 0, 0);
+        let mut conn = t.connect("127.0.0.1", port).await.unwrap();
+        let (_server, _) = listener.accept().await.unwrap();
+
+        let mut buf = [0u8; 1];
+        let read = tokio::io::AsyncReadExt::read_exact(&mut conn, &mut buf);
+        let err = t.read(read).await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(TimedOut(Phase::Read))));
+
+        let deadline = Instant::now() + Duration::from_millis(10);
+        let read = tokio::io::AsyncReadExt::read_exact(&mut conn, &mut buf);
+        let err = t
+            .read_until(Phase::Handshake, deadline, read)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(TimedOut(Phase::Handshake))
+        ));
+
+        let counts = FailureCounts::default();
+        counts.record(&err);
+        assert_eq!(counts.timeouts.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn metered_connects_draw_connection_tokens() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let limiter = Arc::new(RateLimiter::default());
+        let t = ProbeTimeouts::default().metered(&limiter);
+        assert_ne!(t, ProbeTimeouts::default());
+        for _ in 0..2 {
+            t.connect("127.0.0.1", port).await.unwrap();
+        }
+        assert_eq!(limiter.connections_opened.load(Ordering::Relaxed), 2);
+        assert_eq!(limiter.packets_sent.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn metered_packets_draw_packet_tokens() {
+        let limiter = Arc::new(RateLimiter::default());
+        let t = ProbeTimeouts::default().metered(&limiter);
+        t.packet().await;
+        t.packet().await;
+        assert_eq!(limiter.packets_sent.load(Ordering::Relaxed), 2);
+        assert_eq!(limiter.connections_opened.load(Ordering::Relaxed), 0);
+        ProbeTimeouts::default().packet().await;
+        assert_eq!(limiter.packets_sent.load(Ordering::Relaxed), 2);
+    }
+}